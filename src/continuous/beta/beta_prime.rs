@@ -0,0 +1,233 @@
+use super::inv_inc_beta_diff;
+use crate::{
+    ConditionDifferentiableDistribution, DistributionError, ReparameterizableDistribution,
+    SamplableDistribution, ValueDifferentiableDistribution,
+};
+use crate::{DependentJoint, Distribution, IndependentJoint, RandomVariable};
+use rand::prelude::*;
+use special::Gamma;
+use std::{ops::BitAnd, ops::Mul};
+
+/// Beta prime distribution
+#[derive(Clone, Debug)]
+pub struct BetaPrime;
+
+#[derive(thiserror::Error, Debug)]
+pub enum BetaPrimeError {
+    #[error("'α' must be positive")]
+    AlphaMustBePositive,
+    #[error("'β' must be positive")]
+    BetaMustBePositive,
+}
+
+impl Distribution for BetaPrime {
+    type Value = f64;
+    type Condition = BetaPrimeParams;
+
+    fn p_kernel(&self, x: &Self::Value, theta: &Self::Condition) -> Result<f64, DistributionError> {
+        let alpha = theta.alpha();
+        let beta = theta.beta();
+
+        Ok(x.powf(alpha - 1.0) * (1.0 + x).powf(-alpha - beta))
+    }
+}
+
+impl ValueDifferentiableDistribution for BetaPrime {
+    fn ln_diff_value(
+        &self,
+        x: &Self::Value,
+        theta: &Self::Condition,
+    ) -> Result<Vec<f64>, DistributionError> {
+        let alpha = theta.alpha();
+        let beta = theta.beta();
+        let f_x = (alpha - 1.0) / x - (alpha + beta) / (1.0 + x);
+        Ok(vec![f_x])
+    }
+}
+
+impl ConditionDifferentiableDistribution for BetaPrime {
+    fn ln_diff_condition(
+        &self,
+        x: &Self::Value,
+        theta: &Self::Condition,
+    ) -> Result<Vec<f64>, DistributionError> {
+        let alpha = theta.alpha();
+        let beta = theta.beta();
+        let f_alpha = x.ln() - (1.0 + x).ln() - alpha.digamma() + (alpha + beta).digamma();
+        let f_beta = -(1.0 + x).ln() - beta.digamma() + (alpha + beta).digamma();
+        Ok(vec![f_alpha, f_beta])
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BetaPrimeParams {
+    alpha: f64,
+    beta: f64,
+}
+
+impl BetaPrimeParams {
+    pub fn new(alpha: f64, beta: f64) -> Result<Self, DistributionError> {
+        if alpha <= 0.0 {
+            return Err(DistributionError::InvalidParameters(
+                BetaPrimeError::AlphaMustBePositive.into(),
+            ));
+        }
+        if beta <= 0.0 {
+            return Err(DistributionError::InvalidParameters(
+                BetaPrimeError::BetaMustBePositive.into(),
+            ));
+        }
+
+        Ok(Self { alpha, beta })
+    }
+
+    pub fn alpha(&self) -> f64 {
+        self.alpha
+    }
+
+    pub fn beta(&self) -> f64 {
+        self.beta
+    }
+}
+
+impl RandomVariable for BetaPrimeParams {
+    type RestoreInfo = ();
+
+    fn transform_vec(&self) -> (Vec<f64>, Self::RestoreInfo) {
+        (vec![self.alpha, self.beta], ())
+    }
+
+    fn len(&self) -> usize {
+        2usize
+    }
+
+    fn restore(v: &[f64], _: &Self::RestoreInfo) -> Result<Self, DistributionError> {
+        if v.len() != 2 {
+            return Err(DistributionError::InvalidRestoreVector);
+        }
+        Self::new(v[0], v[1])
+    }
+}
+
+impl<Rhs, TRhs> Mul<Rhs> for BetaPrime
+where
+    Rhs: Distribution<Value = TRhs, Condition = BetaPrimeParams>,
+    TRhs: RandomVariable,
+{
+    type Output = IndependentJoint<Self, Rhs, f64, TRhs, BetaPrimeParams>;
+
+    fn mul(self, rhs: Rhs) -> Self::Output {
+        IndependentJoint::new(self, rhs)
+    }
+}
+
+impl<Rhs, URhs> BitAnd<Rhs> for BetaPrime
+where
+    Rhs: Distribution<Value = BetaPrimeParams, Condition = URhs>,
+    URhs: RandomVariable,
+{
+    type Output = DependentJoint<Self, Rhs, f64, BetaPrimeParams, URhs>;
+
+    fn bitand(self, rhs: Rhs) -> Self::Output {
+        DependentJoint::new(self, rhs)
+    }
+}
+
+impl SamplableDistribution for BetaPrime {
+    fn sample(
+        &self,
+        theta: &Self::Condition,
+        rng: &mut dyn RngCore,
+    ) -> Result<Self::Value, DistributionError> {
+        let eps = self.sample_noise(theta, rng)?;
+
+        self.sample_reparam(theta, &eps)
+    }
+}
+
+impl ReparameterizableDistribution for BetaPrime {
+    /// `ε ~ U(0, 1)`
+    type Noise = f64;
+
+    fn sample_noise(
+        &self,
+        _theta: &Self::Condition,
+        rng: &mut dyn RngCore,
+    ) -> Result<Self::Noise, DistributionError> {
+        Ok(rng.gen_range(0.0..1.0))
+    }
+
+    /// `x = y / (1 - y)` where `y = I_ε^{-1}(α, β)` is the inverse CDF of the beta distribution.
+    fn sample_reparam(
+        &self,
+        theta: &Self::Condition,
+        eps: &Self::Noise,
+    ) -> Result<Self::Value, DistributionError> {
+        let (y, _, _) = inv_inc_beta_diff(*eps, theta.alpha(), theta.beta());
+
+        Ok(y / (1.0 - y))
+    }
+
+    fn sample_reparam_diff_condition(
+        &self,
+        theta: &Self::Condition,
+        eps: &Self::Noise,
+    ) -> Result<Vec<f64>, DistributionError> {
+        let (y, f_alpha, f_beta) = inv_inc_beta_diff(*eps, theta.alpha(), theta.beta());
+        let dx_dy = (1.0 - y).powi(-2);
+
+        Ok(vec![dx_dy * f_alpha, dx_dy * f_beta])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        BetaPrime, BetaPrimeParams, Distribution, ReparameterizableDistribution,
+        SamplableDistribution, ValueDifferentiableDistribution,
+    };
+    use rand::prelude::*;
+
+    #[test]
+    fn it_works() {
+        let n = BetaPrime;
+        let mut rng = StdRng::from_seed([1; 32]);
+
+        let x = n
+            .sample(&BetaPrimeParams::new(2.0, 3.0).unwrap(), &mut rng)
+            .unwrap();
+
+        assert!(0.0 < x);
+    }
+
+    #[test]
+    fn it_works2() {
+        let n = BetaPrime;
+        let theta = BetaPrimeParams::new(2.0, 3.0).unwrap();
+        let x = 0.8;
+
+        let f = n.ln_diff_value(&x, &theta).unwrap();
+        let h = 1e-6;
+        let numerical =
+            (n.p_kernel(&(x + h), &theta).unwrap().ln() - n.p_kernel(&x, &theta).unwrap().ln()) / h;
+        assert!((f[0] - numerical).abs() < 1e-4);
+    }
+
+    #[test]
+    fn it_works_3() {
+        let n = BetaPrime;
+        let eps = 0.3;
+        let h = 1e-4;
+
+        let f = n
+            .sample_reparam_diff_condition(&BetaPrimeParams::new(2.0, 3.0).unwrap(), &eps)
+            .unwrap();
+        let x_a = n
+            .sample_reparam(&BetaPrimeParams::new(2.0 + h, 3.0).unwrap(), &eps)
+            .unwrap();
+        let x_a2 = n
+            .sample_reparam(&BetaPrimeParams::new(2.0 - h, 3.0).unwrap(), &eps)
+            .unwrap();
+        assert!((f[0] - (x_a - x_a2) / (2.0 * h)).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,300 @@
+use super::inv_inc_beta_diff;
+use crate::{
+    ConditionDifferentiableDistribution, DistributionError, ReparameterizableDistribution,
+    SamplableDistribution, ValueDifferentiableDistribution,
+};
+use crate::{DependentJoint, Distribution, IndependentJoint, RandomVariable};
+use rand::prelude::*;
+use special::Gamma;
+use std::{ops::BitAnd, ops::Mul};
+
+/// Generalized beta distribution of the first kind
+/// `x = b * y^{1/a}` where `y ~ Beta(p, q)`, so that `0 < x < b`.
+#[derive(Clone, Debug)]
+pub struct GeneralizedBeta;
+
+#[derive(thiserror::Error, Debug)]
+pub enum GeneralizedBetaError {
+    #[error("'a' must be positive")]
+    AMustBePositive,
+    #[error("'b' must be positive")]
+    BMustBePositive,
+    #[error("'p' must be positive")]
+    PMustBePositive,
+    #[error("'q' must be positive")]
+    QMustBePositive,
+}
+
+impl Distribution for GeneralizedBeta {
+    type Value = f64;
+    type Condition = GeneralizedBetaParams;
+
+    fn p_kernel(&self, x: &Self::Value, theta: &Self::Condition) -> Result<f64, DistributionError> {
+        let a = theta.a();
+        let b = theta.b();
+        let p = theta.p();
+        let q = theta.q();
+
+        Ok(x.powf(a * p - 1.0) * (1.0 - (x / b).powf(a)).powf(q - 1.0))
+    }
+}
+
+impl ValueDifferentiableDistribution for GeneralizedBeta {
+    fn ln_diff_value(
+        &self,
+        x: &Self::Value,
+        theta: &Self::Condition,
+    ) -> Result<Vec<f64>, DistributionError> {
+        let a = theta.a();
+        let b = theta.b();
+        let p = theta.p();
+        let q = theta.q();
+        let t = (x / b).powf(a);
+        let f_x = (a * p - 1.0) / x - (q - 1.0) * a * t / (x * (1.0 - t));
+        Ok(vec![f_x])
+    }
+}
+
+impl ConditionDifferentiableDistribution for GeneralizedBeta {
+    fn ln_diff_condition(
+        &self,
+        x: &Self::Value,
+        theta: &Self::Condition,
+    ) -> Result<Vec<f64>, DistributionError> {
+        let a = theta.a();
+        let b = theta.b();
+        let p = theta.p();
+        let q = theta.q();
+        let ln_x_b = (x / b).ln();
+        let t = (x / b).powf(a);
+
+        let f_a = 1.0 / a + p * ln_x_b - (q - 1.0) * t * ln_x_b / (1.0 - t);
+        let f_b = (q - 1.0) * a * t / (b * (1.0 - t)) - a * p / b;
+        let f_p = a * ln_x_b - p.digamma() + (p + q).digamma();
+        let f_q = (1.0 - t).ln() - q.digamma() + (p + q).digamma();
+        Ok(vec![f_a, f_b, f_p, f_q])
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GeneralizedBetaParams {
+    a: f64,
+    b: f64,
+    p: f64,
+    q: f64,
+}
+
+impl GeneralizedBetaParams {
+    /// - `a`: Power
+    /// - `b`: Upper bound
+    /// - `p`, `q`: Shapes of the underlying beta distribution
+    pub fn new(a: f64, b: f64, p: f64, q: f64) -> Result<Self, DistributionError> {
+        if a <= 0.0 {
+            return Err(DistributionError::InvalidParameters(
+                GeneralizedBetaError::AMustBePositive.into(),
+            ));
+        }
+        if b <= 0.0 {
+            return Err(DistributionError::InvalidParameters(
+                GeneralizedBetaError::BMustBePositive.into(),
+            ));
+        }
+        if p <= 0.0 {
+            return Err(DistributionError::InvalidParameters(
+                GeneralizedBetaError::PMustBePositive.into(),
+            ));
+        }
+        if q <= 0.0 {
+            return Err(DistributionError::InvalidParameters(
+                GeneralizedBetaError::QMustBePositive.into(),
+            ));
+        }
+
+        Ok(Self { a, b, p, q })
+    }
+
+    pub fn a(&self) -> f64 {
+        self.a
+    }
+
+    pub fn b(&self) -> f64 {
+        self.b
+    }
+
+    pub fn p(&self) -> f64 {
+        self.p
+    }
+
+    pub fn q(&self) -> f64 {
+        self.q
+    }
+}
+
+impl RandomVariable for GeneralizedBetaParams {
+    type RestoreInfo = ();
+
+    fn transform_vec(&self) -> (Vec<f64>, Self::RestoreInfo) {
+        (vec![self.a, self.b, self.p, self.q], ())
+    }
+
+    fn len(&self) -> usize {
+        4usize
+    }
+
+    fn restore(v: &[f64], _: &Self::RestoreInfo) -> Result<Self, DistributionError> {
+        if v.len() != 4 {
+            return Err(DistributionError::InvalidRestoreVector);
+        }
+        Self::new(v[0], v[1], v[2], v[3])
+    }
+}
+
+impl<Rhs, TRhs> Mul<Rhs> for GeneralizedBeta
+where
+    Rhs: Distribution<Value = TRhs, Condition = GeneralizedBetaParams>,
+    TRhs: RandomVariable,
+{
+    type Output = IndependentJoint<Self, Rhs, f64, TRhs, GeneralizedBetaParams>;
+
+    fn mul(self, rhs: Rhs) -> Self::Output {
+        IndependentJoint::new(self, rhs)
+    }
+}
+
+impl<Rhs, URhs> BitAnd<Rhs> for GeneralizedBeta
+where
+    Rhs: Distribution<Value = GeneralizedBetaParams, Condition = URhs>,
+    URhs: RandomVariable,
+{
+    type Output = DependentJoint<Self, Rhs, f64, GeneralizedBetaParams, URhs>;
+
+    fn bitand(self, rhs: Rhs) -> Self::Output {
+        DependentJoint::new(self, rhs)
+    }
+}
+
+impl SamplableDistribution for GeneralizedBeta {
+    fn sample(
+        &self,
+        theta: &Self::Condition,
+        rng: &mut dyn RngCore,
+    ) -> Result<Self::Value, DistributionError> {
+        let eps = self.sample_noise(theta, rng)?;
+
+        self.sample_reparam(theta, &eps)
+    }
+}
+
+impl ReparameterizableDistribution for GeneralizedBeta {
+    /// `ε ~ U(0, 1)`
+    type Noise = f64;
+
+    fn sample_noise(
+        &self,
+        _theta: &Self::Condition,
+        rng: &mut dyn RngCore,
+    ) -> Result<Self::Noise, DistributionError> {
+        Ok(rng.gen_range(0.0..1.0))
+    }
+
+    /// `x = b * y^{1/a}` where `y = I_ε^{-1}(p, q)` is the inverse CDF of the beta distribution.
+    fn sample_reparam(
+        &self,
+        theta: &Self::Condition,
+        eps: &Self::Noise,
+    ) -> Result<Self::Value, DistributionError> {
+        let (y, _, _) = inv_inc_beta_diff(*eps, theta.p(), theta.q());
+
+        Ok(theta.b() * y.powf(1.0 / theta.a()))
+    }
+
+    fn sample_reparam_diff_condition(
+        &self,
+        theta: &Self::Condition,
+        eps: &Self::Noise,
+    ) -> Result<Vec<f64>, DistributionError> {
+        let a = theta.a();
+        let b = theta.b();
+        let (y, f_p, f_q) = inv_inc_beta_diff(*eps, theta.p(), theta.q());
+        let x = b * y.powf(1.0 / a);
+
+        let f_a = -x * y.ln() / a.powi(2);
+        let f_b = x / b;
+        let dx_dy = x / (a * y);
+        Ok(vec![f_a, f_b, dx_dy * f_p, dx_dy * f_q])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ConditionDifferentiableDistribution, Distribution, GeneralizedBeta, GeneralizedBetaParams,
+        ReparameterizableDistribution, SamplableDistribution, ValueDifferentiableDistribution,
+    };
+    use rand::prelude::*;
+
+    #[test]
+    fn it_works() {
+        let n = GeneralizedBeta;
+        let mut rng = StdRng::from_seed([1; 32]);
+
+        let x = n
+            .sample(
+                &GeneralizedBetaParams::new(2.0, 3.0, 1.5, 2.5).unwrap(),
+                &mut rng,
+            )
+            .unwrap();
+
+        assert!(0.0 < x && x < 3.0);
+    }
+
+    #[test]
+    fn it_works2() {
+        let n = GeneralizedBeta;
+        let theta = GeneralizedBetaParams::new(2.0, 3.0, 1.5, 2.5).unwrap();
+        let x = 1.2;
+
+        let f = n.ln_diff_value(&x, &theta).unwrap();
+        let h = 1e-6;
+        let numerical =
+            (n.p_kernel(&(x + h), &theta).unwrap().ln() - n.p_kernel(&x, &theta).unwrap().ln()) / h;
+        assert!((f[0] - numerical).abs() < 1e-4);
+
+        let f = n.ln_diff_condition(&x, &theta).unwrap();
+        assert_eq!(f.len(), 4);
+    }
+
+    #[test]
+    fn it_works_3() {
+        let n = GeneralizedBeta;
+        let eps = 0.3;
+        let h = 1e-6;
+
+        let f = n
+            .sample_reparam_diff_condition(
+                &GeneralizedBetaParams::new(2.0, 3.0, 1.5, 2.5).unwrap(),
+                &eps,
+            )
+            .unwrap();
+        let x = n
+            .sample_reparam(
+                &GeneralizedBetaParams::new(2.0, 3.0, 1.5, 2.5).unwrap(),
+                &eps,
+            )
+            .unwrap();
+        let x_a = n
+            .sample_reparam(
+                &GeneralizedBetaParams::new(2.0 + h, 3.0, 1.5, 2.5).unwrap(),
+                &eps,
+            )
+            .unwrap();
+        let x_b = n
+            .sample_reparam(
+                &GeneralizedBetaParams::new(2.0, 3.0 + h, 1.5, 2.5).unwrap(),
+                &eps,
+            )
+            .unwrap();
+        assert!((f[0] - (x_a - x) / h).abs() < 1e-4);
+        assert!((f[1] - (x_b - x) / h).abs() < 1e-4);
+    }
+}
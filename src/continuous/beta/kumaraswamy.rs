@@ -0,0 +1,243 @@
+use crate::{
+    ConditionDifferentiableDistribution, DistributionError, ReparameterizableDistribution,
+    SamplableDistribution, ValueDifferentiableDistribution,
+};
+use crate::{DependentJoint, Distribution, IndependentJoint, RandomVariable};
+use rand::prelude::*;
+use std::{ops::BitAnd, ops::Mul};
+
+/// Kumaraswamy distribution
+#[derive(Clone, Debug)]
+pub struct Kumaraswamy;
+
+#[derive(thiserror::Error, Debug)]
+pub enum KumaraswamyError {
+    #[error("'a' must be positive")]
+    AMustBePositive,
+    #[error("'b' must be positive")]
+    BMustBePositive,
+}
+
+impl Distribution for Kumaraswamy {
+    type Value = f64;
+    type Condition = KumaraswamyParams;
+
+    fn p_kernel(&self, x: &Self::Value, theta: &Self::Condition) -> Result<f64, DistributionError> {
+        let a = theta.a();
+        let b = theta.b();
+
+        Ok(x.powf(a - 1.0) * (1.0 - x.powf(a)).powf(b - 1.0))
+    }
+}
+
+impl ValueDifferentiableDistribution for Kumaraswamy {
+    fn ln_diff_value(
+        &self,
+        x: &Self::Value,
+        theta: &Self::Condition,
+    ) -> Result<Vec<f64>, DistributionError> {
+        let a = theta.a();
+        let b = theta.b();
+        let x_a = x.powf(a);
+        let f_x = (a - 1.0) / x - (b - 1.0) * a * x_a / (x * (1.0 - x_a));
+        Ok(vec![f_x])
+    }
+}
+
+impl ConditionDifferentiableDistribution for Kumaraswamy {
+    fn ln_diff_condition(
+        &self,
+        x: &Self::Value,
+        theta: &Self::Condition,
+    ) -> Result<Vec<f64>, DistributionError> {
+        let a = theta.a();
+        let b = theta.b();
+        let x_a = x.powf(a);
+        let f_a = 1.0 / a + x.ln() - (b - 1.0) * x_a * x.ln() / (1.0 - x_a);
+        let f_b = 1.0 / b + (1.0 - x_a).ln();
+        Ok(vec![f_a, f_b])
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct KumaraswamyParams {
+    a: f64,
+    b: f64,
+}
+
+impl KumaraswamyParams {
+    pub fn new(a: f64, b: f64) -> Result<Self, DistributionError> {
+        if a <= 0.0 {
+            return Err(DistributionError::InvalidParameters(
+                KumaraswamyError::AMustBePositive.into(),
+            ));
+        }
+        if b <= 0.0 {
+            return Err(DistributionError::InvalidParameters(
+                KumaraswamyError::BMustBePositive.into(),
+            ));
+        }
+
+        Ok(Self { a, b })
+    }
+
+    pub fn a(&self) -> f64 {
+        self.a
+    }
+
+    pub fn b(&self) -> f64 {
+        self.b
+    }
+}
+
+impl RandomVariable for KumaraswamyParams {
+    type RestoreInfo = ();
+
+    fn transform_vec(&self) -> (Vec<f64>, Self::RestoreInfo) {
+        (vec![self.a, self.b], ())
+    }
+
+    fn len(&self) -> usize {
+        2usize
+    }
+
+    fn restore(v: &[f64], _: &Self::RestoreInfo) -> Result<Self, DistributionError> {
+        if v.len() != 2 {
+            return Err(DistributionError::InvalidRestoreVector);
+        }
+        Self::new(v[0], v[1])
+    }
+}
+
+impl<Rhs, TRhs> Mul<Rhs> for Kumaraswamy
+where
+    Rhs: Distribution<Value = TRhs, Condition = KumaraswamyParams>,
+    TRhs: RandomVariable,
+{
+    type Output = IndependentJoint<Self, Rhs, f64, TRhs, KumaraswamyParams>;
+
+    fn mul(self, rhs: Rhs) -> Self::Output {
+        IndependentJoint::new(self, rhs)
+    }
+}
+
+impl<Rhs, URhs> BitAnd<Rhs> for Kumaraswamy
+where
+    Rhs: Distribution<Value = KumaraswamyParams, Condition = URhs>,
+    URhs: RandomVariable,
+{
+    type Output = DependentJoint<Self, Rhs, f64, KumaraswamyParams, URhs>;
+
+    fn bitand(self, rhs: Rhs) -> Self::Output {
+        DependentJoint::new(self, rhs)
+    }
+}
+
+impl SamplableDistribution for Kumaraswamy {
+    fn sample(
+        &self,
+        theta: &Self::Condition,
+        rng: &mut dyn RngCore,
+    ) -> Result<Self::Value, DistributionError> {
+        let eps = self.sample_noise(theta, rng)?;
+
+        self.sample_reparam(theta, &eps)
+    }
+}
+
+impl ReparameterizableDistribution for Kumaraswamy {
+    /// `ε ~ U(0, 1)`
+    type Noise = f64;
+
+    fn sample_noise(
+        &self,
+        _theta: &Self::Condition,
+        rng: &mut dyn RngCore,
+    ) -> Result<Self::Noise, DistributionError> {
+        Ok(rng.gen_range(0.0..1.0))
+    }
+
+    /// Inverse CDF `x = (1 - (1 - ε)^{1/b})^{1/a}`
+    fn sample_reparam(
+        &self,
+        theta: &Self::Condition,
+        eps: &Self::Noise,
+    ) -> Result<Self::Value, DistributionError> {
+        let a = theta.a();
+        let b = theta.b();
+
+        Ok((1.0 - (1.0 - eps).powf(1.0 / b)).powf(1.0 / a))
+    }
+
+    fn sample_reparam_diff_condition(
+        &self,
+        theta: &Self::Condition,
+        eps: &Self::Noise,
+    ) -> Result<Vec<f64>, DistributionError> {
+        let a = theta.a();
+        let b = theta.b();
+        let w = (1.0 - eps).powf(1.0 / b);
+        let s = 1.0 - w;
+        let x = s.powf(1.0 / a);
+
+        let f_a = -x * s.ln() / a.powi(2);
+        let f_b = x / (a * s) * w * (1.0 - eps).ln() / b.powi(2);
+        Ok(vec![f_a, f_b])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Distribution, Kumaraswamy, KumaraswamyParams, ReparameterizableDistribution,
+        SamplableDistribution, ValueDifferentiableDistribution,
+    };
+    use rand::prelude::*;
+
+    #[test]
+    fn it_works() {
+        let n = Kumaraswamy;
+        let mut rng = StdRng::from_seed([1; 32]);
+
+        let x = n
+            .sample(&KumaraswamyParams::new(2.0, 3.0).unwrap(), &mut rng)
+            .unwrap();
+
+        assert!(0.0 < x && x < 1.0);
+    }
+
+    #[test]
+    fn it_works2() {
+        let n = Kumaraswamy;
+        let theta = KumaraswamyParams::new(2.0, 3.0).unwrap();
+        let x = 0.4;
+
+        let f = n.ln_diff_value(&x, &theta).unwrap();
+        let h = 1e-6;
+        let numerical =
+            (n.p_kernel(&(x + h), &theta).unwrap().ln() - n.p_kernel(&x, &theta).unwrap().ln()) / h;
+        assert!((f[0] - numerical).abs() < 1e-4);
+    }
+
+    #[test]
+    fn it_works_3() {
+        let n = Kumaraswamy;
+        let eps = 0.3;
+        let h = 1e-6;
+
+        let f = n
+            .sample_reparam_diff_condition(&KumaraswamyParams::new(2.0, 3.0).unwrap(), &eps)
+            .unwrap();
+        let x = n
+            .sample_reparam(&KumaraswamyParams::new(2.0, 3.0).unwrap(), &eps)
+            .unwrap();
+        let x_a = n
+            .sample_reparam(&KumaraswamyParams::new(2.0 + h, 3.0).unwrap(), &eps)
+            .unwrap();
+        let x_b = n
+            .sample_reparam(&KumaraswamyParams::new(2.0, 3.0 + h).unwrap(), &eps)
+            .unwrap();
+        assert!((f[0] - (x_a - x) / h).abs() < 1e-4);
+        assert!((f[1] - (x_b - x) / h).abs() < 1e-4);
+    }
+}
@@ -1,5 +1,29 @@
+pub mod beta_prime;
+pub mod generalized_beta;
+pub mod kumaraswamy;
 pub mod multivariate;
 pub mod univariate;
 
+pub use beta_prime::*;
+pub use generalized_beta::*;
+pub use kumaraswamy::*;
 pub use multivariate::*;
 pub use univariate::*;
+
+use special::Beta as SpecialBeta;
+
+/// `y = I_u^{-1}(p, q)` and `(∂y/∂p, ∂y/∂q)` by implicit differentiation of `I_y(p, q) = u`.
+/// The derivatives of the regularized incomplete beta function are taken numerically because they have no closed form.
+pub(crate) fn inv_inc_beta_diff(u: f64, p: f64, q: f64) -> (f64, f64, f64) {
+    let ln_beta = p.ln_beta(q);
+    let y = u.inv_inc_beta(p, q, ln_beta);
+
+    let inc_beta = |p: f64, q: f64| y.inc_beta(p, q, p.ln_beta(q));
+    let hp = 1e-6 * p.max(1.0);
+    let hq = 1e-6 * q.max(1.0);
+    let f_p = (inc_beta(p + hp, q) - inc_beta(p - hp, q)) / (2.0 * hp);
+    let f_q = (inc_beta(p, q + hq) - inc_beta(p, q - hq)) / (2.0 * hq);
+    let pdf = ((p - 1.0) * y.ln() + (q - 1.0) * (1.0 - y).ln() - ln_beta).exp();
+
+    (y, -f_p / pdf, -f_q / pdf)
+}
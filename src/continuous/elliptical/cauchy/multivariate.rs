@@ -1,11 +1,9 @@
 use crate::{
     ConditionDifferentiableDistribution, DependentJoint, Distribution, ExactEllipticalParams,
-    ExactMultivariateStudentTParams, IndependentJoint, MultivariateStudentT,
-    MultivariateStudentTParams, MultivariateStudentTWrapper, RandomVariable, SamplableDistribution,
-    ValueDifferentiableDistribution,
+    IndependentJoint, MultivariateStudentT, MultivariateStudentTWrapper, RandomVariable,
+    SamplableDistribution, ValueDifferentiableDistribution,
 };
 use crate::{DistributionError, EllipticalParams};
-use opensrdk_linear_algebra::Vector;
 use rand::prelude::*;
 use std::marker::PhantomData;
 use std::{ops::BitAnd, ops::Mul};
@@ -45,16 +43,6 @@ where
 
         MultivariateStudentT::new().p_kernel(x, &studentt_params)
     }
-
-    // fn sample(
-    //     &self,
-    //     theta: &Self::Condition,
-    //     rng: &mut dyn RngCore,
-    // ) -> Result<Self::Value, DistributionError> {
-    //     let studentt_params = MultivariateStudentTWrapper::new(theta);
-
-    //     MultivariateStudentT::new().sample(&studentt_params, rng)
-    // }
 }
 
 impl<T, Rhs, TRhs> Mul<Rhs> for MultivariateCauchy<T>
@@ -83,62 +71,50 @@ where
     }
 }
 
-impl SamplableDistribution for MultivariateCauchy {
+impl<T> SamplableDistribution for MultivariateCauchy<T>
+where
+    T: EllipticalParams,
+{
     fn sample(
         &self,
         theta: &Self::Condition,
         rng: &mut dyn RngCore,
     ) -> Result<Self::Value, DistributionError> {
-        let studentt_params_orig = MultivariateStudentTWrapper::new(theta);
-        let studentt_params = ExactMultivariateStudentTParams::new(
-            studentt_params_orig.nu(),
-            studentt_params_orig.elliptical().mu().clone(),
-            studentt_params_orig.elliptical().lsigma().clone(),
-        )
-        .unwrap();
+        let studentt_params = MultivariateStudentTWrapper::new(theta);
+
         MultivariateStudentT::new().sample(&studentt_params, rng)
     }
 }
 
-impl ValueDifferentiableDistribution for MultivariateCauchy {
+impl<T> ValueDifferentiableDistribution for MultivariateCauchy<T>
+where
+    T: EllipticalParams,
+{
     fn ln_diff_value(
         &self,
         x: &Self::Value,
         theta: &Self::Condition,
     ) -> Result<Vec<f64>, DistributionError> {
-        let x_mat = x.clone().row_mat();
-        let mu_mat = theta.mu().clone().row_mat();
-        let x_mu = x_mat - mu_mat;
-        let x_mu_t = x_mu.t();
-        let sigma_inv = theta.lsigma().clone().pptri()?.to_mat();
+        let studentt_params = MultivariateStudentTWrapper::new(theta);
 
-        let n = x.len() as f64;
-        let d = (&x_mu * &sigma_inv * &x_mu_t)[(0, 0)];
-        let f_x = -(1.0 + n) / (1.0 + &d) * x_mu * sigma_inv;
-        Ok(f_x.vec())
+        MultivariateStudentT::new().ln_diff_value(x, &studentt_params)
     }
 }
 
 impl ConditionDifferentiableDistribution for MultivariateCauchy {
+    /// Gradients with respect to `μ` and packed `L` in this order.
     fn ln_diff_condition(
         &self,
         x: &Self::Value,
         theta: &Self::Condition,
     ) -> Result<Vec<f64>, DistributionError> {
-        let x_mat = x.clone().row_mat();
-        let mu_mat = theta.mu().clone().row_mat();
-        let x_mu = x_mat - mu_mat;
-        let x_mu_t = x_mu.t();
-        let sigma_inv = theta.lsigma().clone().pptri()?.to_mat();
-        let n = x.len() as f64;
-        let d = (&x_mu * &sigma_inv * &x_mu_t)[(0, 0)];
-        let m = sigma_inv
-            .clone()
-            .hadamard_prod(&sigma_inv)
-            .hadamard_prod(&sigma_inv);
-        let f_mu = (1.0 + n) / (1.0 + &d) * (&x_mu * &sigma_inv);
-        let f_lsigma = (1.0 + n) / (1.0 + &d) * (&x_mu * &m * &x_mu_t);
-        Ok([f_mu.vec(), f_lsigma.vec()].concat())
+        let studentt_params = MultivariateStudentTWrapper::new(theta);
+        let mut f = MultivariateStudentT::<_, ExactEllipticalParams>::new()
+            .ln_diff_condition(x, &studentt_params)?;
+        // Drop the gradient with respect to ν
+        f.pop();
+
+        Ok(f)
     }
 }
 
@@ -146,10 +122,11 @@ impl ConditionDifferentiableDistribution for MultivariateCauchy {
 mod tests {
     use crate::{
         ConditionDifferentiableDistribution, Distribution, ExactMultivariateCauchyParams,
-        MultivariateCauchy, SamplableDistribution, ValueDifferentiableDistribution,
+        MultivariateCauchy, RandomVariable, SamplableDistribution, ValueDifferentiableDistribution,
     };
     use opensrdk_linear_algebra::{pp::trf::PPTRF, *};
     use rand::prelude::*;
+    use special::Gamma;
     #[test]
     fn it_works() {
         let cauchy = MultivariateCauchy::new();
@@ -177,49 +154,69 @@ mod tests {
         println!("{:#?}", x);
     }
 
-    #[test]
-    fn it_works2() {
-        let cauchy = MultivariateCauchy::new();
-
-        let mu = vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0];
+    fn params() -> ExactMultivariateCauchyParams {
+        let mu = vec![0.0, 1.0, 2.0];
         let lsigma = SymmetricPackedMatrix::from_mat(&mat!(
-           1.0,  0.0,  0.0,  0.0,  0.0,  0.0;
-           2.0,  3.0,  0.0,  0.0,  0.0,  0.0;
-           4.0,  5.0,  6.0,  0.0,  0.0,  0.0;
-           7.0,  8.0,  9.0, 10.0,  0.0,  0.0;
-          11.0, 12.0, 13.0, 14.0, 15.0,  0.0;
-          16.0, 17.0, 18.0, 19.0, 20.0, 21.0
+           1.0, 0.0, 0.0;
+           0.5, 2.0, 0.0;
+           0.2, 0.3, 1.5
         ))
         .unwrap();
-        let x = vec![0.0, 1.0, 2.0, 2.0, 2.0, 4.0];
 
-        let f = cauchy.ln_diff_value(
-            &x,
-            &ExactMultivariateCauchyParams::new(mu, PPTRF(lsigma)).unwrap(),
-        );
-        println!("{:#?}", f);
+        ExactMultivariateCauchyParams::new(mu, PPTRF(lsigma)).unwrap()
     }
 
-    #[test]
-    fn it_works_3() {
+    /// Normalized log density, for checking the gradients with respect to `L`.
+    fn ln_pdf(x: &Vec<f64>, theta: &ExactMultivariateCauchyParams) -> f64 {
         let cauchy = MultivariateCauchy::new();
+        let n = x.len() as f64;
+        let lsigma = theta.lsigma().0.to_mat();
+        let ln_det_l = (0..x.len())
+            .into_iter()
+            .map(|i| lsigma[(i, i)].ln())
+            .sum::<f64>();
+
+        cauchy.p_kernel(x, theta).unwrap().ln() + ((1.0 + n) / 2.0).ln_gamma().0
+            - 0.5f64.ln_gamma().0
+            - n / 2.0 * std::f64::consts::PI.ln()
+            - ln_det_l
+    }
 
-        let mu = vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0];
-        let lsigma = SymmetricPackedMatrix::from_mat(&mat!(
-           1.0,  0.0,  0.0,  0.0,  0.0,  0.0;
-           2.0,  3.0,  0.0,  0.0,  0.0,  0.0;
-           4.0,  5.0,  6.0,  0.0,  0.0,  0.0;
-           7.0,  8.0,  9.0, 10.0,  0.0,  0.0;
-          11.0, 12.0, 13.0, 14.0, 15.0,  0.0;
-          16.0, 17.0, 18.0, 19.0, 20.0, 21.0
-        ))
-        .unwrap();
-        let x = vec![0.0, 1.0, 2.0, 2.0, 2.0, 4.0];
+    #[test]
+    fn it_works2() {
+        let cauchy = MultivariateCauchy::new();
+        let theta = params();
+        let x = vec![0.5, -1.0, 3.0];
+
+        let f = cauchy.ln_diff_value(&x, &theta).unwrap();
+        let h = 1e-6;
+        for i in 0..f.len() {
+            let mut x_h = x.clone();
+            x_h[i] += h;
+            let numerical = (cauchy.p_kernel(&x_h, &theta).unwrap().ln()
+                - cauchy.p_kernel(&x, &theta).unwrap().ln())
+                / h;
+            assert!((f[i] - numerical).abs() < 1e-4);
+        }
+    }
 
-        let f = cauchy.ln_diff_condition(
-            &x,
-            &ExactMultivariateCauchyParams::new(mu, PPTRF(lsigma)).unwrap(),
-        );
-        println!("{:#?}", f);
+    #[test]
+    fn it_works_3() {
+        let cauchy = MultivariateCauchy::new();
+        let theta = params();
+        let x = vec![0.5, -1.0, 3.0];
+
+        let f = cauchy.ln_diff_condition(&x, &theta).unwrap();
+        let (v, info) = theta.transform_vec();
+        assert_eq!(f.len(), v.len());
+
+        let h = 1e-6;
+        for i in 0..f.len() {
+            let mut v_h = v.clone();
+            v_h[i] += h;
+            let theta_h = ExactMultivariateCauchyParams::restore(&v_h, &info).unwrap();
+            let numerical = (ln_pdf(&x, &theta_h) - ln_pdf(&x, &theta)) / h;
+            assert!((f[i] - numerical).abs() < 1e-4);
+        }
     }
 }
@@ -1,12 +1,15 @@
 use crate::{DistributionError, EllipticalParams};
 use crate::{ExactEllipticalParams, MultivariateStudentTParams, RandomVariable};
+use std::borrow::Cow;
 
-#[derive(Clone, Debug, PartialEq)]
+/// Student-t parameters with `ν = 1`, which is not a part of the random variable.
+/// The elliptical parameters are borrowed, and only owned when restored from a vector.
+#[derive(Clone, Debug)]
 pub(crate) struct MultivariateStudentTWrapper<'a, T>
 where
     T: EllipticalParams,
 {
-    elliptical: &'a T,
+    elliptical: Cow<'a, T>,
 }
 
 impl<'a, T> MultivariateStudentTWrapper<'a, T>
@@ -14,7 +17,9 @@ where
     T: EllipticalParams,
 {
     pub(crate) fn new(elliptical: &'a T) -> Self {
-        Self { elliptical }
+        Self {
+            elliptical: Cow::Borrowed(elliptical),
+        }
     }
 }
 
@@ -25,7 +30,7 @@ where
     type RestoreInfo = T::RestoreInfo;
 
     fn transform_vec(&self) -> (Vec<f64>, Self::RestoreInfo) {
-        todo!()
+        self.elliptical.transform_vec()
     }
 
     fn len(&self) -> usize {
@@ -33,7 +38,9 @@ where
     }
 
     fn restore(v: &[f64], info: &Self::RestoreInfo) -> Result<Self, DistributionError> {
-        todo!()
+        Ok(Self {
+            elliptical: Cow::Owned(T::restore(v, info)?),
+        })
     }
 }
 
@@ -46,7 +53,7 @@ where
     }
 
     fn elliptical(&self) -> &T {
-        self.elliptical
+        &self.elliptical
     }
 }
 
@@ -0,0 +1,277 @@
+pub mod params;
+
+pub use params::*;
+
+use crate::{
+    ConditionDifferentiableDistribution, DependentJoint, Distribution, DistributionError,
+    IndependentJoint, RandomVariable, SamplableDistribution, ValueDifferentiableDistribution,
+};
+use opensrdk_linear_algebra::{pp::trf::PPTRF, *};
+use rand::prelude::*;
+use rand_distr::StandardNormal;
+use std::{ops::BitAnd, ops::Mul};
+
+/// Matrix normal distribution
+#[derive(Clone, Debug)]
+pub struct MatrixNormal;
+
+#[derive(thiserror::Error, Debug)]
+pub enum MatrixNormalError {
+    #[error("dimension mismatch (MatrixNormal)")]
+    DimensionMismatch,
+}
+
+impl Distribution for MatrixNormal {
+    type Value = Matrix;
+    type Condition = MatrixNormalParams;
+
+    /// `tr[V^{-1} (X - M)^T U^{-1} (X - M)]` is evaluated without forming `V ⊗ U`.
+    /// `p ln|U| + n ln|V|` is kept so that the kernel agrees with the gradients with respect to `U` and `V`.
+    fn p_kernel(&self, x: &Self::Value, theta: &Self::Condition) -> Result<f64, DistributionError> {
+        let x_m = theta.x_m(x)?;
+        let n = x_m.rows() as f64;
+        let p = x_m.cols() as f64;
+        let u_inv_x_m = theta.lu().pptrs(x_m.clone())?;
+        let v_inv_x_m_t = theta.lv().pptrs(x_m.t())?;
+        let ln_det = p * ln_det_packed(theta.lu()) + n * ln_det_packed(theta.lv());
+
+        Ok((-0.5 * ((v_inv_x_m_t * u_inv_x_m).tr() + ln_det)).exp())
+    }
+}
+
+impl<Rhs, TRhs> Mul<Rhs> for MatrixNormal
+where
+    Rhs: Distribution<Value = TRhs, Condition = MatrixNormalParams>,
+    TRhs: RandomVariable,
+{
+    type Output = IndependentJoint<Self, Rhs, Matrix, TRhs, MatrixNormalParams>;
+
+    fn mul(self, rhs: Rhs) -> Self::Output {
+        IndependentJoint::new(self, rhs)
+    }
+}
+
+impl<Rhs, URhs> BitAnd<Rhs> for MatrixNormal
+where
+    Rhs: Distribution<Value = MatrixNormalParams, Condition = URhs>,
+    URhs: RandomVariable,
+{
+    type Output = DependentJoint<Self, Rhs, Matrix, MatrixNormalParams, URhs>;
+
+    fn bitand(self, rhs: Rhs) -> Self::Output {
+        DependentJoint::new(self, rhs)
+    }
+}
+
+impl SamplableDistribution for MatrixNormal {
+    fn sample(
+        &self,
+        theta: &Self::Condition,
+        rng: &mut dyn RngCore,
+    ) -> Result<Self::Value, DistributionError> {
+        let n = theta.m().rows();
+        let p = theta.m().cols();
+
+        let z = Matrix::from(
+            n,
+            (0..n * p)
+                .into_iter()
+                .map(|_| rng.sample(StandardNormal))
+                .collect::<Vec<f64>>(),
+        )?;
+
+        // X = M + L_U * Z * L_V^T
+        let lu = theta.lu().0.to_mat();
+        let lv = theta.lv().0.to_mat();
+
+        Ok(theta.m().clone() + lu * z * lv.t())
+    }
+}
+
+impl ValueDifferentiableDistribution for MatrixNormal {
+    fn ln_diff_value(
+        &self,
+        x: &Self::Value,
+        theta: &Self::Condition,
+    ) -> Result<Vec<f64>, DistributionError> {
+        let x_m = theta.x_m(x)?;
+        let u_inv_x_m = theta.lu().pptrs(x_m)?;
+        // U^{-1} (X - M) V^{-1} = (V^{-1} (U^{-1} (X - M))^T)^T
+        let f_x = -1.0 * theta.lv().pptrs(u_inv_x_m.t())?.t();
+
+        Ok(f_x.vec())
+    }
+}
+
+impl ConditionDifferentiableDistribution for MatrixNormal {
+    /// Gradients with respect to `M`, packed `L_U` and packed `L_V` in this order.
+    fn ln_diff_condition(
+        &self,
+        x: &Self::Value,
+        theta: &Self::Condition,
+    ) -> Result<Vec<f64>, DistributionError> {
+        let x_m = theta.x_m(x)?;
+        let n = x_m.rows();
+        let p = x_m.cols();
+        let lu = theta.lu().0.to_mat();
+        let lv = theta.lv().0.to_mat();
+
+        let u_inv_x_m = theta.lu().pptrs(x_m.clone())?;
+        let v_inv_x_m_t = theta.lv().pptrs(x_m.t())?;
+        // U^{-1} (X - M) V^{-1}
+        let f_m = theta.lv().pptrs(u_inv_x_m.t())?.t();
+
+        // U^{-1} (X - M) V^{-1} (X - M)^T U^{-1} L_U - p * diag(L_U)^{-1}
+        let f_lu_mat = &f_m * u_inv_x_m.t() * &lu;
+        let f_lu = tril_packed(&f_lu_mat, &lu, p as f64);
+
+        // V^{-1} (X - M)^T U^{-1} (X - M) V^{-1} L_V - n * diag(L_V)^{-1}
+        let f_lv_mat = f_m.t() * v_inv_x_m_t.t() * &lv;
+        let f_lv = tril_packed(&f_lv_mat, &lv, n as f64);
+
+        Ok([f_m.vec(), f_lu, f_lv].concat())
+    }
+}
+
+/// `ln|L L^T|` of a packed cholesky factor `L`
+pub(crate) fn ln_det_packed(l: &PPTRF) -> f64 {
+    let l = l.0.to_mat();
+
+    (0..l.rows())
+        .into_iter()
+        .map(|i| 2.0 * l[(i, i)].ln())
+        .sum::<f64>()
+}
+
+/// Packs the lower triangle of `m` column by column and subtracts the gradient of `c * ln|L L^T| / 2`.
+pub(crate) fn tril_packed(m: &Matrix, l: &Matrix, c: f64) -> Vec<f64> {
+    let dim = m.rows();
+
+    (0..dim)
+        .into_iter()
+        .flat_map(|j| {
+            (j..dim).into_iter().map(move |i| {
+                if i == j {
+                    m[(i, j)] - c / l[(i, j)]
+                } else {
+                    m[(i, j)]
+                }
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ConditionDifferentiableDistribution, Distribution, MatrixNormal, MatrixNormalParams,
+        RandomVariable, SamplableDistribution, ValueDifferentiableDistribution, WishartParams,
+    };
+    use opensrdk_linear_algebra::{pp::trf::PPTRF, *};
+    use rand::prelude::*;
+
+    fn params() -> MatrixNormalParams {
+        let m = mat!(
+            0.0, 1.0;
+            2.0, 3.0;
+            4.0, 5.0
+        );
+        let lu = SymmetricPackedMatrix::from_mat(&mat!(
+            1.0, 0.0, 0.0;
+            0.5, 2.0, 0.0;
+            0.2, 0.3, 1.5
+        ))
+        .unwrap();
+        let lv = SymmetricPackedMatrix::from_mat(&mat!(
+            1.0, 0.0;
+            0.4, 0.8
+        ))
+        .unwrap();
+
+        MatrixNormalParams::new(m, PPTRF(lu), PPTRF(lv)).unwrap()
+    }
+
+    #[test]
+    fn it_works() {
+        let matrix_normal = MatrixNormal;
+        let mut rng = StdRng::from_seed([1; 32]);
+
+        let x = matrix_normal.sample(&params(), &mut rng).unwrap();
+
+        assert_eq!(x.rows(), 3);
+        assert_eq!(x.cols(), 2);
+        println!("{:#?}", x);
+    }
+
+    #[test]
+    fn it_works2() {
+        let matrix_normal = MatrixNormal;
+        let theta = params();
+        let x = mat!(
+            0.5, 1.0;
+            1.0, 2.0;
+            3.0, 6.0
+        );
+
+        let f = matrix_normal.ln_diff_value(&x, &theta).unwrap();
+        let h = 1e-6;
+        for i in 0..f.len() {
+            let mut elems = x.elems().to_vec();
+            elems[i] += h;
+            let x_h = Matrix::from(3, elems).unwrap();
+            let numerical = (matrix_normal.p_kernel(&x_h, &theta).unwrap().ln()
+                - matrix_normal.p_kernel(&x, &theta).unwrap().ln())
+                / h;
+            assert!((f[i] - numerical).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn it_works_3() {
+        let matrix_normal = MatrixNormal;
+        let x = mat!(
+            0.5, 1.0;
+            1.0, 2.0;
+            3.0, 6.0
+        );
+
+        let theta = params();
+
+        let f = matrix_normal.ln_diff_condition(&x, &theta).unwrap();
+        assert_eq!(f.len(), 6 + 6 + 3);
+
+        let ln_p = |theta: &MatrixNormalParams| matrix_normal.p_kernel(&x, theta).unwrap().ln();
+        let (v, info) = theta.transform_vec();
+        let h = 1e-6;
+        for k in 0..v.len() {
+            let mut v_plus = v.clone();
+            v_plus[k] += h;
+            let mut v_minus = v.clone();
+            v_minus[k] -= h;
+            let numerical = (ln_p(&MatrixNormalParams::restore(&v_plus, &info).unwrap())
+                - ln_p(&MatrixNormalParams::restore(&v_minus, &info).unwrap()))
+                / (2.0 * h);
+            assert!((f[k] - numerical).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn it_works4() {
+        let theta = params();
+        let x = mat!(
+            0.5, 1.0;
+            1.0, 2.0;
+            3.0, 6.0
+        );
+        let lv0 = SymmetricPackedMatrix::from_mat(&mat!(
+            1.0, 0.0;
+            0.0, 1.0
+        ))
+        .unwrap();
+        let prior = WishartParams::new(PPTRF(lv0), 3.0).unwrap();
+
+        let posterior = theta.col_precision_posterior(&x, &prior).unwrap();
+
+        assert_eq!(posterior.n(), 6.0);
+    }
+}
@@ -0,0 +1,145 @@
+use crate::{DistributionError, MatrixNormalError, RandomVariable, WishartParams};
+use opensrdk_linear_algebra::{pp::trf::PPTRF, *};
+
+#[derive(Clone, Debug)]
+pub struct MatrixNormalParams {
+    m: Matrix,
+    lu: PPTRF,
+    lv: PPTRF,
+}
+
+impl MatrixNormalParams {
+    /// # Matrix normal
+    /// `vec(X) ~ N(vec(M), V ⊗ U)`
+    ///
+    /// - `m`: Mean matrix `n×p`
+    /// - `lu`: `L_U` under decomposition `U = L_U * L_U^T` of the row covariance `n×n`
+    /// - `lv`: `L_V` under decomposition `V = L_V * L_V^T` of the column covariance `p×p`
+    pub fn new(m: Matrix, lu: PPTRF, lv: PPTRF) -> Result<Self, DistributionError> {
+        if m.rows() != lu.0.dim() || m.cols() != lv.0.dim() {
+            return Err(DistributionError::InvalidParameters(
+                MatrixNormalError::DimensionMismatch.into(),
+            ));
+        }
+
+        Ok(Self { m, lu, lv })
+    }
+
+    pub fn m(&self) -> &Matrix {
+        &self.m
+    }
+
+    pub fn lu(&self) -> &PPTRF {
+        &self.lu
+    }
+
+    pub fn lv(&self) -> &PPTRF {
+        &self.lv
+    }
+
+    pub fn eject(self) -> (Matrix, PPTRF, PPTRF) {
+        (self.m, self.lu, self.lv)
+    }
+
+    pub(crate) fn x_m(&self, x: &Matrix) -> Result<Matrix, DistributionError> {
+        if x.rows() != self.m.rows() || x.cols() != self.m.cols() {
+            return Err(DistributionError::InvalidParameters(
+                MatrixNormalError::DimensionMismatch.into(),
+            ));
+        }
+
+        Ok(x.clone() - self.m.clone())
+    }
+
+    /// Posterior of the column precision `V^{-1} ~ W(prior)` given an observation `x`, with `M` and `U` fixed.
+    pub fn col_precision_posterior(
+        &self,
+        x: &Matrix,
+        prior: &WishartParams,
+    ) -> Result<WishartParams, DistributionError> {
+        let p = self.m.cols();
+        if prior.lv().0.dim() != p {
+            return Err(DistributionError::InvalidParameters(
+                MatrixNormalError::DimensionMismatch.into(),
+            ));
+        }
+        let x_m = self.x_m(x)?;
+        let n = x_m.rows();
+
+        // V'^{-1} = V0^{-1} + (X - M)^T U^{-1} (X - M)
+        let identity = DiagonalMatrix::<f64>::identity(p).mat();
+        let v0_inv = prior.lv().pptrs(identity)?;
+        let scatter = x_m.t() * self.lu.pptrs(x_m)?;
+        let v_inv = v0_inv + scatter;
+        let lv = posterior_scale(v_inv)?;
+
+        WishartParams::new(lv, prior.n() + n as f64)
+    }
+
+    /// Posterior of the row precision `U^{-1} ~ W(prior)` given an observation `x`, with `M` and `V` fixed.
+    pub fn row_precision_posterior(
+        &self,
+        x: &Matrix,
+        prior: &WishartParams,
+    ) -> Result<WishartParams, DistributionError> {
+        let n = self.m.rows();
+        if prior.lv().0.dim() != n {
+            return Err(DistributionError::InvalidParameters(
+                MatrixNormalError::DimensionMismatch.into(),
+            ));
+        }
+        let x_m = self.x_m(x)?;
+        let p = x_m.cols();
+
+        // U'^{-1} = U0^{-1} + (X - M) V^{-1} (X - M)^T
+        let identity = DiagonalMatrix::<f64>::identity(n).mat();
+        let u0_inv = prior.lv().pptrs(identity)?;
+        let scatter = &x_m * self.lv.pptrs(x_m.t())?;
+        let u_inv = u0_inv + scatter;
+        let lu = posterior_scale(u_inv)?;
+
+        WishartParams::new(lu, prior.n() + p as f64)
+    }
+}
+
+/// Cholesky decomposition of the inverse of the posterior scale precision.
+fn posterior_scale(scale_inv: Matrix) -> Result<PPTRF, DistributionError> {
+    let scale = SymmetricPackedMatrix::from_mat(&scale_inv)
+        .unwrap()
+        .pptrf()?
+        .pptri()?;
+
+    Ok(scale.pptrf()?)
+}
+
+impl RandomVariable for MatrixNormalParams {
+    type RestoreInfo = (usize, usize);
+
+    fn transform_vec(&self) -> (Vec<f64>, Self::RestoreInfo) {
+        let n = self.m.rows();
+        let p = self.m.cols();
+        (
+            [self.m.elems(), self.lu.0.elems(), self.lv.0.elems()].concat(),
+            (n, p),
+        )
+    }
+
+    fn len(&self) -> usize {
+        self.m.elems().len() + self.lu.0.elems().len() + self.lv.0.elems().len()
+    }
+
+    fn restore(v: &[f64], info: &Self::RestoreInfo) -> Result<Self, DistributionError> {
+        let (n, p) = *info;
+        let lu_len = n * (n + 1) / 2;
+        let lv_len = p * (p + 1) / 2;
+        if v.len() != n * p + lu_len + lv_len {
+            return Err(DistributionError::InvalidRestoreVector);
+        }
+        let m = Matrix::from(n, v[0..n * p].to_vec())?;
+        let lu = PPTRF(SymmetricPackedMatrix::from(n, v[n * p..n * p + lu_len].to_vec()).unwrap());
+        let lv =
+            PPTRF(SymmetricPackedMatrix::from(p, v[n * p + lu_len..v.len()].to_vec()).unwrap());
+
+        Self::new(m, lu, lv)
+    }
+}
@@ -0,0 +1,316 @@
+pub mod params;
+
+pub use params::*;
+
+use super::matrix_normal::tril_packed;
+use crate::{
+    ConditionDifferentiableDistribution, DependentJoint, Distribution, DistributionError,
+    IndependentJoint, RandomVariable, SamplableDistribution, ValueDifferentiableDistribution,
+};
+use opensrdk_linear_algebra::{pp::trf::PPTRF, *};
+use rand::prelude::*;
+use rand_distr::{ChiSquared as RandChiSquared, StandardNormal};
+use special::Gamma;
+use std::{ops::BitAnd, ops::Mul};
+
+/// Matrix t distribution
+#[derive(Clone, Debug)]
+pub struct MatrixT;
+
+#[derive(thiserror::Error, Debug)]
+pub enum MatrixTError {
+    #[error("'ν' must be positive")]
+    NuMustBePositive,
+}
+
+/// Intermediate values shared by the density and its gradients.
+struct MatrixTTerms {
+    /// `V^{-1} (X - M)^T`
+    v_inv_x_m_t: Matrix,
+    /// `L_S` under decomposition `S = U + (X - M) V^{-1} (X - M)^T`
+    ls: PPTRF,
+    /// `ln|I + U^{-1} (X - M) V^{-1} (X - M)^T|`
+    ln_det: f64,
+}
+
+impl MatrixTTerms {
+    fn new(x: &Matrix, theta: &MatrixTParams) -> Result<Self, DistributionError> {
+        let x_m = theta.matrix_normal().x_m(x)?;
+        let lu = theta.lu().0.to_mat();
+        let u = &lu * lu.t();
+        let v_inv_x_m_t = theta.lv().pptrs(x_m.t())?;
+        let s = u + &x_m * &v_inv_x_m_t;
+        let ls = SymmetricPackedMatrix::from_mat(&s).unwrap().pptrf()?;
+
+        // |I + U^{-1} (X - M) V^{-1} (X - M)^T| = |S| / |U|
+        let ln_det = ln_det(&ls.0.to_mat()) - ln_det(&lu);
+
+        Ok(Self {
+            v_inv_x_m_t,
+            ls,
+            ln_det,
+        })
+    }
+}
+
+fn ln_det(l: &Matrix) -> f64 {
+    (0..l.rows())
+        .into_iter()
+        .map(|i| 2.0 * l[(i, i)].ln())
+        .sum::<f64>()
+}
+
+/// `ln Γ_n(a)` without the constant `n (n - 1) ln π / 4`
+fn ln_multivariate_gamma(a: f64, n: usize) -> f64 {
+    (1..=n)
+        .into_iter()
+        .map(|j| (a + (1.0 - j as f64) / 2.0).ln_gamma().0)
+        .sum::<f64>()
+}
+
+impl Distribution for MatrixT {
+    type Value = Matrix;
+    type Condition = MatrixTParams;
+
+    /// The normalizer except for `π^{np/2}` is kept so that the kernel agrees with the gradients with respect to `U`, `V` and `ν`.
+    fn p_kernel(&self, x: &Self::Value, theta: &Self::Condition) -> Result<f64, DistributionError> {
+        let rows = x.rows();
+        let n = rows as f64;
+        let p = x.cols() as f64;
+        let nu = theta.nu();
+        let c = nu + n + p - 1.0;
+        let terms = MatrixTTerms::new(x, theta)?;
+
+        let ln_normalizer = ln_multivariate_gamma(c / 2.0, rows)
+            - ln_multivariate_gamma((nu + n - 1.0) / 2.0, rows)
+            - 0.5 * (p * ln_det(&theta.lu().0.to_mat()) + n * ln_det(&theta.lv().0.to_mat()));
+
+        Ok((ln_normalizer - c / 2.0 * terms.ln_det).exp())
+    }
+}
+
+impl<Rhs, TRhs> Mul<Rhs> for MatrixT
+where
+    Rhs: Distribution<Value = TRhs, Condition = MatrixTParams>,
+    TRhs: RandomVariable,
+{
+    type Output = IndependentJoint<Self, Rhs, Matrix, TRhs, MatrixTParams>;
+
+    fn mul(self, rhs: Rhs) -> Self::Output {
+        IndependentJoint::new(self, rhs)
+    }
+}
+
+impl<Rhs, URhs> BitAnd<Rhs> for MatrixT
+where
+    Rhs: Distribution<Value = MatrixTParams, Condition = URhs>,
+    URhs: RandomVariable,
+{
+    type Output = DependentJoint<Self, Rhs, Matrix, MatrixTParams, URhs>;
+
+    fn bitand(self, rhs: Rhs) -> Self::Output {
+        DependentJoint::new(self, rhs)
+    }
+}
+
+impl SamplableDistribution for MatrixT {
+    /// `X = M + L_U A^{-T} Z L_V^T` where `A A^T ~ W(ν + n - 1, I)` by Bartlett decomposition and `Z ~ MN(0, I, I)`.
+    fn sample(
+        &self,
+        theta: &Self::Condition,
+        rng: &mut dyn RngCore,
+    ) -> Result<Self::Value, DistributionError> {
+        let n = theta.m().rows();
+        let p = theta.m().cols();
+        let dof = theta.nu() + n as f64 - 1.0;
+
+        let mut a = vec![0.0; n * n];
+        for j in 0..n {
+            let chi_squared = match RandChiSquared::new(dof - j as f64) {
+                Ok(v) => Ok(v),
+                Err(e) => Err(DistributionError::Others(e.into())),
+            }?;
+            a[j + j * n] = rng.sample(chi_squared).sqrt();
+            for i in j + 1..n {
+                a[i + j * n] = rng.sample(StandardNormal);
+            }
+        }
+
+        // A^T Y = Z by back substitution
+        let mut y = (0..n * p)
+            .into_iter()
+            .map(|_| rng.sample(StandardNormal))
+            .collect::<Vec<f64>>();
+        for k in 0..p {
+            let yk = &mut y[k * n..(k + 1) * n];
+            for i in (0..n).rev() {
+                let s = (i + 1..n)
+                    .into_iter()
+                    .map(|j| a[j + i * n] * yk[j])
+                    .sum::<f64>();
+                yk[i] = (yk[i] - s) / a[i + i * n];
+            }
+        }
+        let y = Matrix::from(n, y)?;
+
+        let lu = theta.lu().0.to_mat();
+        let lv = theta.lv().0.to_mat();
+
+        Ok(theta.m().clone() + lu * y * lv.t())
+    }
+}
+
+impl ValueDifferentiableDistribution for MatrixT {
+    fn ln_diff_value(
+        &self,
+        x: &Self::Value,
+        theta: &Self::Condition,
+    ) -> Result<Vec<f64>, DistributionError> {
+        let n = x.rows() as f64;
+        let p = x.cols() as f64;
+        let nu = theta.nu();
+        let terms = MatrixTTerms::new(x, theta)?;
+
+        // -(ν + n + p - 1) S^{-1} (X - M) V^{-1}
+        let s_inv_x_m_v_inv = terms.ls.pptrs(terms.v_inv_x_m_t.t())?;
+        let f_x = -(nu + n + p - 1.0) * s_inv_x_m_v_inv;
+
+        Ok(f_x.vec())
+    }
+}
+
+impl ConditionDifferentiableDistribution for MatrixT {
+    /// Gradients with respect to `M`, packed `L_U`, packed `L_V` and `ν` in this order.
+    fn ln_diff_condition(
+        &self,
+        x: &Self::Value,
+        theta: &Self::Condition,
+    ) -> Result<Vec<f64>, DistributionError> {
+        let rows = x.rows();
+        let n = rows as f64;
+        let p = x.cols() as f64;
+        let nu = theta.nu();
+        let c = nu + n + p - 1.0;
+        let lu = theta.lu().0.to_mat();
+        let lv = theta.lv().0.to_mat();
+        let terms = MatrixTTerms::new(x, theta)?;
+
+        let s_inv_x_m_v_inv = terms.ls.pptrs(terms.v_inv_x_m_t.t())?;
+
+        let f_m = c * s_inv_x_m_v_inv.clone();
+
+        // -(ν + n + p - 1) S^{-1} L_U + (ν + n - 1) diag(L_U)^{-1}
+        let f_lu_mat = -c * terms.ls.pptrs(lu.clone())?;
+        let f_lu = tril_packed(&f_lu_mat, &lu, p - c);
+
+        // (ν + n + p - 1) V^{-1} (X - M)^T S^{-1} (X - M) V^{-1} L_V - n diag(L_V)^{-1}
+        let f_lv_mat = c * (&terms.v_inv_x_m_t * &s_inv_x_m_v_inv * &lv);
+        let f_lv = tril_packed(&f_lv_mat, &lv, n);
+
+        let f_nu = 0.5
+            * (1..=rows)
+                .into_iter()
+                .map(|j| {
+                    let j = j as f64;
+                    ((nu + n + p - j) / 2.0).digamma() - ((nu + n - j) / 2.0).digamma()
+                })
+                .sum::<f64>()
+            - 0.5 * terms.ln_det;
+
+        Ok([f_m.vec(), f_lu, f_lv, vec![f_nu]].concat())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ConditionDifferentiableDistribution, Distribution, MatrixT, MatrixTParams, RandomVariable,
+        SamplableDistribution, ValueDifferentiableDistribution,
+    };
+    use opensrdk_linear_algebra::{pp::trf::PPTRF, *};
+    use rand::prelude::*;
+
+    fn params() -> MatrixTParams {
+        let m = mat!(
+            0.0, 1.0;
+            2.0, 3.0;
+            4.0, 5.0
+        );
+        let lu = SymmetricPackedMatrix::from_mat(&mat!(
+            1.0, 0.0, 0.0;
+            0.5, 2.0, 0.0;
+            0.2, 0.3, 1.5
+        ))
+        .unwrap();
+        let lv = SymmetricPackedMatrix::from_mat(&mat!(
+            1.0, 0.0;
+            0.4, 0.8
+        ))
+        .unwrap();
+
+        MatrixTParams::new(3.0, m, PPTRF(lu), PPTRF(lv)).unwrap()
+    }
+
+    #[test]
+    fn it_works() {
+        let matrix_t = MatrixT;
+        let mut rng = StdRng::from_seed([1; 32]);
+
+        let x = matrix_t.sample(&params(), &mut rng).unwrap();
+
+        assert_eq!(x.rows(), 3);
+        assert_eq!(x.cols(), 2);
+    }
+
+    #[test]
+    fn it_works2() {
+        let matrix_t = MatrixT;
+        let theta = params();
+        let x = mat!(
+            0.5, 1.0;
+            1.0, 2.0;
+            3.0, 6.0
+        );
+
+        let f = matrix_t.ln_diff_value(&x, &theta).unwrap();
+        let h = 1e-6;
+        for i in 0..f.len() {
+            let mut elems = x.elems().to_vec();
+            elems[i] += h;
+            let x_h = Matrix::from(3, elems).unwrap();
+            let numerical = (matrix_t.p_kernel(&x_h, &theta).unwrap().ln()
+                - matrix_t.p_kernel(&x, &theta).unwrap().ln())
+                / h;
+            assert!((f[i] - numerical).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn it_works_3() {
+        let matrix_t = MatrixT;
+        let x = mat!(
+            0.5, 1.0;
+            1.0, 2.0;
+            3.0, 6.0
+        );
+
+        let theta = params();
+
+        let f = matrix_t.ln_diff_condition(&x, &theta).unwrap();
+        assert_eq!(f.len(), 6 + 6 + 3 + 1);
+
+        let ln_p = |theta: &MatrixTParams| matrix_t.p_kernel(&x, theta).unwrap().ln();
+        let (v, info) = theta.transform_vec();
+        let h = 1e-6;
+        for k in 0..v.len() {
+            let mut v_plus = v.clone();
+            v_plus[k] += h;
+            let mut v_minus = v.clone();
+            v_minus[k] -= h;
+            let numerical = (ln_p(&MatrixTParams::restore(&v_plus, &info).unwrap())
+                - ln_p(&MatrixTParams::restore(&v_minus, &info).unwrap()))
+                / (2.0 * h);
+            assert!((f[k] - numerical).abs() < 1e-5);
+        }
+    }
+}
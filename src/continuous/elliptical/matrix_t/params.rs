@@ -0,0 +1,70 @@
+use crate::{DistributionError, MatrixNormalParams, MatrixTError, RandomVariable};
+use opensrdk_linear_algebra::{pp::trf::PPTRF, *};
+
+#[derive(Clone, Debug)]
+pub struct MatrixTParams {
+    nu: f64,
+    matrix_normal: MatrixNormalParams,
+}
+
+impl MatrixTParams {
+    /// # Matrix t
+    /// - `nu`: Degrees of freedom
+    /// - `m`: Mean matrix `n×p`
+    /// - `lu`: `L_U` under decomposition `U = L_U * L_U^T` of the row scale `n×n`
+    /// - `lv`: `L_V` under decomposition `V = L_V * L_V^T` of the column scale `p×p`
+    pub fn new(nu: f64, m: Matrix, lu: PPTRF, lv: PPTRF) -> Result<Self, DistributionError> {
+        if nu <= 0.0 {
+            return Err(DistributionError::InvalidParameters(
+                MatrixTError::NuMustBePositive.into(),
+            ));
+        }
+        let matrix_normal = MatrixNormalParams::new(m, lu, lv)?;
+
+        Ok(Self { nu, matrix_normal })
+    }
+
+    pub fn nu(&self) -> f64 {
+        self.nu
+    }
+
+    pub fn m(&self) -> &Matrix {
+        self.matrix_normal.m()
+    }
+
+    pub fn lu(&self) -> &PPTRF {
+        self.matrix_normal.lu()
+    }
+
+    pub fn lv(&self) -> &PPTRF {
+        self.matrix_normal.lv()
+    }
+
+    pub fn matrix_normal(&self) -> &MatrixNormalParams {
+        &self.matrix_normal
+    }
+}
+
+impl RandomVariable for MatrixTParams {
+    type RestoreInfo = (usize, usize);
+
+    fn transform_vec(&self) -> (Vec<f64>, Self::RestoreInfo) {
+        let (v, info) = self.matrix_normal.transform_vec();
+        ([v, vec![self.nu]].concat(), info)
+    }
+
+    fn len(&self) -> usize {
+        self.matrix_normal.len() + 1usize
+    }
+
+    fn restore(v: &[f64], info: &Self::RestoreInfo) -> Result<Self, DistributionError> {
+        if v.len() == 0 {
+            return Err(DistributionError::InvalidRestoreVector);
+        }
+        let matrix_normal = MatrixNormalParams::restore(&v[0..v.len() - 1], info)?;
+        let nu = v[v.len() - 1];
+        let (m, lu, lv) = matrix_normal.eject();
+
+        Self::new(nu, m, lu, lv)
+    }
+}
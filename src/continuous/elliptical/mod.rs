@@ -1,9 +1,13 @@
 pub mod cauchy;
+pub mod matrix_normal;
+pub mod matrix_t;
 pub mod normal;
 pub mod params;
 pub mod student_t;
 
 pub use cauchy::*;
+pub use matrix_normal::*;
+pub use matrix_t::*;
 pub use normal::*;
 pub use params::*;
 pub use student_t::*;
@@ -12,4 +16,8 @@ pub use student_t::*;
 pub enum EllipticalError {
     #[error("dimension mismatch (Ellptical)")]
     DimensionMismatch,
+    #[error("diagonal elements must be positive")]
+    DiagonalMustBePositive,
+    #[error("factor must be lower triangular")]
+    NotLowerTriangular,
 }
@@ -1,7 +1,9 @@
 use crate::nonparametric::ExactEllipticalProcessParams;
 use crate::{
-    ConditionDifferentiableDistribution, DependentJoint, Distribution, ExactEllipticalParams,
-    IndependentJoint, RandomVariable, SamplableDistribution, ValueDifferentiableDistribution,
+    tril_packed, ConditionDifferentiableDistribution, DependentJoint, DiagonalEllipticalParams,
+    Distribution, ExactEllipticalParams, IndependentJoint, LowRankEllipticalParams,
+    PrecisionEllipticalParams, RandomVariable, SamplableDistribution,
+    SparsePrecisionEllipticalParams, ValueDifferentiableDistribution,
 };
 use crate::{DistributionError, EllipticalParams};
 use opensrdk_kernel_method::PositiveDefiniteKernel;
@@ -43,10 +45,11 @@ where
 
     fn p_kernel(&self, x: &Self::Value, theta: &Self::Condition) -> Result<f64, DistributionError> {
         let x_mu = theta.x_mu(x)?.col_mat();
-        let n = x.len();
 
-        // For preventing the result from being zero, dividing e^n
-        Ok((-1.0 / 2.0 * (x_mu.t() * theta.sigma_inv_mul(x_mu)?)[(0, 0)] / (n as f64).exp()).exp())
+        let d = (x_mu.t() * theta.sigma_inv_mul(x_mu)?)[(0, 0)];
+
+        // ln|Sigma| is kept so that the kernel agrees with the gradients with respect to Sigma
+        Ok((-1.0 / 2.0 * (d + theta.ln_det_sigma())).exp())
     }
 }
 
@@ -103,10 +106,8 @@ where
         x: &Self::Value,
         theta: &Self::Condition,
     ) -> Result<Vec<f64>, DistributionError> {
-        let mu_mat = theta.x_mu(x)?.row_mat();
-        let x_mat = x.clone().row_mat();
-        let x_mu_mat = x_mat - mu_mat;
-        let f_x = theta.sigma_inv_mul(x_mu_mat).unwrap();
+        let x_mu_mat = theta.x_mu(x)?.col_mat();
+        let f_x = -1.0 * theta.sigma_inv_mul(x_mu_mat)?;
         Ok(f_x.vec())
     }
 }
@@ -145,6 +146,84 @@ impl ConditionDifferentiableDistribution for MultivariateNormal {
     }
 }
 
+impl ConditionDifferentiableDistribution for MultivariateNormal<DiagonalEllipticalParams> {
+    /// Gradients with respect to `μ` and `d` in this order.
+    fn ln_diff_condition(
+        &self,
+        x: &Self::Value,
+        theta: &Self::Condition,
+    ) -> Result<Vec<f64>, DistributionError> {
+        let x_mu_mat = theta.x_mu(x)?.col_mat();
+        let f_mu = theta.sigma_inv_mul(x_mu_mat)?.vec();
+        let f_d = theta.ln_diff_sigma_params(&f_mu, 1.0);
+
+        Ok([f_mu, f_d].concat())
+    }
+}
+
+impl ConditionDifferentiableDistribution for MultivariateNormal<LowRankEllipticalParams> {
+    /// Gradients with respect to `μ`, `d` and `W` in this order.
+    fn ln_diff_condition(
+        &self,
+        x: &Self::Value,
+        theta: &Self::Condition,
+    ) -> Result<Vec<f64>, DistributionError> {
+        let x_mu_mat = theta.x_mu(x)?.col_mat();
+        let f_mu = theta.sigma_inv_mul(x_mu_mat)?.vec();
+        let f_sigma = theta.ln_diff_sigma_params(&f_mu, 1.0)?;
+
+        Ok([f_mu, f_sigma].concat())
+    }
+}
+
+impl ConditionDifferentiableDistribution for MultivariateNormal<PrecisionEllipticalParams> {
+    /// Gradients with respect to `μ` and packed `L` of the precision in this order.
+    fn ln_diff_condition(
+        &self,
+        x: &Self::Value,
+        theta: &Self::Condition,
+    ) -> Result<Vec<f64>, DistributionError> {
+        let x_mu_mat = theta.x_mu(x)?.col_mat();
+        let llambda = theta.llambda().0.to_mat();
+        let f_mu = theta.sigma_inv_mul(x_mu_mat.clone())?;
+
+        // -(x - μ) (x - μ)^T L + diag(L)^{-1}
+        let f_llambda_mat = -1.0 * (&x_mu_mat * (x_mu_mat.t() * &llambda));
+        let f_llambda = tril_packed(&f_llambda_mat, &llambda, -1.0);
+
+        Ok([f_mu.vec(), f_llambda].concat())
+    }
+}
+
+impl ConditionDifferentiableDistribution for MultivariateNormal<SparsePrecisionEllipticalParams> {
+    /// Gradients with respect to `μ` and the non-zero elements of `L` of the precision in this order.
+    fn ln_diff_condition(
+        &self,
+        x: &Self::Value,
+        theta: &Self::Condition,
+    ) -> Result<Vec<f64>, DistributionError> {
+        let x_mu = theta.x_mu(x)?;
+        let l_t_x_mu = theta.l_t_mul(&x_mu);
+        let f_mu = theta.l_mul(&l_t_x_mu);
+
+        // -(x - μ)_i (L^T (x - μ))_j + δ_ij / L_ii
+        let f_llambda = theta
+            .pattern()
+            .iter()
+            .map(|&(i, j)| {
+                let f = -x_mu[i] * l_t_x_mu[j];
+                if i == j {
+                    f + 1.0 / theta.llambda()[(i, i)]
+                } else {
+                    f
+                }
+            })
+            .collect::<Vec<_>>();
+
+        Ok([f_mu, f_llambda].concat())
+    }
+}
+
 impl<K, T> ConditionDifferentiableDistribution
     for MultivariateNormal<ExactEllipticalProcessParams<K, T>>
 where
@@ -194,8 +273,10 @@ where
 #[cfg(test)]
 mod tests {
     use crate::{
-        ConditionDifferentiableDistribution, Distribution, ExactMultivariateNormalParams,
-        MultivariateNormal, SamplableDistribution, ValueDifferentiableDistribution,
+        ConditionDifferentiableDistribution, DiagonalMultivariateNormalParams, Distribution,
+        ExactMultivariateNormalParams, LowRankMultivariateNormalParams, MultivariateNormal,
+        PrecisionMultivariateNormalParams, RandomVariable, SamplableDistribution,
+        SparsePrecisionMultivariateNormalParams, ValueDifferentiableDistribution,
     };
     use opensrdk_linear_algebra::{pp::trf::PPTRF, *};
     use rand::prelude::*;
@@ -299,4 +380,111 @@ mod tests {
             .unwrap();
         println!("{:#?}", f);
     }
+
+    #[test]
+    fn it_works4() {
+        let normal = MultivariateNormal::new();
+        let mut rng = StdRng::from_seed([1; 32]);
+
+        let theta = DiagonalMultivariateNormalParams::new(vec![0.0, 1.0, 2.0], vec![1.0, 4.0, 0.5])
+            .unwrap();
+        let x = normal.sample(&theta, &mut rng).unwrap();
+        assert_eq!(x.len(), 3);
+
+        let f = normal.ln_diff_condition(&x, &theta).unwrap();
+        assert_eq!(f.len(), 6);
+
+        let ln_p =
+            |theta: &DiagonalMultivariateNormalParams| normal.p_kernel(&x, theta).unwrap().ln();
+        let (v, info) = theta.transform_vec();
+        let h = 1e-6;
+        for k in 0..v.len() {
+            let mut v_plus = v.clone();
+            v_plus[k] += h;
+            let mut v_minus = v.clone();
+            v_minus[k] -= h;
+            let numerical =
+                (ln_p(&DiagonalMultivariateNormalParams::restore(&v_plus, &info).unwrap())
+                    - ln_p(&DiagonalMultivariateNormalParams::restore(&v_minus, &info).unwrap()))
+                    / (2.0 * h);
+            assert!((f[k] - numerical).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn it_works5() {
+        let normal = MultivariateNormal::new();
+        let mut rng = StdRng::from_seed([1; 32]);
+
+        let w = mat!(
+            1.0, 0.0;
+            0.5, 1.0;
+            0.2, 0.3
+        );
+        let theta =
+            LowRankMultivariateNormalParams::new(vec![0.0, 1.0, 2.0], vec![1.0, 2.0, 0.5], w)
+                .unwrap();
+        let x = normal.sample(&theta, &mut rng).unwrap();
+        assert_eq!(x.len(), 3);
+
+        let f = normal.ln_diff_condition(&x, &theta).unwrap();
+        assert_eq!(f.len(), 3 + 3 + 6);
+
+        let ln_p =
+            |theta: &LowRankMultivariateNormalParams| normal.p_kernel(&x, theta).unwrap().ln();
+        let (v, info) = theta.transform_vec();
+        let h = 1e-6;
+        for k in 0..v.len() {
+            let mut v_plus = v.clone();
+            v_plus[k] += h;
+            let mut v_minus = v.clone();
+            v_minus[k] -= h;
+            let numerical =
+                (ln_p(&LowRankMultivariateNormalParams::restore(&v_plus, &info).unwrap())
+                    - ln_p(&LowRankMultivariateNormalParams::restore(&v_minus, &info).unwrap()))
+                    / (2.0 * h);
+            assert!((f[k] - numerical).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn it_works6() {
+        let normal = MultivariateNormal::new();
+        let mut rng = StdRng::from_seed([1; 32]);
+
+        let n = 4;
+        let mut llambda = SparseMatrix::new(n, n);
+        for i in 0..n {
+            llambda[(i, i)] = 2.0;
+            if i + 1 < n {
+                llambda[(i + 1, i)] = -1.0;
+            }
+        }
+        let theta = SparsePrecisionMultivariateNormalParams::new(vec![0.0; n], llambda).unwrap();
+        let x = normal.sample(&theta, &mut rng).unwrap();
+        assert_eq!(x.len(), n);
+
+        let f = normal.ln_diff_condition(&x, &theta).unwrap();
+        assert_eq!(f.len(), n + 2 * n - 1);
+
+        let dense = PrecisionMultivariateNormalParams::new(
+            vec![0.0; n],
+            PPTRF(
+                SymmetricPackedMatrix::from_mat(&mat!(
+                     2.0,  0.0,  0.0, 0.0;
+                    -1.0,  2.0,  0.0, 0.0;
+                     0.0, -1.0,  2.0, 0.0;
+                     0.0,  0.0, -1.0, 2.0
+                ))
+                .unwrap(),
+            ),
+        )
+        .unwrap();
+        let f_dense = MultivariateNormal::new()
+            .ln_diff_condition(&x, &dense)
+            .unwrap();
+        for i in 0..n {
+            assert!((f[i] - f_dense[i]).abs() < 1e-10);
+        }
+    }
 }
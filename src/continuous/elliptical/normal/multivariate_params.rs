@@ -1,3 +1,10 @@
-use crate::ExactEllipticalParams;
+use crate::{
+    DiagonalEllipticalParams, ExactEllipticalParams, LowRankEllipticalParams,
+    PrecisionEllipticalParams, SparsePrecisionEllipticalParams,
+};
 
 pub type ExactMultivariateNormalParams = ExactEllipticalParams;
+pub type DiagonalMultivariateNormalParams = DiagonalEllipticalParams;
+pub type LowRankMultivariateNormalParams = LowRankEllipticalParams;
+pub type PrecisionMultivariateNormalParams = PrecisionEllipticalParams;
+pub type SparsePrecisionMultivariateNormalParams = SparsePrecisionEllipticalParams;
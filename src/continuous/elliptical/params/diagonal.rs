@@ -0,0 +1,145 @@
+use crate::{DistributionError, EllipticalError, EllipticalParams, RandomVariable};
+use opensrdk_linear_algebra::*;
+
+#[derive(Clone, Debug)]
+pub struct DiagonalEllipticalParams {
+    mu: Vec<f64>,
+    d: Vec<f64>,
+}
+
+impl DiagonalEllipticalParams {
+    /// # Diagonal
+    /// `Sigma = diag(d)`
+    ///
+    /// - `mu`: Mean
+    /// - `d`: Variances, which must be positive
+    pub fn new(mu: Vec<f64>, d: Vec<f64>) -> Result<Self, DistributionError> {
+        if mu.len() != d.len() {
+            return Err(DistributionError::InvalidParameters(
+                EllipticalError::DimensionMismatch.into(),
+            ));
+        }
+        if d.iter().any(|&di| di <= 0.0) {
+            return Err(DistributionError::InvalidParameters(
+                EllipticalError::DiagonalMustBePositive.into(),
+            ));
+        }
+
+        Ok(Self { mu, d })
+    }
+
+    pub fn mu(&self) -> &Vec<f64> {
+        &self.mu
+    }
+
+    pub fn d(&self) -> &Vec<f64> {
+        &self.d
+    }
+
+    pub fn eject(self) -> (Vec<f64>, Vec<f64>) {
+        (self.mu, self.d)
+    }
+
+    /// Gradient of `-ln|Sigma| / 2 - w (x - mu)^T Sigma^{-1} (x - mu) / 2` with respect to `d`, where `a = Sigma^{-1} (x - mu)`.
+    pub(crate) fn ln_diff_sigma_params(&self, a: &[f64], w: f64) -> Vec<f64> {
+        a.iter()
+            .zip(self.d.iter())
+            .map(|(&ai, &di)| 0.5 * (w * ai * ai - 1.0 / di))
+            .collect()
+    }
+}
+
+/// `diag(d)^{-1} v`
+pub(crate) fn diag_inv_mul(d: &[f64], v: Matrix) -> Result<Matrix, DistributionError> {
+    let n = d.len();
+    if v.rows() != n {
+        return Err(DistributionError::InvalidParameters(
+            EllipticalError::DimensionMismatch.into(),
+        ));
+    }
+    let elems = v
+        .vec()
+        .into_iter()
+        .enumerate()
+        .map(|(i, vi)| vi / d[i % n])
+        .collect::<Vec<_>>();
+
+    Ok(Matrix::from(n, elems)?)
+}
+
+impl RandomVariable for DiagonalEllipticalParams {
+    type RestoreInfo = usize;
+
+    fn transform_vec(&self) -> (Vec<f64>, Self::RestoreInfo) {
+        let n = self.mu.len();
+        ([self.mu(), self.d()].concat(), n)
+    }
+
+    fn len(&self) -> usize {
+        2 * self.mu.len()
+    }
+
+    fn restore(v: &[f64], info: &Self::RestoreInfo) -> Result<Self, DistributionError> {
+        let n = *info;
+        if v.len() != 2 * n {
+            return Err(DistributionError::InvalidRestoreVector);
+        }
+        let mu = v[0..n].to_vec();
+        let d = v[n..v.len()].to_vec();
+        Self::new(mu, d)
+    }
+}
+
+impl EllipticalParams for DiagonalEllipticalParams {
+    fn mu(&self) -> &Vec<f64> {
+        self.mu()
+    }
+
+    fn sigma_inv_mul(&self, v: Matrix) -> Result<Matrix, DistributionError> {
+        diag_inv_mul(&self.d, v)
+    }
+
+    /// `ln|Sigma|`
+    fn ln_det_sigma(&self) -> f64 {
+        self.d.iter().map(|di| di.ln()).sum::<f64>()
+    }
+
+    fn lsigma_cols(&self) -> usize {
+        self.d.len()
+    }
+
+    fn sample(&self, z: Vec<f64>) -> Result<Vec<f64>, DistributionError> {
+        if z.len() != self.d.len() {
+            return Err(DistributionError::InvalidParameters(
+                EllipticalError::DimensionMismatch.into(),
+            ));
+        }
+
+        Ok(self
+            .mu
+            .iter()
+            .zip(self.d.iter())
+            .zip(z.iter())
+            .map(|((&mui, &di), &zi)| mui + di.sqrt() * zi)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DiagonalEllipticalParams, EllipticalParams};
+    use opensrdk_linear_algebra::*;
+
+    #[test]
+    fn it_works() {
+        let theta =
+            DiagonalEllipticalParams::new(vec![0.0, 1.0, 2.0], vec![1.0, 4.0, 0.5]).unwrap();
+
+        let v = vec![1.0, 2.0, 3.0].col_mat();
+        let result = theta.sigma_inv_mul(v).unwrap();
+        assert_eq!(result.vec(), vec![1.0, 0.5, 6.0]);
+
+        let ln_det = (1.0f64 * 4.0 * 0.5).ln();
+        assert!((theta.ln_det_sigma() - ln_det).abs() < 1e-12);
+    }
+}
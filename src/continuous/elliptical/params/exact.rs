@@ -72,6 +72,16 @@ impl EllipticalParams for ExactEllipticalParams {
         Ok(self.lsigma.pptrs(v)?)
     }
 
+    /// `ln|Sigma| = 2 ln|L|`
+    fn ln_det_sigma(&self) -> f64 {
+        let l = self.lsigma.0.to_mat();
+
+        (0..l.rows())
+            .into_iter()
+            .map(|i| 2.0 * l[(i, i)].ln())
+            .sum::<f64>()
+    }
+
     fn lsigma_cols(&self) -> usize {
         self.lsigma.0.dim()
     }
@@ -0,0 +1,202 @@
+use crate::{diag_inv_mul, DistributionError, EllipticalError, EllipticalParams, RandomVariable};
+use opensrdk_linear_algebra::{pp::trf::PPTRF, *};
+
+#[derive(Clone, Debug)]
+pub struct LowRankEllipticalParams {
+    mu: Vec<f64>,
+    d: Vec<f64>,
+    w: Matrix,
+    lcapacitance: PPTRF,
+}
+
+impl LowRankEllipticalParams {
+    /// # Low rank plus diagonal
+    /// `Sigma = diag(d) + W * W^T`
+    ///
+    /// - `mu`: Mean `n`
+    /// - `d`: Diagonal variances `n`, which must be positive
+    /// - `w`: Factor loadings `n×k`
+    pub fn new(mu: Vec<f64>, d: Vec<f64>, w: Matrix) -> Result<Self, DistributionError> {
+        let n = mu.len();
+        if n != d.len() || n != w.rows() {
+            return Err(DistributionError::InvalidParameters(
+                EllipticalError::DimensionMismatch.into(),
+            ));
+        }
+        if d.iter().any(|&di| di <= 0.0) {
+            return Err(DistributionError::InvalidParameters(
+                EllipticalError::DiagonalMustBePositive.into(),
+            ));
+        }
+
+        // C = I + W^T D^{-1} W
+        let k = w.cols();
+        let d_inv_w = diag_inv_mul(&d, w.clone())?;
+        let capacitance = DiagonalMatrix::<f64>::identity(k).mat() + w.t() * d_inv_w;
+        let lcapacitance = SymmetricPackedMatrix::from_mat(&capacitance)
+            .unwrap()
+            .pptrf()?;
+
+        Ok(Self {
+            mu,
+            d,
+            w,
+            lcapacitance,
+        })
+    }
+
+    pub fn mu(&self) -> &Vec<f64> {
+        &self.mu
+    }
+
+    pub fn d(&self) -> &Vec<f64> {
+        &self.d
+    }
+
+    pub fn w(&self) -> &Matrix {
+        &self.w
+    }
+
+    pub fn eject(self) -> (Vec<f64>, Vec<f64>, Matrix) {
+        (self.mu, self.d, self.w)
+    }
+
+    /// Gradient of `-ln|Sigma| / 2 - w (x - mu)^T Sigma^{-1} (x - mu) / 2` with respect to `d` and `W`, where `a = Sigma^{-1} (x - mu)`.
+    pub(crate) fn ln_diff_sigma_params(
+        &self,
+        a: &[f64],
+        w: f64,
+    ) -> Result<Vec<f64>, DistributionError> {
+        let n = self.d.len();
+
+        // diag(Sigma^{-1}) = D^{-1} - diag(D^{-1} W C^{-1} W^T D^{-1})
+        let d_inv_w = diag_inv_mul(&self.d, self.w.clone())?;
+        let c_inv_w_t_d_inv = self.lcapacitance.pptrs(d_inv_w.t())?;
+        let sigma_inv_diag = (0..n)
+            .into_iter()
+            .map(|i| {
+                1.0 / self.d[i]
+                    - (0..self.w.cols())
+                        .into_iter()
+                        .map(|j| d_inv_w[(i, j)] * c_inv_w_t_d_inv[(j, i)])
+                        .sum::<f64>()
+            })
+            .collect::<Vec<_>>();
+        let f_d = a
+            .iter()
+            .zip(sigma_inv_diag.iter())
+            .map(|(&ai, &si)| 0.5 * (w * ai * ai - si))
+            .collect::<Vec<_>>();
+
+        // (w a a^T - Sigma^{-1}) W
+        let a = a.to_vec().col_mat();
+        let a_t_w = a.t() * &self.w;
+        let f_w = w * (a * a_t_w) - self.sigma_inv_mul(self.w.clone())?;
+
+        Ok([f_d, f_w.vec()].concat())
+    }
+}
+
+impl RandomVariable for LowRankEllipticalParams {
+    type RestoreInfo = (usize, usize);
+
+    fn transform_vec(&self) -> (Vec<f64>, Self::RestoreInfo) {
+        let n = self.mu.len();
+        let k = self.w.cols();
+        ([self.mu(), self.d(), self.w.elems()].concat(), (n, k))
+    }
+
+    fn len(&self) -> usize {
+        2 * self.mu.len() + self.w.elems().len()
+    }
+
+    fn restore(v: &[f64], info: &Self::RestoreInfo) -> Result<Self, DistributionError> {
+        let (n, k) = *info;
+        if v.len() != 2 * n + n * k {
+            return Err(DistributionError::InvalidRestoreVector);
+        }
+        let mu = v[0..n].to_vec();
+        let d = v[n..2 * n].to_vec();
+        let w = Matrix::from(n, v[2 * n..v.len()].to_vec())?;
+        Self::new(mu, d, w)
+    }
+}
+
+impl EllipticalParams for LowRankEllipticalParams {
+    fn mu(&self) -> &Vec<f64> {
+        self.mu()
+    }
+
+    /// `Sigma^{-1} v = D^{-1} v - D^{-1} W C^{-1} W^T D^{-1} v` by the Woodbury identity.
+    fn sigma_inv_mul(&self, v: Matrix) -> Result<Matrix, DistributionError> {
+        let d_inv_v = diag_inv_mul(&self.d, v)?;
+        let c_inv_w_t_d_inv_v = self.lcapacitance.pptrs(self.w.t() * &d_inv_v)?;
+        let correction = diag_inv_mul(&self.d, &self.w * c_inv_w_t_d_inv_v)?;
+
+        Ok(d_inv_v - correction)
+    }
+
+    /// `ln|Sigma| = ln|D| + ln|I + W^T D^{-1} W|` by the matrix determinant lemma.
+    fn ln_det_sigma(&self) -> f64 {
+        let lc = self.lcapacitance.0.to_mat();
+        let ln_det_c = (0..lc.rows())
+            .into_iter()
+            .map(|i| 2.0 * lc[(i, i)].ln())
+            .sum::<f64>();
+
+        self.d.iter().map(|di| di.ln()).sum::<f64>() + ln_det_c
+    }
+
+    /// `z` is split into `n` elements for `D` and `k` elements for `W`.
+    fn lsigma_cols(&self) -> usize {
+        self.d.len() + self.w.cols()
+    }
+
+    fn sample(&self, z: Vec<f64>) -> Result<Vec<f64>, DistributionError> {
+        let n = self.d.len();
+        if z.len() != self.lsigma_cols() {
+            return Err(DistributionError::InvalidParameters(
+                EllipticalError::DimensionMismatch.into(),
+            ));
+        }
+        let wz = (&self.w * z[n..z.len()].to_vec().col_mat()).vec();
+
+        Ok((0..n)
+            .into_iter()
+            .map(|i| self.mu[i] + self.d[i].sqrt() * z[i] + wz[i])
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{EllipticalParams, LowRankEllipticalParams};
+    use opensrdk_linear_algebra::*;
+
+    #[test]
+    fn it_works() {
+        let d = vec![1.0, 2.0, 0.5];
+        let w = mat!(
+            1.0, 0.0;
+            0.5, 1.0;
+            0.2, 0.3
+        );
+        let theta =
+            LowRankEllipticalParams::new(vec![0.0, 1.0, 2.0], d.clone(), w.clone()).unwrap();
+
+        let sigma = d.clone().diag().mat() + &w * w.t();
+        let v = vec![1.0, 2.0, 3.0].col_mat();
+        let result = theta.sigma_inv_mul(v.clone()).unwrap();
+        let expected = sigma.clone().potrf().unwrap().potrs(v).unwrap();
+        for (r, e) in result.vec().iter().zip(expected.vec().iter()) {
+            assert!((r - e).abs() < 1e-10);
+        }
+
+        let lsigma = sigma.potrf().unwrap().0;
+        let ln_det = (0..3)
+            .into_iter()
+            .map(|i| 2.0 * lsigma[(i, i)].ln())
+            .sum::<f64>();
+        assert!((theta.ln_det_sigma() - ln_det).abs() < 1e-10);
+    }
+}
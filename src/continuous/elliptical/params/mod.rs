@@ -1,6 +1,14 @@
+pub mod diagonal;
 pub mod exact;
+pub mod low_rank;
+pub mod precision;
+pub mod sparse_precision;
 
+pub use diagonal::*;
 pub use exact::*;
+pub use low_rank::*;
+pub use precision::*;
+pub use sparse_precision::*;
 
 use crate::{DistributionError, EllipticalError, RandomVariable};
 use opensrdk_linear_algebra::{pp::trf::PPTRF, *};
@@ -30,6 +38,9 @@ pub trait EllipticalParams: RandomVariable {
 
     fn sigma_inv_mul(&self, v: Matrix) -> Result<Matrix, DistributionError>;
 
+    /// `ln|Sigma|`
+    fn ln_det_sigma(&self) -> f64;
+
     fn lsigma_cols(&self) -> usize;
     fn sample(&self, z: Vec<f64>) -> Result<Vec<f64>, DistributionError>;
 }
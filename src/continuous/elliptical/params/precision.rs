@@ -0,0 +1,124 @@
+use crate::{DistributionError, EllipticalError, EllipticalParams, RandomVariable};
+use opensrdk_linear_algebra::{pp::trf::PPTRF, *};
+
+#[derive(Clone, Debug)]
+pub struct PrecisionEllipticalParams {
+    mu: Vec<f64>,
+    llambda: PPTRF,
+}
+
+impl PrecisionEllipticalParams {
+    /// # Precision
+    /// `L` is needed as second argument under decomposition `Lambda = Sigma^{-1} = L * L^T`
+    /// l_lambda = lambda.pptrf()?;
+    pub fn new(mu: Vec<f64>, llambda: PPTRF) -> Result<Self, DistributionError> {
+        let p = mu.len();
+        if p != llambda.0.dim() {
+            return Err(DistributionError::InvalidParameters(
+                EllipticalError::DimensionMismatch.into(),
+            ));
+        }
+
+        Ok(Self { mu, llambda })
+    }
+
+    pub fn mu(&self) -> &Vec<f64> {
+        &self.mu
+    }
+
+    pub fn llambda(&self) -> &PPTRF {
+        &self.llambda
+    }
+
+    pub fn eject(self) -> (Vec<f64>, PPTRF) {
+        (self.mu, self.llambda)
+    }
+}
+
+impl RandomVariable for PrecisionEllipticalParams {
+    type RestoreInfo = usize;
+
+    fn transform_vec(&self) -> (Vec<f64>, Self::RestoreInfo) {
+        let n = self.mu.len();
+        ([self.mu(), self.llambda.0.elems()].concat(), n)
+    }
+
+    fn len(&self) -> usize {
+        self.mu.len() + self.llambda.0.elems().len()
+    }
+
+    fn restore(v: &[f64], info: &Self::RestoreInfo) -> Result<Self, DistributionError> {
+        if v.len() != info + info * (info + 1) / 2 {
+            return Err(DistributionError::InvalidRestoreVector);
+        }
+        let n = *info;
+        let mu = v[0..n].to_vec();
+        let llambda = PPTRF(SymmetricPackedMatrix::from(n, v[n..v.len()].to_vec()).unwrap());
+        Self::new(mu, llambda)
+    }
+}
+
+impl EllipticalParams for PrecisionEllipticalParams {
+    fn mu(&self) -> &Vec<f64> {
+        self.mu()
+    }
+
+    /// `Sigma^{-1} v = L * L^T * v`
+    fn sigma_inv_mul(&self, v: Matrix) -> Result<Matrix, DistributionError> {
+        let l = self.llambda.0.to_mat();
+
+        Ok(&l * (l.t() * v))
+    }
+
+    /// `ln|Sigma| = -2 ln|L|`
+    fn ln_det_sigma(&self) -> f64 {
+        let l = self.llambda.0.to_mat();
+
+        -(0..l.rows())
+            .into_iter()
+            .map(|i| 2.0 * l[(i, i)].ln())
+            .sum::<f64>()
+    }
+
+    fn lsigma_cols(&self) -> usize {
+        self.llambda.0.dim()
+    }
+
+    /// `x = mu + L^{-T} z`, where `L^{-T} z = Lambda^{-1} L z`
+    fn sample(&self, z: Vec<f64>) -> Result<Vec<f64>, DistributionError> {
+        let l = self.llambda.0.to_mat();
+        let l_inv_t_z = self.llambda.pptrs(l * z.col_mat())?;
+
+        Ok((self.mu.clone().col_mat() + l_inv_t_z).vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{EllipticalParams, PrecisionEllipticalParams};
+    use opensrdk_linear_algebra::{pp::trf::PPTRF, *};
+
+    #[test]
+    fn it_works() {
+        let llambda = mat!(
+            2.0, 0.0, 0.0;
+            0.5, 1.0, 0.0;
+            0.2, 0.3, 1.5
+        );
+        let theta = PrecisionEllipticalParams::new(
+            vec![0.0, 1.0, 2.0],
+            PPTRF(SymmetricPackedMatrix::from_mat(&llambda).unwrap()),
+        )
+        .unwrap();
+
+        let z = vec![1.0, -1.0, 0.5];
+        let x = theta.sample(z.clone()).unwrap();
+
+        // L^T (x - mu) = z
+        let x_mu = vec![x[0] - 0.0, x[1] - 1.0, x[2] - 2.0].col_mat();
+        let result = (llambda.t() * x_mu).vec();
+        for (r, e) in result.iter().zip(z.iter()) {
+            assert!((r - e).abs() < 1e-10);
+        }
+    }
+}
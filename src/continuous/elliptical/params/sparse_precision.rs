@@ -0,0 +1,225 @@
+use crate::{DistributionError, EllipticalError, EllipticalParams, RandomVariable};
+use opensrdk_linear_algebra::*;
+
+#[derive(Clone, Debug)]
+pub struct SparsePrecisionEllipticalParams {
+    mu: Vec<f64>,
+    llambda: SparseMatrix,
+    pattern: Vec<(usize, usize)>,
+}
+
+impl SparsePrecisionEllipticalParams {
+    /// # Sparse precision
+    /// `L` is needed as second argument under decomposition `Lambda = Sigma^{-1} = L * L^T`.
+    /// `L` must be lower triangular with positive diagonal elements, and its non-zero pattern is kept as is, e.g. banded for GMRF.
+    pub fn new(mu: Vec<f64>, llambda: SparseMatrix) -> Result<Self, DistributionError> {
+        let n = mu.len();
+        if llambda.rows != n || llambda.cols != n {
+            return Err(DistributionError::InvalidParameters(
+                EllipticalError::DimensionMismatch.into(),
+            ));
+        }
+        if llambda.elems.keys().any(|&(i, j)| i < j) {
+            return Err(DistributionError::InvalidParameters(
+                EllipticalError::NotLowerTriangular.into(),
+            ));
+        }
+        if (0..n)
+            .into_iter()
+            .any(|i| llambda.elems.get(&(i, i)).map_or(true, |&lii| lii <= 0.0))
+        {
+            return Err(DistributionError::InvalidParameters(
+                EllipticalError::DiagonalMustBePositive.into(),
+            ));
+        }
+
+        let mut pattern = llambda.elems.keys().cloned().collect::<Vec<_>>();
+        pattern.sort_by_key(|&(i, j)| (j, i));
+
+        Ok(Self {
+            mu,
+            llambda,
+            pattern,
+        })
+    }
+
+    pub fn mu(&self) -> &Vec<f64> {
+        &self.mu
+    }
+
+    pub fn llambda(&self) -> &SparseMatrix {
+        &self.llambda
+    }
+
+    /// Non-zero indices of `L` in column-major order.
+    pub fn pattern(&self) -> &[(usize, usize)] {
+        &self.pattern
+    }
+
+    pub fn eject(self) -> (Vec<f64>, SparseMatrix) {
+        (self.mu, self.llambda)
+    }
+
+    /// `L * v`
+    pub(crate) fn l_mul(&self, v: &[f64]) -> Vec<f64> {
+        let mut result = vec![0.0; v.len()];
+        for (&(i, j), &lij) in self.llambda.elems.iter() {
+            result[i] += lij * v[j];
+        }
+
+        result
+    }
+
+    /// `L^T * v`
+    pub(crate) fn l_t_mul(&self, v: &[f64]) -> Vec<f64> {
+        let mut result = vec![0.0; v.len()];
+        for (&(i, j), &lij) in self.llambda.elems.iter() {
+            result[j] += lij * v[i];
+        }
+
+        result
+    }
+
+    /// `L^{-T} * v` by back substitution
+    fn l_t_solve(&self, v: &[f64]) -> Vec<f64> {
+        let n = v.len();
+        let mut below = vec![vec![]; n];
+        for &(i, j) in self.pattern.iter() {
+            if i != j {
+                below[j].push((i, self.llambda[(i, j)]));
+            }
+        }
+
+        let mut result = vec![0.0; n];
+        for j in (0..n).rev() {
+            let s = below[j]
+                .iter()
+                .map(|&(i, lij)| lij * result[i])
+                .sum::<f64>();
+            result[j] = (v[j] - s) / self.llambda[(j, j)];
+        }
+
+        result
+    }
+}
+
+impl RandomVariable for SparsePrecisionEllipticalParams {
+    type RestoreInfo = (usize, Vec<(usize, usize)>);
+
+    fn transform_vec(&self) -> (Vec<f64>, Self::RestoreInfo) {
+        let values = self
+            .pattern
+            .iter()
+            .map(|&(i, j)| self.llambda[(i, j)])
+            .collect::<Vec<_>>();
+
+        (
+            [self.mu.clone(), values].concat(),
+            (self.mu.len(), self.pattern.clone()),
+        )
+    }
+
+    fn len(&self) -> usize {
+        self.mu.len() + self.pattern.len()
+    }
+
+    fn restore(v: &[f64], info: &Self::RestoreInfo) -> Result<Self, DistributionError> {
+        let (n, pattern) = info;
+        let n = *n;
+        if v.len() != n + pattern.len() {
+            return Err(DistributionError::InvalidRestoreVector);
+        }
+        let mu = v[0..n].to_vec();
+        let mut llambda = SparseMatrix::new(n, n);
+        for (&(i, j), &lij) in pattern.iter().zip(v[n..v.len()].iter()) {
+            llambda[(i, j)] = lij;
+        }
+
+        Self::new(mu, llambda)
+    }
+}
+
+impl EllipticalParams for SparsePrecisionEllipticalParams {
+    fn mu(&self) -> &Vec<f64> {
+        self.mu()
+    }
+
+    /// `Sigma^{-1} v = L * L^T * v` for each column of `v`
+    fn sigma_inv_mul(&self, v: Matrix) -> Result<Matrix, DistributionError> {
+        let n = self.mu.len();
+        if v.rows() != n {
+            return Err(DistributionError::InvalidParameters(
+                EllipticalError::DimensionMismatch.into(),
+            ));
+        }
+        let elems = (0..v.cols())
+            .into_iter()
+            .flat_map(|k| self.l_mul(&self.l_t_mul(&v[k])))
+            .collect::<Vec<_>>();
+
+        Ok(Matrix::from(n, elems)?)
+    }
+
+    /// `ln|Sigma| = -2 ln|L|`
+    fn ln_det_sigma(&self) -> f64 {
+        -(0..self.mu.len())
+            .into_iter()
+            .map(|i| 2.0 * self.llambda[(i, i)].ln())
+            .sum::<f64>()
+    }
+
+    fn lsigma_cols(&self) -> usize {
+        self.mu.len()
+    }
+
+    /// `x = mu + L^{-T} z`
+    fn sample(&self, z: Vec<f64>) -> Result<Vec<f64>, DistributionError> {
+        if z.len() != self.mu.len() {
+            return Err(DistributionError::InvalidParameters(
+                EllipticalError::DimensionMismatch.into(),
+            ));
+        }
+
+        Ok(self
+            .l_t_solve(&z)
+            .into_iter()
+            .zip(self.mu.iter())
+            .map(|(yi, &mui)| mui + yi)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{EllipticalParams, SparsePrecisionEllipticalParams};
+    use opensrdk_linear_algebra::*;
+
+    #[test]
+    fn it_works() {
+        // Banded factor of a first order random walk
+        let n = 4;
+        let mut llambda = SparseMatrix::new(n, n);
+        for i in 0..n {
+            llambda[(i, i)] = 2.0;
+            if i + 1 < n {
+                llambda[(i + 1, i)] = -1.0;
+            }
+        }
+        let theta = SparsePrecisionEllipticalParams::new(vec![0.0; n], llambda).unwrap();
+
+        let z = vec![1.0, -1.0, 0.5, 2.0];
+        let x = theta.sample(z.clone()).unwrap();
+
+        // L^T x = z
+        let result = theta.l_t_mul(&x);
+        for (r, e) in result.iter().zip(z.iter()) {
+            assert!((r - e).abs() < 1e-10);
+        }
+
+        let sigma_inv_x = theta.sigma_inv_mul(x.clone().col_mat()).unwrap();
+        let expected = theta.l_mul(&z);
+        for (r, e) in sigma_inv_x.vec().iter().zip(expected.iter()) {
+            assert!((r - e).abs() < 1e-10);
+        }
+    }
+}
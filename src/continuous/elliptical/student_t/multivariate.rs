@@ -1,12 +1,13 @@
 use crate::{
-    ConditionDifferentiableDistribution, DependentJoint, Distribution, ExactEllipticalParams,
-    IndependentJoint, RandomVariable, SamplableDistribution, ValueDifferentiableDistribution,
+    tril_packed, ConditionDifferentiableDistribution, DependentJoint, DiagonalEllipticalParams,
+    Distribution, ExactEllipticalParams, IndependentJoint, LowRankEllipticalParams, RandomVariable,
+    SamplableDistribution, ValueDifferentiableDistribution,
 };
 use crate::{DistributionError, EllipticalParams};
 use opensrdk_linear_algebra::pp::trf::PPTRF;
 use opensrdk_linear_algebra::*;
 use rand::prelude::*;
-use rand_distr::StudentT as RandStudentT;
+use rand_distr::{ChiSquared as RandChiSquared, StandardNormal};
 use special::Gamma;
 use std::marker::PhantomData;
 use std::{ops::BitAnd, ops::Mul};
@@ -58,56 +59,119 @@ where
     }
 }
 
-impl ValueDifferentiableDistribution for MultivariateStudentT {
+impl<T, U> ValueDifferentiableDistribution for MultivariateStudentT<T, U>
+where
+    T: MultivariateStudentTParams<U>,
+    U: EllipticalParams,
+{
     fn ln_diff_value(
         &self,
         x: &Self::Value,
         theta: &Self::Condition,
     ) -> Result<Vec<f64>, DistributionError> {
-        let x_mat = x.clone().row_mat();
-        let mu_mat = theta.mu().clone().row_mat();
-        let x_mu = x_mat - mu_mat;
-        let x_mu_t = x_mu.t();
-        let sigma_inv = theta.lsigma().clone().pptri()?.to_mat();
+        let elliptical = theta.elliptical();
+        let x_mu = elliptical.x_mu(x)?.col_mat();
+        let sigma_inv_x_mu = elliptical.sigma_inv_mul(x_mu.clone())?;
         let nu = theta.nu();
         let n = x.len() as f64;
-        let d = (&x_mu * &sigma_inv * &x_mu_t)[(0, 0)];
-        let f_x = -(&nu + &n) / &nu * (1.0 + &d).powi(-1) * (x_mu * sigma_inv);
+        let d = (x_mu.t() * &sigma_inv_x_mu)[(0, 0)];
+
+        // -(ν + n) / (ν + d) Σ^{-1} (x - μ)
+        let f_x = -(nu + n) / (nu + d) * sigma_inv_x_mu;
+
         Ok(f_x.vec())
     }
 }
 
-impl ConditionDifferentiableDistribution for MultivariateStudentT {
+impl<T> ConditionDifferentiableDistribution for MultivariateStudentT<T, ExactEllipticalParams>
+where
+    T: MultivariateStudentTParams<ExactEllipticalParams>,
+{
+    /// Gradients with respect to `μ`, packed `L` and `ν` in this order.
+    fn ln_diff_condition(
+        &self,
+        x: &Self::Value,
+        theta: &Self::Condition,
+    ) -> Result<Vec<f64>, DistributionError> {
+        let elliptical = theta.elliptical();
+        let x_mu = elliptical.x_mu(x)?.col_mat();
+        let lsigma = elliptical.lsigma().0.to_mat();
+        let sigma_inv_x_mu = elliptical.lsigma().pptrs(x_mu.clone())?;
+        let nu = theta.nu();
+        let n = x.len() as f64;
+        let d = (x_mu.t() * &sigma_inv_x_mu)[(0, 0)];
+
+        let f_mu = (nu + n) / (nu + d) * sigma_inv_x_mu.clone();
+
+        // (ν + n) / (ν + d) Σ^{-1} (x - μ) (x - μ)^T Σ^{-1} L - diag(L)^{-1}
+        let f_lsigma_mat = (nu + n) / (nu + d) * (&sigma_inv_x_mu * sigma_inv_x_mu.t() * &lsigma);
+        let f_lsigma = tril_packed(&f_lsigma_mat, &lsigma, 1.0);
+
+        let f_nu = ln_diff_nu(nu, n, d);
+
+        Ok([f_mu.vec(), f_lsigma, vec![f_nu]].concat())
+    }
+}
+
+impl ConditionDifferentiableDistribution
+    for MultivariateStudentT<DiagonalMultivariateStudentTParams, DiagonalEllipticalParams>
+{
+    /// Gradients with respect to `μ`, `d` and `ν` in this order.
+    fn ln_diff_condition(
+        &self,
+        x: &Self::Value,
+        theta: &Self::Condition,
+    ) -> Result<Vec<f64>, DistributionError> {
+        let elliptical = theta.elliptical();
+        let x_mu = elliptical.x_mu(x)?.col_mat();
+        let sigma_inv_x_mu = elliptical.sigma_inv_mul(x_mu.clone())?;
+        let nu = theta.nu();
+        let n = x.len() as f64;
+        let d = (x_mu.t() * &sigma_inv_x_mu)[(0, 0)];
+        let w = (nu + n) / (nu + d);
+
+        let a = sigma_inv_x_mu.vec();
+        let f_d = elliptical.ln_diff_sigma_params(&a, w);
+        let f_mu = a.into_iter().map(|ai| w * ai).collect::<Vec<_>>();
+        let f_nu = ln_diff_nu(nu, n, d);
+
+        Ok([f_mu, f_d, vec![f_nu]].concat())
+    }
+}
+
+impl ConditionDifferentiableDistribution
+    for MultivariateStudentT<LowRankMultivariateStudentTParams, LowRankEllipticalParams>
+{
+    /// Gradients with respect to `μ`, `d`, `W` and `ν` in this order.
     fn ln_diff_condition(
         &self,
         x: &Self::Value,
         theta: &Self::Condition,
     ) -> Result<Vec<f64>, DistributionError> {
-        let x_mat = x.clone().row_mat();
-        let mu_mat = theta.mu().clone().row_mat();
-        let x_mu = x_mat - mu_mat;
-        let x_mu_t = x_mu.t();
-        let sigma_inv = theta.lsigma().clone().pptri()?.to_mat();
+        let elliptical = theta.elliptical();
+        let x_mu = elliptical.x_mu(x)?.col_mat();
+        let sigma_inv_x_mu = elliptical.sigma_inv_mul(x_mu.clone())?;
         let nu = theta.nu();
         let n = x.len() as f64;
-        let d = (&x_mu * &sigma_inv * &x_mu_t)[(0, 0)];
-        // Hadamard product (L*L*L)
-        let m = sigma_inv
-            .clone()
-            .hadamard_prod(&sigma_inv)
-            .hadamard_prod(&sigma_inv);
-        let f_mu = (&nu + &n) / &nu * (1.0 + &d).powi(-1) * (&x_mu * &sigma_inv);
-        let f_lsigma = (&nu + &n) / &nu * (1.0 + &d / &nu).powi(-1) * (&x_mu * &m * &x_mu_t);
-        let f_nu = 0.5
-            * ((0.5 * (nu + n)).digamma()
-                - (n / nu)
-                - (0.5 * nu).digamma()
-                - (nu + n) * d / nu.powi(2) * (1.0 + d / nu).powi(-1)
-                - (1.0 + d / nu).ln());
-        Ok([f_mu.vec(), f_lsigma.vec(), vec![f_nu]].concat())
+        let d = (x_mu.t() * &sigma_inv_x_mu)[(0, 0)];
+        let w = (nu + n) / (nu + d);
+
+        let a = sigma_inv_x_mu.vec();
+        let f_sigma = elliptical.ln_diff_sigma_params(&a, w)?;
+        let f_mu = a.into_iter().map(|ai| w * ai).collect::<Vec<_>>();
+        let f_nu = ln_diff_nu(nu, n, d);
+
+        Ok([f_mu, f_sigma, vec![f_nu]].concat())
     }
 }
 
+/// `d` is the squared Mahalanobis distance `(x - μ)^T Σ^{-1} (x - μ)`.
+fn ln_diff_nu(nu: f64, n: f64, d: f64) -> f64 {
+    0.5 * ((0.5 * (nu + n)).digamma() - (0.5 * nu).digamma() - n / nu
+        + (nu + n) * d / (nu * (nu + d))
+        - (1.0 + d / nu).ln())
+}
+
 pub trait MultivariateStudentTParams<T>: RandomVariable
 where
     T: EllipticalParams,
@@ -174,6 +238,72 @@ impl MultivariateStudentTParams<ExactEllipticalParams> for ExactMultivariateStud
     }
 }
 
+/// Student-t parameters over any elliptical parameterization.
+#[derive(Clone, Debug)]
+pub struct EllipticalMultivariateStudentTParams<T>
+where
+    T: EllipticalParams,
+{
+    nu: f64,
+    elliptical: T,
+}
+
+impl<T> EllipticalMultivariateStudentTParams<T>
+where
+    T: EllipticalParams,
+{
+    pub fn new(nu: f64, elliptical: T) -> Result<Self, DistributionError> {
+        Ok(Self { nu, elliptical })
+    }
+
+    pub fn eject(self) -> (f64, T) {
+        (self.nu, self.elliptical)
+    }
+}
+
+impl<T> RandomVariable for EllipticalMultivariateStudentTParams<T>
+where
+    T: EllipticalParams,
+{
+    type RestoreInfo = T::RestoreInfo;
+
+    fn transform_vec(&self) -> (Vec<f64>, Self::RestoreInfo) {
+        let (v, info) = self.elliptical.transform_vec();
+        ([v, vec![self.nu]].concat(), info)
+    }
+
+    fn len(&self) -> usize {
+        self.elliptical.len() + 1
+    }
+
+    fn restore(v: &[f64], info: &Self::RestoreInfo) -> Result<Self, DistributionError> {
+        if v.is_empty() {
+            return Err(DistributionError::InvalidRestoreVector);
+        }
+        let elliptical = T::restore(&v[0..v.len() - 1], info)?;
+        let nu = v[v.len() - 1];
+        Self::new(nu, elliptical)
+    }
+}
+
+impl<T> MultivariateStudentTParams<T> for EllipticalMultivariateStudentTParams<T>
+where
+    T: EllipticalParams,
+{
+    fn nu(&self) -> f64 {
+        self.nu
+    }
+
+    fn elliptical(&self) -> &T {
+        &self.elliptical
+    }
+}
+
+pub type DiagonalMultivariateStudentTParams =
+    EllipticalMultivariateStudentTParams<DiagonalEllipticalParams>;
+pub type LowRankMultivariateStudentTParams =
+    EllipticalMultivariateStudentTParams<LowRankEllipticalParams>;
+
 impl<T, U, Rhs, TRhs> Mul<Rhs> for MultivariateStudentT<T, U>
 where
     T: MultivariateStudentTParams<U>,
@@ -202,7 +332,12 @@ where
     }
 }
 
-impl SamplableDistribution for MultivariateStudentT {
+impl<T, U> SamplableDistribution for MultivariateStudentT<T, U>
+where
+    T: MultivariateStudentTParams<U>,
+    U: EllipticalParams,
+{
+    /// `x = μ + L z sqrt(ν / w)` where `z ~ N(0, I)` and `w ~ χ²(ν)`.
     fn sample(
         &self,
         theta: &Self::Condition,
@@ -211,14 +346,15 @@ impl SamplableDistribution for MultivariateStudentT {
         let nu = theta.nu();
         let elliptical = theta.elliptical();
 
-        let student_t = match RandStudentT::new(nu) {
+        let chi_squared = match RandChiSquared::new(nu) {
             Ok(v) => Ok(v),
             Err(e) => Err(DistributionError::Others(e.into())),
         }?;
+        let scale = (nu / rng.sample(chi_squared)).sqrt();
 
         let z = (0..elliptical.lsigma_cols())
             .into_iter()
-            .map(|_| rng.sample(student_t))
+            .map(|_| scale * rng.sample::<f64, _>(StandardNormal))
             .collect::<Vec<_>>();
 
         Ok(elliptical.sample(z)?)
@@ -229,10 +365,42 @@ impl SamplableDistribution for MultivariateStudentT {
 mod tests {
     use crate::{
         ConditionDifferentiableDistribution, Distribution, ExactMultivariateStudentTParams,
-        MultivariateStudentT, SamplableDistribution, ValueDifferentiableDistribution,
+        LowRankEllipticalParams, LowRankMultivariateStudentTParams, MultivariateStudentT,
+        RandomVariable, SamplableDistribution, ValueDifferentiableDistribution,
     };
     use opensrdk_linear_algebra::{pp::trf::PPTRF, *};
     use rand::prelude::*;
+    use special::Gamma;
+
+    fn params() -> ExactMultivariateStudentTParams {
+        let mu = vec![0.0, 1.0, 2.0];
+        let lsigma = SymmetricPackedMatrix::from_mat(&mat!(
+           1.0, 0.0, 0.0;
+           0.5, 2.0, 0.0;
+           0.2, 0.3, 1.5
+        ))
+        .unwrap();
+
+        ExactMultivariateStudentTParams::new(3.0, mu, PPTRF(lsigma)).unwrap()
+    }
+
+    /// Normalized log density, for checking the gradients with respect to `L` and `ν`.
+    fn ln_pdf(x: &Vec<f64>, theta: &ExactMultivariateStudentTParams) -> f64 {
+        let student_t = MultivariateStudentT::new();
+        let n = x.len() as f64;
+        let nu = theta.nu;
+        let lsigma = theta.lsigma().0.to_mat();
+        let ln_det_l = (0..x.len())
+            .into_iter()
+            .map(|i| lsigma[(i, i)].ln())
+            .sum::<f64>();
+
+        student_t.p_kernel(x, theta).unwrap().ln() + ((nu + n) / 2.0).ln_gamma().0
+            - (nu / 2.0).ln_gamma().0
+            - n / 2.0 * (nu * std::f64::consts::PI).ln()
+            - ln_det_l
+    }
+
     #[test]
     fn it_works() {
         let student_t = MultivariateStudentT::new();
@@ -263,50 +431,69 @@ mod tests {
     #[test]
     fn it_works2() {
         let student_t = MultivariateStudentT::new();
-
-        let mu = vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0];
-        let lsigma = SymmetricPackedMatrix::from_mat(&mat!(
-           1.0,  0.0,  0.0,  0.0,  0.0,  0.0;
-           2.0,  3.0,  0.0,  0.0,  0.0,  0.0;
-           4.0,  5.0,  6.0,  0.0,  0.0,  0.0;
-           7.0,  8.0,  9.0, 10.0,  0.0,  0.0;
-          11.0, 12.0, 13.0, 14.0, 15.0,  0.0;
-          16.0, 17.0, 18.0, 19.0, 20.0, 21.0
-        ))
-        .unwrap();
-        println!("{:#?}", lsigma);
-
-        let x = vec![0.0, 1.0, 2.0, 0.0, 1.0, 2.0];
-
-        let f = student_t.ln_diff_value(
-            &x,
-            &ExactMultivariateStudentTParams::new(1.0, mu, PPTRF(lsigma)).unwrap(),
-        );
-        println!("{:#?}", f);
+        let theta = params();
+        let x = vec![0.5, -1.0, 3.0];
+
+        let f = student_t.ln_diff_value(&x, &theta).unwrap();
+        let h = 1e-6;
+        for i in 0..f.len() {
+            let mut x_h = x.clone();
+            x_h[i] += h;
+            let numerical = (student_t.p_kernel(&x_h, &theta).unwrap().ln()
+                - student_t.p_kernel(&x, &theta).unwrap().ln())
+                / h;
+            assert!((f[i] - numerical).abs() < 1e-4);
+        }
     }
 
     #[test]
     fn it_works_3() {
         let student_t = MultivariateStudentT::new();
+        let theta = params();
+        let x = vec![0.5, -1.0, 3.0];
+
+        let f = student_t.ln_diff_condition(&x, &theta).unwrap();
+        let (v, info) = theta.transform_vec();
+        assert_eq!(f.len(), v.len());
+
+        let h = 1e-6;
+        for i in 0..f.len() {
+            let mut v_h = v.clone();
+            v_h[i] += h;
+            let theta_h = ExactMultivariateStudentTParams::restore(&v_h, &info).unwrap();
+            let numerical = (ln_pdf(&x, &theta_h) - ln_pdf(&x, &theta)) / h;
+            assert!((f[i] - numerical).abs() < 1e-4);
+        }
+    }
 
-        let mu = vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0];
-        let lsigma = SymmetricPackedMatrix::from_mat(&mat!(
-           1.0,  0.0,  0.0,  0.0,  0.0,  0.0;
-           2.0,  3.0,  0.0,  0.0,  0.0,  0.0;
-           4.0,  5.0,  6.0,  0.0,  0.0,  0.0;
-           7.0,  8.0,  9.0, 10.0,  0.0,  0.0;
-          11.0, 12.0, 13.0, 14.0, 15.0,  0.0;
-          16.0, 17.0, 18.0, 19.0, 20.0, 21.0
-        ))
-        .unwrap();
-        println!("{:#?}", lsigma);
+    #[test]
+    fn it_works4() {
+        let mut rng = StdRng::from_seed([1; 32]);
+        let w = mat!(
+            1.0, 0.0;
+            0.5, 1.0;
+            0.2, 0.3
+        );
+        let elliptical =
+            LowRankEllipticalParams::new(vec![0.0, 1.0, 2.0], vec![1.0, 2.0, 0.5], w).unwrap();
+        let theta = LowRankMultivariateStudentTParams::new(3.0, elliptical).unwrap();
+        let student_t = MultivariateStudentT::new();
 
-        let x = vec![0.0, 1.0, 2.0, 0.0, 1.0, 2.0];
+        let x = student_t.sample(&theta, &mut rng).unwrap();
+        assert_eq!(x.len(), 3);
+
+        let f = student_t.ln_diff_value(&x, &theta).unwrap();
+        let h = 1e-6;
+        for i in 0..f.len() {
+            let mut x_h = x.clone();
+            x_h[i] += h;
+            let numerical = (student_t.p_kernel(&x_h, &theta).unwrap().ln()
+                - student_t.p_kernel(&x, &theta).unwrap().ln())
+                / h;
+            assert!((f[i] - numerical).abs() < 1e-4);
+        }
 
-        let f = student_t.ln_diff_condition(
-            &x,
-            &ExactMultivariateStudentTParams::new(1.0, mu, PPTRF(lsigma)).unwrap(),
-        );
-        println!("{:#?}", f);
+        let f = student_t.ln_diff_condition(&x, &theta).unwrap();
+        assert_eq!(f.len(), theta.len());
     }
 }
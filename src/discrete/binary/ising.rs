@@ -0,0 +1,350 @@
+use super::BinarySampler;
+use crate::discrete::util::sigmoid;
+use crate::{
+    DependentJoint, DiscreteDistribution, Distribution, DistributionError, IndependentJoint,
+    RandomVariable, SamplableDistribution,
+};
+use opensrdk_linear_algebra::*;
+use rand::prelude::*;
+use std::{ops::BitAnd, ops::Mul};
+
+/// Ising model over spins `s_i = ±1`, represented by `x_i = (s_i == 1)`
+/// `p(x | J, h) ∝ exp(Σ_{i<j} J_ij s_i s_j + Σ_i h_i s_i)`
+///
+/// `p_kernel` is not normalized because the partition function is intractable in general.
+/// For small models, `DiscretePosterior` with `bit_vectors` as the range enumerates the states exactly.
+#[derive(Clone, Debug, Default)]
+pub struct Ising {
+    sampler: BinarySampler,
+}
+
+impl Ising {
+    pub fn new(sampler: BinarySampler) -> Self {
+        Self { sampler }
+    }
+
+    pub fn sampler(&self) -> &BinarySampler {
+        &self.sampler
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum IsingError {
+    #[error("Dimension mismatch")]
+    DimensionMismatch,
+    #[error("Coupling matrix must be symmetric with zero diagonal")]
+    CouplingMustBeSymmetric,
+}
+
+impl Distribution for Ising {
+    type Value = Vec<bool>;
+    type Condition = IsingParams;
+
+    fn p_kernel(&self, x: &Self::Value, theta: &Self::Condition) -> Result<f64, DistributionError> {
+        Ok(theta.ln_unnormalized(x)?.exp())
+    }
+}
+
+impl DiscreteDistribution for Ising {}
+
+impl<Rhs, TRhs> Mul<Rhs> for Ising
+where
+    Rhs: Distribution<Value = TRhs, Condition = IsingParams>,
+    TRhs: RandomVariable,
+{
+    type Output = IndependentJoint<Self, Rhs, Vec<bool>, TRhs, IsingParams>;
+
+    fn mul(self, rhs: Rhs) -> Self::Output {
+        IndependentJoint::new(self, rhs)
+    }
+}
+
+impl<Rhs, URhs> BitAnd<Rhs> for Ising
+where
+    Rhs: Distribution<Value = IsingParams, Condition = URhs>,
+    URhs: RandomVariable,
+{
+    type Output = DependentJoint<Self, Rhs, Vec<bool>, IsingParams, URhs>;
+
+    fn bitand(self, rhs: Rhs) -> Self::Output {
+        DependentJoint::new(self, rhs)
+    }
+}
+
+impl SamplableDistribution for Ising {
+    /// Runs the sampler from a uniformly random state.
+    fn sample(
+        &self,
+        theta: &Self::Condition,
+        rng: &mut dyn RngCore,
+    ) -> Result<Self::Value, DistributionError> {
+        let mut x = (0..theta.h().len())
+            .map(|_| rng.gen_bool(0.5))
+            .collect::<Vec<_>>();
+
+        match self.sampler {
+            BinarySampler::Gibbs { sweeps } => {
+                for _ in 0..sweeps {
+                    theta.gibbs_sweep(&mut x, rng);
+                }
+            }
+            BinarySampler::SwendsenWang { sweeps } => {
+                for _ in 0..sweeps {
+                    theta.swendsen_wang_sweep(&mut x, rng);
+                }
+            }
+        }
+
+        Ok(x)
+    }
+}
+
+fn spin(xi: bool) -> f64 {
+    if xi {
+        1.0
+    } else {
+        -1.0
+    }
+}
+
+fn find(parent: &mut [usize], i: usize) -> usize {
+    let mut root = i;
+    while parent[root] != root {
+        root = parent[root];
+    }
+    let mut i = i;
+    while parent[i] != root {
+        let next = parent[i];
+        parent[i] = root;
+        i = next;
+    }
+
+    root
+}
+
+#[derive(Clone, Debug)]
+pub struct IsingParams {
+    j: Matrix,
+    h: Vec<f64>,
+}
+
+impl IsingParams {
+    /// - `j`: Symmetric coupling matrix with zero diagonal
+    /// - `h`: External field
+    pub fn new(j: Matrix, h: Vec<f64>) -> Result<Self, DistributionError> {
+        let n = h.len();
+        if j.rows() != n || j.cols() != n {
+            return Err(DistributionError::InvalidParameters(
+                IsingError::DimensionMismatch.into(),
+            ));
+        }
+        for a in 0..n {
+            if j[(a, a)] != 0.0 || (0..a).any(|b| j[(a, b)] != j[(b, a)]) {
+                return Err(DistributionError::InvalidParameters(
+                    IsingError::CouplingMustBeSymmetric.into(),
+                ));
+            }
+        }
+
+        Ok(Self { j, h })
+    }
+
+    pub fn j(&self) -> &Matrix {
+        &self.j
+    }
+
+    pub fn h(&self) -> &Vec<f64> {
+        &self.h
+    }
+
+    /// `Σ_{i<j} J_ij s_i s_j + Σ_i h_i s_i`
+    pub fn ln_unnormalized(&self, x: &[bool]) -> Result<f64, DistributionError> {
+        let n = self.h.len();
+        if x.len() != n {
+            return Err(DistributionError::InvalidParameters(
+                IsingError::DimensionMismatch.into(),
+            ));
+        }
+
+        Ok((0..n)
+            .map(|a| {
+                spin(x[a]) * (self.h[a] + (0..a).map(|b| self.j[(a, b)] * spin(x[b])).sum::<f64>())
+            })
+            .sum())
+    }
+
+    /// `h_i + Σ_j J_ij s_j`
+    fn local_field(&self, x: &[bool], i: usize) -> f64 {
+        self.h[i]
+            + (0..x.len())
+                .filter(|&k| k != i)
+                .map(|k| self.j[(i, k)] * spin(x[k]))
+                .sum::<f64>()
+    }
+
+    /// Updates each site from `p(s_i = 1 | s_{-i}) = σ(2 (h_i + Σ_j J_ij s_j))` in turn.
+    pub fn gibbs_sweep(&self, x: &mut [bool], rng: &mut dyn RngCore) {
+        for i in 0..x.len() {
+            let p = sigmoid(2.0 * self.local_field(x, i));
+            x[i] = rng.gen_range(0.0..1.0) < p;
+        }
+    }
+
+    /// Bonds each satisfied pair `J_ij s_i s_j > 0` with probability `1 - exp(-2|J_ij|)`,
+    /// then flips each cluster `C` with probability `σ(-2 Σ_{i∈C} h_i s_i)`.
+    /// Frustrated (mixed sign) couplings are valid but mix more slowly.
+    pub fn swendsen_wang_sweep(&self, x: &mut [bool], rng: &mut dyn RngCore) {
+        let n = x.len();
+        let mut parent = (0..n).collect::<Vec<_>>();
+        for a in 0..n {
+            for b in 0..a {
+                let jab = self.j[(a, b)];
+                if jab * spin(x[a]) * spin(x[b]) <= 0.0 {
+                    continue;
+                }
+                if rng.gen_range(0.0..1.0) < -(-2.0 * jab.abs()).exp_m1() {
+                    let ra = find(&mut parent, a);
+                    let rb = find(&mut parent, b);
+                    parent[ra] = rb;
+                }
+            }
+        }
+
+        let mut field = vec![0.0; n];
+        for i in 0..n {
+            let r = find(&mut parent, i);
+            field[r] += self.h[i] * spin(x[i]);
+        }
+        let flip = field
+            .iter()
+            .map(|&m| rng.gen_range(0.0..1.0) < sigmoid(-2.0 * m))
+            .collect::<Vec<_>>();
+        for i in 0..n {
+            if flip[find(&mut parent, i)] {
+                x[i] = !x[i];
+            }
+        }
+    }
+}
+
+impl RandomVariable for IsingParams {
+    type RestoreInfo = usize;
+
+    /// Strictly lower triangle of `J` row by row, followed by `h`
+    fn transform_vec(&self) -> (Vec<f64>, Self::RestoreInfo) {
+        let n = self.h.len();
+        let j = (0..n)
+            .flat_map(|a| (0..a).map(move |b| (a, b)))
+            .map(|(a, b)| self.j[(a, b)])
+            .collect::<Vec<_>>();
+
+        ([j, self.h.clone()].concat(), n)
+    }
+
+    fn len(&self) -> usize {
+        let n = self.h.len();
+
+        n * n.saturating_sub(1) / 2 + n
+    }
+
+    fn restore(v: &[f64], info: &Self::RestoreInfo) -> Result<Self, DistributionError> {
+        let n = *info;
+        let m = n * n.saturating_sub(1) / 2;
+        if v.len() != m + n {
+            return Err(DistributionError::InvalidRestoreVector);
+        }
+        let mut j = Matrix::new(n, n);
+        let mut k = 0;
+        for a in 0..n {
+            for b in 0..a {
+                j[(a, b)] = v[k];
+                j[(b, a)] = v[k];
+                k += 1;
+            }
+        }
+
+        Self::new(j, v[m..].to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bit_vectors, BinarySampler, ConditionMappableDistribution, DiscretePosterior, Distribution,
+        Ising, IsingParams, MultivariateBernoulli, MultivariateBernoulliParams,
+        SamplableDistribution,
+    };
+    use opensrdk_linear_algebra::*;
+    use rand::prelude::*;
+
+    fn ring(n: usize, coupling: f64) -> Matrix {
+        let mut j = Matrix::new(n, n);
+        for a in 0..n {
+            let b = (a + 1) % n;
+            j[(a, b)] = coupling;
+            j[(b, a)] = coupling;
+        }
+        j
+    }
+
+    #[test]
+    fn it_works() {
+        let n = 4;
+        let theta = IsingParams::new(ring(n, 0.4), vec![0.3, -0.2, 0.0, 0.1]).unwrap();
+        let states = bit_vectors(n).into_iter().collect::<Vec<_>>();
+        let z = states
+            .iter()
+            .map(|x| Ising::default().p_kernel(x, &theta).unwrap())
+            .sum::<f64>();
+
+        let samplers = vec![
+            BinarySampler::Gibbs { sweeps: 1 },
+            BinarySampler::SwendsenWang { sweeps: 1 },
+        ];
+        for sampler in samplers {
+            let ising = Ising::new(sampler);
+            let mut rng = StdRng::from_seed([1; 32]);
+            let m = 40000;
+            let mut count = vec![0usize; states.len()];
+            let mut x = ising.sample(&theta, &mut rng).unwrap();
+            for _ in 0..m {
+                match ising.sampler() {
+                    BinarySampler::Gibbs { .. } => theta.gibbs_sweep(&mut x, &mut rng),
+                    BinarySampler::SwendsenWang { .. } => {
+                        theta.swendsen_wang_sweep(&mut x, &mut rng)
+                    }
+                }
+                count[states.iter().position(|s| *s == x).unwrap()] += 1;
+            }
+            for (s, c) in states.iter().zip(count.iter()) {
+                let p = ising.p_kernel(s, &theta).unwrap() / z;
+                assert!((*c as f64 / m as f64 - p).abs() < 0.02);
+            }
+        }
+    }
+
+    #[test]
+    fn it_works2() {
+        // Denoising a bit vector with the Ising prior, enumerated exactly by DiscretePosterior
+        let n = 4;
+        let theta = IsingParams::new(ring(n, 0.8), vec![0.0; n]).unwrap();
+        let prior = Ising::default().map_condition(move |_: &()| Ok(theta.clone()));
+        let likelihood = MultivariateBernoulli.map_condition(|x: &Vec<bool>| {
+            MultivariateBernoulliParams::new(
+                x.iter().map(|&xi| if xi { 1.5 } else { -1.5 }).collect(),
+            )
+        });
+        let posterior = DiscretePosterior::new(likelihood, prior, bit_vectors(n));
+
+        let y = vec![true, true, false, true];
+        let mut rng = StdRng::from_seed([1; 32]);
+        let x = posterior.sample(&y, &mut rng).unwrap();
+        assert_eq!(x.len(), n);
+
+        let smoothed = posterior.p_kernel(&vec![true; n], &y).unwrap();
+        let flipped = posterior
+            .p_kernel(&vec![false, false, true, false], &y)
+            .unwrap();
+        assert!(flipped < smoothed);
+    }
+}
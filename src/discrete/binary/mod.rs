@@ -0,0 +1,31 @@
+pub mod ising;
+pub mod multivariate_bernoulli;
+pub mod restricted_boltzmann_machine;
+
+pub use ising::*;
+pub use multivariate_bernoulli::*;
+pub use restricted_boltzmann_machine::*;
+
+use std::collections::HashSet;
+
+/// MCMC used to sample models over bit vectors whose normalizer is intractable.
+#[derive(Clone, Debug)]
+pub enum BinarySampler {
+    /// Single-site Gibbs updates, `sweeps` times over all sites
+    Gibbs { sweeps: usize },
+    /// Swendsen–Wang cluster updates, `sweeps` times
+    SwendsenWang { sweeps: usize },
+}
+
+impl Default for BinarySampler {
+    fn default() -> Self {
+        Self::SwendsenWang { sweeps: 100 }
+    }
+}
+
+/// All `2^n` bit vectors of length `n`, e.g. for the range of `DiscretePosterior` when `n` is small.
+pub fn bit_vectors(n: usize) -> HashSet<Vec<bool>> {
+    (0..1usize << n)
+        .map(|bits| (0..n).map(|i| (bits >> i) & 1 == 1).collect())
+        .collect()
+}
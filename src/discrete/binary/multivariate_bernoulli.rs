@@ -0,0 +1,200 @@
+use crate::discrete::util::sigmoid;
+use crate::{
+    BernoulliError, ConditionDifferentiableDistribution, DependentJoint, DiscreteDistribution,
+    Distribution, DistributionError, IndependentJoint, RandomVariable, SamplableDistribution,
+};
+use rand::prelude::*;
+use std::{ops::BitAnd, ops::Mul};
+
+/// Independent Bernoulli distributions over a bit vector
+/// `p(x | l) = Π_i σ(l_i)^{x_i} (1 - σ(l_i))^{1 - x_i}`
+#[derive(Clone, Debug)]
+pub struct MultivariateBernoulli;
+
+#[derive(thiserror::Error, Debug)]
+pub enum MultivariateBernoulliError {
+    #[error("Dimension mismatch")]
+    DimensionMismatch,
+}
+
+impl Distribution for MultivariateBernoulli {
+    type Value = Vec<bool>;
+    type Condition = MultivariateBernoulliParams;
+
+    fn p_kernel(&self, x: &Self::Value, theta: &Self::Condition) -> Result<f64, DistributionError> {
+        let logits = theta.logits();
+        if x.len() != logits.len() {
+            return Err(DistributionError::InvalidParameters(
+                MultivariateBernoulliError::DimensionMismatch.into(),
+            ));
+        }
+
+        Ok(x.iter()
+            .zip(logits.iter())
+            .map(|(&xi, &li)| if xi { sigmoid(li) } else { sigmoid(-li) })
+            .product())
+    }
+}
+
+impl DiscreteDistribution for MultivariateBernoulli {}
+
+impl<Rhs, TRhs> Mul<Rhs> for MultivariateBernoulli
+where
+    Rhs: Distribution<Value = TRhs, Condition = MultivariateBernoulliParams>,
+    TRhs: RandomVariable,
+{
+    type Output = IndependentJoint<Self, Rhs, Vec<bool>, TRhs, MultivariateBernoulliParams>;
+
+    fn mul(self, rhs: Rhs) -> Self::Output {
+        IndependentJoint::new(self, rhs)
+    }
+}
+
+impl<Rhs, URhs> BitAnd<Rhs> for MultivariateBernoulli
+where
+    Rhs: Distribution<Value = MultivariateBernoulliParams, Condition = URhs>,
+    URhs: RandomVariable,
+{
+    type Output = DependentJoint<Self, Rhs, Vec<bool>, MultivariateBernoulliParams, URhs>;
+
+    fn bitand(self, rhs: Rhs) -> Self::Output {
+        DependentJoint::new(self, rhs)
+    }
+}
+
+impl ConditionDifferentiableDistribution for MultivariateBernoulli {
+    /// `x_i - σ(l_i)` for each logit
+    fn ln_diff_condition(
+        &self,
+        x: &Self::Value,
+        theta: &Self::Condition,
+    ) -> Result<Vec<f64>, DistributionError> {
+        let logits = theta.logits();
+        if x.len() != logits.len() {
+            return Err(DistributionError::InvalidParameters(
+                MultivariateBernoulliError::DimensionMismatch.into(),
+            ));
+        }
+
+        Ok(x.iter()
+            .zip(logits.iter())
+            .map(|(&xi, &li)| {
+                let xi = if xi { 1.0 } else { 0.0 };
+                xi - sigmoid(li)
+            })
+            .collect())
+    }
+}
+
+impl SamplableDistribution for MultivariateBernoulli {
+    fn sample(
+        &self,
+        theta: &Self::Condition,
+        rng: &mut dyn RngCore,
+    ) -> Result<Self::Value, DistributionError> {
+        Ok(theta
+            .logits()
+            .iter()
+            .map(|&li| rng.gen_range(0.0..1.0) < sigmoid(li))
+            .collect())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MultivariateBernoulliParams {
+    logits: Vec<f64>,
+}
+
+impl MultivariateBernoulliParams {
+    /// - `logits`: `l_i = ln(p_i / (1 - p_i))`
+    pub fn new(logits: Vec<f64>) -> Result<Self, DistributionError> {
+        if logits.iter().any(|li| li.is_nan()) {
+            return Err(DistributionError::InvalidParameters(
+                BernoulliError::PMustBeProbability.into(),
+            ));
+        }
+
+        Ok(Self { logits })
+    }
+
+    pub fn from_p(p: &[f64]) -> Result<Self, DistributionError> {
+        if p.iter().any(|&pi| pi < 0.0 || 1.0 < pi) {
+            return Err(DistributionError::InvalidParameters(
+                BernoulliError::PMustBeProbability.into(),
+            ));
+        }
+
+        Self::new(p.iter().map(|&pi| (pi / (1.0 - pi)).ln()).collect())
+    }
+
+    pub fn logits(&self) -> &Vec<f64> {
+        &self.logits
+    }
+
+    pub fn p(&self) -> Vec<f64> {
+        self.logits.iter().map(|&li| sigmoid(li)).collect()
+    }
+}
+
+impl RandomVariable for MultivariateBernoulliParams {
+    type RestoreInfo = usize;
+
+    fn transform_vec(&self) -> (Vec<f64>, Self::RestoreInfo) {
+        (self.logits.clone(), self.logits.len())
+    }
+
+    fn len(&self) -> usize {
+        self.logits.len()
+    }
+
+    fn restore(v: &[f64], info: &Self::RestoreInfo) -> Result<Self, DistributionError> {
+        if v.len() != *info {
+            return Err(DistributionError::InvalidRestoreVector);
+        }
+        Self::new(v.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bit_vectors, ConditionDifferentiableDistribution, Distribution, MultivariateBernoulli,
+        MultivariateBernoulliParams, SamplableDistribution,
+    };
+    use rand::prelude::*;
+
+    #[test]
+    fn it_works() {
+        let bernoulli = MultivariateBernoulli;
+        let mut rng = StdRng::from_seed([1; 32]);
+        let theta = MultivariateBernoulliParams::from_p(&[0.2, 0.5, 0.9]).unwrap();
+
+        let sum = bit_vectors(3)
+            .iter()
+            .map(|x| bernoulli.p_kernel(x, &theta).unwrap())
+            .sum::<f64>();
+        assert!((sum - 1.0).abs() < 1e-12);
+
+        let x = bernoulli.sample(&theta, &mut rng).unwrap();
+        assert_eq!(x.len(), 3);
+    }
+
+    #[test]
+    fn it_works2() {
+        let bernoulli = MultivariateBernoulli;
+        let logits = vec![0.5, -1.0, 2.0];
+        let x = vec![true, true, false];
+        let h = 1e-6;
+
+        let theta = MultivariateBernoulliParams::new(logits.clone()).unwrap();
+        let f = bernoulli.ln_diff_condition(&x, &theta).unwrap();
+        let ln_p = bernoulli.p_kernel(&x, &theta).unwrap().ln();
+        for i in 0..logits.len() {
+            let mut logits_h = logits.clone();
+            logits_h[i] += h;
+            let theta_h = MultivariateBernoulliParams::new(logits_h).unwrap();
+            let numerical = (bernoulli.p_kernel(&x, &theta_h).unwrap().ln() - ln_p) / h;
+            assert!((f[i] - numerical).abs() < 1e-4);
+        }
+    }
+}
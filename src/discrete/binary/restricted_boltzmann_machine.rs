@@ -0,0 +1,294 @@
+use super::BinarySampler;
+use crate::discrete::util::sigmoid;
+use crate::{
+    DependentJoint, DiscreteDistribution, Distribution, DistributionError, IndependentJoint,
+    IsingParams, RandomVariable, SamplableDistribution,
+};
+use opensrdk_linear_algebra::*;
+use rand::prelude::*;
+use std::{ops::BitAnd, ops::Mul};
+
+/// Restricted Boltzmann machine over visible units `v`, with binary hidden units `h` marginalized out
+/// `p(v) ∝ Σ_h exp(b^T v + c^T h + v^T W h) = exp(b^T v) Π_j (1 + exp(c_j + (W^T v)_j))`
+///
+/// `p_kernel` is not normalized because the partition function is intractable in general.
+#[derive(Clone, Debug, Default)]
+pub struct RestrictedBoltzmannMachine {
+    sampler: BinarySampler,
+}
+
+impl RestrictedBoltzmannMachine {
+    pub fn new(sampler: BinarySampler) -> Self {
+        Self { sampler }
+    }
+
+    pub fn sampler(&self) -> &BinarySampler {
+        &self.sampler
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum RestrictedBoltzmannMachineError {
+    #[error("Dimension mismatch")]
+    DimensionMismatch,
+}
+
+fn bit(xi: bool) -> f64 {
+    if xi {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+impl Distribution for RestrictedBoltzmannMachine {
+    type Value = Vec<bool>;
+    type Condition = RestrictedBoltzmannMachineParams;
+
+    fn p_kernel(&self, x: &Self::Value, theta: &Self::Condition) -> Result<f64, DistributionError> {
+        Ok((-theta.free_energy(x)?).exp())
+    }
+}
+
+impl DiscreteDistribution for RestrictedBoltzmannMachine {}
+
+impl<Rhs, TRhs> Mul<Rhs> for RestrictedBoltzmannMachine
+where
+    Rhs: Distribution<Value = TRhs, Condition = RestrictedBoltzmannMachineParams>,
+    TRhs: RandomVariable,
+{
+    type Output = IndependentJoint<Self, Rhs, Vec<bool>, TRhs, RestrictedBoltzmannMachineParams>;
+
+    fn mul(self, rhs: Rhs) -> Self::Output {
+        IndependentJoint::new(self, rhs)
+    }
+}
+
+impl<Rhs, URhs> BitAnd<Rhs> for RestrictedBoltzmannMachine
+where
+    Rhs: Distribution<Value = RestrictedBoltzmannMachineParams, Condition = URhs>,
+    URhs: RandomVariable,
+{
+    type Output = DependentJoint<Self, Rhs, Vec<bool>, RestrictedBoltzmannMachineParams, URhs>;
+
+    fn bitand(self, rhs: Rhs) -> Self::Output {
+        DependentJoint::new(self, rhs)
+    }
+}
+
+impl SamplableDistribution for RestrictedBoltzmannMachine {
+    /// Block Gibbs alternates `h | v` and `v | h`.
+    /// Swendsen–Wang runs on the equivalent Ising model over `(v, h)` and returns the visible part.
+    fn sample(
+        &self,
+        theta: &Self::Condition,
+        rng: &mut dyn RngCore,
+    ) -> Result<Self::Value, DistributionError> {
+        let nv = theta.b().len();
+        let nh = theta.c().len();
+
+        match self.sampler {
+            BinarySampler::Gibbs { sweeps } => {
+                let mut v = (0..nv).map(|_| rng.gen_bool(0.5)).collect::<Vec<_>>();
+                for _ in 0..sweeps {
+                    let h = theta.sample_hidden(&v, rng);
+                    v = theta.sample_visible(&h, rng);
+                }
+
+                Ok(v)
+            }
+            BinarySampler::SwendsenWang { sweeps } => {
+                let ising = theta.to_ising()?;
+                let mut x = (0..nv + nh).map(|_| rng.gen_bool(0.5)).collect::<Vec<_>>();
+                for _ in 0..sweeps {
+                    ising.swendsen_wang_sweep(&mut x, rng);
+                }
+                x.truncate(nv);
+
+                Ok(x)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RestrictedBoltzmannMachineParams {
+    w: Matrix,
+    b: Vec<f64>,
+    c: Vec<f64>,
+}
+
+impl RestrictedBoltzmannMachineParams {
+    /// - `w`: Visible × hidden weights
+    /// - `b`: Visible biases
+    /// - `c`: Hidden biases
+    pub fn new(w: Matrix, b: Vec<f64>, c: Vec<f64>) -> Result<Self, DistributionError> {
+        if w.rows() != b.len() || w.cols() != c.len() {
+            return Err(DistributionError::InvalidParameters(
+                RestrictedBoltzmannMachineError::DimensionMismatch.into(),
+            ));
+        }
+
+        Ok(Self { w, b, c })
+    }
+
+    pub fn w(&self) -> &Matrix {
+        &self.w
+    }
+
+    pub fn b(&self) -> &Vec<f64> {
+        &self.b
+    }
+
+    pub fn c(&self) -> &Vec<f64> {
+        &self.c
+    }
+
+    /// `c_j + (W^T v)_j`
+    fn hidden_logits(&self, v: &[bool]) -> Vec<f64> {
+        (0..self.c.len())
+            .map(|j| {
+                self.c[j]
+                    + v.iter()
+                        .enumerate()
+                        .map(|(i, &vi)| self.w[(i, j)] * bit(vi))
+                        .sum::<f64>()
+            })
+            .collect()
+    }
+
+    /// `-b^T v - Σ_j ln(1 + exp(c_j + (W^T v)_j))`
+    pub fn free_energy(&self, v: &[bool]) -> Result<f64, DistributionError> {
+        if v.len() != self.b.len() {
+            return Err(DistributionError::InvalidParameters(
+                RestrictedBoltzmannMachineError::DimensionMismatch.into(),
+            ));
+        }
+        let bv = v
+            .iter()
+            .zip(self.b.iter())
+            .map(|(&vi, bi)| bit(vi) * bi)
+            .sum::<f64>();
+        let softplus = self
+            .hidden_logits(v)
+            .into_iter()
+            .map(|z| z.max(0.0) + (-z.abs()).exp().ln_1p())
+            .sum::<f64>();
+
+        Ok(-bv - softplus)
+    }
+
+    pub fn sample_hidden(&self, v: &[bool], rng: &mut dyn RngCore) -> Vec<bool> {
+        self.hidden_logits(v)
+            .into_iter()
+            .map(|z| rng.gen_range(0.0..1.0) < sigmoid(z))
+            .collect()
+    }
+
+    pub fn sample_visible(&self, h: &[bool], rng: &mut dyn RngCore) -> Vec<bool> {
+        (0..self.b.len())
+            .map(|i| {
+                let z = self.b[i]
+                    + h.iter()
+                        .enumerate()
+                        .map(|(j, &hj)| self.w[(i, j)] * bit(hj))
+                        .sum::<f64>();
+                rng.gen_range(0.0..1.0) < sigmoid(z)
+            })
+            .collect()
+    }
+
+    /// Equivalent Ising model over `(v, h)` with `s = 2x - 1`:
+    /// `J_{i, nv + j} = W_ij / 4`, `h_i = b_i / 2 + Σ_j W_ij / 4` and `h_{nv + j} = c_j / 2 + Σ_i W_ij / 4`.
+    pub fn to_ising(&self) -> Result<IsingParams, DistributionError> {
+        let nv = self.b.len();
+        let nh = self.c.len();
+        let mut j = Matrix::new(nv + nh, nv + nh);
+        let mut h = self
+            .b
+            .iter()
+            .chain(self.c.iter())
+            .map(|bi| bi / 2.0)
+            .collect::<Vec<_>>();
+        for a in 0..nv {
+            for b in 0..nh {
+                let wab = self.w[(a, b)] / 4.0;
+                j[(a, nv + b)] = wab;
+                j[(nv + b, a)] = wab;
+                h[a] += wab;
+                h[nv + b] += wab;
+            }
+        }
+
+        IsingParams::new(j, h)
+    }
+}
+
+impl RandomVariable for RestrictedBoltzmannMachineParams {
+    type RestoreInfo = (usize, usize);
+
+    fn transform_vec(&self) -> (Vec<f64>, Self::RestoreInfo) {
+        (
+            [self.w.vec(), self.b.clone(), self.c.clone()].concat(),
+            (self.b.len(), self.c.len()),
+        )
+    }
+
+    fn len(&self) -> usize {
+        self.b.len() * self.c.len() + self.b.len() + self.c.len()
+    }
+
+    fn restore(v: &[f64], info: &Self::RestoreInfo) -> Result<Self, DistributionError> {
+        let (nv, nh) = *info;
+        if v.len() != nv * nh + nv + nh {
+            return Err(DistributionError::InvalidRestoreVector);
+        }
+        let w = Matrix::from(nv, v[0..nv * nh].to_vec())?;
+        let b = v[nv * nh..nv * nh + nv].to_vec();
+        let c = v[nv * nh + nv..].to_vec();
+
+        Self::new(w, b, c)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bit_vectors, BinarySampler, Distribution, RestrictedBoltzmannMachine,
+        RestrictedBoltzmannMachineParams, SamplableDistribution,
+    };
+    use opensrdk_linear_algebra::*;
+    use rand::prelude::*;
+
+    #[test]
+    fn it_works() {
+        let w = Matrix::from(3, vec![1.0, -0.5, 0.8, 0.3, 0.2, -1.2]).unwrap();
+        let theta =
+            RestrictedBoltzmannMachineParams::new(w, vec![0.1, -0.3, 0.2], vec![0.0, 0.5]).unwrap();
+        let states = bit_vectors(3).into_iter().collect::<Vec<_>>();
+        let rbm = RestrictedBoltzmannMachine::default();
+        let z = states
+            .iter()
+            .map(|v| rbm.p_kernel(v, &theta).unwrap())
+            .sum::<f64>();
+
+        let samplers = vec![
+            BinarySampler::Gibbs { sweeps: 20 },
+            BinarySampler::SwendsenWang { sweeps: 20 },
+        ];
+        for sampler in samplers {
+            let rbm = RestrictedBoltzmannMachine::new(sampler);
+            let mut rng = StdRng::from_seed([1; 32]);
+            let m = 20000;
+            let mut count = vec![0usize; states.len()];
+            for _ in 0..m {
+                let v = rbm.sample(&theta, &mut rng).unwrap();
+                count[states.iter().position(|s| *s == v).unwrap()] += 1;
+            }
+            for (s, c) in states.iter().zip(count.iter()) {
+                let p = rbm.p_kernel(s, &theta).unwrap() / z;
+                assert!((*c as f64 / m as f64 - p).abs() < 0.02);
+            }
+        }
+    }
+}
@@ -0,0 +1,15 @@
+pub mod multivariate;
+pub mod univariate;
+
+pub use multivariate::*;
+pub use univariate::*;
+
+use special::Gamma;
+
+/// `ln C(n, k)`
+fn ln_binomial(n: u64, k: u64) -> f64 {
+    let n = n as f64;
+    let k = k as f64;
+
+    (n + 1.0).ln_gamma().0 - (k + 1.0).ln_gamma().0 - (n - k + 1.0).ln_gamma().0
+}
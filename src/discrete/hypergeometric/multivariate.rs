@@ -0,0 +1,190 @@
+use super::ln_binomial;
+use crate::{DependentJoint, Distribution, IndependentJoint, RandomVariable};
+use crate::{DiscreteDistribution, DistributionError, SamplableDistribution};
+use rand::prelude::*;
+use std::{ops::BitAnd, ops::Mul};
+
+/// Multivariate hypergeometric distribution
+/// Counts of each category in `n` draws without replacement from an urn with `K_k` items of category `k`.
+#[derive(Clone, Debug)]
+pub struct MultivariateHypergeometric;
+
+#[derive(thiserror::Error, Debug)]
+pub enum MultivariateHypergeometricError {
+    #[error("Dimension mismatch")]
+    DimensionMismatch,
+    #[error("Length of 'K' must be >= 2")]
+    KLenMustBeGTE2,
+    #[error("'n' must be less than or equal to the population size")]
+    NMustBeLTEPopulation,
+}
+
+impl Distribution for MultivariateHypergeometric {
+    type Value = Vec<u64>;
+    type Condition = MultivariateHypergeometricParams;
+
+    /// `Π_k C(K_k, x_k) / C(N, n)`
+    fn p_kernel(&self, x: &Self::Value, theta: &Self::Condition) -> Result<f64, DistributionError> {
+        let k = theta.k();
+        if x.len() != k.len() {
+            return Err(DistributionError::InvalidParameters(
+                MultivariateHypergeometricError::DimensionMismatch.into(),
+            ));
+        }
+        if x.iter().sum::<u64>() != theta.n() || x.iter().zip(k.iter()).any(|(xi, ki)| ki < xi) {
+            return Ok(0.0);
+        }
+
+        let ln_p = x
+            .iter()
+            .zip(k.iter())
+            .map(|(&xi, &ki)| ln_binomial(ki, xi))
+            .sum::<f64>()
+            - ln_binomial(theta.population(), theta.n());
+
+        Ok(ln_p.exp())
+    }
+}
+
+impl DiscreteDistribution for MultivariateHypergeometric {}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MultivariateHypergeometricParams {
+    n: u64,
+    k: Vec<u64>,
+}
+
+impl MultivariateHypergeometricParams {
+    /// - `n`: Number of draws
+    /// - `k`: Number of items of each category in the urn
+    pub fn new(n: u64, k: Vec<u64>) -> Result<Self, DistributionError> {
+        if k.len() < 2 {
+            return Err(DistributionError::InvalidParameters(
+                MultivariateHypergeometricError::KLenMustBeGTE2.into(),
+            ));
+        }
+        if k.iter().sum::<u64>() < n {
+            return Err(DistributionError::InvalidParameters(
+                MultivariateHypergeometricError::NMustBeLTEPopulation.into(),
+            ));
+        }
+
+        Ok(Self { n, k })
+    }
+
+    pub fn n(&self) -> u64 {
+        self.n
+    }
+
+    pub fn k(&self) -> &[u64] {
+        &self.k
+    }
+
+    /// `N = Σ_k K_k`
+    pub fn population(&self) -> u64 {
+        self.k.iter().sum()
+    }
+}
+
+impl RandomVariable for MultivariateHypergeometricParams {
+    type RestoreInfo = (u64, Vec<u64>);
+
+    fn transform_vec(&self) -> (Vec<f64>, Self::RestoreInfo) {
+        (vec![], (self.n, self.k.clone()))
+    }
+
+    fn len(&self) -> usize {
+        0usize
+    }
+
+    fn restore(v: &[f64], info: &Self::RestoreInfo) -> Result<Self, DistributionError> {
+        if !v.is_empty() {
+            return Err(DistributionError::InvalidRestoreVector);
+        }
+        Self::new(info.0, info.1.clone())
+    }
+}
+
+impl<Rhs, TRhs> Mul<Rhs> for MultivariateHypergeometric
+where
+    Rhs: Distribution<Value = TRhs, Condition = MultivariateHypergeometricParams>,
+    TRhs: RandomVariable,
+{
+    type Output = IndependentJoint<Self, Rhs, Vec<u64>, TRhs, MultivariateHypergeometricParams>;
+
+    fn mul(self, rhs: Rhs) -> Self::Output {
+        IndependentJoint::new(self, rhs)
+    }
+}
+
+impl<Rhs, URhs> BitAnd<Rhs> for MultivariateHypergeometric
+where
+    Rhs: Distribution<Value = MultivariateHypergeometricParams, Condition = URhs>,
+    URhs: RandomVariable,
+{
+    type Output = DependentJoint<Self, Rhs, Vec<u64>, MultivariateHypergeometricParams, URhs>;
+
+    fn bitand(self, rhs: Rhs) -> Self::Output {
+        DependentJoint::new(self, rhs)
+    }
+}
+
+impl SamplableDistribution for MultivariateHypergeometric {
+    /// Draws items from the urn one by one without replacement
+    fn sample(
+        &self,
+        theta: &Self::Condition,
+        rng: &mut dyn RngCore,
+    ) -> Result<Self::Value, DistributionError> {
+        let mut rest = theta.k().to_vec();
+        let mut rest_total = theta.population();
+        let mut x = vec![0u64; rest.len()];
+
+        for _ in 0..theta.n() {
+            let mut u = rng.gen_range(0..rest_total);
+            for (i, ri) in rest.iter_mut().enumerate() {
+                if u < *ri {
+                    *ri -= 1;
+                    x[i] += 1;
+                    break;
+                }
+                u -= *ri;
+            }
+            rest_total -= 1;
+        }
+
+        Ok(x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Distribution, MultivariateHypergeometric, MultivariateHypergeometricParams,
+        SamplableDistribution,
+    };
+    use rand::prelude::*;
+
+    #[test]
+    fn it_works() {
+        let hypergeometric = MultivariateHypergeometric;
+        let mut rng = StdRng::from_seed([1; 32]);
+        let theta = MultivariateHypergeometricParams::new(5, vec![3, 4, 2]).unwrap();
+
+        let x = hypergeometric.sample(&theta, &mut rng).unwrap();
+        assert_eq!(x.iter().sum::<u64>(), 5);
+        assert!(x.iter().zip(theta.k().iter()).all(|(xi, ki)| xi <= ki));
+
+        let mut sum = 0.0;
+        for x0 in 0..=3u64 {
+            for x1 in 0..=4u64 {
+                if x0 + x1 <= 5 {
+                    sum += hypergeometric
+                        .p_kernel(&vec![x0, x1, 5 - x0 - x1], &theta)
+                        .unwrap();
+                }
+            }
+        }
+        assert!((sum - 1.0).abs() < 1e-10);
+    }
+}
@@ -0,0 +1,188 @@
+use super::ln_binomial;
+use crate::{DependentJoint, Distribution, IndependentJoint, RandomVariable};
+use crate::{DiscreteDistribution, DistributionError, SamplableDistribution};
+use rand::prelude::*;
+use std::{ops::BitAnd, ops::Mul};
+
+/// Hypergeometric distribution
+/// Number of successes in `n` draws without replacement from a population of `N` items with `K` successes.
+#[derive(Clone, Debug)]
+pub struct Hypergeometric;
+
+#[derive(thiserror::Error, Debug)]
+pub enum HypergeometricError {
+    #[error("'K' must be less than or equal to 'N'")]
+    KMustBeLTEN,
+    #[error("'n' must be less than or equal to 'N'")]
+    NMustBeLTEPopulation,
+}
+
+impl Distribution for Hypergeometric {
+    type Value = u64;
+    type Condition = HypergeometricParams;
+
+    /// `C(K, x) C(N - K, n - x) / C(N, n)`
+    fn p_kernel(&self, x: &Self::Value, theta: &Self::Condition) -> Result<f64, DistributionError> {
+        let x = *x;
+        if x < theta.min() || theta.max() < x {
+            return Ok(0.0);
+        }
+        let population = theta.population();
+        let k = theta.k();
+        let n = theta.n();
+
+        Ok(
+            (ln_binomial(k, x) + ln_binomial(population - k, n - x) - ln_binomial(population, n))
+                .exp(),
+        )
+    }
+}
+
+impl DiscreteDistribution for Hypergeometric {}
+
+impl<Rhs, TRhs> Mul<Rhs> for Hypergeometric
+where
+    Rhs: Distribution<Value = TRhs, Condition = HypergeometricParams>,
+    TRhs: RandomVariable,
+{
+    type Output = IndependentJoint<Self, Rhs, u64, TRhs, HypergeometricParams>;
+
+    fn mul(self, rhs: Rhs) -> Self::Output {
+        IndependentJoint::new(self, rhs)
+    }
+}
+
+impl<Rhs, URhs> BitAnd<Rhs> for Hypergeometric
+where
+    Rhs: Distribution<Value = HypergeometricParams, Condition = URhs>,
+    URhs: RandomVariable,
+{
+    type Output = DependentJoint<Self, Rhs, u64, HypergeometricParams, URhs>;
+
+    fn bitand(self, rhs: Rhs) -> Self::Output {
+        DependentJoint::new(self, rhs)
+    }
+}
+
+impl SamplableDistribution for Hypergeometric {
+    /// Inversion along the support with the ratio `p(x + 1) / p(x)`
+    fn sample(
+        &self,
+        theta: &Self::Condition,
+        rng: &mut dyn RngCore,
+    ) -> Result<Self::Value, DistributionError> {
+        let population = theta.population() as f64;
+        let k = theta.k() as f64;
+        let n = theta.n() as f64;
+        let min = theta.min();
+        let max = theta.max();
+
+        let u = rng.gen_range(0.0..1.0);
+        let mut x = min;
+        let mut p = self.p_kernel(&min, theta)?;
+        let mut cdf = p;
+        while cdf <= u && x < max {
+            let xf = x as f64;
+            p *= (k - xf) * (n - xf) / ((xf + 1.0) * (population - k - n + xf + 1.0));
+            x += 1;
+            cdf += p;
+        }
+
+        Ok(x)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct HypergeometricParams {
+    population: u64,
+    k: u64,
+    n: u64,
+}
+
+impl HypergeometricParams {
+    /// - `population`: Population size `N`
+    /// - `k`: Number of successes `K` in the population
+    /// - `n`: Number of draws
+    pub fn new(population: u64, k: u64, n: u64) -> Result<Self, DistributionError> {
+        if population < k {
+            return Err(DistributionError::InvalidParameters(
+                HypergeometricError::KMustBeLTEN.into(),
+            ));
+        }
+        if population < n {
+            return Err(DistributionError::InvalidParameters(
+                HypergeometricError::NMustBeLTEPopulation.into(),
+            ));
+        }
+
+        Ok(Self { population, k, n })
+    }
+
+    pub fn population(&self) -> u64 {
+        self.population
+    }
+
+    pub fn k(&self) -> u64 {
+        self.k
+    }
+
+    pub fn n(&self) -> u64 {
+        self.n
+    }
+
+    /// `max(0, n + K - N)`
+    pub fn min(&self) -> u64 {
+        (self.n + self.k).saturating_sub(self.population)
+    }
+
+    /// `min(n, K)`
+    pub fn max(&self) -> u64 {
+        self.n.min(self.k)
+    }
+}
+
+impl RandomVariable for HypergeometricParams {
+    type RestoreInfo = (u64, u64, u64);
+
+    fn transform_vec(&self) -> (Vec<f64>, Self::RestoreInfo) {
+        (vec![], (self.population, self.k, self.n))
+    }
+
+    fn len(&self) -> usize {
+        0usize
+    }
+
+    fn restore(v: &[f64], info: &Self::RestoreInfo) -> Result<Self, DistributionError> {
+        if !v.is_empty() {
+            return Err(DistributionError::InvalidRestoreVector);
+        }
+        Self::new(info.0, info.1, info.2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Distribution, Hypergeometric, HypergeometricParams, SamplableDistribution};
+    use rand::prelude::*;
+
+    #[test]
+    fn it_works() {
+        let hypergeometric = Hypergeometric;
+        let mut rng = StdRng::from_seed([1; 32]);
+        let theta = HypergeometricParams::new(20, 7, 12).unwrap();
+
+        let p = (0..=12)
+            .map(|x| hypergeometric.p_kernel(&x, &theta).unwrap())
+            .collect::<Vec<_>>();
+        assert!((p.iter().sum::<f64>() - 1.0).abs() < 1e-10);
+
+        let n = 100000;
+        let mut count = vec![0usize; p.len()];
+        for _ in 0..n {
+            count[hypergeometric.sample(&theta, &mut rng).unwrap() as usize] += 1;
+        }
+        for (ci, pi) in count.iter().zip(p.iter()) {
+            assert!((*ci as f64 / n as f64 - pi).abs() < 0.01);
+        }
+    }
+}
@@ -19,12 +19,8 @@ impl Distribution for Bernoulli {
     type Value = bool;
     type Condition = BernoulliParams;
 
-    fn p_kernel(
-        &self,
-        _x: &Self::Value,
-        theta: &Self::Condition,
-    ) -> Result<f64, DistributionError> {
-        Ok(theta.p())
+    fn p_kernel(&self, x: &Self::Value, theta: &Self::Condition) -> Result<f64, DistributionError> {
+        Ok(if *x { theta.p() } else { 1.0 - theta.p() })
     }
 }
 
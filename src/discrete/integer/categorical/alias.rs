@@ -0,0 +1,81 @@
+use crate::{CategoricalError, DistributionError};
+use rand::prelude::*;
+
+/// Walker's alias table for `O(1)` sampling of a categorical distribution after `O(K)` construction.
+#[derive(Clone, Debug)]
+pub struct AliasTable {
+    prob: Vec<f64>,
+    alias: Vec<usize>,
+}
+
+impl AliasTable {
+    /// `p` needs to be non-negative, but need not sum to one.
+    pub fn new(p: &[f64]) -> Result<Self, DistributionError> {
+        let k = p.len();
+        if k == 0 {
+            return Err(DistributionError::InvalidParameters(
+                CategoricalError::PMustBeProbability.into(),
+            ));
+        }
+        if p.iter().any(|&pi| pi < 0.0 || !pi.is_finite()) {
+            return Err(DistributionError::InvalidParameters(
+                CategoricalError::PMustBeProbability.into(),
+            ));
+        }
+        let sum = p.iter().sum::<f64>();
+        if sum <= 0.0 {
+            return Err(DistributionError::InvalidParameters(
+                CategoricalError::SumOfPMustBeOne.into(),
+            ));
+        }
+
+        let mut scaled = p.iter().map(|&pi| pi * k as f64 / sum).collect::<Vec<_>>();
+        let mut prob = vec![1.0; k];
+        let mut alias = (0..k).collect::<Vec<_>>();
+        let (mut small, mut large): (Vec<usize>, Vec<usize>) =
+            (0..k).partition(|&i| scaled[i] < 1.0);
+
+        while let (Some(s), Some(&l)) = (small.pop(), large.last()) {
+            prob[s] = scaled[s];
+            alias[s] = l;
+            scaled[l] -= 1.0 - scaled[s];
+            if scaled[l] < 1.0 {
+                large.pop();
+                small.push(l);
+            }
+        }
+
+        Ok(Self { prob, alias })
+    }
+
+    pub fn sample(&self, rng: &mut dyn RngCore) -> usize {
+        let i = rng.gen_range(0..self.prob.len());
+        if rng.gen_range(0.0..1.0) < self.prob[i] {
+            i
+        } else {
+            self.alias[i]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::AliasTable;
+    use rand::prelude::*;
+
+    #[test]
+    fn it_works() {
+        let mut rng = StdRng::from_seed([1; 32]);
+        let p = vec![0.1, 0.2, 0.3, 0.4];
+        let table = AliasTable::new(&p).unwrap();
+
+        let n = 100000;
+        let mut count = vec![0usize; p.len()];
+        for _ in 0..n {
+            count[table.sample(&mut rng)] += 1;
+        }
+        for (ci, pi) in count.iter().zip(p.iter()) {
+            assert!((*ci as f64 / n as f64 - pi).abs() < 0.01);
+        }
+    }
+}
@@ -0,0 +1,180 @@
+use crate::{
+    AliasTable, CategoricalError, CategoricalParams, DependentJoint, DiscreteDistribution,
+    Distribution, DistributionError, IndependentJoint, RandomVariable, SamplableDistribution,
+};
+use std::marker::PhantomData;
+use std::ops::{BitAnd, Mul};
+
+/// Categorical distribution over arbitrary labels `T`
+#[derive(Clone, Debug)]
+pub struct LabeledCategorical<T>
+where
+    T: RandomVariable + Eq,
+{
+    phantom: PhantomData<T>,
+}
+
+impl<T> LabeledCategorical<T>
+where
+    T: RandomVariable + Eq,
+{
+    pub fn new() -> Self {
+        Self {
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T> Distribution for LabeledCategorical<T>
+where
+    T: RandomVariable + Eq,
+{
+    type Value = T;
+    type Condition = LabeledCategoricalParams<T>;
+
+    fn p_kernel(&self, x: &Self::Value, theta: &Self::Condition) -> Result<f64, DistributionError> {
+        Ok(theta
+            .labels()
+            .iter()
+            .zip(theta.p().iter())
+            .filter(|(label, _)| *label == x)
+            .map(|(_, &pi)| pi)
+            .sum())
+    }
+}
+
+impl<T> DiscreteDistribution for LabeledCategorical<T> where T: RandomVariable + Eq {}
+
+impl<T, Rhs, TRhs> Mul<Rhs> for LabeledCategorical<T>
+where
+    T: RandomVariable + Eq,
+    Rhs: Distribution<Value = TRhs, Condition = LabeledCategoricalParams<T>>,
+    TRhs: RandomVariable,
+{
+    type Output = IndependentJoint<Self, Rhs, T, TRhs, LabeledCategoricalParams<T>>;
+
+    fn mul(self, rhs: Rhs) -> Self::Output {
+        IndependentJoint::new(self, rhs)
+    }
+}
+
+impl<T, Rhs, URhs> BitAnd<Rhs> for LabeledCategorical<T>
+where
+    T: RandomVariable + Eq,
+    Rhs: Distribution<Value = LabeledCategoricalParams<T>, Condition = URhs>,
+    URhs: RandomVariable,
+{
+    type Output = DependentJoint<Self, Rhs, T, LabeledCategoricalParams<T>, URhs>;
+
+    fn bitand(self, rhs: Rhs) -> Self::Output {
+        DependentJoint::new(self, rhs)
+    }
+}
+
+impl<T> SamplableDistribution for LabeledCategorical<T>
+where
+    T: RandomVariable + Eq,
+{
+    /// Uses the alias table cached in the params, so repeated draws cost `O(1)` each.
+    fn sample(
+        &self,
+        theta: &Self::Condition,
+        rng: &mut dyn rand::RngCore,
+    ) -> Result<Self::Value, DistributionError> {
+        let k = theta.alias_table().sample(rng);
+
+        Ok(theta.labels()[k].clone())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct LabeledCategoricalParams<T>
+where
+    T: RandomVariable + Eq,
+{
+    labels: Vec<T>,
+    categorical: CategoricalParams,
+    alias_table: AliasTable,
+}
+
+impl<T> LabeledCategoricalParams<T>
+where
+    T: RandomVariable + Eq,
+{
+    /// - `labels`: Value of each category
+    /// - `p`: Probability of each category
+    pub fn new(labels: Vec<T>, p: Vec<f64>) -> Result<Self, DistributionError> {
+        if labels.len() != p.len() {
+            return Err(DistributionError::InvalidParameters(
+                CategoricalError::IndexOutOfRange.into(),
+            ));
+        }
+        let alias_table = AliasTable::new(&p)?;
+        let categorical = CategoricalParams::new(p)?;
+
+        Ok(Self {
+            labels,
+            categorical,
+            alias_table,
+        })
+    }
+
+    pub fn labels(&self) -> &Vec<T> {
+        &self.labels
+    }
+
+    pub fn p(&self) -> &Vec<f64> {
+        self.categorical.p()
+    }
+
+    pub fn categorical(&self) -> &CategoricalParams {
+        &self.categorical
+    }
+
+    pub fn alias_table(&self) -> &AliasTable {
+        &self.alias_table
+    }
+}
+
+impl<T> RandomVariable for LabeledCategoricalParams<T>
+where
+    T: RandomVariable + Eq,
+{
+    type RestoreInfo = Vec<T>;
+
+    fn transform_vec(&self) -> (Vec<f64>, Self::RestoreInfo) {
+        (self.p().clone(), self.labels.clone())
+    }
+
+    fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    fn restore(v: &[f64], info: &Self::RestoreInfo) -> Result<Self, DistributionError> {
+        if v.len() != info.len() {
+            return Err(DistributionError::InvalidRestoreVector);
+        }
+        Self::new(info.clone(), v.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Distribution, LabeledCategorical, LabeledCategoricalParams, SamplableDistribution,
+    };
+    use rand::prelude::*;
+
+    #[test]
+    fn it_works() {
+        let mut rng = StdRng::from_seed([1; 32]);
+        let labeled = LabeledCategorical::new();
+        let theta =
+            LabeledCategoricalParams::new(vec![10u64, 20, 30], vec![0.2, 0.5, 0.3]).unwrap();
+
+        let x = labeled.sample(&theta, &mut rng).unwrap();
+        assert!(theta.labels().contains(&x));
+        assert_eq!(labeled.p_kernel(&20, &theta).unwrap(), 0.5);
+        assert_eq!(labeled.p_kernel(&40, &theta).unwrap(), 0.0);
+    }
+}
@@ -0,0 +1,206 @@
+use crate::{
+    AliasTable, CategoricalError, CategoricalParams, ConditionDifferentiableDistribution,
+    DependentJoint, DiscreteDistribution, Distribution, DistributionError, IndependentJoint,
+    RandomVariable, SamplableDistribution,
+};
+use std::ops::{BitAnd, Mul};
+
+/// Categorical distribution parameterized by unnormalized logits
+#[derive(Clone, Debug)]
+pub struct CategoricalLogits;
+
+impl Distribution for CategoricalLogits {
+    type Value = usize;
+    type Condition = CategoricalLogitsParams;
+
+    fn p_kernel(&self, x: &Self::Value, theta: &Self::Condition) -> Result<f64, DistributionError> {
+        Ok(theta.ln_p(*x)?.exp())
+    }
+}
+
+impl DiscreteDistribution for CategoricalLogits {}
+
+impl<Rhs, TRhs> Mul<Rhs> for CategoricalLogits
+where
+    Rhs: Distribution<Value = TRhs, Condition = CategoricalLogitsParams>,
+    TRhs: RandomVariable,
+{
+    type Output = IndependentJoint<Self, Rhs, usize, TRhs, CategoricalLogitsParams>;
+
+    fn mul(self, rhs: Rhs) -> Self::Output {
+        IndependentJoint::new(self, rhs)
+    }
+}
+
+impl<Rhs, URhs> BitAnd<Rhs> for CategoricalLogits
+where
+    Rhs: Distribution<Value = CategoricalLogitsParams, Condition = URhs>,
+    URhs: RandomVariable,
+{
+    type Output = DependentJoint<Self, Rhs, usize, CategoricalLogitsParams, URhs>;
+
+    fn bitand(self, rhs: Rhs) -> Self::Output {
+        DependentJoint::new(self, rhs)
+    }
+}
+
+impl ConditionDifferentiableDistribution for CategoricalLogits {
+    /// `e_x - softmax(logits)`
+    fn ln_diff_condition(
+        &self,
+        x: &Self::Value,
+        theta: &Self::Condition,
+    ) -> Result<Vec<f64>, DistributionError> {
+        let k = *x;
+        if theta.logits().len() <= k {
+            return Err(DistributionError::InvalidParameters(
+                CategoricalError::IndexOutOfRange.into(),
+            ));
+        }
+
+        Ok(theta
+            .p()
+            .into_iter()
+            .enumerate()
+            .map(|(i, pi)| if i == k { 1.0 - pi } else { -pi })
+            .collect())
+    }
+}
+
+impl SamplableDistribution for CategoricalLogits {
+    fn sample(
+        &self,
+        theta: &Self::Condition,
+        rng: &mut dyn rand::RngCore,
+    ) -> Result<Self::Value, DistributionError> {
+        Ok(theta.alias.sample(rng))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CategoricalLogitsParams {
+    logits: Vec<f64>,
+    ln_sum_exp: f64,
+    alias: AliasTable,
+}
+
+impl CategoricalLogitsParams {
+    /// `p = softmax(logits)`, normalized internally with log-sum-exp.
+    /// The alias table for sampling is built here.
+    pub fn new(logits: Vec<f64>) -> Result<Self, DistributionError> {
+        if logits.is_empty() || logits.iter().any(|l| l.is_nan() || *l == f64::INFINITY) {
+            return Err(DistributionError::InvalidParameters(
+                CategoricalError::PMustBeProbability.into(),
+            ));
+        }
+        let max = logits.iter().fold(f64::NEG_INFINITY, |m, &l| m.max(l));
+        if max == f64::NEG_INFINITY {
+            return Err(DistributionError::InvalidParameters(
+                CategoricalError::SumOfPMustBeOne.into(),
+            ));
+        }
+        let ln_sum_exp = max + logits.iter().map(|&l| (l - max).exp()).sum::<f64>().ln();
+        let alias = AliasTable::new(
+            &logits
+                .iter()
+                .map(|&l| (l - ln_sum_exp).exp())
+                .collect::<Vec<_>>(),
+        )?;
+
+        Ok(Self {
+            logits,
+            ln_sum_exp,
+            alias,
+        })
+    }
+
+    pub fn logits(&self) -> &Vec<f64> {
+        &self.logits
+    }
+
+    pub fn ln_sum_exp(&self) -> f64 {
+        self.ln_sum_exp
+    }
+
+    /// `ln p_k = logits_k - ln Σ exp(logits)`
+    pub fn ln_p(&self, k: usize) -> Result<f64, DistributionError> {
+        if self.logits.len() <= k {
+            return Err(DistributionError::InvalidParameters(
+                CategoricalError::IndexOutOfRange.into(),
+            ));
+        }
+
+        Ok(self.logits[k] - self.ln_sum_exp)
+    }
+
+    pub fn p(&self) -> Vec<f64> {
+        self.logits
+            .iter()
+            .map(|&l| (l - self.ln_sum_exp).exp())
+            .collect()
+    }
+
+    pub fn to_categorical(&self) -> Result<CategoricalParams, DistributionError> {
+        CategoricalParams::new(self.p())
+    }
+}
+
+impl RandomVariable for CategoricalLogitsParams {
+    type RestoreInfo = usize;
+
+    fn transform_vec(&self) -> (Vec<f64>, Self::RestoreInfo) {
+        (self.logits.clone(), self.logits.len())
+    }
+
+    fn len(&self) -> usize {
+        self.logits.len()
+    }
+
+    fn restore(v: &[f64], info: &Self::RestoreInfo) -> Result<Self, DistributionError> {
+        if v.len() != *info {
+            return Err(DistributionError::InvalidRestoreVector);
+        }
+        Self::new(v.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        CategoricalLogits, CategoricalLogitsParams, ConditionDifferentiableDistribution,
+        Distribution, SamplableDistribution,
+    };
+    use rand::prelude::*;
+
+    #[test]
+    fn it_works() {
+        let mut rng = StdRng::from_seed([1; 32]);
+        let theta = CategoricalLogitsParams::new(vec![1000.0, 1001.0, 999.0]).unwrap();
+
+        let sum = (0..3)
+            .map(|k| CategoricalLogits.p_kernel(&k, &theta).unwrap())
+            .sum::<f64>();
+        assert!((sum - 1.0).abs() < 1e-12);
+
+        let x = CategoricalLogits.sample(&theta, &mut rng).unwrap();
+        assert!(x < 3);
+    }
+
+    #[test]
+    fn it_works2() {
+        let logits = vec![0.5, -1.0, 2.0];
+        let theta = CategoricalLogitsParams::new(logits.clone()).unwrap();
+        let x = 1;
+        let h = 1e-6;
+
+        let f = CategoricalLogits.ln_diff_condition(&x, &theta).unwrap();
+        let ln_p = CategoricalLogits.p_kernel(&x, &theta).unwrap().ln();
+        for k in 0..logits.len() {
+            let mut logits_h = logits.clone();
+            logits_h[k] += h;
+            let theta_h = CategoricalLogitsParams::new(logits_h).unwrap();
+            let numerical = (CategoricalLogits.p_kernel(&x, &theta_h).unwrap().ln() - ln_p) / h;
+            assert!((f[k] - numerical).abs() < 1e-4);
+        }
+    }
+}
@@ -1,11 +1,15 @@
+pub mod alias;
+pub mod labeled;
+pub mod logits;
 pub mod params;
 
+pub use alias::*;
+pub use labeled::*;
+pub use logits::*;
 pub use params::*;
 
 use crate::*;
 use crate::{Distribution, DistributionError};
-use rand::distributions::WeightedIndex;
-use rand_distr::Distribution as RandDistribution;
 use std::ops::{BitAnd, Mul};
 
 #[derive(Clone, Debug)]
@@ -29,7 +33,7 @@ impl Distribution for Categorical {
 
     fn p_kernel(&self, x: &Self::Value, theta: &Self::Condition) -> Result<f64, DistributionError> {
         let k = *x;
-        if k < theta.p().len() {
+        if theta.p().len() <= k {
             return Err(DistributionError::InvalidParameters(
                 CategoricalError::IndexOutOfRange.into(),
             ));
@@ -70,33 +74,31 @@ impl SamplableDistribution for Categorical {
         theta: &Self::Condition,
         rng: &mut dyn rand::RngCore,
     ) -> Result<Self::Value, DistributionError> {
-        let index = match WeightedIndex::new(theta.p().clone()) {
-            Ok(v) => Ok(v),
-            Err(e) => Err(DistributionError::Others(e.into())),
-        }?
-        .sample(rng);
-
-        Ok(index)
+        Ok(theta.alias().sample(rng))
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{Categorical, CategoricalParams, Distribution, SamplableDistribution};
+    use crate::{Categorical, CategoricalParams, SamplableDistribution};
     use rand::prelude::*;
     #[test]
     fn it_works() {
         let mut rng = StdRng::from_seed([1; 32]);
 
-        // {
-        //     // 0.17522173557509294
-        //     println!("{}", rng.gen_range(0.0..=1.0));
-        //     return;
-        // }
         let p = vec![0.1, 0.2, 0.3, 0.4];
-        let theta = CategoricalParams::new(p).unwrap();
+        let theta = CategoricalParams::new(p.clone()).unwrap();
+
+        let n = 100000;
+        let mut count = vec![0usize; p.len()];
+        for _ in 0..n {
+            count[Categorical.sample(&theta, &mut rng).unwrap()] += 1;
+        }
+        for (ci, pi) in count.iter().zip(p.iter()) {
+            assert!((*ci as f64 / n as f64 - pi).abs() < 0.01);
+        }
 
-        let hoge = Categorical.sample(&theta, &mut rng).unwrap();
-        assert_eq!(hoge, 1);
+        assert!(CategoricalParams::new(vec![0.5, -0.5]).is_err());
+        assert!(CategoricalParams::new(vec![]).is_err());
     }
 }
@@ -1,18 +1,26 @@
-use crate::{DistributionError, RandomVariable};
+use crate::{AliasTable, DistributionError, RandomVariable};
 
 #[derive(Clone, Debug)]
 pub struct CategoricalParams {
     p: Vec<f64>,
+    alias: AliasTable,
 }
 
 impl CategoricalParams {
+    /// The alias table for sampling is built here, so `p` needs to be non-negative with a positive sum.
     pub fn new(p: Vec<f64>) -> Result<Self, DistributionError> {
-        Ok(Self { p })
+        let alias = AliasTable::new(&p)?;
+
+        Ok(Self { p, alias })
     }
 
     pub fn p(&self) -> &Vec<f64> {
         &self.p
     }
+
+    pub fn alias(&self) -> &AliasTable {
+        &self.alias
+    }
 }
 
 impl RandomVariable for CategoricalParams {
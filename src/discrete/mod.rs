@@ -1,11 +1,27 @@
+pub mod binary;
 pub mod geometric;
+pub mod hypergeometric;
 pub mod integer;
+pub mod negative_binomial;
 pub mod nomial;
+pub mod ordinal;
+pub mod permutation;
 pub mod poisson;
+pub mod power_law;
 pub mod uniform;
+pub mod zero_inflated;
 
+pub(crate) mod util;
+
+pub use binary::*;
 pub use geometric::*;
+pub use hypergeometric::*;
 pub use integer::*;
+pub use negative_binomial::*;
 pub use nomial::*;
+pub use ordinal::*;
+pub use permutation::*;
 pub use poisson::*;
+pub use power_law::*;
 pub use uniform::*;
+pub use zero_inflated::*;
@@ -0,0 +1,213 @@
+pub mod params;
+
+pub use params::*;
+
+use crate::{
+    ConditionDifferentiableDistribution, DependentJoint, Distribution, IndependentJoint,
+    RandomVariable, SamplableDistribution,
+};
+use crate::{DiscreteDistribution, DistributionError};
+use rand::prelude::*;
+use rand_distr::{Gamma as RandGamma, Poisson as RandPoisson};
+use special::Gamma;
+use std::marker::PhantomData;
+use std::{ops::BitAnd, ops::Mul};
+
+/// Negative binomial
+#[derive(Clone, Debug)]
+pub struct NegativeBinomial<T = ProbabilityNegativeBinomialParams>
+where
+    T: NegativeBinomialParams,
+{
+    phantom: PhantomData<T>,
+}
+
+impl<T> NegativeBinomial<T>
+where
+    T: NegativeBinomialParams,
+{
+    pub fn new() -> Self {
+        Self {
+            phantom: PhantomData,
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum NegativeBinomialError {
+    #[error("'r' must be positive")]
+    RMustBePositive,
+    #[error("'p' must be probability.")]
+    PMustBeProbability,
+    #[error("'μ' must be positive")]
+    MuMustBePositive,
+    #[error("'φ' must be positive")]
+    PhiMustBePositive,
+}
+
+impl<T> Distribution for NegativeBinomial<T>
+where
+    T: NegativeBinomialParams,
+{
+    type Value = u64;
+    type Condition = T;
+
+    fn p_kernel(&self, x: &Self::Value, theta: &Self::Condition) -> Result<f64, DistributionError> {
+        let r = theta.r();
+        let p = theta.p();
+        let x = *x as f64;
+
+        Ok(
+            ((x + r).ln_gamma().0 - r.ln_gamma().0 - (x + 1.0).ln_gamma().0
+                + r * p.ln()
+                + x * (1.0 - p).ln())
+            .exp(),
+        )
+    }
+}
+
+impl<T> DiscreteDistribution for NegativeBinomial<T> where T: NegativeBinomialParams {}
+
+impl<T, Rhs, TRhs> Mul<Rhs> for NegativeBinomial<T>
+where
+    T: NegativeBinomialParams,
+    Rhs: Distribution<Value = TRhs, Condition = T>,
+    TRhs: RandomVariable,
+{
+    type Output = IndependentJoint<Self, Rhs, u64, TRhs, T>;
+
+    fn mul(self, rhs: Rhs) -> Self::Output {
+        IndependentJoint::new(self, rhs)
+    }
+}
+
+impl<T, Rhs, URhs> BitAnd<Rhs> for NegativeBinomial<T>
+where
+    T: NegativeBinomialParams,
+    Rhs: Distribution<Value = T, Condition = URhs>,
+    URhs: RandomVariable,
+{
+    type Output = DependentJoint<Self, Rhs, u64, T, URhs>;
+
+    fn bitand(self, rhs: Rhs) -> Self::Output {
+        DependentJoint::new(self, rhs)
+    }
+}
+
+impl ConditionDifferentiableDistribution for NegativeBinomial<ProbabilityNegativeBinomialParams> {
+    fn ln_diff_condition(
+        &self,
+        x: &Self::Value,
+        theta: &Self::Condition,
+    ) -> Result<Vec<f64>, DistributionError> {
+        let r = theta.r();
+        let p = theta.p();
+        let x = *x as f64;
+
+        let f_r = (x + r).digamma() - r.digamma() + p.ln();
+        let f_p = r / p - x / (1.0 - p);
+        Ok(vec![f_r, f_p])
+    }
+}
+
+impl ConditionDifferentiableDistribution for NegativeBinomial<MeanNegativeBinomialParams> {
+    fn ln_diff_condition(
+        &self,
+        x: &Self::Value,
+        theta: &Self::Condition,
+    ) -> Result<Vec<f64>, DistributionError> {
+        let mu = theta.mu();
+        let phi = theta.phi();
+        let x = *x as f64;
+
+        let f_mu = x / mu - (x + phi) / (phi + mu);
+        let f_phi =
+            (x + phi).digamma() - phi.digamma() + (phi / (phi + mu)).ln() + (mu - x) / (phi + mu);
+        Ok(vec![f_mu, f_phi])
+    }
+}
+
+impl<T> SamplableDistribution for NegativeBinomial<T>
+where
+    T: NegativeBinomialParams,
+{
+    /// Poisson distribution whose rate follows `Gamma(r, (1 - p) / p)`
+    fn sample(
+        &self,
+        theta: &Self::Condition,
+        rng: &mut dyn RngCore,
+    ) -> Result<Self::Value, DistributionError> {
+        let r = theta.r();
+        let p = theta.p();
+        if p == 1.0 {
+            return Ok(0);
+        }
+
+        let gamma = match RandGamma::new(r, (1.0 - p) / p) {
+            Ok(v) => Ok(v),
+            Err(e) => Err(DistributionError::Others(e.into())),
+        }?;
+        let lambda = rng.sample(gamma);
+        if lambda <= 0.0 {
+            return Ok(0);
+        }
+
+        let poisson = match RandPoisson::new(lambda) {
+            Ok(v) => Ok(v),
+            Err(e) => Err(DistributionError::Others(e.into())),
+        }?;
+
+        Ok(rng.sample(poisson) as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ConditionDifferentiableDistribution, Distribution, MeanNegativeBinomialParams,
+        NegativeBinomial, ProbabilityNegativeBinomialParams, SamplableDistribution,
+    };
+    use rand::prelude::*;
+
+    #[test]
+    fn it_works() {
+        let negative_binomial = NegativeBinomial::new();
+        let mut rng = StdRng::from_seed([1; 32]);
+        let theta = ProbabilityNegativeBinomialParams::new(3.0, 0.4).unwrap();
+
+        let x = negative_binomial.sample(&theta, &mut rng).unwrap();
+        println!("{}", x);
+
+        let sum = (0..500)
+            .into_iter()
+            .map(|x| negative_binomial.p_kernel(&x, &theta).unwrap())
+            .sum::<f64>();
+        assert!((sum - 1.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn it_works2() {
+        let negative_binomial = NegativeBinomial::new();
+        let x = 4;
+        let h = 1e-6;
+
+        let theta = MeanNegativeBinomialParams::new(2.5, 1.5).unwrap();
+        let theta_mu = MeanNegativeBinomialParams::new(2.5 + h, 1.5).unwrap();
+        let theta_phi = MeanNegativeBinomialParams::new(2.5, 1.5 + h).unwrap();
+
+        let f = negative_binomial.ln_diff_condition(&x, &theta).unwrap();
+        let ln_p = negative_binomial.p_kernel(&x, &theta).unwrap().ln();
+        let f_mu = (negative_binomial.p_kernel(&x, &theta_mu).unwrap().ln() - ln_p) / h;
+        let f_phi = (negative_binomial.p_kernel(&x, &theta_phi).unwrap().ln() - ln_p) / h;
+        assert!((f[0] - f_mu).abs() < 1e-4);
+        assert!((f[1] - f_phi).abs() < 1e-4);
+    }
+
+    #[test]
+    fn it_works3() {
+        assert!(ProbabilityNegativeBinomialParams::new(3.0, 1.0).is_err());
+        assert!(ProbabilityNegativeBinomialParams::new(3.0, 0.0).is_err());
+        assert!(ProbabilityNegativeBinomialParams::new(3.0, f64::NAN).is_err());
+        assert!(ProbabilityNegativeBinomialParams::new(3.0, 1.0 - 1e-12).is_ok());
+    }
+}
@@ -0,0 +1,125 @@
+use crate::{DistributionError, NegativeBinomialError, RandomVariable};
+
+pub trait NegativeBinomialParams: RandomVariable {
+    /// Number of successes
+    fn r(&self) -> f64;
+    /// Probability of success
+    fn p(&self) -> f64;
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProbabilityNegativeBinomialParams {
+    r: f64,
+    p: f64,
+}
+
+impl ProbabilityNegativeBinomialParams {
+    /// # Negative binomial
+    /// Number of failures before the `r`-th success with probability of success `p`.
+    /// `p` must be in (0, 1), because all mass is at 0 for `p = 1`.
+    pub fn new(r: f64, p: f64) -> Result<Self, DistributionError> {
+        if r <= 0.0 {
+            return Err(DistributionError::InvalidParameters(
+                NegativeBinomialError::RMustBePositive.into(),
+            ));
+        }
+        if p <= 0.0 || 1.0 <= p || p.is_nan() {
+            return Err(DistributionError::InvalidParameters(
+                NegativeBinomialError::PMustBeProbability.into(),
+            ));
+        }
+
+        Ok(Self { r, p })
+    }
+}
+
+impl NegativeBinomialParams for ProbabilityNegativeBinomialParams {
+    fn r(&self) -> f64 {
+        self.r
+    }
+
+    fn p(&self) -> f64 {
+        self.p
+    }
+}
+
+impl RandomVariable for ProbabilityNegativeBinomialParams {
+    type RestoreInfo = ();
+
+    fn transform_vec(&self) -> (Vec<f64>, Self::RestoreInfo) {
+        (vec![self.r, self.p], ())
+    }
+
+    fn len(&self) -> usize {
+        2usize
+    }
+
+    fn restore(v: &[f64], _: &Self::RestoreInfo) -> Result<Self, DistributionError> {
+        if v.len() != 2 {
+            return Err(DistributionError::InvalidRestoreVector);
+        }
+        Self::new(v[0], v[1])
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MeanNegativeBinomialParams {
+    mu: f64,
+    phi: f64,
+}
+
+impl MeanNegativeBinomialParams {
+    /// # Negative binomial
+    /// Mean `mu` and dispersion `phi`, so that the variance is `mu + mu^2 / phi`.
+    pub fn new(mu: f64, phi: f64) -> Result<Self, DistributionError> {
+        if mu <= 0.0 {
+            return Err(DistributionError::InvalidParameters(
+                NegativeBinomialError::MuMustBePositive.into(),
+            ));
+        }
+        if phi <= 0.0 {
+            return Err(DistributionError::InvalidParameters(
+                NegativeBinomialError::PhiMustBePositive.into(),
+            ));
+        }
+
+        Ok(Self { mu, phi })
+    }
+
+    pub fn mu(&self) -> f64 {
+        self.mu
+    }
+
+    pub fn phi(&self) -> f64 {
+        self.phi
+    }
+}
+
+impl NegativeBinomialParams for MeanNegativeBinomialParams {
+    fn r(&self) -> f64 {
+        self.phi
+    }
+
+    fn p(&self) -> f64 {
+        self.phi / (self.phi + self.mu)
+    }
+}
+
+impl RandomVariable for MeanNegativeBinomialParams {
+    type RestoreInfo = ();
+
+    fn transform_vec(&self) -> (Vec<f64>, Self::RestoreInfo) {
+        (vec![self.mu, self.phi], ())
+    }
+
+    fn len(&self) -> usize {
+        2usize
+    }
+
+    fn restore(v: &[f64], _: &Self::RestoreInfo) -> Result<Self, DistributionError> {
+        if v.len() != 2 {
+            return Err(DistributionError::InvalidRestoreVector);
+        }
+        Self::new(v[0], v[1])
+    }
+}
@@ -0,0 +1,161 @@
+pub mod params;
+
+pub use params::*;
+
+use crate::{
+    ConditionDifferentiableDistribution, DependentJoint, Distribution, IndependentJoint,
+    RandomVariable, SamplableDistribution,
+};
+use crate::{DiscreteDistribution, DistributionError};
+use rand::prelude::*;
+use rand_distr::{Beta as RandBeta, Binomial as RandBinomial};
+use special::Gamma;
+use std::{ops::BitAnd, ops::Mul};
+
+/// Beta-binomial distribution
+#[derive(Clone, Debug)]
+pub struct BetaBinomial;
+
+#[derive(thiserror::Error, Debug)]
+pub enum BetaBinomialError {
+    #[error("'α' must be positive")]
+    AlphaMustBePositive,
+    #[error("'β' must be positive")]
+    BetaMustBePositive,
+}
+
+fn ln_beta(a: f64, b: f64) -> f64 {
+    a.ln_gamma().0 + b.ln_gamma().0 - (a + b).ln_gamma().0
+}
+
+impl Distribution for BetaBinomial {
+    type Value = u64;
+    type Condition = BetaBinomialParams;
+
+    /// `C(n, x) B(x + α, n - x + β) / B(α, β)`
+    fn p_kernel(&self, x: &Self::Value, theta: &Self::Condition) -> Result<f64, DistributionError> {
+        let n = theta.n();
+        if n < *x {
+            return Ok(0.0);
+        }
+        let alpha = theta.alpha();
+        let beta = theta.beta();
+        let nf = n as f64;
+        let x = *x as f64;
+
+        let ln_binomial =
+            (nf + 1.0).ln_gamma().0 - (x + 1.0).ln_gamma().0 - (nf - x + 1.0).ln_gamma().0;
+
+        Ok((ln_binomial + ln_beta(x + alpha, nf - x + beta) - ln_beta(alpha, beta)).exp())
+    }
+}
+
+impl DiscreteDistribution for BetaBinomial {}
+
+impl<Rhs, TRhs> Mul<Rhs> for BetaBinomial
+where
+    Rhs: Distribution<Value = TRhs, Condition = BetaBinomialParams>,
+    TRhs: RandomVariable,
+{
+    type Output = IndependentJoint<Self, Rhs, u64, TRhs, BetaBinomialParams>;
+
+    fn mul(self, rhs: Rhs) -> Self::Output {
+        IndependentJoint::new(self, rhs)
+    }
+}
+
+impl<Rhs, URhs> BitAnd<Rhs> for BetaBinomial
+where
+    Rhs: Distribution<Value = BetaBinomialParams, Condition = URhs>,
+    URhs: RandomVariable,
+{
+    type Output = DependentJoint<Self, Rhs, u64, BetaBinomialParams, URhs>;
+
+    fn bitand(self, rhs: Rhs) -> Self::Output {
+        DependentJoint::new(self, rhs)
+    }
+}
+
+impl ConditionDifferentiableDistribution for BetaBinomial {
+    fn ln_diff_condition(
+        &self,
+        x: &Self::Value,
+        theta: &Self::Condition,
+    ) -> Result<Vec<f64>, DistributionError> {
+        let n = theta.n() as f64;
+        let alpha = theta.alpha();
+        let beta = theta.beta();
+        let x = *x as f64;
+
+        let f_alpha = (x + alpha).digamma() - (n + alpha + beta).digamma() - alpha.digamma()
+            + (alpha + beta).digamma();
+        let f_beta = (n - x + beta).digamma() - (n + alpha + beta).digamma() - beta.digamma()
+            + (alpha + beta).digamma();
+        Ok(vec![f_alpha, f_beta])
+    }
+}
+
+impl SamplableDistribution for BetaBinomial {
+    /// Binomial distribution whose probability follows `Beta(α, β)`
+    fn sample(
+        &self,
+        theta: &Self::Condition,
+        rng: &mut dyn RngCore,
+    ) -> Result<Self::Value, DistributionError> {
+        let beta = match RandBeta::new(theta.alpha(), theta.beta()) {
+            Ok(v) => Ok(v),
+            Err(e) => Err(DistributionError::Others(e.into())),
+        }?;
+        let p = rng.sample(beta);
+
+        let binomial = match RandBinomial::new(theta.n(), p) {
+            Ok(v) => Ok(v),
+            Err(e) => Err(DistributionError::Others(e.into())),
+        }?;
+
+        Ok(rng.sample(binomial))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        BetaBinomial, BetaBinomialParams, ConditionDifferentiableDistribution, Distribution,
+        SamplableDistribution,
+    };
+    use rand::prelude::*;
+
+    #[test]
+    fn it_works() {
+        let beta_binomial = BetaBinomial;
+        let mut rng = StdRng::from_seed([1; 32]);
+        let theta = BetaBinomialParams::new(10, 2.0, 3.0).unwrap();
+
+        let x = beta_binomial.sample(&theta, &mut rng).unwrap();
+        assert!(x <= 10);
+
+        let sum = (0..=10)
+            .into_iter()
+            .map(|x| beta_binomial.p_kernel(&x, &theta).unwrap())
+            .sum::<f64>();
+        assert!((sum - 1.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn it_works2() {
+        let beta_binomial = BetaBinomial;
+        let x = 4;
+        let h = 1e-6;
+
+        let theta = BetaBinomialParams::new(10, 2.0, 3.0).unwrap();
+        let theta_alpha = BetaBinomialParams::new(10, 2.0 + h, 3.0).unwrap();
+        let theta_beta = BetaBinomialParams::new(10, 2.0, 3.0 + h).unwrap();
+
+        let f = beta_binomial.ln_diff_condition(&x, &theta).unwrap();
+        let ln_p = beta_binomial.p_kernel(&x, &theta).unwrap().ln();
+        let f_alpha = (beta_binomial.p_kernel(&x, &theta_alpha).unwrap().ln() - ln_p) / h;
+        let f_beta = (beta_binomial.p_kernel(&x, &theta_beta).unwrap().ln() - ln_p) / h;
+        assert!((f[0] - f_alpha).abs() < 1e-4);
+        assert!((f[1] - f_beta).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,56 @@
+use crate::{BetaBinomialError, DistributionError, RandomVariable};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BetaBinomialParams {
+    n: u64,
+    alpha: f64,
+    beta: f64,
+}
+
+impl BetaBinomialParams {
+    pub fn new(n: u64, alpha: f64, beta: f64) -> Result<Self, DistributionError> {
+        if alpha <= 0.0 {
+            return Err(DistributionError::InvalidParameters(
+                BetaBinomialError::AlphaMustBePositive.into(),
+            ));
+        }
+        if beta <= 0.0 {
+            return Err(DistributionError::InvalidParameters(
+                BetaBinomialError::BetaMustBePositive.into(),
+            ));
+        }
+
+        Ok(Self { n, alpha, beta })
+    }
+
+    pub fn n(&self) -> u64 {
+        self.n
+    }
+
+    pub fn alpha(&self) -> f64 {
+        self.alpha
+    }
+
+    pub fn beta(&self) -> f64 {
+        self.beta
+    }
+}
+
+impl RandomVariable for BetaBinomialParams {
+    type RestoreInfo = u64;
+
+    fn transform_vec(&self) -> (Vec<f64>, Self::RestoreInfo) {
+        (vec![self.alpha, self.beta], self.n)
+    }
+
+    fn len(&self) -> usize {
+        2usize
+    }
+
+    fn restore(v: &[f64], info: &Self::RestoreInfo) -> Result<Self, DistributionError> {
+        if v.len() != 2 {
+            return Err(DistributionError::InvalidRestoreVector);
+        }
+        Self::new(*info, v[0], v[1])
+    }
+}
@@ -0,0 +1,196 @@
+pub mod params;
+
+pub use params::*;
+
+use crate::{
+    ConditionDifferentiableDistribution, DependentJoint, Distribution, IndependentJoint,
+    RandomVariable, SamplableDistribution,
+};
+use crate::{DiscreteDistribution, DistributionError};
+use rand::prelude::*;
+use rand_distr::{Binomial as RandBinomial, Dirichlet as RandDirichlet};
+use special::Gamma;
+use std::{ops::BitAnd, ops::Mul};
+
+/// Dirichlet-multinomial distribution
+/// Multinomial counts whose category probabilities follow `Dir(α)` and are integrated out.
+#[derive(Clone, Debug)]
+pub struct DirichletMultinomial;
+
+#[derive(thiserror::Error, Debug)]
+pub enum DirichletMultinomialError {
+    #[error("Dimension mismatch")]
+    DimensionMismatch,
+    #[error("Length of 'α' must be >= 2")]
+    AlphaLenMustBeGTE2,
+    #[error("'α' must be positive")]
+    AlphaMustBePositive,
+}
+
+impl Distribution for DirichletMultinomial {
+    type Value = Vec<u64>;
+    type Condition = DirichletMultinomialParams;
+
+    /// `n! Γ(A) / Γ(n + A) Π_k Γ(x_k + α_k) / (x_k! Γ(α_k))` where `A = Σ_k α_k`
+    fn p_kernel(&self, x: &Self::Value, theta: &Self::Condition) -> Result<f64, DistributionError> {
+        let alpha = theta.alpha();
+        if x.len() != alpha.len() {
+            return Err(DistributionError::InvalidParameters(
+                DirichletMultinomialError::DimensionMismatch.into(),
+            ));
+        }
+        let n = theta.n();
+        if x.iter().sum::<u64>() != n {
+            return Ok(0.0);
+        }
+        let n = n as f64;
+        let alpha_sum = alpha.iter().sum::<f64>();
+
+        let ln_p = (n + 1.0).ln_gamma().0 + alpha_sum.ln_gamma().0 - (n + alpha_sum).ln_gamma().0
+            + x.iter()
+                .zip(alpha.iter())
+                .map(|(&xk, &alphak)| {
+                    let xk = xk as f64;
+                    (xk + alphak).ln_gamma().0 - (xk + 1.0).ln_gamma().0 - alphak.ln_gamma().0
+                })
+                .sum::<f64>();
+
+        Ok(ln_p.exp())
+    }
+}
+
+impl DiscreteDistribution for DirichletMultinomial {}
+
+impl<Rhs, TRhs> Mul<Rhs> for DirichletMultinomial
+where
+    Rhs: Distribution<Value = TRhs, Condition = DirichletMultinomialParams>,
+    TRhs: RandomVariable,
+{
+    type Output = IndependentJoint<Self, Rhs, Vec<u64>, TRhs, DirichletMultinomialParams>;
+
+    fn mul(self, rhs: Rhs) -> Self::Output {
+        IndependentJoint::new(self, rhs)
+    }
+}
+
+impl<Rhs, URhs> BitAnd<Rhs> for DirichletMultinomial
+where
+    Rhs: Distribution<Value = DirichletMultinomialParams, Condition = URhs>,
+    URhs: RandomVariable,
+{
+    type Output = DependentJoint<Self, Rhs, Vec<u64>, DirichletMultinomialParams, URhs>;
+
+    fn bitand(self, rhs: Rhs) -> Self::Output {
+        DependentJoint::new(self, rhs)
+    }
+}
+
+impl ConditionDifferentiableDistribution for DirichletMultinomial {
+    /// `ψ(A) - ψ(n + A) + ψ(x_k + α_k) - ψ(α_k)` for each `α_k`
+    fn ln_diff_condition(
+        &self,
+        x: &Self::Value,
+        theta: &Self::Condition,
+    ) -> Result<Vec<f64>, DistributionError> {
+        let alpha = theta.alpha();
+        if x.len() != alpha.len() {
+            return Err(DistributionError::InvalidParameters(
+                DirichletMultinomialError::DimensionMismatch.into(),
+            ));
+        }
+        let n = theta.n() as f64;
+        let alpha_sum = alpha.iter().sum::<f64>();
+        let common = alpha_sum.digamma() - (n + alpha_sum).digamma();
+
+        Ok(x.iter()
+            .zip(alpha.iter())
+            .map(|(&xk, &alphak)| common + (xk as f64 + alphak).digamma() - alphak.digamma())
+            .collect())
+    }
+}
+
+impl SamplableDistribution for DirichletMultinomial {
+    /// Multinomial counts by conditional binomials, with probabilities drawn from `Dir(α)`
+    fn sample(
+        &self,
+        theta: &Self::Condition,
+        rng: &mut dyn RngCore,
+    ) -> Result<Self::Value, DistributionError> {
+        let alpha = theta.alpha();
+        let dirichlet = match RandDirichlet::new(alpha) {
+            Ok(v) => Ok(v),
+            Err(e) => Err(DistributionError::Others(e.into())),
+        }?;
+        let p = rng.sample(dirichlet);
+
+        let k = p.len();
+        let mut x = vec![0u64; k];
+        let mut rest_n = theta.n();
+        let mut rest_p = 1.0;
+        for i in 0..k - 1 {
+            if rest_n == 0 {
+                break;
+            }
+            let pi = (p[i] / rest_p).min(1.0).max(0.0);
+            let binomial = match RandBinomial::new(rest_n, pi) {
+                Ok(v) => Ok(v),
+                Err(e) => Err(DistributionError::Others(e.into())),
+            }?;
+            x[i] = rng.sample(binomial);
+            rest_n -= x[i];
+            rest_p -= p[i];
+        }
+        x[k - 1] = rest_n;
+
+        Ok(x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ConditionDifferentiableDistribution, DirichletMultinomial, DirichletMultinomialParams,
+        Distribution, SamplableDistribution,
+    };
+    use rand::prelude::*;
+
+    #[test]
+    fn it_works() {
+        let dirichlet_multinomial = DirichletMultinomial;
+        let mut rng = StdRng::from_seed([1; 32]);
+        let theta = DirichletMultinomialParams::new(6, vec![0.5, 1.0, 2.0]).unwrap();
+
+        let x = dirichlet_multinomial.sample(&theta, &mut rng).unwrap();
+        assert_eq!(x.iter().sum::<u64>(), 6);
+
+        let mut sum = 0.0;
+        for x0 in 0..=6u64 {
+            for x1 in 0..=6 - x0 {
+                sum += dirichlet_multinomial
+                    .p_kernel(&vec![x0, x1, 6 - x0 - x1], &theta)
+                    .unwrap();
+            }
+        }
+        assert!((sum - 1.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn it_works2() {
+        let dirichlet_multinomial = DirichletMultinomial;
+        let x = vec![1, 3, 2];
+        let alpha = vec![0.5, 1.0, 2.0];
+        let h = 1e-6;
+
+        let theta = DirichletMultinomialParams::new(6, alpha.clone()).unwrap();
+        let f = dirichlet_multinomial.ln_diff_condition(&x, &theta).unwrap();
+        let ln_p = dirichlet_multinomial.p_kernel(&x, &theta).unwrap().ln();
+
+        for k in 0..alpha.len() {
+            let mut alpha_h = alpha.clone();
+            alpha_h[k] += h;
+            let theta_h = DirichletMultinomialParams::new(6, alpha_h).unwrap();
+            let numerical = (dirichlet_multinomial.p_kernel(&x, &theta_h).unwrap().ln() - ln_p) / h;
+            assert!((f[k] - numerical).abs() < 1e-4);
+        }
+    }
+}
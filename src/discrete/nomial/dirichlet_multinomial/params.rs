@@ -0,0 +1,50 @@
+use crate::{DirichletMultinomialError, DistributionError, RandomVariable};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DirichletMultinomialParams {
+    n: u64,
+    alpha: Vec<f64>,
+}
+
+impl DirichletMultinomialParams {
+    /// - `n`: Number of trials
+    /// - `alpha`: Concentration of the Dirichlet prior over the category probabilities
+    pub fn new(n: u64, alpha: Vec<f64>) -> Result<Self, DistributionError> {
+        if alpha.len() < 2 {
+            return Err(DistributionError::InvalidParameters(
+                DirichletMultinomialError::AlphaLenMustBeGTE2.into(),
+            ));
+        }
+        if alpha.iter().any(|&alpha_i| alpha_i <= 0.0) {
+            return Err(DistributionError::InvalidParameters(
+                DirichletMultinomialError::AlphaMustBePositive.into(),
+            ));
+        }
+
+        Ok(Self { n, alpha })
+    }
+
+    pub fn n(&self) -> u64 {
+        self.n
+    }
+
+    pub fn alpha(&self) -> &[f64] {
+        &self.alpha
+    }
+}
+
+impl RandomVariable for DirichletMultinomialParams {
+    type RestoreInfo = u64;
+
+    fn transform_vec(&self) -> (Vec<f64>, Self::RestoreInfo) {
+        (self.alpha.clone(), self.n)
+    }
+
+    fn len(&self) -> usize {
+        self.alpha.len()
+    }
+
+    fn restore(v: &[f64], info: &Self::RestoreInfo) -> Result<Self, DistributionError> {
+        Self::new(*info, v.to_vec())
+    }
+}
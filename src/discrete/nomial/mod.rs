@@ -1,5 +1,9 @@
+pub mod beta_binomial;
 pub mod binomial;
+pub mod dirichlet_multinomial;
 pub mod multinomial;
 
+pub use beta_binomial::*;
 pub use binomial::*;
+pub use dirichlet_multinomial::*;
 pub use multinomial::*;
@@ -0,0 +1,133 @@
+use super::{ordered_ln_diff, ordered_p, ordered_sample};
+use crate::discrete::util::{sigmoid, sigmoid_diff};
+use crate::{
+    ConditionDifferentiableDistribution, DependentJoint, DiscreteDistribution, Distribution,
+    DistributionError, IndependentJoint, OrderedParams, RandomVariable, SamplableDistribution,
+};
+use rand::prelude::*;
+use std::{ops::BitAnd, ops::Mul};
+
+/// Ordered logistic distribution (proportional odds model)
+/// `p(x = k | η, c) = σ(c_k - η) - σ(c_{k-1} - η)` for `k = 0, ..., K - 1`
+#[derive(Clone, Debug)]
+pub struct OrderedLogistic;
+
+impl Distribution for OrderedLogistic {
+    type Value = usize;
+    type Condition = OrderedParams;
+
+    fn p_kernel(&self, x: &Self::Value, theta: &Self::Condition) -> Result<f64, DistributionError> {
+        ordered_p(*x, theta, sigmoid)
+    }
+}
+
+impl DiscreteDistribution for OrderedLogistic {}
+
+impl<Rhs, TRhs> Mul<Rhs> for OrderedLogistic
+where
+    Rhs: Distribution<Value = TRhs, Condition = OrderedParams>,
+    TRhs: RandomVariable,
+{
+    type Output = IndependentJoint<Self, Rhs, usize, TRhs, OrderedParams>;
+
+    fn mul(self, rhs: Rhs) -> Self::Output {
+        IndependentJoint::new(self, rhs)
+    }
+}
+
+impl<Rhs, URhs> BitAnd<Rhs> for OrderedLogistic
+where
+    Rhs: Distribution<Value = OrderedParams, Condition = URhs>,
+    URhs: RandomVariable,
+{
+    type Output = DependentJoint<Self, Rhs, usize, OrderedParams, URhs>;
+
+    fn bitand(self, rhs: Rhs) -> Self::Output {
+        DependentJoint::new(self, rhs)
+    }
+}
+
+impl ConditionDifferentiableDistribution for OrderedLogistic {
+    fn ln_diff_condition(
+        &self,
+        x: &Self::Value,
+        theta: &Self::Condition,
+    ) -> Result<Vec<f64>, DistributionError> {
+        ordered_ln_diff(*x, theta, sigmoid, sigmoid_diff)
+    }
+}
+
+impl SamplableDistribution for OrderedLogistic {
+    fn sample(
+        &self,
+        theta: &Self::Condition,
+        rng: &mut dyn RngCore,
+    ) -> Result<Self::Value, DistributionError> {
+        let u: f64 = rng.gen_range(0.0..1.0);
+
+        Ok(ordered_sample(theta, (u / (1.0 - u)).ln()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ConditionDifferentiableDistribution, Distribution, DistributionProduct, OrderedLogistic,
+        OrderedParams, SamplableDistribution,
+    };
+    use rand::prelude::*;
+
+    #[test]
+    fn it_works() {
+        let ordered = OrderedLogistic;
+        let mut rng = StdRng::from_seed([1; 32]);
+        let theta = OrderedParams::new(0.3, vec![-1.0, 0.5, 2.0]).unwrap();
+
+        let p = (0..4)
+            .map(|k| ordered.p_kernel(&k, &theta).unwrap())
+            .collect::<Vec<_>>();
+        assert!((p.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+
+        let n = 100000;
+        let mut count = vec![0usize; 4];
+        for _ in 0..n {
+            count[ordered.sample(&theta, &mut rng).unwrap()] += 1;
+        }
+        for (ci, pi) in count.iter().zip(p.iter()) {
+            assert!((*ci as f64 / n as f64 - pi).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn it_works2() {
+        let ordered = OrderedLogistic;
+        let h = 1e-6;
+        let v = vec![0.3, -1.0, 0.5, 2.0];
+
+        for x in 0..4 {
+            let theta = OrderedParams::new(v[0], v[1..].to_vec()).unwrap();
+            let f = ordered.ln_diff_condition(&x, &theta).unwrap();
+            let ln_p = ordered.p_kernel(&x, &theta).unwrap().ln();
+            for i in 0..v.len() {
+                let mut v_h = v.clone();
+                v_h[i] += h;
+                let theta_h = OrderedParams::new(v_h[0], v_h[1..].to_vec()).unwrap();
+                let numerical = (ordered.p_kernel(&x, &theta_h).unwrap().ln() - ln_p) / h;
+                assert!((f[i] - numerical).abs() < 1e-4);
+            }
+        }
+    }
+
+    #[test]
+    fn it_works_3() {
+        let model = vec![OrderedLogistic; 3].into_iter().joint();
+        let theta = vec![
+            OrderedParams::new(-0.5, vec![0.0, 1.0]).unwrap(),
+            OrderedParams::new(0.0, vec![0.0, 1.0]).unwrap(),
+            OrderedParams::new(1.5, vec![0.0, 1.0]).unwrap(),
+        ];
+
+        let f = model.ln_diff_condition(&vec![0, 1, 2], &theta).unwrap();
+        assert_eq!(f.len(), 9);
+    }
+}
@@ -0,0 +1,70 @@
+pub mod logistic;
+pub mod params;
+pub mod probit;
+
+pub use logistic::*;
+pub use params::*;
+pub use probit::*;
+
+use crate::DistributionError;
+
+#[derive(thiserror::Error, Debug)]
+pub enum OrderedError {
+    #[error("Cutpoints must not be empty")]
+    CutpointsMustNotBeEmpty,
+    #[error("Cutpoints must be strictly increasing")]
+    CutpointsMustBeIncreasing,
+    #[error("Category is out of range")]
+    CategoryOutOfRange,
+}
+
+/// `F(c_k - η) - F(c_{k-1} - η)` where `c_0 = -∞` and `c_K = ∞`
+fn ordered_p(
+    x: usize,
+    theta: &OrderedParams,
+    cdf: impl Fn(f64) -> f64,
+) -> Result<f64, DistributionError> {
+    let c = theta.cutpoints();
+    if c.len() < x {
+        return Err(DistributionError::InvalidParameters(
+            OrderedError::CategoryOutOfRange.into(),
+        ));
+    }
+    let eta = theta.eta();
+    let upper = if x < c.len() { cdf(c[x] - eta) } else { 1.0 };
+    let lower = if 0 < x { cdf(c[x - 1] - eta) } else { 0.0 };
+
+    Ok(upper - lower)
+}
+
+/// Gradient of `ln p(x)` with respect to `[η, c_1, ..., c_{K-1}]`
+fn ordered_ln_diff(
+    x: usize,
+    theta: &OrderedParams,
+    cdf: impl Fn(f64) -> f64,
+    pdf: impl Fn(f64) -> f64,
+) -> Result<Vec<f64>, DistributionError> {
+    let p = ordered_p(x, theta, &cdf)?;
+    let c = theta.cutpoints();
+    let eta = theta.eta();
+    let f_upper = if x < c.len() { pdf(c[x] - eta) } else { 0.0 };
+    let f_lower = if 0 < x { pdf(c[x - 1] - eta) } else { 0.0 };
+
+    let mut f = vec![0.0; c.len() + 1];
+    f[0] = -(f_upper - f_lower) / p;
+    if x < c.len() {
+        f[x + 1] = f_upper / p;
+    }
+    if 0 < x {
+        f[x] = -f_lower / p;
+    }
+
+    Ok(f)
+}
+
+/// Latent `z = η + ε` where `ε` follows the link distribution, cut by the cutpoints
+fn ordered_sample(theta: &OrderedParams, eps: f64) -> usize {
+    let z = theta.eta() + eps;
+
+    theta.cutpoints().iter().take_while(|&&c| c < z).count()
+}
@@ -0,0 +1,61 @@
+use crate::{DistributionError, OrderedError, RandomVariable};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct OrderedParams {
+    eta: f64,
+    cutpoints: Vec<f64>,
+}
+
+impl OrderedParams {
+    /// - `eta`: Linear predictor
+    /// - `cutpoints`: Strictly increasing `c_1 < ... < c_{K-1}` for `K` categories
+    pub fn new(eta: f64, cutpoints: Vec<f64>) -> Result<Self, DistributionError> {
+        if cutpoints.is_empty() {
+            return Err(DistributionError::InvalidParameters(
+                OrderedError::CutpointsMustNotBeEmpty.into(),
+            ));
+        }
+        if cutpoints.windows(2).any(|c| c[1] <= c[0]) {
+            return Err(DistributionError::InvalidParameters(
+                OrderedError::CutpointsMustBeIncreasing.into(),
+            ));
+        }
+
+        Ok(Self { eta, cutpoints })
+    }
+
+    pub fn eta(&self) -> f64 {
+        self.eta
+    }
+
+    pub fn cutpoints(&self) -> &Vec<f64> {
+        &self.cutpoints
+    }
+
+    /// Number of categories `K`
+    pub fn categories(&self) -> usize {
+        self.cutpoints.len() + 1
+    }
+}
+
+impl RandomVariable for OrderedParams {
+    type RestoreInfo = usize;
+
+    fn transform_vec(&self) -> (Vec<f64>, Self::RestoreInfo) {
+        (
+            [vec![self.eta], self.cutpoints.clone()].concat(),
+            self.cutpoints.len(),
+        )
+    }
+
+    fn len(&self) -> usize {
+        self.cutpoints.len() + 1
+    }
+
+    fn restore(v: &[f64], info: &Self::RestoreInfo) -> Result<Self, DistributionError> {
+        if v.len() != *info + 1 {
+            return Err(DistributionError::InvalidRestoreVector);
+        }
+        Self::new(v[0], v[1..].to_vec())
+    }
+}
@@ -0,0 +1,259 @@
+use crate::{
+    ConditionDifferentiableDistribution, DependentJoint, DiscreteDistribution, Distribution,
+    DistributionError, IndependentJoint, RandomVariable, SamplableDistribution,
+};
+use rand::prelude::*;
+use std::{ops::BitAnd, ops::Mul};
+
+/// Mallows distribution over permutations with Kendall's tau distance
+/// `p(x | θ, σ) = exp(-θ d(x, σ)) / Z(θ)` where `d` counts the pairs ordered differently in `x` and `σ`.
+#[derive(Clone, Debug)]
+pub struct Mallows;
+
+#[derive(thiserror::Error, Debug)]
+pub enum MallowsError {
+    #[error("'θ' must be positive")]
+    ThetaMustBePositive,
+    #[error("Must be a permutation")]
+    NotPermutation,
+}
+
+fn is_permutation(x: &[usize]) -> bool {
+    let mut seen = vec![false; x.len()];
+    for &xi in x.iter() {
+        if x.len() <= xi || seen[xi] {
+            return false;
+        }
+        seen[xi] = true;
+    }
+
+    true
+}
+
+/// Kendall's tau distance between `x` and `sigma`
+pub fn kendall_tau_distance(x: &[usize], sigma: &[usize]) -> Result<usize, DistributionError> {
+    if x.len() != sigma.len() || !is_permutation(x) {
+        return Err(DistributionError::InvalidParameters(
+            MallowsError::NotPermutation.into(),
+        ));
+    }
+    let mut rank = vec![0usize; sigma.len()];
+    for (r, &s) in sigma.iter().enumerate() {
+        rank[s] = r;
+    }
+    let r = x.iter().map(|&xi| rank[xi]).collect::<Vec<_>>();
+
+    Ok((0..r.len())
+        .map(|i| (i + 1..r.len()).filter(|&j| r[j] < r[i]).count())
+        .sum())
+}
+
+impl Distribution for Mallows {
+    type Value = Vec<usize>;
+    type Condition = MallowsParams;
+
+    fn p_kernel(&self, x: &Self::Value, theta: &Self::Condition) -> Result<f64, DistributionError> {
+        let d = kendall_tau_distance(x, theta.sigma())? as f64;
+
+        Ok((-theta.theta() * d - theta.ln_normalizer()).exp())
+    }
+}
+
+impl DiscreteDistribution for Mallows {}
+
+impl<Rhs, TRhs> Mul<Rhs> for Mallows
+where
+    Rhs: Distribution<Value = TRhs, Condition = MallowsParams>,
+    TRhs: RandomVariable,
+{
+    type Output = IndependentJoint<Self, Rhs, Vec<usize>, TRhs, MallowsParams>;
+
+    fn mul(self, rhs: Rhs) -> Self::Output {
+        IndependentJoint::new(self, rhs)
+    }
+}
+
+impl<Rhs, URhs> BitAnd<Rhs> for Mallows
+where
+    Rhs: Distribution<Value = MallowsParams, Condition = URhs>,
+    URhs: RandomVariable,
+{
+    type Output = DependentJoint<Self, Rhs, Vec<usize>, MallowsParams, URhs>;
+
+    fn bitand(self, rhs: Rhs) -> Self::Output {
+        DependentJoint::new(self, rhs)
+    }
+}
+
+impl ConditionDifferentiableDistribution for Mallows {
+    /// `E[d] - d(x, σ)`; the center `σ` is discrete and has no gradient.
+    fn ln_diff_condition(
+        &self,
+        x: &Self::Value,
+        theta: &Self::Condition,
+    ) -> Result<Vec<f64>, DistributionError> {
+        let d = kendall_tau_distance(x, theta.sigma())? as f64;
+        let t = theta.theta();
+        let mean_d = (1..=theta.sigma().len())
+            .map(|j| {
+                let j = j as f64;
+                1.0 / t.exp_m1() - j / (j * t).exp_m1()
+            })
+            .sum::<f64>();
+
+        Ok(vec![mean_d - d])
+    }
+}
+
+impl SamplableDistribution for Mallows {
+    /// Repeated insertion model: the `i`-th item of `σ` is inserted before `r_i` of the preceding items,
+    /// where `p(r_i = r) ∝ exp(-θ r)` for `r = 0, ..., i`.
+    fn sample(
+        &self,
+        theta: &Self::Condition,
+        rng: &mut dyn RngCore,
+    ) -> Result<Self::Value, DistributionError> {
+        let t = theta.theta();
+        let mut x = Vec::with_capacity(theta.sigma().len());
+        for (i, &si) in theta.sigma().iter().enumerate() {
+            // Inverse CDF of the geometric distribution truncated to 0..=i
+            let u: f64 = rng.gen_range(0.0..1.0);
+            let total = -(-t * (i + 1) as f64).exp_m1();
+            let r = ((1.0 - u * total).ln() / -t).floor() as usize;
+            x.insert(i - r.min(i), si);
+        }
+
+        Ok(x)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MallowsParams {
+    theta: f64,
+    sigma: Vec<usize>,
+}
+
+impl MallowsParams {
+    /// - `theta`: Concentration
+    /// - `sigma`: Central permutation
+    pub fn new(theta: f64, sigma: Vec<usize>) -> Result<Self, DistributionError> {
+        if theta <= 0.0 {
+            return Err(DistributionError::InvalidParameters(
+                MallowsError::ThetaMustBePositive.into(),
+            ));
+        }
+        if !is_permutation(&sigma) {
+            return Err(DistributionError::InvalidParameters(
+                MallowsError::NotPermutation.into(),
+            ));
+        }
+
+        Ok(Self { theta, sigma })
+    }
+
+    pub fn theta(&self) -> f64 {
+        self.theta
+    }
+
+    pub fn sigma(&self) -> &Vec<usize> {
+        &self.sigma
+    }
+
+    /// `ln Z(θ) = Σ_{j=1}^n ln((1 - e^{-jθ}) / (1 - e^{-θ}))`
+    pub fn ln_normalizer(&self) -> f64 {
+        let t = self.theta;
+        let ln_denominator = (-(-t).exp_m1()).ln();
+
+        (1..=self.sigma.len())
+            .map(|j| (-(-(j as f64) * t).exp_m1()).ln() - ln_denominator)
+            .sum()
+    }
+}
+
+impl RandomVariable for MallowsParams {
+    type RestoreInfo = Vec<usize>;
+
+    fn transform_vec(&self) -> (Vec<f64>, Self::RestoreInfo) {
+        (vec![self.theta], self.sigma.clone())
+    }
+
+    fn len(&self) -> usize {
+        1usize
+    }
+
+    fn restore(v: &[f64], info: &Self::RestoreInfo) -> Result<Self, DistributionError> {
+        if v.len() != 1 {
+            return Err(DistributionError::InvalidRestoreVector);
+        }
+        Self::new(v[0], info.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        kendall_tau_distance, ConditionDifferentiableDistribution, Distribution, Mallows,
+        MallowsParams, SamplableDistribution,
+    };
+    use rand::prelude::*;
+
+    fn permutations(n: usize) -> Vec<Vec<usize>> {
+        if n == 0 {
+            return vec![vec![]];
+        }
+        permutations(n - 1)
+            .into_iter()
+            .flat_map(|p| {
+                (0..n).map(move |i| {
+                    let mut q = p.clone();
+                    q.insert(i, n - 1);
+                    q
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn it_works() {
+        let mallows = Mallows;
+        let mut rng = StdRng::from_seed([1; 32]);
+        let theta = MallowsParams::new(0.7, vec![2, 0, 3, 1]).unwrap();
+
+        let perms = permutations(4);
+        let p = perms
+            .iter()
+            .map(|x| mallows.p_kernel(x, &theta).unwrap())
+            .collect::<Vec<_>>();
+        assert!((p.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+
+        let n = 100000;
+        let mean_d = perms
+            .iter()
+            .zip(p.iter())
+            .map(|(x, pi)| kendall_tau_distance(x, theta.sigma()).unwrap() as f64 * pi)
+            .sum::<f64>();
+        let sample_mean_d = (0..n)
+            .map(|_| {
+                let x = mallows.sample(&theta, &mut rng).unwrap();
+                kendall_tau_distance(&x, theta.sigma()).unwrap() as f64
+            })
+            .sum::<f64>()
+            / n as f64;
+        assert!((sample_mean_d - mean_d).abs() < 0.02);
+    }
+
+    #[test]
+    fn it_works2() {
+        let mallows = Mallows;
+        let x = vec![0, 3, 2, 1];
+        let h = 1e-6;
+
+        let theta = MallowsParams::new(0.7, vec![2, 0, 3, 1]).unwrap();
+        let theta_h = MallowsParams::new(0.7 + h, vec![2, 0, 3, 1]).unwrap();
+        let f = mallows.ln_diff_condition(&x, &theta).unwrap();
+        let numerical = (mallows.p_kernel(&x, &theta_h).unwrap().ln()
+            - mallows.p_kernel(&x, &theta).unwrap().ln())
+            / h;
+        assert!((f[0] - numerical).abs() < 1e-4);
+    }
+}
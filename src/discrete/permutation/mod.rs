@@ -0,0 +1,5 @@
+pub mod mallows;
+pub mod plackett_luce;
+
+pub use mallows::*;
+pub use plackett_luce::*;
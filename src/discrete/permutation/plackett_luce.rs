@@ -0,0 +1,239 @@
+use crate::{
+    ConditionDifferentiableDistribution, DependentJoint, DiscreteDistribution, Distribution,
+    DistributionError, IndependentJoint, RandomVariable, SamplableDistribution,
+};
+use rand::prelude::*;
+use std::{ops::BitAnd, ops::Mul};
+
+/// Plackett–Luce distribution over rankings
+/// `x = (x_0, x_1, ...)` lists items from the most preferred, and may be a top-k partial ranking.
+/// `p(x | w) = Π_j w_{x_j} / Σ_{l >= j} w_{x_l}` where the sum runs over the items not ranked before `j`.
+#[derive(Clone, Debug)]
+pub struct PlackettLuce;
+
+#[derive(thiserror::Error, Debug)]
+pub enum PlackettLuceError {
+    #[error("'w' must be positive")]
+    WMustBePositive,
+    #[error("Length of 'w' must be >= 2")]
+    WLenMustBeGTE2,
+    #[error("Ranking must consist of distinct items in range")]
+    InvalidRanking,
+}
+
+fn validate(x: &[usize], n: usize) -> Result<(), DistributionError> {
+    let mut seen = vec![false; n];
+    for &xi in x.iter() {
+        if n <= xi || seen[xi] {
+            return Err(DistributionError::InvalidParameters(
+                PlackettLuceError::InvalidRanking.into(),
+            ));
+        }
+        seen[xi] = true;
+    }
+
+    Ok(())
+}
+
+impl Distribution for PlackettLuce {
+    type Value = Vec<usize>;
+    type Condition = PlackettLuceParams;
+
+    fn p_kernel(&self, x: &Self::Value, theta: &Self::Condition) -> Result<f64, DistributionError> {
+        let w = theta.w();
+        validate(x, w.len())?;
+
+        let mut rest = w.iter().sum::<f64>();
+        let mut ln_p = 0.0;
+        for &xj in x.iter() {
+            ln_p += w[xj].ln() - rest.ln();
+            rest -= w[xj];
+        }
+
+        Ok(ln_p.exp())
+    }
+}
+
+impl DiscreteDistribution for PlackettLuce {}
+
+impl<Rhs, TRhs> Mul<Rhs> for PlackettLuce
+where
+    Rhs: Distribution<Value = TRhs, Condition = PlackettLuceParams>,
+    TRhs: RandomVariable,
+{
+    type Output = IndependentJoint<Self, Rhs, Vec<usize>, TRhs, PlackettLuceParams>;
+
+    fn mul(self, rhs: Rhs) -> Self::Output {
+        IndependentJoint::new(self, rhs)
+    }
+}
+
+impl<Rhs, URhs> BitAnd<Rhs> for PlackettLuce
+where
+    Rhs: Distribution<Value = PlackettLuceParams, Condition = URhs>,
+    URhs: RandomVariable,
+{
+    type Output = DependentJoint<Self, Rhs, Vec<usize>, PlackettLuceParams, URhs>;
+
+    fn bitand(self, rhs: Rhs) -> Self::Output {
+        DependentJoint::new(self, rhs)
+    }
+}
+
+impl ConditionDifferentiableDistribution for PlackettLuce {
+    /// `1[i ranked] / w_i - Σ_j 1[i not ranked before j] / Σ_{l >= j} w_{x_l}`
+    fn ln_diff_condition(
+        &self,
+        x: &Self::Value,
+        theta: &Self::Condition,
+    ) -> Result<Vec<f64>, DistributionError> {
+        let w = theta.w();
+        validate(x, w.len())?;
+
+        let mut f = vec![0.0; w.len()];
+        let mut ranked = vec![false; w.len()];
+        let mut rest = w.iter().sum::<f64>();
+        let mut acc = 0.0;
+        for &xj in x.iter() {
+            acc += 1.0 / rest;
+            // The item ranked at j was in every denominator up to j
+            f[xj] = 1.0 / w[xj] - acc;
+            ranked[xj] = true;
+            rest -= w[xj];
+        }
+        for (fi, &ri) in f.iter_mut().zip(ranked.iter()) {
+            if !ri {
+                *fi = -acc;
+            }
+        }
+
+        Ok(f)
+    }
+}
+
+impl SamplableDistribution for PlackettLuce {
+    /// Full ranking by sorting `ln w_i + g_i` in descending order with Gumbel noises `g_i`
+    fn sample(
+        &self,
+        theta: &Self::Condition,
+        rng: &mut dyn RngCore,
+    ) -> Result<Self::Value, DistributionError> {
+        let mut keys = theta
+            .w()
+            .iter()
+            .enumerate()
+            .map(|(i, wi)| {
+                let u: f64 = rng.gen_range(0.0..1.0);
+                (i, wi.ln() - (-(1.0 - u).ln()).ln())
+            })
+            .collect::<Vec<_>>();
+        keys.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+
+        Ok(keys.into_iter().map(|(i, _)| i).collect())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlackettLuceParams {
+    w: Vec<f64>,
+}
+
+impl PlackettLuceParams {
+    /// - `w`: Positive worth of each item
+    pub fn new(w: Vec<f64>) -> Result<Self, DistributionError> {
+        if w.len() < 2 {
+            return Err(DistributionError::InvalidParameters(
+                PlackettLuceError::WLenMustBeGTE2.into(),
+            ));
+        }
+        if w.iter().any(|&wi| wi <= 0.0) {
+            return Err(DistributionError::InvalidParameters(
+                PlackettLuceError::WMustBePositive.into(),
+            ));
+        }
+
+        Ok(Self { w })
+    }
+
+    pub fn w(&self) -> &Vec<f64> {
+        &self.w
+    }
+}
+
+impl RandomVariable for PlackettLuceParams {
+    type RestoreInfo = usize;
+
+    fn transform_vec(&self) -> (Vec<f64>, Self::RestoreInfo) {
+        (self.w.clone(), self.w.len())
+    }
+
+    fn len(&self) -> usize {
+        self.w.len()
+    }
+
+    fn restore(v: &[f64], info: &Self::RestoreInfo) -> Result<Self, DistributionError> {
+        if v.len() != *info {
+            return Err(DistributionError::InvalidRestoreVector);
+        }
+        Self::new(v.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ConditionDifferentiableDistribution, Distribution, PlackettLuce, PlackettLuceParams,
+        SamplableDistribution,
+    };
+    use rand::prelude::*;
+
+    #[test]
+    fn it_works() {
+        let plackett_luce = PlackettLuce;
+        let mut rng = StdRng::from_seed([1; 32]);
+        let theta = PlackettLuceParams::new(vec![1.0, 2.0, 3.0]).unwrap();
+
+        let perms = vec![
+            vec![0, 1, 2],
+            vec![0, 2, 1],
+            vec![1, 0, 2],
+            vec![1, 2, 0],
+            vec![2, 0, 1],
+            vec![2, 1, 0],
+        ];
+        let p = perms
+            .iter()
+            .map(|x| plackett_luce.p_kernel(x, &theta).unwrap())
+            .collect::<Vec<_>>();
+        assert!((p.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+
+        let n = 60000;
+        let mut count = vec![0usize; perms.len()];
+        for _ in 0..n {
+            let x = plackett_luce.sample(&theta, &mut rng).unwrap();
+            count[perms.iter().position(|perm| *perm == x).unwrap()] += 1;
+        }
+        for (ci, pi) in count.iter().zip(p.iter()) {
+            assert!((*ci as f64 / n as f64 - pi).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn it_works2() {
+        let plackett_luce = PlackettLuce;
+        let w = vec![1.0, 2.0, 3.0, 0.5];
+        let x = vec![2, 0];
+        let h = 1e-6;
+
+        let theta = PlackettLuceParams::new(w.clone()).unwrap();
+        let f = plackett_luce.ln_diff_condition(&x, &theta).unwrap();
+        let ln_p = plackett_luce.p_kernel(&x, &theta).unwrap().ln();
+        for i in 0..w.len() {
+            let mut w_h = w.clone();
+            w_h[i] += h;
+            let theta_h = PlackettLuceParams::new(w_h).unwrap();
+            let numerical = (plackett_luce.p_kernel(&x, &theta_h).unwrap().ln() - ln_p) / h;
+            assert!((f[i] - numerical).abs() < 1e-4);
+        }
+    }
+}
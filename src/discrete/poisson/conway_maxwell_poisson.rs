@@ -0,0 +1,370 @@
+use crate::{
+    ConditionDifferentiableDistribution, DependentJoint, Distribution, IndependentJoint, Poisson,
+    PoissonParams, RandomVariable, SamplableDistribution,
+};
+use crate::{DiscreteDistribution, DistributionError};
+use rand::prelude::*;
+use rand_distr::StandardNormal;
+use special::Gamma;
+use std::f64::consts::PI;
+use std::{ops::BitAnd, ops::Mul};
+
+/// Mode `λ^{1/ν}` above which the normalizer and the moments are evaluated by the asymptotic expansion
+const ASYMPTOTIC_MODE: f64 = 1e4;
+/// Terms smaller than the largest one by this factor in log scale are neglected
+const LN_TRUNCATION: f64 = 40.0;
+
+/// Conway–Maxwell–Poisson distribution
+/// `p(x | λ, ν) = λ^x / (x!)^ν / Z(λ, ν)`, which is over-dispersed for `ν < 1` and under-dispersed for `ν > 1`.
+#[derive(Clone, Debug)]
+pub struct ConwayMaxwellPoisson;
+
+#[derive(thiserror::Error, Debug)]
+pub enum ConwayMaxwellPoissonError {
+    #[error("'λ' must be positive")]
+    LambdaMustBePositive,
+    #[error("'ν' must be positive")]
+    NuMustBePositive,
+}
+
+impl Distribution for ConwayMaxwellPoisson {
+    type Value = u64;
+    type Condition = ConwayMaxwellPoissonParams;
+
+    fn p_kernel(&self, x: &Self::Value, theta: &Self::Condition) -> Result<f64, DistributionError> {
+        Ok((theta.ln_unnormalized(*x) - theta.ln_normalizer()).exp())
+    }
+}
+
+impl DiscreteDistribution for ConwayMaxwellPoisson {}
+
+impl<Rhs, TRhs> Mul<Rhs> for ConwayMaxwellPoisson
+where
+    Rhs: Distribution<Value = TRhs, Condition = ConwayMaxwellPoissonParams>,
+    TRhs: RandomVariable,
+{
+    type Output = IndependentJoint<Self, Rhs, u64, TRhs, ConwayMaxwellPoissonParams>;
+
+    fn mul(self, rhs: Rhs) -> Self::Output {
+        IndependentJoint::new(self, rhs)
+    }
+}
+
+impl<Rhs, URhs> BitAnd<Rhs> for ConwayMaxwellPoisson
+where
+    Rhs: Distribution<Value = ConwayMaxwellPoissonParams, Condition = URhs>,
+    URhs: RandomVariable,
+{
+    type Output = DependentJoint<Self, Rhs, u64, ConwayMaxwellPoissonParams, URhs>;
+
+    fn bitand(self, rhs: Rhs) -> Self::Output {
+        DependentJoint::new(self, rhs)
+    }
+}
+
+impl ConditionDifferentiableDistribution for ConwayMaxwellPoisson {
+    /// `(x - E[x]) / λ` and `E[ln x!] - ln x!`
+    fn ln_diff_condition(
+        &self,
+        x: &Self::Value,
+        theta: &Self::Condition,
+    ) -> Result<Vec<f64>, DistributionError> {
+        let lambda = theta.lambda();
+        let (e_x, e_ln_factorial) = theta.moments();
+        let xf = *x as f64;
+
+        let f_lambda = (xf - e_x) / lambda;
+        let f_nu = e_ln_factorial - (xf + 1.0).ln_gamma().0;
+        Ok(vec![f_lambda, f_nu])
+    }
+}
+
+impl SamplableDistribution for ConwayMaxwellPoisson {
+    /// Rejection sampling (Benson and Friel, 2021) from the Poisson envelope with the rate `μ = λ^{1/ν}` for `ν ≥ 1`,
+    /// and from the geometric envelope with the mean near `μ` for `ν < 1`.
+    /// Above the asymptotic mode, the normal approximation `N(μ - (ν - 1) / 2ν, μ / ν)` is used.
+    fn sample(
+        &self,
+        theta: &Self::Condition,
+        rng: &mut dyn RngCore,
+    ) -> Result<Self::Value, DistributionError> {
+        let nu = theta.nu();
+        let mu = theta.mode();
+        let ln_mu = mu.ln();
+
+        if theta.window.is_none() {
+            let mean = mu - (nu - 1.0) / (2.0 * nu);
+            let sd = (mu / nu).sqrt();
+            let x = (mean + sd * rng.sample::<f64, _>(StandardNormal)).round();
+            return Ok(x.max(0.0) as u64);
+        }
+
+        if 1.0 <= nu {
+            // (μ^y / y!)^ν / (μ^y / y!) is largest at y = ⌊μ⌋
+            let ln_ratio = |y: f64| (nu - 1.0) * (y * ln_mu - (y + 1.0).ln_gamma().0);
+            let ln_bound = ln_ratio(mu.floor());
+            let envelope = PoissonParams::new(mu)?;
+            loop {
+                let y = Poisson.sample(&envelope, rng)?;
+                let u: f64 = rng.gen_range(0.0..1.0);
+                if u.ln() < ln_ratio(y as f64) - ln_bound {
+                    return Ok(y);
+                }
+            }
+        }
+
+        // (μ^y / y!)^ν / (1 - p)^y is largest at y = ⌊μ / (1 - p)^{1/ν}⌋
+        let p = 2.0 * nu / (2.0 * mu * nu + 1.0 + nu);
+        let ln_q = (1.0 - p).ln();
+        let ln_ratio = |y: f64| nu * (y * ln_mu - (y + 1.0).ln_gamma().0) - y * ln_q;
+        let ln_bound = ln_ratio((mu / (1.0 - p).powf(1.0 / nu)).floor());
+        loop {
+            let v: f64 = 1.0 - rng.gen_range(0.0..1.0);
+            let y = (v.ln() / ln_q).floor();
+            let u: f64 = rng.gen_range(0.0..1.0);
+            if u.ln() < ln_ratio(y) - ln_bound {
+                return Ok(y as u64);
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConwayMaxwellPoissonParams {
+    lambda: f64,
+    nu: f64,
+    ln_normalizer: f64,
+    window: Option<(u64, u64)>,
+}
+
+impl ConwayMaxwellPoissonParams {
+    /// - `lambda`: Rate
+    /// - `nu`: Dispersion, where `ν = 1` is Poisson
+    pub fn new(lambda: f64, nu: f64) -> Result<Self, DistributionError> {
+        if lambda <= 0.0 {
+            return Err(DistributionError::InvalidParameters(
+                ConwayMaxwellPoissonError::LambdaMustBePositive.into(),
+            ));
+        }
+        if nu <= 0.0 {
+            return Err(DistributionError::InvalidParameters(
+                ConwayMaxwellPoissonError::NuMustBePositive.into(),
+            ));
+        }
+
+        let mut params = Self {
+            lambda,
+            nu,
+            ln_normalizer: 0.0,
+            window: None,
+        };
+        let mode = params.mode();
+        if ASYMPTOTIC_MODE < mode {
+            // ln Z ≈ ν μ - (ν - 1) / 2ν ln λ - (ν - 1) / 2 ln 2π - ln ν / 2 (Gaunt et al., 2019)
+            params.ln_normalizer = nu * mode
+                - (nu - 1.0) / (2.0 * nu) * lambda.ln()
+                - (nu - 1.0) / 2.0 * (2.0 * PI).ln()
+                - 0.5 * nu.ln();
+            return Ok(params);
+        }
+
+        let mode = mode.floor() as u64;
+        let ln_max = params.ln_unnormalized(mode);
+        let negligible = |j: u64| params.ln_unnormalized(j) < ln_max - LN_TRUNCATION;
+        let mut lower = mode;
+        while 0 < lower && !negligible(lower - 1) {
+            lower -= 1;
+        }
+        let mut upper = mode;
+        while !negligible(upper + 1) {
+            upper += 1;
+        }
+        params.window = Some((lower, upper));
+
+        params.ln_normalizer = ln_max
+            + params
+                .terms()
+                .map(|(_, ln_t)| (ln_t - ln_max).exp())
+                .sum::<f64>()
+                .ln();
+
+        Ok(params)
+    }
+
+    pub fn lambda(&self) -> f64 {
+        self.lambda
+    }
+
+    pub fn nu(&self) -> f64 {
+        self.nu
+    }
+
+    /// `ln Z(λ, ν) = ln Σ_j λ^j / (j!)^ν`, evaluated by the terms around the mode `λ^{1/ν}`,
+    /// or by the asymptotic expansion if the mode is large.
+    pub fn ln_normalizer(&self) -> f64 {
+        self.ln_normalizer
+    }
+
+    fn ln_unnormalized(&self, x: u64) -> f64 {
+        let x = x as f64;
+
+        x * self.lambda.ln() - self.nu * (x + 1.0).ln_gamma().0
+    }
+
+    /// `μ = λ^{1/ν}`, around which the terms of the normalizer series are largest
+    fn mode(&self) -> f64 {
+        self.lambda.powf(1.0 / self.nu)
+    }
+
+    /// Non-negligible terms `(j, ln(λ^j / (j!)^ν))` of the normalizer series.
+    /// The log terms are concave in `j`, so the terms outside the window decrease at least geometrically
+    /// from ones below `e^{-40}` times the largest.
+    fn terms(&self) -> impl Iterator<Item = (u64, f64)> + '_ {
+        let (lower, upper) = self.window.unwrap_or((1, 0));
+
+        (lower..=upper).map(move |j| (j, self.ln_unnormalized(j)))
+    }
+
+    /// `(E[x], E[ln x!])`
+    fn moments(&self) -> (f64, f64) {
+        if self.window.is_none() {
+            // λ ∂ ln Z / ∂λ and -∂ ln Z / ∂ν of the asymptotic expansion
+            let (lambda, nu, mu) = (self.lambda, self.nu, self.mode());
+            let e_x = mu - (nu - 1.0) / (2.0 * nu);
+            let e_ln_factorial = -mu * (1.0 - lambda.ln() / nu)
+                + lambda.ln() / (2.0 * nu.powi(2))
+                + 0.5 * (2.0 * PI).ln()
+                + 0.5 / nu;
+            return (e_x, e_ln_factorial);
+        }
+
+        self.terms()
+            .map(|(j, ln_t)| {
+                let p = (ln_t - self.ln_normalizer).exp();
+                (j as f64 * p, (j as f64 + 1.0).ln_gamma().0 * p)
+            })
+            .fold((0.0, 0.0), |(a, b), (c, d)| (a + c, b + d))
+    }
+}
+
+impl RandomVariable for ConwayMaxwellPoissonParams {
+    type RestoreInfo = ();
+
+    fn transform_vec(&self) -> (Vec<f64>, Self::RestoreInfo) {
+        (vec![self.lambda, self.nu], ())
+    }
+
+    fn len(&self) -> usize {
+        2usize
+    }
+
+    fn restore(v: &[f64], _: &Self::RestoreInfo) -> Result<Self, DistributionError> {
+        if v.len() != 2 {
+            return Err(DistributionError::InvalidRestoreVector);
+        }
+        Self::new(v[0], v[1])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ConditionDifferentiableDistribution, ConwayMaxwellPoisson, ConwayMaxwellPoissonParams,
+        Distribution, Poisson, PoissonParams, SamplableDistribution,
+    };
+    use rand::prelude::*;
+
+    #[test]
+    fn it_works() {
+        let com_poisson = ConwayMaxwellPoisson;
+        let theta = ConwayMaxwellPoissonParams::new(4.0, 1.0).unwrap();
+        let poisson_theta = PoissonParams::new(4.0).unwrap();
+
+        for x in 0..20 {
+            let p = com_poisson.p_kernel(&x, &theta).unwrap();
+            let expected = Poisson.p_kernel(&x, &poisson_theta).unwrap();
+            assert!((p - expected).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn it_works2() {
+        let com_poisson = ConwayMaxwellPoisson;
+        let mut rng = StdRng::from_seed([1; 32]);
+        let theta = ConwayMaxwellPoissonParams::new(6.0, 1.8).unwrap();
+
+        let (mean, sq) = (0..100)
+            .into_iter()
+            .map(|x| {
+                let p = com_poisson.p_kernel(&x, &theta).unwrap();
+                (x as f64 * p, (x as f64).powi(2) * p)
+            })
+            .fold((0.0, 0.0), |(a, b), (c, d)| (a + c, b + d));
+        let var = sq - mean.powi(2);
+        // Under-dispersed
+        assert!(var < mean);
+
+        let n = 100000;
+        let x = (0..n)
+            .map(|_| com_poisson.sample(&theta, &mut rng).unwrap() as f64)
+            .collect::<Vec<_>>();
+        let sample_mean = x.iter().sum::<f64>() / n as f64;
+        let sample_var = x.iter().map(|xi| (xi - sample_mean).powi(2)).sum::<f64>() / n as f64;
+        assert!((sample_mean - mean).abs() < 0.02);
+        assert!((sample_var - var).abs() < 0.05);
+    }
+
+    #[test]
+    fn it_works_3() {
+        let com_poisson = ConwayMaxwellPoisson;
+        let h = 1e-6;
+        let x = 3;
+        let theta = ConwayMaxwellPoissonParams::new(6.0, 1.8).unwrap();
+        let theta_lambda = ConwayMaxwellPoissonParams::new(6.0 + h, 1.8).unwrap();
+        let theta_nu = ConwayMaxwellPoissonParams::new(6.0, 1.8 + h).unwrap();
+
+        let f = com_poisson.ln_diff_condition(&x, &theta).unwrap();
+        let ln_p = com_poisson.p_kernel(&x, &theta).unwrap().ln();
+        let f_lambda = (com_poisson.p_kernel(&x, &theta_lambda).unwrap().ln() - ln_p) / h;
+        let f_nu = (com_poisson.p_kernel(&x, &theta_nu).unwrap().ln() - ln_p) / h;
+        assert!((f[0] - f_lambda).abs() < 1e-4);
+        assert!((f[1] - f_nu).abs() < 1e-4);
+    }
+
+    #[test]
+    fn it_works_4() {
+        let com_poisson = ConwayMaxwellPoisson;
+        let mut rng = StdRng::from_seed([1; 32]);
+
+        // Small ν and large λ, where the modes λ^{1/ν} are 1e20 and 1e12
+        let theta = ConwayMaxwellPoissonParams::new(10.0, 0.05).unwrap();
+        assert!(theta.ln_normalizer().is_finite());
+        let theta = ConwayMaxwellPoissonParams::new(1e6, 0.5).unwrap();
+        let n = 1000;
+        let mean = (0..n)
+            .map(|_| com_poisson.sample(&theta, &mut rng).unwrap() as f64)
+            .sum::<f64>()
+            / n as f64;
+        // sd of x is √(μ / ν) ≈ 1.4e6
+        assert!((mean - 1e12).abs() < 2e5);
+
+        // The asymptotic normalizer just above the threshold, with the mode 1.6^20 ≈ 12089
+        let theta = ConwayMaxwellPoissonParams::new(1.6, 0.05).unwrap();
+        let sum = (5000..20000)
+            .map(|x| com_poisson.p_kernel(&x, &theta).unwrap())
+            .sum::<f64>();
+        assert!((sum - 1.0).abs() < 1e-3);
+
+        // Rejection from the geometric envelope
+        let theta = ConwayMaxwellPoissonParams::new(1.5, 0.1).unwrap();
+        let mean = (0..400)
+            .map(|x| x as f64 * com_poisson.p_kernel(&x, &theta).unwrap())
+            .sum::<f64>();
+        let n = 100000;
+        let sample_mean = (0..n)
+            .map(|_| com_poisson.sample(&theta, &mut rng).unwrap() as f64)
+            .sum::<f64>()
+            / n as f64;
+        assert!((sample_mean - mean).abs() < 0.3);
+    }
+}
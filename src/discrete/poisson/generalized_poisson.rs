@@ -0,0 +1,229 @@
+use crate::{
+    ConditionDifferentiableDistribution, DependentJoint, Distribution, IndependentJoint,
+    RandomVariable, SamplableDistribution,
+};
+use crate::{DiscreteDistribution, DistributionError};
+use rand::prelude::*;
+use rand_distr::Poisson as RandPoisson;
+use special::Gamma;
+use std::{ops::BitAnd, ops::Mul};
+
+/// Generalized Poisson distribution by Consul
+/// `p(x | θ, λ) = θ (θ + λx)^{x - 1} e^{-θ - λx} / x!`, whose mean is `θ / (1 - λ)` and variance is `θ / (1 - λ)^3`.
+/// Only the overdispersed case `0 ≤ λ < 1` is supported.
+/// The underdispersed case `max(-1, -θ/m) ≤ λ < 0`, which is truncated at the largest `m` with `θ + λm > 0`, is not normalized and is rejected.
+#[derive(Clone, Debug)]
+pub struct GeneralizedPoisson;
+
+#[derive(thiserror::Error, Debug)]
+pub enum GeneralizedPoissonError {
+    #[error("'θ' must be positive")]
+    ThetaMustBePositive,
+    #[error("'λ' must be in [0, 1), and the underdispersed case λ < 0 is not supported")]
+    LambdaMustBeInRange,
+}
+
+impl Distribution for GeneralizedPoisson {
+    type Value = u64;
+    type Condition = GeneralizedPoissonParams;
+
+    fn p_kernel(&self, x: &Self::Value, theta: &Self::Condition) -> Result<f64, DistributionError> {
+        let t = theta.theta();
+        let lambda = theta.lambda();
+        let x = *x as f64;
+        let s = t + lambda * x;
+
+        Ok((t.ln() + (x - 1.0) * s.ln() - s - (x + 1.0).ln_gamma().0).exp())
+    }
+}
+
+impl DiscreteDistribution for GeneralizedPoisson {}
+
+impl<Rhs, TRhs> Mul<Rhs> for GeneralizedPoisson
+where
+    Rhs: Distribution<Value = TRhs, Condition = GeneralizedPoissonParams>,
+    TRhs: RandomVariable,
+{
+    type Output = IndependentJoint<Self, Rhs, u64, TRhs, GeneralizedPoissonParams>;
+
+    fn mul(self, rhs: Rhs) -> Self::Output {
+        IndependentJoint::new(self, rhs)
+    }
+}
+
+impl<Rhs, URhs> BitAnd<Rhs> for GeneralizedPoisson
+where
+    Rhs: Distribution<Value = GeneralizedPoissonParams, Condition = URhs>,
+    URhs: RandomVariable,
+{
+    type Output = DependentJoint<Self, Rhs, u64, GeneralizedPoissonParams, URhs>;
+
+    fn bitand(self, rhs: Rhs) -> Self::Output {
+        DependentJoint::new(self, rhs)
+    }
+}
+
+impl ConditionDifferentiableDistribution for GeneralizedPoisson {
+    fn ln_diff_condition(
+        &self,
+        x: &Self::Value,
+        theta: &Self::Condition,
+    ) -> Result<Vec<f64>, DistributionError> {
+        let t = theta.theta();
+        let lambda = theta.lambda();
+        let x = *x as f64;
+        let s = t + lambda * x;
+
+        let f_theta = 1.0 / t + (x - 1.0) / s - 1.0;
+        let f_lambda = x * (x - 1.0) / s - x;
+        Ok(vec![f_theta, f_lambda])
+    }
+}
+
+impl SamplableDistribution for GeneralizedPoisson {
+    /// Total progeny of a branching process with `Poisson(θ)` ancestors and `Poisson(λ)` offspring per individual
+    fn sample(
+        &self,
+        theta: &Self::Condition,
+        rng: &mut dyn RngCore,
+    ) -> Result<Self::Value, DistributionError> {
+        let ancestors = match RandPoisson::new(theta.theta()) {
+            Ok(v) => Ok(v),
+            Err(e) => Err(DistributionError::Others(e.into())),
+        }?;
+        let mut generation = rng.sample(ancestors) as u64;
+        let mut total = generation;
+
+        let lambda = theta.lambda();
+        if lambda == 0.0 {
+            return Ok(total);
+        }
+        while 0 < generation {
+            let offspring = match RandPoisson::new(lambda * generation as f64) {
+                Ok(v) => Ok(v),
+                Err(e) => Err(DistributionError::Others(e.into())),
+            }?;
+            generation = rng.sample(offspring) as u64;
+            total += generation;
+        }
+
+        Ok(total)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GeneralizedPoissonParams {
+    theta: f64,
+    lambda: f64,
+}
+
+impl GeneralizedPoissonParams {
+    /// - `theta`: Rate
+    /// - `lambda`: Dispersion in `[0, 1)`, where `λ = 0` is Poisson. Negative `λ` is rejected.
+    pub fn new(theta: f64, lambda: f64) -> Result<Self, DistributionError> {
+        if theta <= 0.0 {
+            return Err(DistributionError::InvalidParameters(
+                GeneralizedPoissonError::ThetaMustBePositive.into(),
+            ));
+        }
+        if lambda < 0.0 || 1.0 <= lambda {
+            return Err(DistributionError::InvalidParameters(
+                GeneralizedPoissonError::LambdaMustBeInRange.into(),
+            ));
+        }
+
+        Ok(Self { theta, lambda })
+    }
+
+    pub fn theta(&self) -> f64 {
+        self.theta
+    }
+
+    pub fn lambda(&self) -> f64 {
+        self.lambda
+    }
+}
+
+impl RandomVariable for GeneralizedPoissonParams {
+    type RestoreInfo = ();
+
+    fn transform_vec(&self) -> (Vec<f64>, Self::RestoreInfo) {
+        (vec![self.theta, self.lambda], ())
+    }
+
+    fn len(&self) -> usize {
+        2usize
+    }
+
+    fn restore(v: &[f64], _: &Self::RestoreInfo) -> Result<Self, DistributionError> {
+        if v.len() != 2 {
+            return Err(DistributionError::InvalidRestoreVector);
+        }
+        Self::new(v[0], v[1])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ConditionDifferentiableDistribution, Distribution, GeneralizedPoisson,
+        GeneralizedPoissonParams, SamplableDistribution,
+    };
+    use rand::prelude::*;
+
+    #[test]
+    fn it_works() {
+        let generalized_poisson = GeneralizedPoisson;
+        let theta = GeneralizedPoissonParams::new(2.0, 0.3).unwrap();
+
+        let (sum, mean) = (0..300)
+            .into_iter()
+            .map(|x| {
+                let p = generalized_poisson.p_kernel(&x, &theta).unwrap();
+                (p, x as f64 * p)
+            })
+            .fold((0.0, 0.0), |(a, b), (c, d)| (a + c, b + d));
+        assert!((sum - 1.0).abs() < 1e-10);
+        assert!((mean - 2.0 / 0.7).abs() < 1e-8);
+
+        assert!(GeneralizedPoissonParams::new(2.0, -0.1).is_err());
+    }
+
+    #[test]
+    fn it_works2() {
+        let generalized_poisson = GeneralizedPoisson;
+        let mut rng = StdRng::from_seed([1; 32]);
+        let theta = GeneralizedPoissonParams::new(2.0, 0.3).unwrap();
+
+        let n = 100000;
+        let x = (0..n)
+            .map(|_| generalized_poisson.sample(&theta, &mut rng).unwrap() as f64)
+            .collect::<Vec<_>>();
+        let mean = x.iter().sum::<f64>() / n as f64;
+        let var = x.iter().map(|xi| (xi - mean).powi(2)).sum::<f64>() / n as f64;
+        assert!((mean - 2.0 / 0.7).abs() < 0.05);
+        assert!((var - 2.0 / 0.7f64.powi(3)).abs() < 0.3);
+    }
+
+    #[test]
+    fn it_works_3() {
+        let generalized_poisson = GeneralizedPoisson;
+        let h = 1e-6;
+        let x = 4;
+        let theta = GeneralizedPoissonParams::new(2.0, 0.3).unwrap();
+        let theta_theta = GeneralizedPoissonParams::new(2.0 + h, 0.3).unwrap();
+        let theta_lambda = GeneralizedPoissonParams::new(2.0, 0.3 + h).unwrap();
+
+        let f = generalized_poisson.ln_diff_condition(&x, &theta).unwrap();
+        let ln_p = generalized_poisson.p_kernel(&x, &theta).unwrap().ln();
+        let f_theta = (generalized_poisson.p_kernel(&x, &theta_theta).unwrap().ln() - ln_p) / h;
+        let f_lambda = (generalized_poisson
+            .p_kernel(&x, &theta_lambda)
+            .unwrap()
+            .ln()
+            - ln_p)
+            / h;
+        assert!((f[0] - f_theta).abs() < 1e-4);
+        assert!((f[1] - f_lambda).abs() < 1e-4);
+    }
+}
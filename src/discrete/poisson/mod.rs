@@ -1,6 +1,12 @@
+pub mod conway_maxwell_poisson;
+pub mod generalized_poisson;
 pub mod params;
+pub mod skellam;
 
+pub use conway_maxwell_poisson::*;
+pub use generalized_poisson::*;
 pub use params::*;
+pub use skellam::*;
 
 use crate::{
     ConditionDifferentiableDistribution, DependentJoint, Distribution, IndependentJoint,
@@ -9,6 +15,7 @@ use crate::{
 use crate::{DiscreteDistribution, DistributionError};
 use rand::prelude::*;
 use rand_distr::Poisson as RandPoisson;
+use special::Gamma;
 use std::{ops::BitAnd, ops::Mul};
 
 /// Poisson
@@ -21,13 +28,6 @@ pub enum PoissonError {
     LambdaMustBePositive,
 }
 
-fn factorial(num: u64) -> u64 {
-    match num {
-        0 | 1 => 1,
-        _ => factorial(num - 1) * num,
-    }
-}
-
 impl Distribution for Poisson {
     type Value = u64;
     type Condition = PoissonParams;
@@ -35,7 +35,10 @@ impl Distribution for Poisson {
     fn p_kernel(&self, x: &Self::Value, theta: &Self::Condition) -> Result<f64, DistributionError> {
         let lambda = theta.lambda();
 
-        Ok(lambda.powi(*x as i32) / factorial(*x) as f64 * (-lambda).exp())
+        let x = *x as f64;
+
+        // ln(x!) = ln Γ(x + 1) doesn't overflow unlike factorial in u64
+        Ok((x * lambda.ln() - lambda - (x + 1.0).ln_gamma().0).exp())
     }
 }
 
@@ -96,8 +99,20 @@ impl SamplableDistribution for Poisson {
 
 #[cfg(test)]
 mod tests {
+    use crate::{Distribution, Poisson, PoissonParams};
+
     #[test]
     fn it_works() {
-        assert_eq!(2 + 2, 4);
+        let poisson = Poisson;
+        let theta = PoissonParams::new(30.0).unwrap();
+
+        let p = poisson.p_kernel(&25, &theta).unwrap();
+        assert!((p - 0.05111533742894).abs() < 1e-10);
+
+        let sum = (0..200)
+            .into_iter()
+            .map(|x| poisson.p_kernel(&x, &theta).unwrap())
+            .sum::<f64>();
+        assert!((sum - 1.0).abs() < 1e-10);
     }
 }
@@ -0,0 +1,251 @@
+use crate::{
+    ConditionDifferentiableDistribution, DependentJoint, Distribution, IndependentJoint,
+    RandomVariable, SamplableDistribution,
+};
+use crate::{DiscreteDistribution, DistributionError};
+use rand::prelude::*;
+use rand_distr::Poisson as RandPoisson;
+use special::Gamma;
+use std::{ops::BitAnd, ops::Mul};
+
+/// Skellam distribution
+/// Difference `x = y_1 - y_2` of independent `y_1 ~ Poisson(μ_1)` and `y_2 ~ Poisson(μ_2)`.
+#[derive(Clone, Debug)]
+pub struct Skellam;
+
+#[derive(thiserror::Error, Debug)]
+pub enum SkellamError {
+    #[error("'μ_1' must be positive")]
+    Mu1MustBePositive,
+    #[error("'μ_2' must be positive")]
+    Mu2MustBePositive,
+}
+
+/// `ln Σ_m μ_1^{m + k} μ_2^m / ((m + k)! m!)` for `k >= 0`, i.e. `ln I_k(2 √(μ_1 μ_2)) + k/2 ln(μ_1 / μ_2)`
+fn ln_series(k: u64, mu1: f64, mu2: f64) -> f64 {
+    let k = k as f64;
+    let ln_mu1 = mu1.ln();
+    let ln_mu2 = mu2.ln();
+    let ln_term = |m: f64| {
+        (m + k) * ln_mu1 + m * ln_mu2 - (m + k + 1.0).ln_gamma().0 - (m + 1.0).ln_gamma().0
+    };
+
+    // Terms are unimodal in m, so start from the mode and sum outwards.
+    let mode = ((-k + (k * k + 4.0 * mu1 * mu2).sqrt()) / 2.0).floor();
+    let ln_max = ln_term(mode);
+    let mut sum = 1.0;
+    let mut m = mode + 1.0;
+    loop {
+        let r = (ln_term(m) - ln_max).exp();
+        sum += r;
+        if r < 1e-17 * sum {
+            break;
+        }
+        m += 1.0;
+    }
+    let mut m = mode - 1.0;
+    while 0.0 <= m {
+        let r = (ln_term(m) - ln_max).exp();
+        sum += r;
+        if r < 1e-17 * sum {
+            break;
+        }
+        m -= 1.0;
+    }
+
+    ln_max + sum.ln()
+}
+
+fn ln_p(x: i64, mu1: f64, mu2: f64) -> f64 {
+    // p(-k | μ_1, μ_2) = p(k | μ_2, μ_1)
+    let ln_s = if 0 <= x {
+        ln_series(x as u64, mu1, mu2)
+    } else {
+        ln_series((-x) as u64, mu2, mu1)
+    };
+
+    -(mu1 + mu2) + ln_s
+}
+
+impl Distribution for Skellam {
+    type Value = i64;
+    type Condition = SkellamParams;
+
+    fn p_kernel(&self, x: &Self::Value, theta: &Self::Condition) -> Result<f64, DistributionError> {
+        Ok(ln_p(*x, theta.mu1(), theta.mu2()).exp())
+    }
+}
+
+impl DiscreteDistribution for Skellam {}
+
+impl<Rhs, TRhs> Mul<Rhs> for Skellam
+where
+    Rhs: Distribution<Value = TRhs, Condition = SkellamParams>,
+    TRhs: RandomVariable,
+{
+    type Output = IndependentJoint<Self, Rhs, i64, TRhs, SkellamParams>;
+
+    fn mul(self, rhs: Rhs) -> Self::Output {
+        IndependentJoint::new(self, rhs)
+    }
+}
+
+impl<Rhs, URhs> BitAnd<Rhs> for Skellam
+where
+    Rhs: Distribution<Value = SkellamParams, Condition = URhs>,
+    URhs: RandomVariable,
+{
+    type Output = DependentJoint<Self, Rhs, i64, SkellamParams, URhs>;
+
+    fn bitand(self, rhs: Rhs) -> Self::Output {
+        DependentJoint::new(self, rhs)
+    }
+}
+
+impl ConditionDifferentiableDistribution for Skellam {
+    /// `∂p(x)/∂μ_1 = p(x - 1) - p(x)` and `∂p(x)/∂μ_2 = p(x + 1) - p(x)`
+    fn ln_diff_condition(
+        &self,
+        x: &Self::Value,
+        theta: &Self::Condition,
+    ) -> Result<Vec<f64>, DistributionError> {
+        let mu1 = theta.mu1();
+        let mu2 = theta.mu2();
+        let ln_p_x = ln_p(*x, mu1, mu2);
+
+        let f_mu1 = (ln_p(*x - 1, mu1, mu2) - ln_p_x).exp() - 1.0;
+        let f_mu2 = (ln_p(*x + 1, mu1, mu2) - ln_p_x).exp() - 1.0;
+        Ok(vec![f_mu1, f_mu2])
+    }
+}
+
+impl SamplableDistribution for Skellam {
+    fn sample(
+        &self,
+        theta: &Self::Condition,
+        rng: &mut dyn RngCore,
+    ) -> Result<Self::Value, DistributionError> {
+        let poisson1 = match RandPoisson::new(theta.mu1()) {
+            Ok(v) => Ok(v),
+            Err(e) => Err(DistributionError::Others(e.into())),
+        }?;
+        let poisson2 = match RandPoisson::new(theta.mu2()) {
+            Ok(v) => Ok(v),
+            Err(e) => Err(DistributionError::Others(e.into())),
+        }?;
+
+        Ok(rng.sample(poisson1) as i64 - rng.sample(poisson2) as i64)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SkellamParams {
+    mu1: f64,
+    mu2: f64,
+}
+
+impl SkellamParams {
+    pub fn new(mu1: f64, mu2: f64) -> Result<Self, DistributionError> {
+        if mu1 <= 0.0 {
+            return Err(DistributionError::InvalidParameters(
+                SkellamError::Mu1MustBePositive.into(),
+            ));
+        }
+        if mu2 <= 0.0 {
+            return Err(DistributionError::InvalidParameters(
+                SkellamError::Mu2MustBePositive.into(),
+            ));
+        }
+
+        Ok(Self { mu1, mu2 })
+    }
+
+    pub fn mu1(&self) -> f64 {
+        self.mu1
+    }
+
+    pub fn mu2(&self) -> f64 {
+        self.mu2
+    }
+}
+
+impl RandomVariable for SkellamParams {
+    type RestoreInfo = ();
+
+    fn transform_vec(&self) -> (Vec<f64>, Self::RestoreInfo) {
+        (vec![self.mu1, self.mu2], ())
+    }
+
+    fn len(&self) -> usize {
+        2usize
+    }
+
+    fn restore(v: &[f64], _: &Self::RestoreInfo) -> Result<Self, DistributionError> {
+        if v.len() != 2 {
+            return Err(DistributionError::InvalidRestoreVector);
+        }
+        Self::new(v[0], v[1])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ConditionDifferentiableDistribution, Distribution, SamplableDistribution, Skellam,
+        SkellamParams,
+    };
+    use rand::prelude::*;
+
+    #[test]
+    fn it_works() {
+        let skellam = Skellam;
+        let theta = SkellamParams::new(3.0, 1.5).unwrap();
+
+        let p = (-50..50)
+            .into_iter()
+            .map(|x| (x, skellam.p_kernel(&x, &theta).unwrap()))
+            .collect::<Vec<_>>();
+        let sum = p.iter().map(|&(_, px)| px).sum::<f64>();
+        let mean = p.iter().map(|&(x, px)| x as f64 * px).sum::<f64>();
+        let var = p
+            .iter()
+            .map(|&(x, px)| (x as f64 - mean).powi(2) * px)
+            .sum::<f64>();
+        assert!((sum - 1.0).abs() < 1e-10);
+        assert!((mean - 1.5).abs() < 1e-8);
+        assert!((var - 4.5).abs() < 1e-8);
+    }
+
+    #[test]
+    fn it_works2() {
+        let skellam = Skellam;
+        let mut rng = StdRng::from_seed([1; 32]);
+        let theta = SkellamParams::new(3.0, 1.5).unwrap();
+
+        let n = 100000;
+        let x = (0..n)
+            .map(|_| skellam.sample(&theta, &mut rng).unwrap() as f64)
+            .collect::<Vec<_>>();
+        let mean = x.iter().sum::<f64>() / n as f64;
+        let var = x.iter().map(|xi| (xi - mean).powi(2)).sum::<f64>() / n as f64;
+        assert!((mean - 1.5).abs() < 0.05);
+        assert!((var - 4.5).abs() < 0.1);
+    }
+
+    #[test]
+    fn it_works_3() {
+        let skellam = Skellam;
+        let h = 1e-6;
+        let x = -2;
+        let theta = SkellamParams::new(3.0, 1.5).unwrap();
+        let theta_mu1 = SkellamParams::new(3.0 + h, 1.5).unwrap();
+        let theta_mu2 = SkellamParams::new(3.0, 1.5 + h).unwrap();
+
+        let f = skellam.ln_diff_condition(&x, &theta).unwrap();
+        let ln_p = skellam.p_kernel(&x, &theta).unwrap().ln();
+        let f_mu1 = (skellam.p_kernel(&x, &theta_mu1).unwrap().ln() - ln_p) / h;
+        let f_mu2 = (skellam.p_kernel(&x, &theta_mu2).unwrap().ln() - ln_p) / h;
+        assert!((f[0] - f_mu1).abs() < 1e-4);
+        assert!((f[1] - f_mu2).abs() < 1e-4);
+    }
+}
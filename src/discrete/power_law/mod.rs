@@ -0,0 +1,7 @@
+pub mod yule_simon;
+pub mod zeta;
+pub mod zipf;
+
+pub use yule_simon::*;
+pub use zeta::*;
+pub use zipf::*;
@@ -0,0 +1,185 @@
+use crate::{
+    ConditionDifferentiableDistribution, DependentJoint, Distribution, IndependentJoint,
+    RandomVariable,
+};
+use crate::{DiscreteDistribution, DistributionError, SamplableDistribution};
+use rand::prelude::*;
+use special::Gamma;
+use std::{ops::BitAnd, ops::Mul};
+
+/// Yule–Simon distribution
+/// `p(x | ρ) = ρ B(x, ρ + 1)` for `x = 1, 2, ...`, whose tail decays as `x^{-(ρ + 1)}`.
+#[derive(Clone, Debug)]
+pub struct YuleSimon;
+
+#[derive(thiserror::Error, Debug)]
+pub enum YuleSimonError {
+    #[error("'ρ' must be positive")]
+    RhoMustBePositive,
+}
+
+impl Distribution for YuleSimon {
+    type Value = u64;
+    type Condition = YuleSimonParams;
+
+    fn p_kernel(&self, x: &Self::Value, theta: &Self::Condition) -> Result<f64, DistributionError> {
+        if *x == 0 {
+            return Ok(0.0);
+        }
+        let rho = theta.rho();
+        let x = *x as f64;
+
+        Ok(
+            (rho.ln() + x.ln_gamma().0 + (rho + 1.0).ln_gamma().0 - (x + rho + 1.0).ln_gamma().0)
+                .exp(),
+        )
+    }
+}
+
+impl DiscreteDistribution for YuleSimon {}
+
+impl<Rhs, TRhs> Mul<Rhs> for YuleSimon
+where
+    Rhs: Distribution<Value = TRhs, Condition = YuleSimonParams>,
+    TRhs: RandomVariable,
+{
+    type Output = IndependentJoint<Self, Rhs, u64, TRhs, YuleSimonParams>;
+
+    fn mul(self, rhs: Rhs) -> Self::Output {
+        IndependentJoint::new(self, rhs)
+    }
+}
+
+impl<Rhs, URhs> BitAnd<Rhs> for YuleSimon
+where
+    Rhs: Distribution<Value = YuleSimonParams, Condition = URhs>,
+    URhs: RandomVariable,
+{
+    type Output = DependentJoint<Self, Rhs, u64, YuleSimonParams, URhs>;
+
+    fn bitand(self, rhs: Rhs) -> Self::Output {
+        DependentJoint::new(self, rhs)
+    }
+}
+
+impl ConditionDifferentiableDistribution for YuleSimon {
+    fn ln_diff_condition(
+        &self,
+        x: &Self::Value,
+        theta: &Self::Condition,
+    ) -> Result<Vec<f64>, DistributionError> {
+        let rho = theta.rho();
+        let x = *x as f64;
+
+        let f_rho = 1.0 / rho + (rho + 1.0).digamma() - (x + rho + 1.0).digamma();
+        Ok(vec![f_rho])
+    }
+}
+
+impl SamplableDistribution for YuleSimon {
+    /// Geometric distribution with success probability `e^{-w}` where `w ~ Exp(ρ)`
+    fn sample(
+        &self,
+        theta: &Self::Condition,
+        rng: &mut dyn RngCore,
+    ) -> Result<Self::Value, DistributionError> {
+        let u = 1.0 - rng.gen_range(0.0..1.0);
+        let w = -u.ln() / theta.rho();
+        // ln(1 - e^{-w})
+        let ln_q = (-(-w).exp_m1()).ln();
+        if ln_q == f64::NEG_INFINITY {
+            return Ok(1);
+        }
+
+        let v = 1.0 - rng.gen_range(0.0..1.0);
+        Ok(1 + (v.ln() / ln_q).floor() as u64)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct YuleSimonParams {
+    rho: f64,
+}
+
+impl YuleSimonParams {
+    /// - `rho`: Shape
+    pub fn new(rho: f64) -> Result<Self, DistributionError> {
+        if rho <= 0.0 {
+            return Err(DistributionError::InvalidParameters(
+                YuleSimonError::RhoMustBePositive.into(),
+            ));
+        }
+
+        Ok(Self { rho })
+    }
+
+    pub fn rho(&self) -> f64 {
+        self.rho
+    }
+}
+
+impl RandomVariable for YuleSimonParams {
+    type RestoreInfo = ();
+
+    fn transform_vec(&self) -> (Vec<f64>, Self::RestoreInfo) {
+        (vec![self.rho], ())
+    }
+
+    fn len(&self) -> usize {
+        1usize
+    }
+
+    fn restore(v: &[f64], _: &Self::RestoreInfo) -> Result<Self, DistributionError> {
+        if v.len() != 1 {
+            return Err(DistributionError::InvalidRestoreVector);
+        }
+        Self::new(v[0])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ConditionDifferentiableDistribution, Distribution, SamplableDistribution, YuleSimon,
+        YuleSimonParams,
+    };
+    use rand::prelude::*;
+
+    #[test]
+    fn it_works() {
+        let yule_simon = YuleSimon;
+        let mut rng = StdRng::from_seed([1; 32]);
+        let theta = YuleSimonParams::new(2.0).unwrap();
+
+        // p(1) = ρ / (ρ + 1)
+        assert!((yule_simon.p_kernel(&1, &theta).unwrap() - 2.0 / 3.0).abs() < 1e-12);
+
+        let n = 100000;
+        let mut count = vec![0usize; 6];
+        for _ in 0..n {
+            let x = yule_simon.sample(&theta, &mut rng).unwrap();
+            if x <= 5 {
+                count[x as usize] += 1;
+            }
+        }
+        for x in 1..=5 {
+            let p = yule_simon.p_kernel(&(x as u64), &theta).unwrap();
+            assert!((count[x] as f64 / n as f64 - p).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn it_works2() {
+        let yule_simon = YuleSimon;
+        let h = 1e-6;
+        let x = 4;
+        let theta = YuleSimonParams::new(2.0).unwrap();
+        let theta_h = YuleSimonParams::new(2.0 + h).unwrap();
+
+        let f = yule_simon.ln_diff_condition(&x, &theta).unwrap();
+        let numerical = (yule_simon.p_kernel(&x, &theta_h).unwrap().ln()
+            - yule_simon.p_kernel(&x, &theta).unwrap().ln())
+            / h;
+        assert!((f[0] - numerical).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,174 @@
+use crate::{DependentJoint, Distribution, IndependentJoint, RandomVariable};
+use crate::{DiscreteDistribution, DistributionError, SamplableDistribution};
+use rand::prelude::*;
+use std::{ops::BitAnd, ops::Mul};
+
+/// Zeta distribution
+/// `p(x | s) = x^{-s} / ζ(s)` for `x = 1, 2, ...`
+#[derive(Clone, Debug)]
+pub struct Zeta;
+
+#[derive(thiserror::Error, Debug)]
+pub enum ZetaError {
+    #[error("'s' must be greater than 1")]
+    SMustBeGT1,
+}
+
+/// Riemann zeta function `ζ(s)` for `s > 1` by Euler–Maclaurin summation
+pub fn riemann_zeta(s: f64) -> f64 {
+    let n = 16.0;
+    let head = (1..16).map(|k| (k as f64).powf(-s)).sum::<f64>();
+    let ns = n.powf(-s);
+
+    head + n.powf(1.0 - s) / (s - 1.0) + ns / 2.0 + s * ns / (12.0 * n)
+        - s * (s + 1.0) * (s + 2.0) * ns / (720.0 * n.powi(3))
+        + s * (s + 1.0) * (s + 2.0) * (s + 3.0) * (s + 4.0) * ns / (30240.0 * n.powi(5))
+}
+
+impl Distribution for Zeta {
+    type Value = u64;
+    type Condition = ZetaParams;
+
+    fn p_kernel(&self, x: &Self::Value, theta: &Self::Condition) -> Result<f64, DistributionError> {
+        if *x == 0 {
+            return Ok(0.0);
+        }
+
+        Ok((*x as f64).powf(-theta.s()) / theta.zeta())
+    }
+}
+
+impl DiscreteDistribution for Zeta {}
+
+impl<Rhs, TRhs> Mul<Rhs> for Zeta
+where
+    Rhs: Distribution<Value = TRhs, Condition = ZetaParams>,
+    TRhs: RandomVariable,
+{
+    type Output = IndependentJoint<Self, Rhs, u64, TRhs, ZetaParams>;
+
+    fn mul(self, rhs: Rhs) -> Self::Output {
+        IndependentJoint::new(self, rhs)
+    }
+}
+
+impl<Rhs, URhs> BitAnd<Rhs> for Zeta
+where
+    Rhs: Distribution<Value = ZetaParams, Condition = URhs>,
+    URhs: RandomVariable,
+{
+    type Output = DependentJoint<Self, Rhs, u64, ZetaParams, URhs>;
+
+    fn bitand(self, rhs: Rhs) -> Self::Output {
+        DependentJoint::new(self, rhs)
+    }
+}
+
+impl SamplableDistribution for Zeta {
+    /// Devroye's rejection from the Pareto-like `x = ⌊u^{-1/(s-1)}⌋`
+    fn sample(
+        &self,
+        theta: &Self::Condition,
+        rng: &mut dyn RngCore,
+    ) -> Result<Self::Value, DistributionError> {
+        let s = theta.s();
+        let b = 2f64.powf(s - 1.0);
+
+        loop {
+            let u = 1.0 - rng.gen_range(0.0..1.0);
+            let v = rng.gen_range(0.0..1.0);
+            let x = u.powf(-1.0 / (s - 1.0)).floor();
+            if !x.is_finite() || u64::MAX as f64 <= x {
+                continue;
+            }
+            let t = (1.0 + 1.0 / x).powf(s - 1.0);
+            if v * x * (t - 1.0) / (b - 1.0) <= t / b {
+                return Ok(x as u64);
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ZetaParams {
+    s: f64,
+    zeta: f64,
+}
+
+impl ZetaParams {
+    /// - `s`: Exponent, which must be greater than 1
+    pub fn new(s: f64) -> Result<Self, DistributionError> {
+        if s <= 1.0 {
+            return Err(DistributionError::InvalidParameters(
+                ZetaError::SMustBeGT1.into(),
+            ));
+        }
+
+        Ok(Self {
+            s,
+            zeta: riemann_zeta(s),
+        })
+    }
+
+    pub fn s(&self) -> f64 {
+        self.s
+    }
+
+    /// `ζ(s)`
+    pub fn zeta(&self) -> f64 {
+        self.zeta
+    }
+}
+
+impl RandomVariable for ZetaParams {
+    type RestoreInfo = ();
+
+    fn transform_vec(&self) -> (Vec<f64>, Self::RestoreInfo) {
+        (vec![self.s], ())
+    }
+
+    fn len(&self) -> usize {
+        1usize
+    }
+
+    fn restore(v: &[f64], _: &Self::RestoreInfo) -> Result<Self, DistributionError> {
+        if v.len() != 1 {
+            return Err(DistributionError::InvalidRestoreVector);
+        }
+        Self::new(v[0])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{riemann_zeta, Distribution, SamplableDistribution, Zeta, ZetaParams};
+    use rand::prelude::*;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn it_works() {
+        assert!((riemann_zeta(2.0) - PI.powi(2) / 6.0).abs() < 1e-10);
+        assert!((riemann_zeta(4.0) - PI.powi(4) / 90.0).abs() < 1e-10);
+        assert!((riemann_zeta(1.1) - 10.584448464950809).abs() < 1e-9);
+    }
+
+    #[test]
+    fn it_works2() {
+        let zeta = Zeta;
+        let mut rng = StdRng::from_seed([1; 32]);
+        let theta = ZetaParams::new(2.5).unwrap();
+
+        let n = 100000;
+        let mut count = vec![0usize; 6];
+        for _ in 0..n {
+            let x = zeta.sample(&theta, &mut rng).unwrap();
+            if x <= 5 {
+                count[x as usize] += 1;
+            }
+        }
+        for x in 1..=5 {
+            let p = zeta.p_kernel(&(x as u64), &theta).unwrap();
+            assert!((count[x] as f64 / n as f64 - p).abs() < 0.01);
+        }
+    }
+}
@@ -0,0 +1,186 @@
+use crate::{DependentJoint, Distribution, IndependentJoint, RandomVariable};
+use crate::{DiscreteDistribution, DistributionError, SamplableDistribution};
+use rand::prelude::*;
+use std::{ops::BitAnd, ops::Mul};
+
+/// Zipf distribution
+/// `p(x | n, s) = x^{-s} / H_{n,s}` for `x = 1, ..., n`
+#[derive(Clone, Debug)]
+pub struct Zipf;
+
+#[derive(thiserror::Error, Debug)]
+pub enum ZipfError {
+    #[error("'n' must be positive")]
+    NMustBePositive,
+    #[error("'s' must be positive")]
+    SMustBePositive,
+}
+
+impl Distribution for Zipf {
+    type Value = u64;
+    type Condition = ZipfParams;
+
+    fn p_kernel(&self, x: &Self::Value, theta: &Self::Condition) -> Result<f64, DistributionError> {
+        if *x == 0 || theta.n() < *x {
+            return Ok(0.0);
+        }
+
+        Ok((*x as f64).powf(-theta.s()) / theta.harmonic())
+    }
+}
+
+impl DiscreteDistribution for Zipf {}
+
+impl<Rhs, TRhs> Mul<Rhs> for Zipf
+where
+    Rhs: Distribution<Value = TRhs, Condition = ZipfParams>,
+    TRhs: RandomVariable,
+{
+    type Output = IndependentJoint<Self, Rhs, u64, TRhs, ZipfParams>;
+
+    fn mul(self, rhs: Rhs) -> Self::Output {
+        IndependentJoint::new(self, rhs)
+    }
+}
+
+impl<Rhs, URhs> BitAnd<Rhs> for Zipf
+where
+    Rhs: Distribution<Value = ZipfParams, Condition = URhs>,
+    URhs: RandomVariable,
+{
+    type Output = DependentJoint<Self, Rhs, u64, ZipfParams, URhs>;
+
+    fn bitand(self, rhs: Rhs) -> Self::Output {
+        DependentJoint::new(self, rhs)
+    }
+}
+
+impl SamplableDistribution for Zipf {
+    /// Rejection-inversion from the continuous hat `h(y) = min(1, y^{-s})` on `[0, n]`.
+    /// `y` is drawn by inverting the integral of `h`, and `x = ⌊y⌋ + 1` is accepted with probability `x^{-s} / h(y)`.
+    fn sample(
+        &self,
+        theta: &Self::Condition,
+        rng: &mut dyn RngCore,
+    ) -> Result<Self::Value, DistributionError> {
+        let n = theta.n() as f64;
+        let s = theta.s();
+        let one_minus_s = 1.0 - s;
+        // ∫_0^n h(y) dy
+        let total = if s == 1.0 {
+            1.0 + n.ln()
+        } else {
+            (n.powf(one_minus_s) - s) / one_minus_s
+        };
+
+        loop {
+            let area = rng.gen_range(0.0..1.0) * total;
+            let y = if area <= 1.0 {
+                area
+            } else if s == 1.0 {
+                (area - 1.0).exp()
+            } else {
+                (area * one_minus_s + s).powf(1.0 / one_minus_s)
+            };
+            let x = (y.floor() + 1.0).min(n);
+
+            let mut ratio = x.powf(-s);
+            if 1.0 < y {
+                ratio *= y.powf(s);
+            }
+            if rng.gen_range(0.0..1.0) < ratio {
+                return Ok(x as u64);
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ZipfParams {
+    n: u64,
+    s: f64,
+    harmonic: f64,
+}
+
+impl ZipfParams {
+    /// - `n`: Number of elements
+    /// - `s`: Exponent
+    pub fn new(n: u64, s: f64) -> Result<Self, DistributionError> {
+        if n == 0 {
+            return Err(DistributionError::InvalidParameters(
+                ZipfError::NMustBePositive.into(),
+            ));
+        }
+        if s <= 0.0 {
+            return Err(DistributionError::InvalidParameters(
+                ZipfError::SMustBePositive.into(),
+            ));
+        }
+        let harmonic = (1..=n).rev().map(|k| (k as f64).powf(-s)).sum::<f64>();
+
+        Ok(Self { n, s, harmonic })
+    }
+
+    pub fn n(&self) -> u64 {
+        self.n
+    }
+
+    pub fn s(&self) -> f64 {
+        self.s
+    }
+
+    /// Generalized harmonic number `H_{n,s} = Σ_{k=1}^n k^{-s}`
+    pub fn harmonic(&self) -> f64 {
+        self.harmonic
+    }
+}
+
+impl RandomVariable for ZipfParams {
+    type RestoreInfo = u64;
+
+    fn transform_vec(&self) -> (Vec<f64>, Self::RestoreInfo) {
+        (vec![self.s], self.n)
+    }
+
+    fn len(&self) -> usize {
+        1usize
+    }
+
+    fn restore(v: &[f64], info: &Self::RestoreInfo) -> Result<Self, DistributionError> {
+        if v.len() != 1 {
+            return Err(DistributionError::InvalidRestoreVector);
+        }
+        Self::new(*info, v[0])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Distribution, SamplableDistribution, Zipf, ZipfParams};
+    use rand::prelude::*;
+
+    #[test]
+    fn it_works() {
+        let zipf = Zipf;
+        let mut rng = StdRng::from_seed([1; 32]);
+
+        for &s in [0.5, 1.0, 2.0].iter() {
+            let theta = ZipfParams::new(10, s).unwrap();
+            let p = (1..=10)
+                .map(|x| zipf.p_kernel(&x, &theta).unwrap())
+                .collect::<Vec<_>>();
+            assert!((p.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+
+            let n = 100000;
+            let mut count = vec![0usize; 10];
+            for _ in 0..n {
+                let x = zipf.sample(&theta, &mut rng).unwrap();
+                assert!((1..=10).contains(&x));
+                count[x as usize - 1] += 1;
+            }
+            for (ci, pi) in count.iter().zip(p.iter()) {
+                assert!((*ci as f64 / n as f64 - pi).abs() < 0.01);
+            }
+        }
+    }
+}
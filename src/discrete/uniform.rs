@@ -38,11 +38,11 @@ where
     type Value = T;
     type Condition = HashSet<T>;
 
-    fn p_kernel(
-        &self,
-        _x: &Self::Value,
-        theta: &Self::Condition,
-    ) -> Result<f64, DistributionError> {
+    fn p_kernel(&self, x: &Self::Value, theta: &Self::Condition) -> Result<f64, DistributionError> {
+        if !theta.contains(x) {
+            return Ok(0.0);
+        }
+
         Ok(1.0 / theta.len() as f64)
     }
 }
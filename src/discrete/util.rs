@@ -0,0 +1,11 @@
+/// σ(z) = 1 / (1 + e^{-z})
+pub(crate) fn sigmoid(z: f64) -> f64 {
+    1.0 / (1.0 + (-z).exp())
+}
+
+/// dσ(z)/dz = σ(z) (1 - σ(z))
+pub(crate) fn sigmoid_diff(z: f64) -> f64 {
+    let s = sigmoid(z);
+
+    s * (1.0 - s)
+}
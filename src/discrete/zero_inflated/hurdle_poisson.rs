@@ -0,0 +1,202 @@
+use crate::{
+    ConditionDifferentiableDistribution, DependentJoint, Distribution, IndependentJoint, Poisson,
+    PoissonParams, RandomVariable, SamplableDistribution, ZeroInflatedError,
+};
+use crate::{DiscreteDistribution, DistributionError};
+use rand::prelude::*;
+use std::{ops::BitAnd, ops::Mul};
+
+/// Hurdle Poisson
+#[derive(Clone, Debug)]
+pub struct HurdlePoisson;
+
+impl Distribution for HurdlePoisson {
+    type Value = u64;
+    type Condition = HurdlePoissonParams;
+
+    /// `π` for `x = 0`, and `(1 - π) Poisson(x | λ) / (1 - e^{-λ})` for `x > 0`
+    fn p_kernel(&self, x: &Self::Value, theta: &Self::Condition) -> Result<f64, DistributionError> {
+        let pi = theta.pi();
+        if *x == 0 {
+            return Ok(pi);
+        }
+        let lambda = theta.lambda();
+        let p = Poisson.p_kernel(x, theta.poisson())?;
+
+        Ok((1.0 - pi) * p / (-(-lambda).exp_m1()))
+    }
+}
+
+impl DiscreteDistribution for HurdlePoisson {}
+
+impl<Rhs, TRhs> Mul<Rhs> for HurdlePoisson
+where
+    Rhs: Distribution<Value = TRhs, Condition = HurdlePoissonParams>,
+    TRhs: RandomVariable,
+{
+    type Output = IndependentJoint<Self, Rhs, u64, TRhs, HurdlePoissonParams>;
+
+    fn mul(self, rhs: Rhs) -> Self::Output {
+        IndependentJoint::new(self, rhs)
+    }
+}
+
+impl<Rhs, URhs> BitAnd<Rhs> for HurdlePoisson
+where
+    Rhs: Distribution<Value = HurdlePoissonParams, Condition = URhs>,
+    URhs: RandomVariable,
+{
+    type Output = DependentJoint<Self, Rhs, u64, HurdlePoissonParams, URhs>;
+
+    fn bitand(self, rhs: Rhs) -> Self::Output {
+        DependentJoint::new(self, rhs)
+    }
+}
+
+impl ConditionDifferentiableDistribution for HurdlePoisson {
+    fn ln_diff_condition(
+        &self,
+        x: &Self::Value,
+        theta: &Self::Condition,
+    ) -> Result<Vec<f64>, DistributionError> {
+        let pi = theta.pi();
+        let lambda = theta.lambda();
+
+        if *x == 0 {
+            return Ok(vec![1.0 / pi, 0.0]);
+        }
+
+        let f_pi = -1.0 / (1.0 - pi);
+        // ∂/∂λ ln(1 - e^{-λ}) = 1 / (e^λ - 1)
+        let f_lambda = *x as f64 / lambda - 1.0 - 1.0 / lambda.exp_m1();
+        Ok(vec![f_pi, f_lambda])
+    }
+}
+
+impl SamplableDistribution for HurdlePoisson {
+    fn sample(
+        &self,
+        theta: &Self::Condition,
+        rng: &mut dyn RngCore,
+    ) -> Result<Self::Value, DistributionError> {
+        if rng.gen_bool(theta.pi()) {
+            return Ok(0);
+        }
+
+        let lambda = theta.lambda();
+        if 1.0 < lambda {
+            // Rejection of zeros accepts with probability 1 - e^{-λ} > 0.63
+            loop {
+                let x = Poisson.sample(theta.poisson(), rng)?;
+                if x != 0 {
+                    return Ok(x);
+                }
+            }
+        }
+
+        // Inversion of the zero-truncated CDF
+        let u = rng.gen_range(0.0..1.0) * -(-lambda).exp_m1();
+        let mut x = 1u64;
+        let mut p = lambda * (-lambda).exp();
+        let mut cdf = p;
+        while cdf < u && 0.0 < p {
+            x += 1;
+            p *= lambda / x as f64;
+            cdf += p;
+        }
+
+        Ok(x)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct HurdlePoissonParams {
+    pi: f64,
+    poisson: PoissonParams,
+}
+
+impl HurdlePoissonParams {
+    /// - `pi`: Probability of zero
+    /// - `lambda`: Rate of the zero-truncated Poisson part
+    pub fn new(pi: f64, lambda: f64) -> Result<Self, DistributionError> {
+        if pi <= 0.0 || 1.0 <= pi {
+            return Err(DistributionError::InvalidParameters(
+                ZeroInflatedError::PiMustBeProbability.into(),
+            ));
+        }
+        let poisson = PoissonParams::new(lambda)?;
+
+        Ok(Self { pi, poisson })
+    }
+
+    pub fn pi(&self) -> f64 {
+        self.pi
+    }
+
+    pub fn lambda(&self) -> f64 {
+        self.poisson.lambda()
+    }
+
+    pub fn poisson(&self) -> &PoissonParams {
+        &self.poisson
+    }
+}
+
+impl RandomVariable for HurdlePoissonParams {
+    type RestoreInfo = ();
+
+    fn transform_vec(&self) -> (Vec<f64>, Self::RestoreInfo) {
+        (vec![self.pi, self.lambda()], ())
+    }
+
+    fn len(&self) -> usize {
+        2usize
+    }
+
+    fn restore(v: &[f64], _: &Self::RestoreInfo) -> Result<Self, DistributionError> {
+        if v.len() != 2 {
+            return Err(DistributionError::InvalidRestoreVector);
+        }
+        Self::new(v[0], v[1])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ConditionDifferentiableDistribution, Distribution, HurdlePoisson, HurdlePoissonParams,
+        SamplableDistribution,
+    };
+    use rand::prelude::*;
+
+    #[test]
+    fn it_works() {
+        let hurdle = HurdlePoisson;
+        let mut rng = StdRng::from_seed([1; 32]);
+        let theta = HurdlePoissonParams::new(0.3, 0.5).unwrap();
+
+        for _ in 0..100 {
+            hurdle.sample(&theta, &mut rng).unwrap();
+        }
+
+        let sum = (0..100)
+            .into_iter()
+            .map(|x| hurdle.p_kernel(&x, &theta).unwrap())
+            .sum::<f64>();
+        assert!((sum - 1.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn it_works2() {
+        let hurdle = HurdlePoisson;
+        let h = 1e-6;
+        let theta = HurdlePoissonParams::new(0.3, 2.0).unwrap();
+        let theta_lambda = HurdlePoissonParams::new(0.3, 2.0 + h).unwrap();
+
+        let f = hurdle.ln_diff_condition(&3, &theta).unwrap();
+        let f_lambda = (hurdle.p_kernel(&3, &theta_lambda).unwrap().ln()
+            - hurdle.p_kernel(&3, &theta).unwrap().ln())
+            / h;
+        assert!((f[1] - f_lambda).abs() < 1e-4);
+    }
+}
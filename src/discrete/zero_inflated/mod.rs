@@ -0,0 +1,13 @@
+pub mod hurdle_poisson;
+pub mod negative_binomial;
+pub mod poisson;
+
+pub use hurdle_poisson::*;
+pub use negative_binomial::*;
+pub use poisson::*;
+
+#[derive(thiserror::Error, Debug)]
+pub enum ZeroInflatedError {
+    #[error("'π' must be probability.")]
+    PiMustBeProbability,
+}
@@ -0,0 +1,179 @@
+use crate::{
+    ConditionDifferentiableDistribution, DependentJoint, Distribution, IndependentJoint,
+    MeanNegativeBinomialParams, NegativeBinomial, NegativeBinomialParams, RandomVariable,
+    SamplableDistribution, ZeroInflatedError,
+};
+use crate::{DiscreteDistribution, DistributionError};
+use rand::prelude::*;
+use std::{ops::BitAnd, ops::Mul};
+
+/// Zero-inflated negative binomial
+#[derive(Clone, Debug)]
+pub struct ZeroInflatedNegativeBinomial;
+
+impl Distribution for ZeroInflatedNegativeBinomial {
+    type Value = u64;
+    type Condition = ZeroInflatedNegativeBinomialParams;
+
+    /// `π 1[x = 0] + (1 - π) NB(x | μ, φ)`
+    fn p_kernel(&self, x: &Self::Value, theta: &Self::Condition) -> Result<f64, DistributionError> {
+        let pi = theta.pi();
+        let p = NegativeBinomial::new().p_kernel(x, theta.negative_binomial())?;
+
+        if *x == 0 {
+            Ok(pi + (1.0 - pi) * p)
+        } else {
+            Ok((1.0 - pi) * p)
+        }
+    }
+}
+
+impl DiscreteDistribution for ZeroInflatedNegativeBinomial {}
+
+impl<Rhs, TRhs> Mul<Rhs> for ZeroInflatedNegativeBinomial
+where
+    Rhs: Distribution<Value = TRhs, Condition = ZeroInflatedNegativeBinomialParams>,
+    TRhs: RandomVariable,
+{
+    type Output = IndependentJoint<Self, Rhs, u64, TRhs, ZeroInflatedNegativeBinomialParams>;
+
+    fn mul(self, rhs: Rhs) -> Self::Output {
+        IndependentJoint::new(self, rhs)
+    }
+}
+
+impl<Rhs, URhs> BitAnd<Rhs> for ZeroInflatedNegativeBinomial
+where
+    Rhs: Distribution<Value = ZeroInflatedNegativeBinomialParams, Condition = URhs>,
+    URhs: RandomVariable,
+{
+    type Output = DependentJoint<Self, Rhs, u64, ZeroInflatedNegativeBinomialParams, URhs>;
+
+    fn bitand(self, rhs: Rhs) -> Self::Output {
+        DependentJoint::new(self, rhs)
+    }
+}
+
+impl ConditionDifferentiableDistribution for ZeroInflatedNegativeBinomial {
+    fn ln_diff_condition(
+        &self,
+        x: &Self::Value,
+        theta: &Self::Condition,
+    ) -> Result<Vec<f64>, DistributionError> {
+        let pi = theta.pi();
+        let negative_binomial = NegativeBinomial::new();
+        let f_nb = negative_binomial.ln_diff_condition(x, theta.negative_binomial())?;
+
+        if *x == 0 {
+            let p0 = negative_binomial.p_kernel(x, theta.negative_binomial())?;
+            let p = pi + (1.0 - pi) * p0;
+            let f_pi = (1.0 - p0) / p;
+            // ∂p0/∂θ = p0 ∂ln p0/∂θ
+            let w = (1.0 - pi) * p0 / p;
+            return Ok(vec![f_pi, w * f_nb[0], w * f_nb[1]]);
+        }
+
+        let f_pi = -1.0 / (1.0 - pi);
+        Ok(vec![f_pi, f_nb[0], f_nb[1]])
+    }
+}
+
+impl SamplableDistribution for ZeroInflatedNegativeBinomial {
+    fn sample(
+        &self,
+        theta: &Self::Condition,
+        rng: &mut dyn RngCore,
+    ) -> Result<Self::Value, DistributionError> {
+        if rng.gen_bool(theta.pi()) {
+            return Ok(0);
+        }
+
+        NegativeBinomial::new().sample(theta.negative_binomial(), rng)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ZeroInflatedNegativeBinomialParams {
+    pi: f64,
+    negative_binomial: MeanNegativeBinomialParams,
+}
+
+impl ZeroInflatedNegativeBinomialParams {
+    /// - `pi`: Probability of the structural zero
+    /// - `mu`, `phi`: Mean and dispersion of the negative binomial part
+    pub fn new(pi: f64, mu: f64, phi: f64) -> Result<Self, DistributionError> {
+        if pi < 0.0 || 1.0 <= pi {
+            return Err(DistributionError::InvalidParameters(
+                ZeroInflatedError::PiMustBeProbability.into(),
+            ));
+        }
+        let negative_binomial = MeanNegativeBinomialParams::new(mu, phi)?;
+
+        Ok(Self {
+            pi,
+            negative_binomial,
+        })
+    }
+
+    pub fn pi(&self) -> f64 {
+        self.pi
+    }
+
+    pub fn mu(&self) -> f64 {
+        self.negative_binomial.mu()
+    }
+
+    pub fn phi(&self) -> f64 {
+        self.negative_binomial.phi()
+    }
+
+    pub fn negative_binomial(&self) -> &MeanNegativeBinomialParams {
+        &self.negative_binomial
+    }
+}
+
+impl RandomVariable for ZeroInflatedNegativeBinomialParams {
+    type RestoreInfo = ();
+
+    fn transform_vec(&self) -> (Vec<f64>, Self::RestoreInfo) {
+        (vec![self.pi, self.mu(), self.phi()], ())
+    }
+
+    fn len(&self) -> usize {
+        3usize
+    }
+
+    fn restore(v: &[f64], _: &Self::RestoreInfo) -> Result<Self, DistributionError> {
+        if v.len() != 3 {
+            return Err(DistributionError::InvalidRestoreVector);
+        }
+        Self::new(v[0], v[1], v[2])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ConditionDifferentiableDistribution, Distribution, SamplableDistribution,
+        ZeroInflatedNegativeBinomial, ZeroInflatedNegativeBinomialParams,
+    };
+    use rand::prelude::*;
+
+    #[test]
+    fn it_works() {
+        let zinb = ZeroInflatedNegativeBinomial;
+        let mut rng = StdRng::from_seed([1; 32]);
+        let theta = ZeroInflatedNegativeBinomialParams::new(0.3, 4.0, 2.0).unwrap();
+
+        let x = zinb.sample(&theta, &mut rng).unwrap();
+        println!("{}", x);
+
+        let f = zinb.ln_diff_condition(&0, &theta).unwrap();
+        let h = 1e-6;
+        let theta_mu = ZeroInflatedNegativeBinomialParams::new(0.3, 4.0 + h, 2.0).unwrap();
+        let f_mu = (zinb.p_kernel(&0, &theta_mu).unwrap().ln()
+            - zinb.p_kernel(&0, &theta).unwrap().ln())
+            / h;
+        assert!((f[1] - f_mu).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,186 @@
+use crate::{
+    ConditionDifferentiableDistribution, DependentJoint, Distribution, IndependentJoint, Poisson,
+    PoissonParams, RandomVariable, SamplableDistribution, ZeroInflatedError,
+};
+use crate::{DiscreteDistribution, DistributionError};
+use rand::prelude::*;
+use std::{ops::BitAnd, ops::Mul};
+
+/// Zero-inflated Poisson
+#[derive(Clone, Debug)]
+pub struct ZeroInflatedPoisson;
+
+impl Distribution for ZeroInflatedPoisson {
+    type Value = u64;
+    type Condition = ZeroInflatedPoissonParams;
+
+    /// `π 1[x = 0] + (1 - π) Poisson(x | λ)`
+    fn p_kernel(&self, x: &Self::Value, theta: &Self::Condition) -> Result<f64, DistributionError> {
+        let pi = theta.pi();
+        let p = Poisson.p_kernel(x, theta.poisson())?;
+
+        if *x == 0 {
+            Ok(pi + (1.0 - pi) * p)
+        } else {
+            Ok((1.0 - pi) * p)
+        }
+    }
+}
+
+impl DiscreteDistribution for ZeroInflatedPoisson {}
+
+impl<Rhs, TRhs> Mul<Rhs> for ZeroInflatedPoisson
+where
+    Rhs: Distribution<Value = TRhs, Condition = ZeroInflatedPoissonParams>,
+    TRhs: RandomVariable,
+{
+    type Output = IndependentJoint<Self, Rhs, u64, TRhs, ZeroInflatedPoissonParams>;
+
+    fn mul(self, rhs: Rhs) -> Self::Output {
+        IndependentJoint::new(self, rhs)
+    }
+}
+
+impl<Rhs, URhs> BitAnd<Rhs> for ZeroInflatedPoisson
+where
+    Rhs: Distribution<Value = ZeroInflatedPoissonParams, Condition = URhs>,
+    URhs: RandomVariable,
+{
+    type Output = DependentJoint<Self, Rhs, u64, ZeroInflatedPoissonParams, URhs>;
+
+    fn bitand(self, rhs: Rhs) -> Self::Output {
+        DependentJoint::new(self, rhs)
+    }
+}
+
+impl ConditionDifferentiableDistribution for ZeroInflatedPoisson {
+    fn ln_diff_condition(
+        &self,
+        x: &Self::Value,
+        theta: &Self::Condition,
+    ) -> Result<Vec<f64>, DistributionError> {
+        let pi = theta.pi();
+        let lambda = theta.lambda();
+
+        if *x == 0 {
+            let p0 = (-lambda).exp();
+            let p = pi + (1.0 - pi) * p0;
+            let f_pi = (1.0 - p0) / p;
+            let f_lambda = -(1.0 - pi) * p0 / p;
+            return Ok(vec![f_pi, f_lambda]);
+        }
+
+        let f_pi = -1.0 / (1.0 - pi);
+        let f_lambda = Poisson.ln_diff_condition(x, theta.poisson())?[0];
+        Ok(vec![f_pi, f_lambda])
+    }
+}
+
+impl SamplableDistribution for ZeroInflatedPoisson {
+    fn sample(
+        &self,
+        theta: &Self::Condition,
+        rng: &mut dyn RngCore,
+    ) -> Result<Self::Value, DistributionError> {
+        if rng.gen_bool(theta.pi()) {
+            return Ok(0);
+        }
+
+        Poisson.sample(theta.poisson(), rng)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ZeroInflatedPoissonParams {
+    pi: f64,
+    poisson: PoissonParams,
+}
+
+impl ZeroInflatedPoissonParams {
+    /// - `pi`: Probability of the structural zero
+    /// - `lambda`: Rate of the Poisson part
+    pub fn new(pi: f64, lambda: f64) -> Result<Self, DistributionError> {
+        if pi < 0.0 || 1.0 <= pi {
+            return Err(DistributionError::InvalidParameters(
+                ZeroInflatedError::PiMustBeProbability.into(),
+            ));
+        }
+        let poisson = PoissonParams::new(lambda)?;
+
+        Ok(Self { pi, poisson })
+    }
+
+    pub fn pi(&self) -> f64 {
+        self.pi
+    }
+
+    pub fn lambda(&self) -> f64 {
+        self.poisson.lambda()
+    }
+
+    pub fn poisson(&self) -> &PoissonParams {
+        &self.poisson
+    }
+}
+
+impl RandomVariable for ZeroInflatedPoissonParams {
+    type RestoreInfo = ();
+
+    fn transform_vec(&self) -> (Vec<f64>, Self::RestoreInfo) {
+        (vec![self.pi, self.lambda()], ())
+    }
+
+    fn len(&self) -> usize {
+        2usize
+    }
+
+    fn restore(v: &[f64], _: &Self::RestoreInfo) -> Result<Self, DistributionError> {
+        if v.len() != 2 {
+            return Err(DistributionError::InvalidRestoreVector);
+        }
+        Self::new(v[0], v[1])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ConditionDifferentiableDistribution, Distribution, SamplableDistribution,
+        ZeroInflatedPoisson, ZeroInflatedPoissonParams,
+    };
+    use rand::prelude::*;
+
+    #[test]
+    fn it_works() {
+        let zip = ZeroInflatedPoisson;
+        let mut rng = StdRng::from_seed([1; 32]);
+        let theta = ZeroInflatedPoissonParams::new(0.3, 4.0).unwrap();
+
+        let x = zip.sample(&theta, &mut rng).unwrap();
+        println!("{}", x);
+
+        let sum = (0..100)
+            .into_iter()
+            .map(|x| zip.p_kernel(&x, &theta).unwrap())
+            .sum::<f64>();
+        assert!((sum - 1.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn it_works2() {
+        let zip = ZeroInflatedPoisson;
+        let h = 1e-6;
+        let theta = ZeroInflatedPoissonParams::new(0.3, 4.0).unwrap();
+        let theta_pi = ZeroInflatedPoissonParams::new(0.3 + h, 4.0).unwrap();
+        let theta_lambda = ZeroInflatedPoissonParams::new(0.3, 4.0 + h).unwrap();
+
+        for &x in [0u64, 3].iter() {
+            let f = zip.ln_diff_condition(&x, &theta).unwrap();
+            let ln_p = zip.p_kernel(&x, &theta).unwrap().ln();
+            let f_pi = (zip.p_kernel(&x, &theta_pi).unwrap().ln() - ln_p) / h;
+            let f_lambda = (zip.p_kernel(&x, &theta_lambda).unwrap().ln() - ln_p) / h;
+            assert!((f[0] - f_pi).abs() < 1e-4);
+            assert!((f[1] - f_lambda).abs() < 1e-4);
+        }
+    }
+}
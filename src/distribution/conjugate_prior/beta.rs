@@ -0,0 +1,103 @@
+use super::{ConjugatePrior, ConjugatePriorError};
+use crate::{
+    Bernoulli, BernoulliParams, BetaBinomial, BetaBinomialParams, BetaParams, Binomial,
+    DistributionError, Multinominal,
+};
+
+/// Beta prior of the probability of success `p`
+impl ConjugatePrior<Bernoulli> for BetaParams {
+    type Known = ();
+    type Predictive = Bernoulli;
+
+    /// `Beta(α + Σ x_i, β + Σ (1 - x_i))`
+    fn posterior(&self, observations: &[bool], _: &()) -> Result<Self, DistributionError> {
+        let successes = observations.iter().filter(|&&x| x).count() as f64;
+        let failures = observations.len() as f64 - successes;
+
+        Self::new(self.alpha() + successes, self.beta() + failures)
+    }
+
+    /// `Bernoulli(α / (α + β))`
+    fn predictive(&self, _: &()) -> Result<BernoulliParams, DistributionError> {
+        BernoulliParams::new(self.alpha() / (self.alpha() + self.beta()))
+    }
+}
+
+/// Beta prior of the probability of success `p` with the known number of trials `n`
+impl ConjugatePrior<Binomial> for BetaParams {
+    type Known = u64;
+    type Predictive = BetaBinomial;
+
+    /// `Beta(α + Σ x_i, β + Σ (n - x_i))`
+    fn posterior(&self, observations: &[u64], n: &u64) -> Result<Self, DistributionError> {
+        binomial_posterior(self, observations, *n)
+    }
+
+    /// `BetaBinomial(n, α, β)`
+    fn predictive(&self, n: &u64) -> Result<BetaBinomialParams, DistributionError> {
+        BetaBinomialParams::new(*n, self.alpha(), self.beta())
+    }
+}
+
+/// Beta prior of `p` with the known number of trials `n`
+/// `Multinominal` counts the successes of `n` trials with two outcomes, so the conjugate prior is the beta, not the Dirichlet.
+impl ConjugatePrior<Multinominal> for BetaParams {
+    type Known = u64;
+    type Predictive = BetaBinomial;
+
+    /// `Beta(α + Σ x_i, β + Σ (n - x_i))`
+    fn posterior(&self, observations: &[u64], n: &u64) -> Result<Self, DistributionError> {
+        binomial_posterior(self, observations, *n)
+    }
+
+    /// `BetaBinomial(n, α, β)`
+    fn predictive(&self, n: &u64) -> Result<BetaBinomialParams, DistributionError> {
+        BetaBinomialParams::new(*n, self.alpha(), self.beta())
+    }
+}
+
+fn binomial_posterior(
+    prior: &BetaParams,
+    observations: &[u64],
+    n: u64,
+) -> Result<BetaParams, DistributionError> {
+    if observations.iter().any(|&x| n < x) {
+        return Err(DistributionError::InvalidParameters(
+            ConjugatePriorError::ObservationOutOfRange.into(),
+        ));
+    }
+    let successes = observations.iter().sum::<u64>() as f64;
+    let failures = (n * observations.len() as u64) as f64 - successes;
+
+    BetaParams::new(prior.alpha() + successes, prior.beta() + failures)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn it_works() {
+        let prior = BetaParams::new(2.0, 3.0).unwrap();
+
+        let posterior =
+            ConjugatePrior::<Bernoulli>::posterior(&prior, &[true, true, false, true], &())
+                .unwrap();
+        assert_eq!(posterior, BetaParams::new(5.0, 4.0).unwrap());
+        let predictive = ConjugatePrior::<Bernoulli>::posterior_predictive(
+            &prior,
+            &[true, true, false, true],
+            &(),
+        )
+        .unwrap();
+        assert!((predictive.p() - 5.0 / 9.0).abs() < 1e-12);
+
+        let posterior = ConjugatePrior::<Binomial>::posterior(&prior, &[3, 10, 0], &10).unwrap();
+        assert_eq!(posterior, BetaParams::new(15.0, 20.0).unwrap());
+        let predictive =
+            ConjugatePrior::<Binomial>::posterior_predictive(&prior, &[3, 10, 0], &10).unwrap();
+        assert_eq!(predictive.n(), 10);
+        assert_eq!(predictive.alpha(), 15.0);
+        assert!(ConjugatePrior::<Multinominal>::posterior(&prior, &[11], &10).is_err());
+    }
+}
@@ -0,0 +1,50 @@
+use super::{ConjugatePrior, ConjugatePriorError};
+use crate::{Categorical, CategoricalParams, DirichletParams, DistributionError};
+
+/// Dirichlet prior of the category probabilities `p`
+impl ConjugatePrior<Categorical> for DirichletParams {
+    type Known = ();
+    type Predictive = Categorical;
+
+    /// `Dir(α_k + #{i | x_i = k})`
+    fn posterior(&self, observations: &[usize], _: &()) -> Result<Self, DistributionError> {
+        let mut alpha = self.alpha().to_vec();
+        for &x in observations.iter() {
+            if alpha.len() <= x {
+                return Err(DistributionError::InvalidParameters(
+                    ConjugatePriorError::ObservationOutOfRange.into(),
+                ));
+            }
+            alpha[x] += 1.0;
+        }
+
+        Self::new(alpha)
+    }
+
+    /// `Categorical(α / Σ_k α_k)`
+    fn predictive(&self, _: &()) -> Result<CategoricalParams, DistributionError> {
+        let sum = self.alpha().iter().sum::<f64>();
+
+        CategoricalParams::new(self.alpha().iter().map(|alpha_k| alpha_k / sum).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn it_works() {
+        let prior = DirichletParams::new(vec![1.0, 1.0, 2.0]).unwrap();
+
+        let posterior =
+            ConjugatePrior::<Categorical>::posterior(&prior, &[0, 2, 2, 1, 2], &()).unwrap();
+        assert_eq!(posterior.alpha(), &[2.0, 2.0, 5.0]);
+        let predictive =
+            ConjugatePrior::<Categorical>::posterior_predictive(&prior, &[0, 2, 2, 1, 2], &())
+                .unwrap();
+        assert_eq!(predictive.p(), &vec![2.0 / 9.0, 2.0 / 9.0, 5.0 / 9.0]);
+
+        assert!(ConjugatePrior::<Categorical>::posterior(&prior, &[3], &()).is_err());
+    }
+}
@@ -0,0 +1,42 @@
+use super::ConjugatePrior;
+use crate::{
+    DistributionError, GammaParams, NegativeBinomial, Poisson, ProbabilityNegativeBinomialParams,
+};
+
+/// Gamma prior of the rate `λ`
+impl ConjugatePrior<Poisson> for GammaParams {
+    type Known = ();
+    type Predictive = NegativeBinomial;
+
+    /// `Gamma(k + Σ x_i, θ / (n θ + 1))`
+    fn posterior(&self, observations: &[u64], _: &()) -> Result<Self, DistributionError> {
+        let sum = observations.iter().sum::<u64>() as f64;
+        let n = observations.len() as f64;
+
+        Self::new(self.shape() + sum, self.scale() / (n * self.scale() + 1.0))
+    }
+
+    /// `NB(k, 1 / (1 + θ))`
+    fn predictive(&self, _: &()) -> Result<ProbabilityNegativeBinomialParams, DistributionError> {
+        ProbabilityNegativeBinomialParams::new(self.shape(), 1.0 / (1.0 + self.scale()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn it_works() {
+        let prior = GammaParams::new(2.0, 1.0).unwrap();
+
+        let posterior = ConjugatePrior::<Poisson>::posterior(&prior, &[1, 4, 2], &()).unwrap();
+        assert_eq!(posterior, GammaParams::new(9.0, 0.25).unwrap());
+
+        // The mean of the predictive equals the mean of the posterior of λ
+        let predictive =
+            ConjugatePrior::<Poisson>::posterior_predictive(&prior, &[1, 4, 2], &()).unwrap();
+        let mean = predictive.r() * (1.0 - predictive.p()) / predictive.p();
+        assert!((mean - posterior.shape() * posterior.scale()).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,54 @@
+pub mod beta;
+pub mod dirichlet;
+pub mod gamma;
+pub mod normal;
+
+pub use beta::*;
+pub use dirichlet::*;
+pub use gamma::*;
+pub use normal::*;
+
+use crate::{Distribution, DistributionError, RandomVariable};
+
+#[derive(thiserror::Error, Debug)]
+pub enum ConjugatePriorError {
+    #[error("Dimension mismatch")]
+    DimensionMismatch,
+    #[error("Observation is out of range")]
+    ObservationOutOfRange,
+    #[error("Unknown error")]
+    Unknown,
+}
+
+/// Params of a prior whose posterior given observations of the likelihood `L` is in the same family
+pub trait ConjugatePrior<L>: RandomVariable
+where
+    L: Distribution,
+{
+    /// Params of `L` which are given rather than inferred, such as the number of trials of the binomial
+    type Known: RandomVariable;
+    /// Distribution of a new observation with the params of `L` integrated out
+    type Predictive: Distribution<Value = L::Value>;
+
+    /// Params of the posterior given the i.i.d. `observations`
+    fn posterior(
+        &self,
+        observations: &[L::Value],
+        known: &Self::Known,
+    ) -> Result<Self, DistributionError>;
+
+    /// Params of the prior predictive distribution
+    fn predictive(
+        &self,
+        known: &Self::Known,
+    ) -> Result<<Self::Predictive as Distribution>::Condition, DistributionError>;
+
+    /// Params of the posterior predictive distribution given the i.i.d. `observations`
+    fn posterior_predictive(
+        &self,
+        observations: &[L::Value],
+        known: &Self::Known,
+    ) -> Result<<Self::Predictive as Distribution>::Condition, DistributionError> {
+        self.posterior(observations, known)?.predictive(known)
+    }
+}
@@ -0,0 +1,217 @@
+use super::{ConjugatePrior, ConjugatePriorError};
+use crate::{
+    DistributionError, ExactMultivariateStudentTParams, MultivariateNormal, MultivariateStudentT,
+    Normal, NormalInverseWishartParams, NormalParams, WishartParams,
+};
+use opensrdk_linear_algebra::{pp::trf::PPTRF, Matrix, SymmetricPackedMatrix, Vector};
+
+/// Normal prior of the mean `μ` with the known standard deviation `σ`
+impl ConjugatePrior<Normal> for NormalParams {
+    type Known = f64;
+    type Predictive = Normal;
+
+    /// `N(τ_n^2 (μ_0 / τ_0^2 + Σ x_i / σ^2), τ_n^2)` where `1 / τ_n^2 = 1 / τ_0^2 + n / σ^2`
+    fn posterior(&self, observations: &[f64], sigma: &f64) -> Result<Self, DistributionError> {
+        let precision = 1.0 / self.sigma().powi(2) + observations.len() as f64 / sigma.powi(2);
+        let mu = (self.mu() / self.sigma().powi(2)
+            + observations.iter().sum::<f64>() / sigma.powi(2))
+            / precision;
+
+        Self::new(mu, (1.0 / precision).sqrt())
+    }
+
+    /// `N(μ_0, τ_0^2 + σ^2)`
+    fn predictive(&self, sigma: &f64) -> Result<NormalParams, DistributionError> {
+        Self::new(self.mu(), (self.sigma().powi(2) + sigma.powi(2)).sqrt())
+    }
+}
+
+/// Normal inverse Wishart prior of the mean and the covariance matrix
+impl ConjugatePrior<MultivariateNormal> for NormalInverseWishartParams {
+    type Known = ();
+    type Predictive = MultivariateStudentT;
+
+    /// `NIW((λ μ_0 + n x̄) / (λ + n), λ + n, Ψ + S + λ n / (λ + n) (x̄ - μ_0)(x̄ - μ_0)^T, ν + n)`
+    /// where `S = Σ (x_i - x̄)(x_i - x̄)^T`
+    fn posterior(&self, observations: &[Vec<f64>], _: &()) -> Result<Self, DistributionError> {
+        let d = self.mu0().len();
+        if observations.is_empty() {
+            return Ok(self.clone());
+        }
+        let n = observations.len() as f64;
+        let x_bar = mean(observations, d)?;
+
+        let lpsi = self.lpsi().0.to_mat();
+        let mut psi = &lpsi * lpsi.t();
+        for x in observations.iter() {
+            psi = psi + outer(x, &x_bar);
+        }
+        psi = psi + self.lambda() * n / (self.lambda() + n) * outer(&x_bar, self.mu0());
+
+        let mu0 = self
+            .mu0()
+            .iter()
+            .zip(x_bar.iter())
+            .map(|(mu0_i, x_bar_i)| (self.lambda() * mu0_i + n * x_bar_i) / (self.lambda() + n))
+            .collect();
+        let lpsi = SymmetricPackedMatrix::from_mat(&psi)?.pptrf()?;
+
+        Self::new(mu0, self.lambda() + n, lpsi, self.nu() + n)
+    }
+
+    /// `t_{ν - d + 1}(μ_0, Ψ (λ + 1) / (λ (ν - d + 1)))`
+    fn predictive(&self, _: &()) -> Result<ExactMultivariateStudentTParams, DistributionError> {
+        let d = self.mu0().len();
+        let nu = self.nu() - d as f64 + 1.0;
+        let c = ((self.lambda() + 1.0) / (self.lambda() * nu)).sqrt();
+        let lsigma = scale_packed(self.lpsi(), c)?;
+
+        ExactMultivariateStudentTParams::new(nu, self.mu0().clone(), lsigma)
+    }
+}
+
+/// Wishart prior of the precision matrix `Λ` with the known mean `μ`
+impl ConjugatePrior<MultivariateNormal> for WishartParams {
+    type Known = Vec<f64>;
+    type Predictive = MultivariateStudentT;
+
+    /// `W((V^{-1} + Σ (x_i - μ)(x_i - μ)^T)^{-1}, n + N)`
+    fn posterior(
+        &self,
+        observations: &[Vec<f64>],
+        mu: &Vec<f64>,
+    ) -> Result<Self, DistributionError> {
+        let d = self.lv().0.dim();
+        if mu.len() != d {
+            return Err(DistributionError::InvalidParameters(
+                ConjugatePriorError::DimensionMismatch.into(),
+            ));
+        }
+
+        let mut v_inv = self.lv().clone().pptri()?.to_mat();
+        for x in observations.iter() {
+            if x.len() != d {
+                return Err(DistributionError::InvalidParameters(
+                    ConjugatePriorError::DimensionMismatch.into(),
+                ));
+            }
+            v_inv = v_inv + outer(x, mu);
+        }
+        let lv = SymmetricPackedMatrix::from_mat(&v_inv)?
+            .pptrf()?
+            .pptri()?
+            .pptrf()?;
+
+        Self::new(lv, self.n() + observations.len() as f64)
+    }
+
+    /// `t_{n - d + 1}(μ, ((n - d + 1) V)^{-1})`
+    fn predictive(
+        &self,
+        mu: &Vec<f64>,
+    ) -> Result<ExactMultivariateStudentTParams, DistributionError> {
+        let d = self.lv().0.dim();
+        let nu = self.n() - d as f64 + 1.0;
+        let lv_inv = self.lv().clone().pptri()?.pptrf()?;
+        let lsigma = scale_packed(&lv_inv, 1.0 / nu.sqrt())?;
+
+        ExactMultivariateStudentTParams::new(nu, mu.clone(), lsigma)
+    }
+}
+
+fn mean(observations: &[Vec<f64>], d: usize) -> Result<Vec<f64>, DistributionError> {
+    let mut sum = vec![0.0; d];
+    for x in observations.iter() {
+        if x.len() != d {
+            return Err(DistributionError::InvalidParameters(
+                ConjugatePriorError::DimensionMismatch.into(),
+            ));
+        }
+        for (s, xi) in sum.iter_mut().zip(x.iter()) {
+            *s += xi;
+        }
+    }
+    let n = observations.len() as f64;
+
+    Ok(sum.into_iter().map(|s| s / n).collect())
+}
+
+/// `(x - y)(x - y)^T`
+fn outer(x: &[f64], y: &[f64]) -> Matrix {
+    let x_y = x
+        .iter()
+        .zip(y.iter())
+        .map(|(xi, yi)| xi - yi)
+        .collect::<Vec<_>>()
+        .col_mat();
+
+    &x_y * x_y.t()
+}
+
+/// `c L`, which is the Cholesky factor of `c^2 L L^T`
+fn scale_packed(l: &PPTRF, c: f64) -> Result<PPTRF, DistributionError> {
+    Ok(PPTRF(SymmetricPackedMatrix::from_mat(&(c * l.0.to_mat()))?))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use opensrdk_linear_algebra::{pp::trf::PPTRF, *};
+
+    #[test]
+    fn it_works() {
+        let prior = NormalParams::new(0.0, 2.0).unwrap();
+
+        let posterior =
+            ConjugatePrior::<Normal>::posterior(&prior, &[1.0, 2.0, 3.0], &1.0).unwrap();
+        assert!((posterior.mu() - 6.0 / 3.25).abs() < 1e-12);
+        assert!((posterior.sigma() - (1.0 / 3.25f64).sqrt()).abs() < 1e-12);
+        let predictive =
+            ConjugatePrior::<Normal>::posterior_predictive(&prior, &[1.0, 2.0, 3.0], &1.0).unwrap();
+        assert!((predictive.sigma() - (1.0 / 3.25 + 1.0f64).sqrt()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn it_works2() {
+        let identity = PPTRF(SymmetricPackedMatrix::from(2, vec![1.0, 0.0, 1.0]).unwrap());
+        let observations = vec![vec![1.0, 0.0], vec![3.0, 2.0]];
+
+        // x̄ = (2, 1), S = [[2, 2], [2, 2]]
+        let prior =
+            NormalInverseWishartParams::new(vec![0.0, 0.0], 1.0, identity.clone(), 3.0).unwrap();
+        let posterior =
+            ConjugatePrior::<MultivariateNormal>::posterior(&prior, &observations, &()).unwrap();
+        assert_eq!(posterior.lambda(), 3.0);
+        assert_eq!(posterior.nu(), 5.0);
+        assert!((posterior.mu0()[0] - 4.0 / 3.0).abs() < 1e-12);
+        assert!((posterior.mu0()[1] - 2.0 / 3.0).abs() < 1e-12);
+        let l = posterior.lpsi().0.elems();
+        let psi = [l[0] * l[0], l[1] * l[0], l[1] * l[1] + l[2] * l[2]];
+        for (a, b) in psi.iter().zip([17.0 / 3.0, 10.0 / 3.0, 11.0 / 3.0].iter()) {
+            assert!((a - b).abs() < 1e-10);
+        }
+
+        // S = [[1, 0], [0, 4]] around the known mean
+        let prior = WishartParams::new(identity, 3.0).unwrap();
+        let observations = vec![vec![1.0, 0.0], vec![0.0, 2.0]];
+        let posterior =
+            ConjugatePrior::<MultivariateNormal>::posterior(&prior, &observations, &vec![0.0, 0.0])
+                .unwrap();
+        assert_eq!(posterior.n(), 5.0);
+        let l = posterior.lv().0.elems();
+        assert!((l[0] - 0.5f64.sqrt()).abs() < 1e-10);
+        assert!(l[1].abs() < 1e-10);
+        assert!((l[2] - 0.2f64.sqrt()).abs() < 1e-10);
+
+        let predictive = ConjugatePrior::<MultivariateNormal>::posterior_predictive(
+            &prior,
+            &observations,
+            &vec![0.0, 0.0],
+        )
+        .unwrap();
+        assert_eq!(predictive.nu(), 4.0);
+        let l = predictive.lsigma().0.elems();
+        assert!((l[0] - 0.5f64.sqrt()).abs() < 1e-10);
+        assert!((l[2] - 1.25f64.sqrt()).abs() < 1e-10);
+    }
+}
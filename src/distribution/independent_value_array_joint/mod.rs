@@ -66,6 +66,17 @@ where
     }
 }
 
+impl<D, T, U> IndependentValueArrayJoint<D, T, U>
+where
+    D: Distribution<Value = T, Condition = U>,
+    T: RandomVariable,
+    U: RandomVariable,
+{
+    pub fn distributions(&self) -> &[D] {
+        &self.distributions
+    }
+}
+
 pub trait DistributionValueProduct<D, T, U>
 where
     D: Distribution<Value = T, Condition = U>,
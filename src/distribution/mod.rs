@@ -1,5 +1,6 @@
 pub mod condition_mapped;
 pub mod conditionalize_latent;
+pub mod conjugate_prior;
 pub mod continuous_samples;
 pub mod degenerate;
 pub mod dependent_joint;
@@ -21,6 +22,7 @@ pub mod valued;
 
 pub use condition_mapped::*;
 pub use conditionalize_latent::*;
+pub use conjugate_prior::*;
 pub use continuous_samples::*;
 pub use degenerate::*;
 pub use dependent_joint::*;
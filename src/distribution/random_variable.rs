@@ -67,6 +67,25 @@ impl RandomVariable for u64 {
     }
 }
 
+impl RandomVariable for i64 {
+    type RestoreInfo = i64;
+
+    fn transform_vec(&self) -> (Vec<f64>, Self::RestoreInfo) {
+        (vec![], *self)
+    }
+
+    fn len(&self) -> usize {
+        1
+    }
+
+    fn restore(v: &[f64], info: &Self::RestoreInfo) -> Result<Self, DistributionError> {
+        if v.len() != 0 {
+            return Err(DistributionError::InvalidRestoreVector);
+        }
+        Ok(*info)
+    }
+}
+
 impl RandomVariable for usize {
     type RestoreInfo = usize;
 
@@ -118,7 +137,7 @@ impl RandomVariable for Matrix {
     }
 
     fn restore(v: &[f64], info: &Self::RestoreInfo) -> Result<Self, DistributionError> {
-        if v.len() != info * info {
+        if *info == 0 || v.len() % info != 0 {
             return Err(DistributionError::InvalidRestoreVector);
         }
         Ok(Matrix::from(*info, v.to_vec()).unwrap())
@@ -1,3 +1,7 @@
+pub mod reparameterizable;
+
+pub use reparameterizable::*;
+
 use rand::RngCore;
 
 use crate::{Distribution, DistributionError};
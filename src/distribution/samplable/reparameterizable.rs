@@ -0,0 +1,26 @@
+use crate::{DistributionError, SamplableDistribution};
+use rand::RngCore;
+
+/// Sampling as a deterministic transformation `x = g(θ, ε)` of a noise `ε` which doesn't depend on `θ`, so that `x` can be differentiated in `θ`.
+pub trait ReparameterizableDistribution: SamplableDistribution {
+    type Noise;
+
+    fn sample_noise(
+        &self,
+        theta: &Self::Condition,
+        rng: &mut dyn RngCore,
+    ) -> Result<Self::Noise, DistributionError>;
+
+    fn sample_reparam(
+        &self,
+        theta: &Self::Condition,
+        eps: &Self::Noise,
+    ) -> Result<Self::Value, DistributionError>;
+
+    /// `∂x/∂θ` in the order of the transformed vector of `θ`.
+    fn sample_reparam_diff_condition(
+        &self,
+        theta: &Self::Condition,
+        eps: &Self::Noise,
+    ) -> Result<Vec<f64>, DistributionError>;
+}
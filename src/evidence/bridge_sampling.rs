@@ -0,0 +1,204 @@
+use super::{mean_var, EvidenceError, EvidenceEstimate};
+use crate::{Distribution, DistributionError, RandomVariable, SamplableDistribution};
+use rand::prelude::*;
+
+const MAX_ITER: usize = 1000;
+const TOLERANCE: f64 = 1e-10;
+
+/// Evidence of likelihood p(a|b) and prior p(b) from samples of the posterior p(b|a)
+/// Bridge sampling (Meng and Wong, 1996) with the optimal bridge function, solved by the fixed point iteration.
+/// `proposal` g(b) must be normalized, and so must the prior.
+/// g(b) should be close to the posterior, e.g. a normal fitted to the posterior samples.
+pub struct BridgeSampler<'a, L, P, A, B, G>
+where
+    L: Distribution<Value = A, Condition = B>,
+    P: Distribution<Value = B, Condition = ()>,
+    A: RandomVariable,
+    B: RandomVariable,
+    G: SamplableDistribution<Value = B, Condition = ()>,
+{
+    value: &'a A,
+    likelihood: &'a L,
+    prior: &'a P,
+    proposal: &'a G,
+}
+
+impl<'a, L, P, A, B, G> BridgeSampler<'a, L, P, A, B, G>
+where
+    L: Distribution<Value = A, Condition = B>,
+    P: Distribution<Value = B, Condition = ()>,
+    A: RandomVariable,
+    B: RandomVariable,
+    G: SamplableDistribution<Value = B, Condition = ()>,
+{
+    pub fn new(value: &'a A, likelihood: &'a L, prior: &'a P, proposal: &'a G) -> Self {
+        Self {
+            value,
+            likelihood,
+            prior,
+            proposal,
+        }
+    }
+
+    /// Estimates ln p(a) from `posterior_samples` and `proposal_samples` draws of g(b).
+    /// The standard error is the approximate relative mean squared error of Frühwirth-Schnatter (2004),
+    /// which treats the posterior samples as independent, so thin correlated chains first.
+    pub fn estimate(
+        &self,
+        posterior_samples: &[B],
+        proposal_samples: usize,
+        rng: &mut dyn RngCore,
+    ) -> Result<EvidenceEstimate, DistributionError> {
+        if posterior_samples.is_empty() || proposal_samples == 0 {
+            return Err(DistributionError::InvalidParameters(
+                EvidenceError::SamplesAreEmpty.into(),
+            ));
+        }
+
+        // ln q(b) - ln g(b) where q(b) = p(a|b) p(b)
+        let ln_l1 = posterior_samples
+            .iter()
+            .map(|b| self.ln_ratio(b))
+            .collect::<Result<Vec<_>, _>>()?;
+        let ln_l2 = (0..proposal_samples)
+            .map(|_| self.ln_ratio(&self.proposal.sample(&(), rng)?))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Shifts the ratios to avoid overflow. The estimate is scaled back at the end.
+        let finite = ln_l1.iter().filter(|l| l.is_finite()).collect::<Vec<_>>();
+        let shift = if finite.is_empty() {
+            0.0
+        } else {
+            finite.iter().cloned().sum::<f64>() / finite.len() as f64
+        };
+        let l1 = ln_l1.iter().map(|l| (l - shift).exp()).collect::<Vec<_>>();
+        let l2 = ln_l2.iter().map(|l| (l - shift).exp()).collect::<Vec<_>>();
+
+        let n1 = l1.len() as f64;
+        let n2 = l2.len() as f64;
+        let s1 = n1 / (n1 + n2);
+        let s2 = n2 / (n1 + n2);
+
+        let mut r = l2.iter().sum::<f64>() / n2;
+        if r <= 0.0 || !r.is_finite() {
+            r = 1.0;
+        }
+        let mut converged = false;
+        for _ in 0..MAX_ITER {
+            let numerator = l2.iter().map(|l| l / (s1 * l + s2 * r)).sum::<f64>() / n2;
+            let denominator = l1.iter().map(|l| 1.0 / (s1 * l + s2 * r)).sum::<f64>() / n1;
+            let next = numerator / denominator;
+            if !next.is_finite() || next <= 0.0 {
+                break;
+            }
+            let change = (next - r).abs();
+            r = next;
+            if change <= TOLERANCE * r {
+                converged = true;
+                break;
+            }
+        }
+        if !converged {
+            return Err(DistributionError::Others(
+                EvidenceError::NotConverged.into(),
+            ));
+        }
+
+        let f1 = l2
+            .iter()
+            .map(|l| (l / r) / (s1 * l / r + s2))
+            .collect::<Vec<_>>();
+        let f2 = l1
+            .iter()
+            .map(|l| 1.0 / (s1 * l / r + s2))
+            .collect::<Vec<_>>();
+        let (mean1, var1) = mean_var(&f1);
+        let (mean2, var2) = mean_var(&f2);
+        let re2 = var1 / (n2 * mean1.powi(2)) + var2 / (n1 * mean2.powi(2));
+
+        Ok(EvidenceEstimate {
+            ln_evidence: r.ln() + shift,
+            standard_error: re2.sqrt(),
+        })
+    }
+
+    fn ln_ratio(&self, b: &B) -> Result<f64, DistributionError> {
+        Ok(
+            self.likelihood.p_kernel(self.value, b)?.ln() + self.prior.p_kernel(b, &())?.ln()
+                - self.proposal.p_kernel(b, &())?.ln(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::evidence::fixtures::{linear_regression, normal_mean};
+    use crate::*;
+    use rand::prelude::*;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn it_works() {
+        let (value, likelihood, prior, ln_z) = normal_mean();
+        let proposal = InstantDistribution::new(
+            |b: &f64, _: &()| {
+                Ok((-0.5 * ((b - 1.8) / 0.7).powi(2)).exp() / (0.7 * (2.0 * PI).sqrt()))
+            },
+            |_: &(), rng: &mut dyn RngCore| Normal.sample(&NormalParams::new(1.8, 0.7)?, rng),
+        );
+
+        // p(b|a) = N(6 / 3.25, 1 / 3.25)
+        let mut rng = StdRng::from_seed([1; 32]);
+        let posterior = NormalParams::new(6.0 / 3.25, (1.0 / 3.25f64).sqrt()).unwrap();
+        let samples = (0..2000)
+            .map(|_| Normal.sample(&posterior, &mut rng).unwrap())
+            .collect::<Vec<_>>();
+
+        let sampler = BridgeSampler::new(&value, &likelihood, &prior, &proposal);
+        let estimate = sampler.estimate(&samples, 2000, &mut rng).unwrap();
+
+        assert!((estimate.ln_evidence() - ln_z).abs() < 0.05);
+        assert!(0.0 < estimate.standard_error() && estimate.standard_error() < 0.05);
+
+        assert!(sampler.estimate(&[], 2000, &mut rng).is_err());
+    }
+
+    #[test]
+    fn it_works2() {
+        let (value, likelihood, prior, ln_z) = linear_regression();
+        // p(b|a) = N((6 / 3.25, 2 / 2.25), diag(1 / 3.25, 1 / 2.25)), and g(b) is a wider normal
+        let mean = [6.0 / 3.25, 2.0 / 2.25];
+        let sd = [(1.0 / 3.25f64).sqrt(), (1.0 / 2.25f64).sqrt()];
+        let proposal = InstantDistribution::new(
+            move |b: &Vec<f64>, _: &()| {
+                Ok((0..2)
+                    .map(|i| {
+                        let s = 1.2 * sd[i];
+                        (-0.5 * ((b[i] - mean[i]) / s).powi(2)).exp() / (s * (2.0 * PI).sqrt())
+                    })
+                    .product::<f64>())
+            },
+            move |_: &(), rng: &mut dyn RngCore| {
+                (0..2)
+                    .map(|i| Normal.sample(&NormalParams::new(mean[i], 1.2 * sd[i])?, rng))
+                    .collect()
+            },
+        );
+
+        let mut rng = StdRng::from_seed([1; 32]);
+        let samples = (0..2000)
+            .map(|_| {
+                (0..2)
+                    .map(|i| Normal.sample(&NormalParams::new(mean[i], sd[i]).unwrap(), &mut rng))
+                    .collect::<Result<Vec<_>, _>>()
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        let sampler = BridgeSampler::new(&value, &likelihood, &prior, &proposal);
+        let estimate = sampler.estimate(&samples, 2000, &mut rng).unwrap();
+
+        assert!((estimate.ln_evidence() - ln_z).abs() < 0.05);
+        assert!(0.0 < estimate.standard_error() && estimate.standard_error() < 0.05);
+    }
+}
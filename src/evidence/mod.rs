@@ -0,0 +1,124 @@
+pub mod bridge_sampling;
+pub mod nested_sampling;
+pub mod thermodynamic_integration;
+
+pub use bridge_sampling::*;
+pub use nested_sampling::*;
+pub use thermodynamic_integration::*;
+
+#[derive(thiserror::Error, Debug)]
+pub enum EvidenceError {
+    #[error("Samples are empty")]
+    SamplesAreEmpty,
+    #[error("Inverse temperatures must increase from 0 to 1")]
+    InverseTemperaturesOutOfRange,
+    #[error("Number of live points must be positive")]
+    LivePointsMustBePositive,
+    #[error("Tolerance must be positive")]
+    ToleranceMustBePositive,
+    #[error("Iteration did not converge")]
+    NotConverged,
+    #[error("Unknown error")]
+    Unknown,
+}
+
+/// Estimate of ln p(a) = ln ∫ p(a|b) p(b) db and its standard error
+#[derive(Clone, Debug, PartialEq)]
+pub struct EvidenceEstimate {
+    ln_evidence: f64,
+    standard_error: f64,
+}
+
+impl EvidenceEstimate {
+    pub fn ln_evidence(&self) -> f64 {
+        self.ln_evidence
+    }
+
+    /// Standard error of `ln_evidence`
+    pub fn standard_error(&self) -> f64 {
+        self.standard_error
+    }
+}
+
+/// Sample mean and unbiased sample variance
+fn mean_var(x: &[f64]) -> (f64, f64) {
+    let n = x.len() as f64;
+    let mean = x.iter().sum::<f64>() / n;
+    if x.len() < 2 {
+        return (mean, 0.0);
+    }
+    let var = x.iter().map(|x_i| (x_i - mean).powi(2)).sum::<f64>() / (n - 1.0);
+
+    (mean, var)
+}
+
+/// Models with the closed-form evidence shared by the tests of the estimators
+#[cfg(test)]
+pub(crate) mod fixtures {
+    use crate::*;
+    use rand::prelude::*;
+    use std::f64::consts::PI;
+
+    /// a_i | b ~ N(b, 1) for a = (1, 2, 3) with the unnormalized likelihood Π exp(-(a_i - b)^2 / 2), and b ~ N(0, 2^2).
+    /// Returns a, p(a|b), p(b) and ln p(a) = -ln(13) / 2 + 6^2 / (2 * 3.25) - 14 / 2.
+    /// p(b|a) = N(6 / 3.25, 1 / 3.25).
+    pub(crate) fn normal_mean() -> (
+        Vec<f64>,
+        impl Distribution<Value = Vec<f64>, Condition = f64>,
+        impl SamplableDistribution<Value = f64, Condition = ()>,
+        f64,
+    ) {
+        let likelihood = InstantDistribution::new(
+            |a: &Vec<f64>, b: &f64| {
+                Ok((-0.5 * a.iter().map(|a_i| (a_i - b).powi(2)).sum::<f64>()).exp())
+            },
+            |_: &f64, _rng: &mut dyn RngCore| Ok(vec![]),
+        );
+        let prior = InstantDistribution::new(
+            |b: &f64, _: &()| Ok((-0.5 * (b / 2.0).powi(2)).exp() / (8.0 * PI).sqrt()),
+            |_: &(), rng: &mut dyn RngCore| Normal.sample(&NormalParams::new(0.0, 2.0)?, rng),
+        );
+        let ln_z = -0.5 * 13f64.ln() + 36.0 / 6.5 - 7.0;
+
+        (vec![1.0, 2.0, 3.0], likelihood, prior, ln_z)
+    }
+
+    /// a_i | b ~ N(b_0 + b_1 t_i, 1) for t = (-1, 0, 1) and a = (1, 2, 3) with the unnormalized likelihood, and b ~ N(0, 2^2 I).
+    /// Returns a, p(a|b), p(b) and ln p(a) = -ln|4 Λ| / 2 + h^T Λ^{-1} h / 2 - 14 / 2,
+    /// where Λ = X^T X + I / 4 = diag(3.25, 2.25) and h = X^T a = (6, 2).
+    /// p(b|a) = N(Λ^{-1} h, Λ^{-1}).
+    pub(crate) fn linear_regression() -> (
+        Vec<f64>,
+        impl Distribution<Value = Vec<f64>, Condition = Vec<f64>>,
+        impl SamplableDistribution<Value = Vec<f64>, Condition = ()>,
+        f64,
+    ) {
+        let likelihood = InstantDistribution::new(
+            |a: &Vec<f64>, b: &Vec<f64>| {
+                Ok((-0.5
+                    * a.iter()
+                        .enumerate()
+                        .map(|(i, a_i)| (a_i - b[0] - b[1] * (i as f64 - 1.0)).powi(2))
+                        .sum::<f64>())
+                .exp())
+            },
+            |_: &Vec<f64>, _rng: &mut dyn RngCore| Ok(vec![]),
+        );
+        let prior = InstantDistribution::new(
+            |b: &Vec<f64>, _: &()| {
+                Ok(
+                    (-0.5 * b.iter().map(|b_i| (b_i / 2.0).powi(2)).sum::<f64>()).exp()
+                        / (8.0 * PI),
+                )
+            },
+            |_: &(), rng: &mut dyn RngCore| {
+                (0..2)
+                    .map(|_| Normal.sample(&NormalParams::new(0.0, 2.0)?, rng))
+                    .collect()
+            },
+        );
+        let ln_z = -0.5 * (16.0 * 3.25 * 2.25f64).ln() + 0.5 * (36.0 / 3.25 + 4.0 / 2.25) - 7.0;
+
+        (vec![1.0, 2.0, 3.0], likelihood, prior, ln_z)
+    }
+}
@@ -0,0 +1,361 @@
+use super::{EvidenceError, EvidenceEstimate};
+use crate::mcmc::util::ln_sum_exp;
+use crate::{
+    ContinuousSamplesDistribution, Distribution, DistributionError, MetropolisStatistics,
+    RandomVariable, SamplableDistribution,
+};
+use rand::prelude::*;
+use rand_distr::StandardNormal;
+
+/// Number of live points, random walk steps to replace a point, relative tolerance of the remaining evidence, and maximum number of iterations
+#[derive(Clone, Debug, PartialEq)]
+pub struct NestedSamplingParams {
+    live_points: usize,
+    mcmc_steps: usize,
+    tolerance: f64,
+    max_iter: usize,
+}
+
+impl Default for NestedSamplingParams {
+    fn default() -> Self {
+        Self {
+            live_points: 400,
+            mcmc_steps: 20,
+            tolerance: 1e-3,
+            max_iter: 100000,
+        }
+    }
+}
+
+impl NestedSamplingParams {
+    pub fn new(
+        live_points: usize,
+        mcmc_steps: usize,
+        tolerance: f64,
+        max_iter: usize,
+    ) -> Result<Self, DistributionError> {
+        if live_points == 0 {
+            return Err(DistributionError::InvalidParameters(
+                EvidenceError::LivePointsMustBePositive.into(),
+            ));
+        }
+        if tolerance <= 0.0 || tolerance.is_nan() {
+            return Err(DistributionError::InvalidParameters(
+                EvidenceError::ToleranceMustBePositive.into(),
+            ));
+        }
+
+        Ok(Self {
+            live_points,
+            mcmc_steps,
+            tolerance,
+            max_iter,
+        })
+    }
+
+    pub fn live_points(&self) -> usize {
+        self.live_points
+    }
+
+    pub fn mcmc_steps(&self) -> usize {
+        self.mcmc_steps
+    }
+
+    pub fn tolerance(&self) -> f64 {
+        self.tolerance
+    }
+
+    pub fn max_iter(&self) -> usize {
+        self.max_iter
+    }
+}
+
+/// Evidence, and the discarded and final live points weighted as samples of the posterior
+#[derive(Clone, Debug)]
+pub struct NestedSamples<B>
+where
+    B: RandomVariable,
+{
+    estimate: EvidenceEstimate,
+    information: f64,
+    samples: ContinuousSamplesDistribution<B>,
+    weights: Vec<f64>,
+}
+
+impl<B> NestedSamples<B>
+where
+    B: RandomVariable,
+{
+    pub fn estimate(&self) -> &EvidenceEstimate {
+        &self.estimate
+    }
+
+    /// Information H = ∫ p(b|a) ln(p(b|a) / p(b)) db
+    pub fn information(&self) -> f64 {
+        self.information
+    }
+
+    pub fn samples(&self) -> &ContinuousSamplesDistribution<B> {
+        &self.samples
+    }
+
+    /// Normalized posterior weights of the samples
+    pub fn weights(&self) -> &[f64] {
+        &self.weights
+    }
+}
+
+/// Evidence of likelihood p(a|b) and prior p(b) by nested sampling (Skilling, 2006)
+/// The live point with the lowest likelihood L* is discarded with the prior mass X_i = e^{-i/N} shrinking geometrically,
+/// and replaced by a random walk Metropolis chain on p(b) restricted to p(a|b) > L*, started from another live point.
+/// The steps are scaled by the spread of the live points in each dimension and tuned toward the acceptance rate 1/2.
+/// Iteration stops when the live points can add at most `tolerance` of the evidence so far.
+/// The standard error is √(H / N) with the information H.
+pub struct NestedSampler<'a, L, P, A, B>
+where
+    L: Distribution<Value = A, Condition = B>,
+    P: SamplableDistribution<Value = B, Condition = ()>,
+    A: RandomVariable,
+    B: RandomVariable,
+{
+    value: &'a A,
+    likelihood: &'a L,
+    prior: &'a P,
+    params: NestedSamplingParams,
+}
+
+impl<'a, L, P, A, B> NestedSampler<'a, L, P, A, B>
+where
+    L: Distribution<Value = A, Condition = B>,
+    P: SamplableDistribution<Value = B, Condition = ()>,
+    A: RandomVariable,
+    B: RandomVariable,
+{
+    pub fn new(
+        value: &'a A,
+        likelihood: &'a L,
+        prior: &'a P,
+        params: NestedSamplingParams,
+    ) -> Self {
+        Self {
+            value,
+            likelihood,
+            prior,
+            params,
+        }
+    }
+
+    pub fn sample(&self, rng: &mut dyn RngCore) -> Result<NestedSamples<B>, DistributionError> {
+        let n = self.params.live_points;
+        let mut live = (0..n)
+            .map(|_| {
+                let b = self.prior.sample(&(), rng)?;
+                let ln_l = self.ln_l(&b)?;
+                Ok((b, ln_l))
+            })
+            .collect::<Result<Vec<_>, DistributionError>>()?;
+
+        // ln(X_i - X_{i+1}) = -i / N + ln(1 - e^{-1/N})
+        let ln_shrinkage = (-(-1.0 / n as f64).exp_m1()).ln();
+        let mut ln_z = f64::NEG_INFINITY;
+        let mut dead = vec![];
+        let mut scale = 1.0;
+        let mut ln_x = 0.0;
+
+        for i in 0..self.params.max_iter {
+            let mut worst = 0;
+            for j in 1..n {
+                if live[j].1 < live[worst].1 {
+                    worst = j;
+                }
+            }
+            let (b, ln_l) = live[worst].clone();
+            let ln_w = -(i as f64) / n as f64 + ln_shrinkage;
+            ln_z = ln_sum_exp(ln_z, ln_w + ln_l);
+            dead.push((b, ln_w, ln_l));
+
+            let start = if n == 1 {
+                worst
+            } else {
+                (worst + rng.gen_range(1..n)) % n
+            };
+            live[worst] = self.constrained_walk(&live, start, ln_l, &mut scale, rng)?;
+
+            ln_x = -((i + 1) as f64) / n as f64;
+            let ln_l_max = live
+                .iter()
+                .map(|(_, ln_l)| *ln_l)
+                .fold(f64::NEG_INFINITY, f64::max);
+            if ln_l_max + ln_x < ln_z + self.params.tolerance.ln() {
+                break;
+            }
+        }
+
+        // The remaining prior mass X is shared by the live points
+        let ln_w = ln_x - (n as f64).ln();
+        for (b, ln_l) in live {
+            ln_z = ln_sum_exp(ln_z, ln_w + ln_l);
+            dead.push((b, ln_w, ln_l));
+        }
+
+        let weights = dead
+            .iter()
+            .map(|(_, ln_w, ln_l)| (ln_w + ln_l - ln_z).exp())
+            .collect::<Vec<_>>();
+        let information = weights
+            .iter()
+            .zip(dead.iter())
+            .filter(|(p, _)| 0.0 < **p)
+            .map(|(p, (_, _, ln_l))| p * ln_l)
+            .sum::<f64>()
+            - ln_z;
+
+        Ok(NestedSamples {
+            estimate: EvidenceEstimate {
+                ln_evidence: ln_z,
+                standard_error: (information.max(0.0) / n as f64).sqrt(),
+            },
+            information,
+            samples: ContinuousSamplesDistribution::new(
+                dead.into_iter().map(|(b, _, _)| b).collect(),
+            ),
+            weights,
+        })
+    }
+
+    /// Random walk from the live point `start` on p(b) restricted to p(a|b) > e^{`ln_l_min`}
+    fn constrained_walk(
+        &self,
+        live: &[(B, f64)],
+        start: usize,
+        ln_l_min: f64,
+        scale: &mut f64,
+        rng: &mut dyn RngCore,
+    ) -> Result<(B, f64), DistributionError> {
+        let sigma = spread(live);
+        let (mut state, mut ln_l) = live[start].clone();
+        let (mut theta, info) = state.transform_vec();
+        let mut ln_p = self.prior.p_kernel(&state, &())?.ln();
+        let mut accepted = 0usize;
+        let mut rejected = 0usize;
+
+        for _ in 0..self.params.mcmc_steps {
+            let candidate_theta = theta
+                .iter()
+                .zip(sigma.iter())
+                .map(|(t, s)| t + *scale * s * rng.sample::<f64, _>(StandardNormal))
+                .collect::<Vec<_>>();
+            let candidate = B::restore(&candidate_theta, &info)?;
+            let candidate_ln_p = self.prior.p_kernel(&candidate, &())?.ln();
+            let candidate_ln_l = if candidate_ln_p.is_finite() {
+                self.ln_l(&candidate)?
+            } else {
+                f64::NEG_INFINITY
+            };
+
+            if ln_l_min < candidate_ln_l
+                && MetropolisStatistics::from_ln_ratio(candidate_ln_p - ln_p, rng).accepted
+            {
+                state = candidate;
+                theta = candidate_theta;
+                ln_p = candidate_ln_p;
+                ln_l = candidate_ln_l;
+                accepted += 1;
+            } else {
+                rejected += 1;
+            }
+        }
+
+        // Skilling's step size rule
+        if rejected < accepted {
+            *scale *= (1.0 / accepted as f64).exp();
+        } else if accepted < rejected {
+            *scale /= (1.0 / rejected as f64).exp();
+        }
+
+        Ok((state, ln_l))
+    }
+
+    fn ln_l(&self, b: &B) -> Result<f64, DistributionError> {
+        Ok(self.likelihood.p_kernel(self.value, b)?.ln())
+    }
+}
+
+/// Standard deviation of the live points in each dimension of the transformed vector
+fn spread<B>(live: &[(B, f64)]) -> Vec<f64>
+where
+    B: RandomVariable,
+{
+    let thetas = live
+        .iter()
+        .map(|(b, _)| b.transform_vec().0)
+        .collect::<Vec<_>>();
+    let n = thetas.len() as f64;
+    let d = thetas[0].len();
+
+    (0..d)
+        .map(|k| {
+            let mean = thetas.iter().map(|t| t[k]).sum::<f64>() / n;
+            let var = thetas.iter().map(|t| (t[k] - mean).powi(2)).sum::<f64>() / n;
+            var.sqrt().max(f64::EPSILON)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::evidence::fixtures::{linear_regression, normal_mean};
+    use crate::*;
+    use rand::prelude::*;
+
+    #[test]
+    fn it_works() {
+        let (value, likelihood, prior, ln_z) = normal_mean();
+
+        let params = NestedSamplingParams::new(400, 20, 1e-4, 100000).unwrap();
+        let sampler = NestedSampler::new(&value, &likelihood, &prior, params);
+        let mut rng = StdRng::from_seed([1; 32]);
+        let samples = sampler.sample(&mut rng).unwrap();
+
+        let estimate = samples.estimate();
+        assert!(0.0 < estimate.standard_error() && estimate.standard_error() < 0.1);
+        assert!((estimate.ln_evidence() - ln_z).abs() < 3.0 * estimate.standard_error());
+
+        // p(b|a) = N(6 / 3.25, 1 / 3.25)
+        let mean = samples
+            .samples()
+            .samples()
+            .iter()
+            .zip(samples.weights().iter())
+            .map(|(b, w)| w * b)
+            .sum::<f64>();
+        assert!((mean - 6.0 / 3.25).abs() < 0.1);
+
+        assert!(NestedSamplingParams::new(0, 20, 1e-4, 100).is_err());
+    }
+
+    #[test]
+    fn it_works2() {
+        let (value, likelihood, prior, ln_z) = linear_regression();
+
+        let params = NestedSamplingParams::new(400, 20, 1e-4, 100000).unwrap();
+        let sampler = NestedSampler::new(&value, &likelihood, &prior, params);
+        let mut rng = StdRng::from_seed([1; 32]);
+        let samples = sampler.sample(&mut rng).unwrap();
+
+        let estimate = samples.estimate();
+        assert!((estimate.ln_evidence() - ln_z).abs() < 3.0 * estimate.standard_error());
+
+        // p(b|a) = N((6 / 3.25, 2 / 2.25), diag(1 / 3.25, 1 / 2.25))
+        for (i, expected) in [6.0 / 3.25, 2.0 / 2.25].iter().enumerate() {
+            let mean = samples
+                .samples()
+                .samples()
+                .iter()
+                .zip(samples.weights().iter())
+                .map(|(b, w)| w * b[i])
+                .sum::<f64>();
+            assert!((mean - expected).abs() < 0.1);
+        }
+    }
+}
@@ -0,0 +1,258 @@
+use super::{mean_var, EvidenceError, EvidenceEstimate};
+use crate::mcmc::util::seeds;
+use crate::{
+    Distribution, DistributionError, McmcParams, McmcStatistics, RandomVariable,
+    TemperedDistribution,
+};
+use rand::prelude::*;
+use rayon::prelude::*;
+
+/// Inverse temperatures 0 = β_0 < β_1 < ... < β_K = 1, and the chain run at each of them
+#[derive(Clone, Debug, PartialEq)]
+pub struct ThermodynamicIntegrationParams {
+    betas: Vec<f64>,
+    mcmc: McmcParams,
+}
+
+impl ThermodynamicIntegrationParams {
+    pub fn new(betas: Vec<f64>, mcmc: McmcParams) -> Result<Self, DistributionError> {
+        if betas.len() < 2
+            || betas[0] != 0.0
+            || betas[betas.len() - 1] != 1.0
+            || betas.windows(2).any(|w| w[1] <= w[0])
+        {
+            return Err(DistributionError::InvalidParameters(
+                EvidenceError::InverseTemperaturesOutOfRange.into(),
+            ));
+        }
+
+        Ok(Self { betas, mcmc })
+    }
+
+    /// `rungs` inverse temperatures β_k = (k / (K - 1))^`power`, which are dense near 0 (Friel and Pettitt, 2008)
+    pub fn power_posterior(
+        rungs: usize,
+        power: f64,
+        mcmc: McmcParams,
+    ) -> Result<Self, DistributionError> {
+        let betas = (0..rungs)
+            .map(|k| (k as f64 / (rungs - 1).max(1) as f64).powf(power))
+            .collect();
+
+        Self::new(betas, mcmc)
+    }
+
+    pub fn betas(&self) -> &[f64] {
+        &self.betas
+    }
+
+    pub fn mcmc(&self) -> &McmcParams {
+        &self.mcmc
+    }
+}
+
+/// Evidence of likelihood p(a|b) and prior p(b) by thermodynamic integration
+/// ln p(a) = ∫_0^1 E_β[ln p(a|b)] dβ, where E_β is the expectation under the power posterior p(a|b)^β p(b).
+/// A chain is run at each β in parallel by `transition`, which leaves p(a|b)^β p(b) invariant.
+/// The integral is the trapezoidal rule corrected by the variances Var_β[ln p(a|b)] (Friel et al., 2014).
+/// The standard error is the Monte Carlo error of the expectations by batch means, not the error of the discretization.
+pub struct ThermodynamicIntegrator<'a, L, A, B, T, F>
+where
+    L: Distribution<Value = A, Condition = B>,
+    A: RandomVariable,
+    B: RandomVariable,
+    T: McmcStatistics,
+    F: Fn(&TemperedDistribution<'a, L>, &B, &mut dyn RngCore) -> Result<(B, T), DistributionError>
+        + Send
+        + Sync,
+{
+    value: &'a A,
+    likelihood: &'a L,
+    transition: &'a F,
+    params: ThermodynamicIntegrationParams,
+}
+
+impl<'a, L, A, B, T, F> ThermodynamicIntegrator<'a, L, A, B, T, F>
+where
+    L: Distribution<Value = A, Condition = B>,
+    A: RandomVariable,
+    B: RandomVariable,
+    T: McmcStatistics,
+    F: Fn(&TemperedDistribution<'a, L>, &B, &mut dyn RngCore) -> Result<(B, T), DistributionError>
+        + Send
+        + Sync,
+{
+    pub fn new(
+        value: &'a A,
+        likelihood: &'a L,
+        transition: &'a F,
+        params: ThermodynamicIntegrationParams,
+    ) -> Self {
+        Self {
+            value,
+            likelihood,
+            transition,
+            params,
+        }
+    }
+
+    /// Estimates ln p(a) with the chains of all inverse temperatures started from `initial`
+    pub fn estimate(
+        &self,
+        initial: B,
+        rng: &mut dyn RngCore,
+    ) -> Result<EvidenceEstimate, DistributionError> {
+        if self.params.mcmc.iter() == 0 {
+            return Err(DistributionError::InvalidParameters(
+                EvidenceError::SamplesAreEmpty.into(),
+            ));
+        }
+
+        let seeds = seeds(self.params.betas.len(), rng);
+
+        // (E_β[ln p(a|b)], Var_β[ln p(a|b)], squared standard error of E_β[ln p(a|b)])
+        let moments = self
+            .params
+            .betas
+            .par_iter()
+            .zip(seeds.into_par_iter())
+            .map(|(&beta, seed)| {
+                let mut rng = StdRng::from_seed(seed);
+                let ln_ls = self.run(beta, initial.clone(), &mut rng)?;
+                let (mean, var) = mean_var(&ln_ls);
+
+                Ok((mean, var, batch_means_variance(&ln_ls, mean)))
+            })
+            .collect::<Result<Vec<_>, DistributionError>>()?;
+
+        let mut ln_evidence = 0.0;
+        let mut weights = vec![0.0; moments.len()];
+        for (i, w) in self.params.betas.windows(2).enumerate() {
+            let delta = w[1] - w[0];
+            let (mean0, var0, _) = moments[i];
+            let (mean1, var1, _) = moments[i + 1];
+            ln_evidence += 0.5 * delta * (mean0 + mean1) - delta.powi(2) / 12.0 * (var1 - var0);
+            weights[i] += 0.5 * delta;
+            weights[i + 1] += 0.5 * delta;
+        }
+        let variance = weights
+            .iter()
+            .zip(moments.iter())
+            .map(|(w, &(_, _, se2))| w.powi(2) * se2)
+            .sum::<f64>();
+
+        Ok(EvidenceEstimate {
+            ln_evidence,
+            standard_error: variance.sqrt(),
+        })
+    }
+
+    /// ln p(a|b) of the kept states of the chain at `beta`
+    fn run(
+        &self,
+        beta: f64,
+        initial: B,
+        rng: &mut dyn RngCore,
+    ) -> Result<Vec<f64>, DistributionError> {
+        let mcmc = &self.params.mcmc;
+        let tempered = TemperedDistribution::new(self.likelihood, beta);
+        let mut state = initial;
+        for _ in 0..mcmc.burn_in() {
+            state = (self.transition)(&tempered, &state, rng)?.0;
+        }
+
+        let mut ln_ls = Vec::with_capacity(mcmc.iter());
+        for _ in 0..mcmc.iter() {
+            for _ in 0..mcmc.thinning() {
+                state = (self.transition)(&tempered, &state, rng)?.0;
+            }
+            ln_ls.push(self.likelihood.p_kernel(self.value, &state)?.ln());
+        }
+
+        Ok(ln_ls)
+    }
+}
+
+/// Squared standard error of the mean of a correlated series by √n batches
+fn batch_means_variance(x: &[f64], mean: f64) -> f64 {
+    let n = x.len();
+    let batches = (n as f64).sqrt().floor() as usize;
+    if batches < 2 {
+        return mean_var(x).1 / n as f64;
+    }
+    let size = n / batches;
+
+    let var = x
+        .chunks_exact(size)
+        .take(batches)
+        .map(|batch| (batch.iter().sum::<f64>() / size as f64 - mean).powi(2))
+        .sum::<f64>()
+        * size as f64
+        / (batches - 1) as f64;
+
+    var / n as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::evidence::fixtures::{linear_regression, normal_mean};
+    use crate::*;
+    use rand::prelude::*;
+
+    #[test]
+    fn it_works() {
+        let (value, likelihood, prior, ln_z) = normal_mean();
+        let proposal = InstantDistribution::new(
+            |x: &f64, b: &f64| Ok((-0.5 * (x - b).powi(2)).exp()),
+            |b: &f64, rng: &mut dyn RngCore| Normal.sample(&NormalParams::new(*b, 1.0)?, rng),
+        );
+        let transition = |likelihood: &TemperedDistribution<_>, b: &f64, rng: &mut dyn RngCore| {
+            MetropolisSampler::new(&value, likelihood, &prior, &proposal).transition(b, rng)
+        };
+
+        let mcmc = McmcParams::new(4000, 500, 2).unwrap();
+        let params = ThermodynamicIntegrationParams::power_posterior(20, 5.0, mcmc).unwrap();
+        let integrator = ThermodynamicIntegrator::new(&value, &likelihood, &transition, params);
+        let mut rng = StdRng::from_seed([1; 32]);
+        let estimate = integrator.estimate(0.0, &mut rng).unwrap();
+
+        assert!((estimate.ln_evidence() - ln_z).abs() < 0.1);
+        assert!(0.0 < estimate.standard_error() && estimate.standard_error() < 0.1);
+
+        let mcmc = McmcParams::new(10, 0, 1).unwrap();
+        assert!(ThermodynamicIntegrationParams::new(vec![0.0, 0.5], mcmc).is_err());
+    }
+
+    #[test]
+    fn it_works2() {
+        let (value, likelihood, prior, ln_z) = linear_regression();
+        let proposal = InstantDistribution::new(
+            |x: &Vec<f64>, b: &Vec<f64>| {
+                Ok((-0.5
+                    * x.iter()
+                        .zip(b.iter())
+                        .map(|(x_i, b_i)| (x_i - b_i).powi(2))
+                        .sum::<f64>())
+                .exp())
+            },
+            |b: &Vec<f64>, rng: &mut dyn RngCore| {
+                b.iter()
+                    .map(|b_i| Normal.sample(&NormalParams::new(*b_i, 1.0)?, rng))
+                    .collect()
+            },
+        );
+        let transition =
+            |likelihood: &TemperedDistribution<_>, b: &Vec<f64>, rng: &mut dyn RngCore| {
+                MetropolisSampler::new(&value, likelihood, &prior, &proposal).transition(b, rng)
+            };
+
+        let mcmc = McmcParams::new(4000, 500, 2).unwrap();
+        let params = ThermodynamicIntegrationParams::power_posterior(20, 5.0, mcmc).unwrap();
+        let integrator = ThermodynamicIntegrator::new(&value, &likelihood, &transition, params);
+        let mut rng = StdRng::from_seed([1; 32]);
+        let estimate = integrator.estimate(vec![0.0, 0.0], &mut rng).unwrap();
+
+        assert!((estimate.ln_evidence() - ln_z).abs() < 0.15);
+        assert!(0.0 < estimate.standard_error() && estimate.standard_error() < 0.1);
+    }
+}
@@ -14,6 +14,7 @@ extern crate thiserror;
 pub mod continuous;
 pub mod discrete;
 pub mod distribution;
+pub mod evidence;
 pub mod mcmc;
 pub mod nonparametric;
 pub mod variational;
@@ -21,5 +22,6 @@ pub mod variational;
 pub use continuous::*;
 pub use discrete::*;
 pub use distribution::*;
+pub use evidence::*;
 pub use mcmc::*;
 pub use variational::*;
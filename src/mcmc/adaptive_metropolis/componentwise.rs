@@ -0,0 +1,326 @@
+use super::{AdaptiveMetropolisError, RandomWalkTarget};
+use crate::{
+    Distribution, DistributionError, McmcChain, McmcKernel, MetropolisStatistics, RandomVariable,
+};
+use rand::prelude::*;
+use rand_distr::StandardNormal;
+
+/// Initial proposal scale of every component, target acceptance rate per component, and number of sweeps between adaptations
+#[derive(Clone, Debug, PartialEq)]
+pub struct ComponentwiseAdaptiveMetropolisParams {
+    initial_scale: f64,
+    target_acceptance: f64,
+    batch_size: usize,
+}
+
+impl Default for ComponentwiseAdaptiveMetropolisParams {
+    fn default() -> Self {
+        Self {
+            initial_scale: 1.0,
+            target_acceptance: 0.44,
+            batch_size: 50,
+        }
+    }
+}
+
+impl ComponentwiseAdaptiveMetropolisParams {
+    pub fn new(
+        initial_scale: f64,
+        target_acceptance: f64,
+        batch_size: usize,
+    ) -> Result<Self, DistributionError> {
+        if initial_scale <= 0.0 || !initial_scale.is_finite() {
+            return Err(DistributionError::InvalidParameters(
+                AdaptiveMetropolisError::ScaleMustBePositive.into(),
+            ));
+        }
+        if target_acceptance <= 0.0 || 1.0 <= target_acceptance || target_acceptance.is_nan() {
+            return Err(DistributionError::InvalidParameters(
+                AdaptiveMetropolisError::TargetAcceptanceOutOfRange.into(),
+            ));
+        }
+        if batch_size == 0 {
+            return Err(DistributionError::InvalidParameters(
+                AdaptiveMetropolisError::BatchSizeMustBePositive.into(),
+            ));
+        }
+
+        Ok(Self {
+            initial_scale,
+            target_acceptance,
+            batch_size,
+        })
+    }
+
+    pub fn initial_scale(&self) -> f64 {
+        self.initial_scale
+    }
+
+    pub fn target_acceptance(&self) -> f64 {
+        self.target_acceptance
+    }
+
+    pub fn batch_size(&self) -> usize {
+        self.batch_size
+    }
+}
+
+/// Metropolis within Gibbs which updates each component of the vector of b in turn with the proposal N(b_i, s_i^2)
+/// The statistics of a sweep are accepted if any component moved, with the mean acceptance probability of the components.
+#[derive(Clone, Debug)]
+pub struct ComponentwiseKernel<'a, L, P, A, B>
+where
+    L: Distribution<Value = A, Condition = B>,
+    P: Distribution<Value = B, Condition = ()>,
+    A: RandomVariable,
+    B: RandomVariable,
+{
+    value: &'a A,
+    likelihood: &'a L,
+    prior: &'a P,
+    scales: Vec<f64>,
+}
+
+impl<'a, L, P, A, B> ComponentwiseKernel<'a, L, P, A, B>
+where
+    L: Distribution<Value = A, Condition = B>,
+    P: Distribution<Value = B, Condition = ()>,
+    A: RandomVariable,
+    B: RandomVariable,
+{
+    pub fn new(
+        value: &'a A,
+        likelihood: &'a L,
+        prior: &'a P,
+        scales: Vec<f64>,
+    ) -> Result<Self, DistributionError> {
+        if scales.iter().any(|&s| s <= 0.0 || !s.is_finite()) {
+            return Err(DistributionError::InvalidParameters(
+                AdaptiveMetropolisError::ScaleMustBePositive.into(),
+            ));
+        }
+
+        Ok(Self {
+            value,
+            likelihood,
+            prior,
+            scales,
+        })
+    }
+
+    /// Proposal scale s_i of each component
+    pub fn scales(&self) -> &[f64] {
+        &self.scales
+    }
+}
+
+impl<'a, L, P, A, B> McmcKernel for ComponentwiseKernel<'a, L, P, A, B>
+where
+    L: Distribution<Value = A, Condition = B>,
+    P: Distribution<Value = B, Condition = ()>,
+    A: RandomVariable,
+    B: RandomVariable,
+{
+    type State = B;
+    type Statistics = MetropolisStatistics;
+
+    fn transition(
+        &self,
+        state: &B,
+        rng: &mut dyn RngCore,
+    ) -> Result<(B, MetropolisStatistics), DistributionError> {
+        let (theta, info) = state.transform_vec();
+        if theta.len() != self.scales.len() {
+            return Err(DistributionError::InvalidParameters(
+                AdaptiveMetropolisError::DimensionMismatch.into(),
+            ));
+        }
+        let target = RandomWalkTarget::new(self.value, self.likelihood, self.prior, info);
+        let ln_p = target.ln_p(&theta)?;
+        let (theta, _, statistics) = sweep(&target, theta, ln_p, &self.scales, rng)?;
+
+        Ok((target.restore(&theta)?, summarize(&statistics)))
+    }
+}
+
+/// Sample b from posterior p(b|a) with likelihood p(a|b) and prior p(b)
+/// Adaptive Metropolis within Gibbs (Roberts and Rosenthal, 2009).
+/// After the k-th batch of sweeps, ln s_i is increased by δ_k = min(0.01, k^{-1/2}) if the acceptance rate of the i-th component exceeds the target, and decreased otherwise.
+/// δ_k vanishes, so the adaptation diminishes.
+pub struct ComponentwiseAdaptiveMetropolisSampler<'a, L, P, A, B>
+where
+    L: Distribution<Value = A, Condition = B>,
+    P: Distribution<Value = B, Condition = ()>,
+    A: RandomVariable,
+    B: RandomVariable,
+{
+    value: &'a A,
+    likelihood: &'a L,
+    prior: &'a P,
+    params: ComponentwiseAdaptiveMetropolisParams,
+}
+
+impl<'a, L, P, A, B> ComponentwiseAdaptiveMetropolisSampler<'a, L, P, A, B>
+where
+    L: Distribution<Value = A, Condition = B>,
+    P: Distribution<Value = B, Condition = ()>,
+    A: RandomVariable,
+    B: RandomVariable,
+{
+    pub fn new(
+        value: &'a A,
+        likelihood: &'a L,
+        prior: &'a P,
+        params: ComponentwiseAdaptiveMetropolisParams,
+    ) -> Self {
+        Self {
+            value,
+            likelihood,
+            prior,
+            params,
+        }
+    }
+
+    /// Samples while adapting the scales after every batch
+    pub fn sample(
+        &self,
+        iter: usize,
+        initial: B,
+        rng: &mut dyn RngCore,
+    ) -> Result<McmcChain<B, MetropolisStatistics>, DistributionError> {
+        Ok(self.adapt(iter, initial, rng)?.0)
+    }
+
+    /// Adapts the scales for `iter` sweeps, and returns the last state and the kernel with the scales frozen
+    pub fn warmup(
+        &self,
+        iter: usize,
+        initial: B,
+        rng: &mut dyn RngCore,
+    ) -> Result<(B, ComponentwiseKernel<'a, L, P, A, B>), DistributionError> {
+        let (chain, scales) = self.adapt(iter, initial.clone(), rng)?;
+        let kernel = ComponentwiseKernel::new(self.value, self.likelihood, self.prior, scales)?;
+
+        Ok((chain.last().cloned().unwrap_or(initial), kernel))
+    }
+
+    fn adapt(
+        &self,
+        iter: usize,
+        initial: B,
+        rng: &mut dyn RngCore,
+    ) -> Result<(McmcChain<B, MetropolisStatistics>, Vec<f64>), DistributionError> {
+        let (mut theta, info) = initial.transform_vec();
+        let n = theta.len();
+        let target = RandomWalkTarget::new(self.value, self.likelihood, self.prior, info);
+        let mut ln_p = target.initial_ln_p(&theta)?;
+
+        let mut ln_scales = vec![self.params.initial_scale.ln(); n];
+        let mut accepted = vec![0usize; n];
+        let mut batch = 0;
+        let mut chain = McmcChain::new();
+
+        for t in 0..iter {
+            let scales = ln_scales.iter().map(|ls| ls.exp()).collect::<Vec<_>>();
+            let (next, next_ln_p, statistics) = sweep(&target, theta, ln_p, &scales, rng)?;
+            theta = next;
+            ln_p = next_ln_p;
+
+            for (ai, si) in accepted.iter_mut().zip(statistics.iter()) {
+                if si.accepted {
+                    *ai += 1;
+                }
+            }
+            if (t + 1) % self.params.batch_size == 0 {
+                batch += 1;
+                let delta = (1.0 / (batch as f64).sqrt()).min(0.01);
+                for (ls, ai) in ln_scales.iter_mut().zip(accepted.iter_mut()) {
+                    let rate = *ai as f64 / self.params.batch_size as f64;
+                    if self.params.target_acceptance < rate {
+                        *ls += delta;
+                    } else {
+                        *ls -= delta;
+                    }
+                    *ai = 0;
+                }
+            }
+
+            chain.push(target.restore(&theta)?, summarize(&statistics));
+        }
+
+        Ok((chain, ln_scales.iter().map(|ls| ls.exp()).collect()))
+    }
+}
+
+/// Updates each component in turn
+fn sweep<L, P, A, B>(
+    target: &RandomWalkTarget<L, P, A, B>,
+    mut theta: Vec<f64>,
+    mut ln_p: f64,
+    scales: &[f64],
+    rng: &mut dyn RngCore,
+) -> Result<(Vec<f64>, f64, Vec<MetropolisStatistics>), DistributionError>
+where
+    L: Distribution<Value = A, Condition = B>,
+    P: Distribution<Value = B, Condition = ()>,
+    A: RandomVariable,
+    B: RandomVariable,
+{
+    let mut statistics = Vec::with_capacity(scales.len());
+    for (i, si) in scales.iter().enumerate() {
+        let mut increment = vec![0.0; theta.len()];
+        increment[i] = si * rng.sample::<f64, _>(StandardNormal);
+        let (next, next_ln_p, s) = target.step(theta, ln_p, &increment, rng)?;
+        theta = next;
+        ln_p = next_ln_p;
+        statistics.push(s);
+    }
+
+    Ok((theta, ln_p, statistics))
+}
+
+fn summarize(statistics: &[MetropolisStatistics]) -> MetropolisStatistics {
+    if statistics.is_empty() {
+        return MetropolisStatistics::always_accepted();
+    }
+
+    MetropolisStatistics {
+        accepted: statistics.iter().any(|s| s.accepted),
+        acceptance_probability: statistics
+            .iter()
+            .map(|s| s.acceptance_probability)
+            .sum::<f64>()
+            / statistics.len() as f64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use rand::prelude::*;
+
+    #[test]
+    fn it_works() {
+        // p(b) = N(0, diag(1, 100))
+        let likelihood =
+            InstantDistribution::new(|_: &(), _: &Vec<f64>| Ok(1.0), |_: &Vec<f64>, _rng| Ok(()));
+        let prior = InstantDistribution::new(
+            |b: &Vec<f64>, _: &()| Ok((-0.5 * (b[0].powi(2) + b[1].powi(2) / 100.0)).exp()),
+            |_: &(), _rng| Ok(vec![0.0; 2]),
+        );
+        let params = ComponentwiseAdaptiveMetropolisParams::default();
+        let sampler = ComponentwiseAdaptiveMetropolisSampler::new(&(), &likelihood, &prior, params);
+        let mut rng = StdRng::from_seed([1; 32]);
+
+        let (last, kernel) = sampler.warmup(20000, vec![0.0, 0.0], &mut rng).unwrap();
+        let scales = kernel.scales();
+        assert!(5.0 < scales[1] / scales[0] && scales[1] / scales[0] < 20.0);
+
+        let chain = kernel.run(20000, last, &mut rng).unwrap();
+        let var = chain.samples().iter().map(|s| s[1].powi(2)).sum::<f64>() / 20000.0;
+        assert!((var - 100.0).abs() < 25.0);
+
+        assert!(ComponentwiseAdaptiveMetropolisParams::new(0.0, 0.44, 50).is_err());
+        assert!(ComponentwiseAdaptiveMetropolisParams::new(1.0, 0.44, 0).is_err());
+    }
+}
@@ -0,0 +1,465 @@
+pub mod componentwise;
+pub mod robust;
+
+pub use componentwise::*;
+pub use robust::*;
+
+use crate::{
+    Distribution, DistributionError, MassMatrix, McmcChain, McmcKernel, MetropolisStatistics,
+    RandomVariable,
+};
+use opensrdk_linear_algebra::*;
+use rand::prelude::*;
+use rand_distr::StandardNormal;
+
+#[derive(thiserror::Error, Debug)]
+pub enum AdaptiveMetropolisError {
+    #[error("Regularization must be positive")]
+    RegularizationMustBePositive,
+    #[error("Target acceptance must be in (0, 1)")]
+    TargetAcceptanceOutOfRange,
+    #[error("Decay must be in (1/2, 1]")]
+    DecayOutOfRange,
+    #[error("Scale must be positive")]
+    ScaleMustBePositive,
+    #[error("Batch size must be positive")]
+    BatchSizeMustBePositive,
+    #[error("Dimension mismatch")]
+    DimensionMismatch,
+    #[error("Initial value is out of support")]
+    InitialValueOutOfSupport,
+    #[error("Unknown error")]
+    Unknown,
+}
+
+/// Random walk Metropolis with a fixed proposal N(b, Σ)
+/// Adaptive samplers return it with the adapted Σ after warmup
+#[derive(Clone, Debug)]
+pub struct RandomWalkKernel<'a, L, P, A, B>
+where
+    L: Distribution<Value = A, Condition = B>,
+    P: Distribution<Value = B, Condition = ()>,
+    A: RandomVariable,
+    B: RandomVariable,
+{
+    value: &'a A,
+    likelihood: &'a L,
+    prior: &'a P,
+    covariance: MassMatrix,
+}
+
+impl<'a, L, P, A, B> RandomWalkKernel<'a, L, P, A, B>
+where
+    L: Distribution<Value = A, Condition = B>,
+    P: Distribution<Value = B, Condition = ()>,
+    A: RandomVariable,
+    B: RandomVariable,
+{
+    pub fn new(value: &'a A, likelihood: &'a L, prior: &'a P, covariance: MassMatrix) -> Self {
+        Self {
+            value,
+            likelihood,
+            prior,
+            covariance,
+        }
+    }
+
+    /// Covariance Σ of the increment
+    pub fn covariance(&self) -> &MassMatrix {
+        &self.covariance
+    }
+}
+
+impl<'a, L, P, A, B> McmcKernel for RandomWalkKernel<'a, L, P, A, B>
+where
+    L: Distribution<Value = A, Condition = B>,
+    P: Distribution<Value = B, Condition = ()>,
+    A: RandomVariable,
+    B: RandomVariable,
+{
+    type State = B;
+    type Statistics = MetropolisStatistics;
+
+    fn transition(
+        &self,
+        state: &B,
+        rng: &mut dyn RngCore,
+    ) -> Result<(B, MetropolisStatistics), DistributionError> {
+        let (theta, info) = state.transform_vec();
+        if let Some(n) = self.covariance.dim() {
+            if n != theta.len() {
+                return Err(DistributionError::InvalidParameters(
+                    AdaptiveMetropolisError::DimensionMismatch.into(),
+                ));
+            }
+        }
+        let target = RandomWalkTarget::new(self.value, self.likelihood, self.prior, info);
+        let ln_p = target.ln_p(&theta)?;
+        let increment = self.covariance.sample(theta.len(), rng);
+        let (theta, _, statistics) = target.step(theta, ln_p, &increment, rng)?;
+
+        Ok((target.restore(&theta)?, statistics))
+    }
+}
+
+/// Initial covariance, number of transitions before the empirical covariance is used, and regularization ε
+#[derive(Clone, Debug)]
+pub struct AdaptiveMetropolisParams {
+    initial_covariance: MassMatrix,
+    adaptation_start: usize,
+    epsilon: f64,
+}
+
+impl Default for AdaptiveMetropolisParams {
+    fn default() -> Self {
+        Self {
+            initial_covariance: MassMatrix::Identity,
+            adaptation_start: 100,
+            epsilon: 1e-6,
+        }
+    }
+}
+
+impl AdaptiveMetropolisParams {
+    pub fn new(
+        initial_covariance: MassMatrix,
+        adaptation_start: usize,
+        epsilon: f64,
+    ) -> Result<Self, DistributionError> {
+        if epsilon <= 0.0 || !epsilon.is_finite() {
+            return Err(DistributionError::InvalidParameters(
+                AdaptiveMetropolisError::RegularizationMustBePositive.into(),
+            ));
+        }
+
+        Ok(Self {
+            initial_covariance,
+            adaptation_start,
+            epsilon,
+        })
+    }
+
+    pub fn initial_covariance(&self) -> &MassMatrix {
+        &self.initial_covariance
+    }
+
+    pub fn adaptation_start(&self) -> usize {
+        self.adaptation_start
+    }
+
+    pub fn epsilon(&self) -> f64 {
+        self.epsilon
+    }
+}
+
+/// Sample b from posterior p(b|a) with likelihood p(a|b) and prior p(b)
+/// Adaptive Metropolis (Haario et al., 2001) with the proposal N(b, 2.38^2 / d (C_t + ε I)),
+/// where C_t is the empirical covariance of the chain so far.
+/// C_t changes by O(1/t) per transition, so the adaptation diminishes.
+pub struct AdaptiveMetropolisSampler<'a, L, P, A, B>
+where
+    L: Distribution<Value = A, Condition = B>,
+    P: Distribution<Value = B, Condition = ()>,
+    A: RandomVariable,
+    B: RandomVariable,
+{
+    value: &'a A,
+    likelihood: &'a L,
+    prior: &'a P,
+    params: AdaptiveMetropolisParams,
+}
+
+impl<'a, L, P, A, B> AdaptiveMetropolisSampler<'a, L, P, A, B>
+where
+    L: Distribution<Value = A, Condition = B>,
+    P: Distribution<Value = B, Condition = ()>,
+    A: RandomVariable,
+    B: RandomVariable,
+{
+    pub fn new(
+        value: &'a A,
+        likelihood: &'a L,
+        prior: &'a P,
+        params: AdaptiveMetropolisParams,
+    ) -> Self {
+        Self {
+            value,
+            likelihood,
+            prior,
+            params,
+        }
+    }
+
+    /// Samples while adapting the proposal at every transition
+    pub fn sample(
+        &self,
+        iter: usize,
+        initial: B,
+        rng: &mut dyn RngCore,
+    ) -> Result<McmcChain<B, MetropolisStatistics>, DistributionError> {
+        Ok(self.adapt(iter, initial, rng)?.0)
+    }
+
+    /// Adapts the proposal for `iter` transitions, and returns the last state and the kernel with the proposal frozen
+    pub fn warmup(
+        &self,
+        iter: usize,
+        initial: B,
+        rng: &mut dyn RngCore,
+    ) -> Result<(B, RandomWalkKernel<'a, L, P, A, B>), DistributionError> {
+        let (chain, covariance) = self.adapt(iter, initial.clone(), rng)?;
+        let kernel = RandomWalkKernel::new(self.value, self.likelihood, self.prior, covariance);
+
+        Ok((chain.last().cloned().unwrap_or(initial), kernel))
+    }
+
+    fn adapt(
+        &self,
+        iter: usize,
+        initial: B,
+        rng: &mut dyn RngCore,
+    ) -> Result<(McmcChain<B, MetropolisStatistics>, MassMatrix), DistributionError> {
+        let (mut theta, info) = initial.transform_vec();
+        let n = theta.len();
+        let mut covariance = self.params.initial_covariance.clone();
+        let mut factor = cholesky_factor(&covariance, n)?;
+        let target = RandomWalkTarget::new(self.value, self.likelihood, self.prior, info);
+        let mut ln_p = target.initial_ln_p(&theta)?;
+
+        let scale = 2.38f64.powi(2) / n as f64;
+        let mut empirical = EmpiricalCovariance::new(n);
+        empirical.update(&theta);
+        let mut chain = McmcChain::new();
+
+        for t in 0..iter {
+            let increment = lower_mul(&factor, &standard_normal(n, rng));
+            let (next, next_ln_p, statistics) = target.step(theta, ln_p, &increment, rng)?;
+            theta = next;
+            ln_p = next_ln_p;
+
+            empirical.update(&theta);
+            if self.params.adaptation_start <= t + 1 {
+                if let Some(c) = empirical.covariance(scale, self.params.epsilon) {
+                    factor = cholesky_factor(&c, n)?;
+                    covariance = c;
+                }
+            }
+
+            chain.push(target.restore(&theta)?, statistics);
+        }
+
+        Ok((chain, covariance))
+    }
+}
+
+/// ln p(a|b) + ln p(b) up to a constant as a function of the vector of b
+struct RandomWalkTarget<'a, L, P, A, B>
+where
+    L: Distribution<Value = A, Condition = B>,
+    P: Distribution<Value = B, Condition = ()>,
+    A: RandomVariable,
+    B: RandomVariable,
+{
+    value: &'a A,
+    likelihood: &'a L,
+    prior: &'a P,
+    info: B::RestoreInfo,
+}
+
+impl<'a, L, P, A, B> RandomWalkTarget<'a, L, P, A, B>
+where
+    L: Distribution<Value = A, Condition = B>,
+    P: Distribution<Value = B, Condition = ()>,
+    A: RandomVariable,
+    B: RandomVariable,
+{
+    fn new(value: &'a A, likelihood: &'a L, prior: &'a P, info: B::RestoreInfo) -> Self {
+        Self {
+            value,
+            likelihood,
+            prior,
+            info,
+        }
+    }
+
+    fn restore(&self, theta: &[f64]) -> Result<B, DistributionError> {
+        B::restore(theta, &self.info)
+    }
+
+    fn ln_p(&self, theta: &[f64]) -> Result<f64, DistributionError> {
+        let b = self.restore(theta)?;
+
+        Ok(self.likelihood.p_kernel(self.value, &b)?.ln() + self.prior.p_kernel(&b, &())?.ln())
+    }
+
+    fn initial_ln_p(&self, theta: &[f64]) -> Result<f64, DistributionError> {
+        let ln_p = self.ln_p(theta)?;
+        if !ln_p.is_finite() {
+            return Err(DistributionError::InvalidParameters(
+                AdaptiveMetropolisError::InitialValueOutOfSupport.into(),
+            ));
+        }
+
+        Ok(ln_p)
+    }
+
+    /// Accepts or rejects theta + increment
+    fn step(
+        &self,
+        theta: Vec<f64>,
+        ln_p: f64,
+        increment: &[f64],
+        rng: &mut dyn RngCore,
+    ) -> Result<(Vec<f64>, f64, MetropolisStatistics), DistributionError> {
+        let candidate = theta
+            .iter()
+            .zip(increment.iter())
+            .map(|(ti, di)| ti + di)
+            .collect::<Vec<_>>();
+        let candidate_ln_p = self.ln_p(&candidate)?;
+        let statistics = MetropolisStatistics::from_ln_ratio(candidate_ln_p - ln_p, rng);
+
+        if statistics.accepted {
+            Ok((candidate, candidate_ln_p, statistics))
+        } else {
+            Ok((theta, ln_p, statistics))
+        }
+    }
+}
+
+/// Running mean and scatter matrix by Welford's algorithm
+struct EmpiricalCovariance {
+    n: usize,
+    mean: Vec<f64>,
+    scatter: Matrix,
+}
+
+impl EmpiricalCovariance {
+    fn new(dim: usize) -> Self {
+        Self {
+            n: 0,
+            mean: vec![0.0; dim],
+            scatter: Matrix::new(dim, dim),
+        }
+    }
+
+    fn update(&mut self, x: &[f64]) {
+        self.n += 1;
+        let before = x
+            .iter()
+            .zip(self.mean.iter())
+            .map(|(xi, mi)| xi - mi)
+            .collect::<Vec<_>>();
+        for (mi, di) in self.mean.iter_mut().zip(before.iter()) {
+            *mi += di / self.n as f64;
+        }
+        for (i, di) in before.iter().enumerate() {
+            for (j, (xj, mj)) in x.iter().zip(self.mean.iter()).enumerate() {
+                self.scatter[(i, j)] += di * (xj - mj);
+            }
+        }
+    }
+
+    /// scale (C + ε I), or `None` before two points are observed
+    fn covariance(&self, scale: f64, epsilon: f64) -> Option<MassMatrix> {
+        if self.n < 2 {
+            return None;
+        }
+        let dim = self.mean.len();
+        let mut m = Matrix::new(dim, dim);
+        for i in 0..dim {
+            for j in 0..dim {
+                m[(i, j)] = scale * self.scatter[(i, j)] / (self.n - 1) as f64;
+            }
+            m[(i, i)] += scale * epsilon;
+        }
+
+        MassMatrix::dense(m).ok()
+    }
+}
+
+/// Lower triangular cholesky factor L of a covariance Σ = L L^T
+fn cholesky_factor(covariance: &MassMatrix, n: usize) -> Result<Matrix, DistributionError> {
+    if let Some(dim) = covariance.dim() {
+        if dim != n {
+            return Err(DistributionError::InvalidParameters(
+                AdaptiveMetropolisError::DimensionMismatch.into(),
+            ));
+        }
+    }
+
+    let mut l = Matrix::new(n, n);
+    match covariance {
+        MassMatrix::Identity => {
+            for i in 0..n {
+                l[(i, i)] = 1.0;
+            }
+        }
+        MassMatrix::Diagonal(m) => {
+            for (i, mi) in m.iter().enumerate() {
+                l[(i, i)] = mi.sqrt();
+            }
+        }
+        MassMatrix::Dense(factor) => l = factor.l().clone(),
+    }
+
+    Ok(l)
+}
+
+/// L z
+fn lower_mul(l: &Matrix, z: &[f64]) -> Vec<f64> {
+    (0..z.len())
+        .map(|i| (0..=i).map(|j| l[(i, j)] * z[j]).sum::<f64>())
+        .collect()
+}
+
+fn standard_normal(n: usize, rng: &mut dyn RngCore) -> Vec<f64> {
+    (0..n)
+        .map(|_| rng.sample::<f64, _>(StandardNormal))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use rand::prelude::*;
+
+    #[test]
+    fn it_works() {
+        // p(b) = N(0, Σ), Σ = [[1, 0.8], [0.8, 1]]
+        let likelihood =
+            InstantDistribution::new(|_: &(), _: &Vec<f64>| Ok(1.0), |_: &Vec<f64>, _rng| Ok(()));
+        let prior = InstantDistribution::new(
+            |b: &Vec<f64>, _: &()| {
+                Ok((-0.5 * (b[0].powi(2) - 1.6 * b[0] * b[1] + b[1].powi(2)) / 0.36).exp())
+            },
+            |_: &(), _rng| Ok(vec![0.0; 2]),
+        );
+        let params =
+            AdaptiveMetropolisParams::new(MassMatrix::diagonal(vec![0.01; 2]).unwrap(), 100, 1e-6)
+                .unwrap();
+        let sampler = AdaptiveMetropolisSampler::new(&(), &likelihood, &prior, params);
+        let mut rng = StdRng::from_seed([1; 32]);
+
+        let chain = sampler.sample(20000, vec![0.0, 0.0], &mut rng).unwrap();
+        let samples = &chain.samples()[5000..];
+        let n = samples.len() as f64;
+        let mean = (0..2)
+            .map(|i| samples.iter().map(|s| s[i]).sum::<f64>() / n)
+            .collect::<Vec<_>>();
+        let cov = samples
+            .iter()
+            .map(|s| (s[0] - mean[0]) * (s[1] - mean[1]))
+            .sum::<f64>()
+            / n;
+        assert!(mean[0].abs() < 0.15 && mean[1].abs() < 0.15);
+        assert!((cov - 0.8).abs() < 0.15);
+
+        let (last, kernel) = sampler.warmup(5000, vec![0.0, 0.0], &mut rng).unwrap();
+        let chain = kernel.run(5000, last, &mut rng).unwrap();
+        let rate = chain.acceptance_rate();
+        assert!(0.15 < rate && rate < 0.5);
+
+        assert!(AdaptiveMetropolisParams::new(MassMatrix::Identity, 100, 0.0).is_err());
+    }
+}
@@ -0,0 +1,206 @@
+use super::{
+    cholesky_factor, lower_mul, standard_normal, AdaptiveMetropolisError, RandomWalkKernel,
+    RandomWalkTarget,
+};
+use crate::{
+    Distribution, DistributionError, MassMatrix, McmcChain, MetropolisStatistics, RandomVariable,
+};
+use opensrdk_linear_algebra::*;
+use rand::prelude::*;
+
+/// Initial covariance, target acceptance rate α*, and decay γ of the adaptation rate η_t = min(1, d t^{-γ})
+#[derive(Clone, Debug)]
+pub struct RobustAdaptiveMetropolisParams {
+    initial_covariance: MassMatrix,
+    target_acceptance: f64,
+    decay: f64,
+}
+
+impl Default for RobustAdaptiveMetropolisParams {
+    fn default() -> Self {
+        Self {
+            initial_covariance: MassMatrix::Identity,
+            target_acceptance: 0.234,
+            decay: 2.0 / 3.0,
+        }
+    }
+}
+
+impl RobustAdaptiveMetropolisParams {
+    pub fn new(
+        initial_covariance: MassMatrix,
+        target_acceptance: f64,
+        decay: f64,
+    ) -> Result<Self, DistributionError> {
+        if target_acceptance <= 0.0 || 1.0 <= target_acceptance || target_acceptance.is_nan() {
+            return Err(DistributionError::InvalidParameters(
+                AdaptiveMetropolisError::TargetAcceptanceOutOfRange.into(),
+            ));
+        }
+        if decay <= 0.5 || 1.0 < decay || decay.is_nan() {
+            return Err(DistributionError::InvalidParameters(
+                AdaptiveMetropolisError::DecayOutOfRange.into(),
+            ));
+        }
+
+        Ok(Self {
+            initial_covariance,
+            target_acceptance,
+            decay,
+        })
+    }
+
+    pub fn initial_covariance(&self) -> &MassMatrix {
+        &self.initial_covariance
+    }
+
+    pub fn target_acceptance(&self) -> f64 {
+        self.target_acceptance
+    }
+
+    pub fn decay(&self) -> f64 {
+        self.decay
+    }
+}
+
+/// Sample b from posterior p(b|a) with likelihood p(a|b) and prior p(b)
+/// Robust adaptive Metropolis (Vihola, 2012) with the proposal b + S u, u ~ N(0, I).
+/// After each transition S is updated so that
+/// S S^T <- S (I + η_t (α_t - α*) u u^T / |u|^2) S^T,
+/// which drives the acceptance rate to α*.
+pub struct RobustAdaptiveMetropolisSampler<'a, L, P, A, B>
+where
+    L: Distribution<Value = A, Condition = B>,
+    P: Distribution<Value = B, Condition = ()>,
+    A: RandomVariable,
+    B: RandomVariable,
+{
+    value: &'a A,
+    likelihood: &'a L,
+    prior: &'a P,
+    params: RobustAdaptiveMetropolisParams,
+}
+
+impl<'a, L, P, A, B> RobustAdaptiveMetropolisSampler<'a, L, P, A, B>
+where
+    L: Distribution<Value = A, Condition = B>,
+    P: Distribution<Value = B, Condition = ()>,
+    A: RandomVariable,
+    B: RandomVariable,
+{
+    pub fn new(
+        value: &'a A,
+        likelihood: &'a L,
+        prior: &'a P,
+        params: RobustAdaptiveMetropolisParams,
+    ) -> Self {
+        Self {
+            value,
+            likelihood,
+            prior,
+            params,
+        }
+    }
+
+    /// Samples while adapting the proposal at every transition
+    pub fn sample(
+        &self,
+        iter: usize,
+        initial: B,
+        rng: &mut dyn RngCore,
+    ) -> Result<McmcChain<B, MetropolisStatistics>, DistributionError> {
+        Ok(self.adapt(iter, initial, rng)?.0)
+    }
+
+    /// Adapts the proposal for `iter` transitions, and returns the last state and the kernel with the proposal frozen
+    pub fn warmup(
+        &self,
+        iter: usize,
+        initial: B,
+        rng: &mut dyn RngCore,
+    ) -> Result<(B, RandomWalkKernel<'a, L, P, A, B>), DistributionError> {
+        let (chain, covariance) = self.adapt(iter, initial.clone(), rng)?;
+        let kernel = RandomWalkKernel::new(self.value, self.likelihood, self.prior, covariance);
+
+        Ok((chain.last().cloned().unwrap_or(initial), kernel))
+    }
+
+    fn adapt(
+        &self,
+        iter: usize,
+        initial: B,
+        rng: &mut dyn RngCore,
+    ) -> Result<(McmcChain<B, MetropolisStatistics>, MassMatrix), DistributionError> {
+        let (mut theta, info) = initial.transform_vec();
+        let n = theta.len();
+        let mut covariance = self.params.initial_covariance.clone();
+        let mut factor = cholesky_factor(&covariance, n)?;
+        let target = RandomWalkTarget::new(self.value, self.likelihood, self.prior, info);
+        let mut ln_p = target.initial_ln_p(&theta)?;
+        let mut chain = McmcChain::new();
+
+        for t in 0..iter {
+            let u = standard_normal(n, rng);
+            let increment = lower_mul(&factor, &u);
+            let (next, next_ln_p, statistics) = target.step(theta, ln_p, &increment, rng)?;
+            theta = next;
+            ln_p = next_ln_p;
+
+            let eta = (n as f64 * ((t + 1) as f64).powf(-self.params.decay)).min(1.0);
+            let norm = u.iter().map(|ui| ui.powi(2)).sum::<f64>();
+            if 0.0 < norm {
+                let c = eta * (statistics.acceptance_probability - self.params.target_acceptance)
+                    / norm;
+                // S (I + c u u^T) S^T = S S^T + c (S u) (S u)^T
+                let mut m = Matrix::new(n, n);
+                for (i, di) in increment.iter().enumerate() {
+                    for (j, dj) in increment.iter().enumerate() {
+                        m[(i, j)] = (0..=i.min(j))
+                            .map(|k| factor[(i, k)] * factor[(j, k)])
+                            .sum::<f64>()
+                            + c * di * dj;
+                    }
+                }
+                if let Ok(c) = MassMatrix::dense(m) {
+                    factor = cholesky_factor(&c, n)?;
+                    covariance = c;
+                }
+            }
+
+            chain.push(target.restore(&theta)?, statistics);
+        }
+
+        Ok((chain, covariance))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use rand::prelude::*;
+
+    #[test]
+    fn it_works() {
+        // p(b) = N(0, diag(1, 100))
+        let likelihood =
+            InstantDistribution::new(|_: &(), _: &Vec<f64>| Ok(1.0), |_: &Vec<f64>, _rng| Ok(()));
+        let prior = InstantDistribution::new(
+            |b: &Vec<f64>, _: &()| Ok((-0.5 * (b[0].powi(2) + b[1].powi(2) / 100.0)).exp()),
+            |_: &(), _rng| Ok(vec![0.0; 2]),
+        );
+        let params = RobustAdaptiveMetropolisParams::default();
+        let sampler = RobustAdaptiveMetropolisSampler::new(&(), &likelihood, &prior, params);
+        let mut rng = StdRng::from_seed([1; 32]);
+
+        let (last, kernel) = sampler.warmup(5000, vec![0.0, 0.0], &mut rng).unwrap();
+        let chain = kernel.run(20000, last, &mut rng).unwrap();
+        let rate = chain.acceptance_rate();
+        assert!((rate - 0.234).abs() < 0.05);
+
+        let var = chain.samples().iter().map(|s| s[1].powi(2)).sum::<f64>() / 20000.0;
+        assert!((var - 100.0).abs() < 25.0);
+
+        assert!(RobustAdaptiveMetropolisParams::new(MassMatrix::Identity, 1.0, 0.7).is_err());
+        assert!(RobustAdaptiveMetropolisParams::new(MassMatrix::Identity, 0.234, 0.5).is_err());
+    }
+}
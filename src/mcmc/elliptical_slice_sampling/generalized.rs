@@ -0,0 +1,149 @@
+use super::{elliptical_slice, EllipticalSliceSamplingError};
+use crate::{
+    Distribution, DistributionError, EllipticalParams, McmcChain, McmcKernel, MetropolisStatistics,
+    MultivariateStudentTParams, RandomVariable,
+};
+use opensrdk_linear_algebra::*;
+use rand::prelude::*;
+use rand_distr::{Gamma as RandGamma, StandardNormal};
+use std::marker::PhantomData;
+
+/// Sample `b` from posterior p(b|a) with likelihood p(a|b) and multivariate Student-t prior t_ν(b; μ, Σ)
+/// The prior is a scale mixture b | s ~ N(μ, s Σ), s ~ InvGamma(ν / 2, ν / 2) (Nishihara et al., 2014),
+/// so each transition draws s | b ~ InvGamma((ν + d) / 2, (ν + (b - μ)^T Σ^{-1} (b - μ)) / 2)
+/// and then moves b by elliptical slice sampling with the prior N(μ, s Σ).
+pub struct GeneralizedEllipticalSliceSampler<'a, L, A, T, U>
+where
+    L: Distribution<Value = A, Condition = Vec<f64>>,
+    A: RandomVariable,
+    T: MultivariateStudentTParams<U>,
+    U: EllipticalParams,
+{
+    value: &'a A,
+    likelihood: &'a L,
+    prior: &'a T,
+    phantom: PhantomData<U>,
+}
+
+impl<'a, L, A, T, U> GeneralizedEllipticalSliceSampler<'a, L, A, T, U>
+where
+    L: Distribution<Value = A, Condition = Vec<f64>>,
+    A: RandomVariable,
+    T: MultivariateStudentTParams<U>,
+    U: EllipticalParams,
+{
+    /// - `prior`: Params of the Student-t prior
+    pub fn new(value: &'a A, likelihood: &'a L, prior: &'a T) -> Self {
+        Self {
+            value,
+            likelihood,
+            prior,
+            phantom: PhantomData,
+        }
+    }
+
+    pub fn sample(
+        &self,
+        iter: usize,
+        initial: Vec<f64>,
+        rng: &mut dyn RngCore,
+    ) -> Result<McmcChain<Vec<f64>, MetropolisStatistics>, DistributionError> {
+        self.run(iter, initial, rng)
+    }
+}
+
+impl<'a, L, A, T, U> McmcKernel for GeneralizedEllipticalSliceSampler<'a, L, A, T, U>
+where
+    L: Distribution<Value = A, Condition = Vec<f64>>,
+    A: RandomVariable,
+    T: MultivariateStudentTParams<U>,
+    U: EllipticalParams,
+{
+    type State = Vec<f64>;
+    type Statistics = MetropolisStatistics;
+
+    fn transition(
+        &self,
+        state: &Vec<f64>,
+        rng: &mut dyn RngCore,
+    ) -> Result<(Vec<f64>, MetropolisStatistics), DistributionError> {
+        let elliptical = self.prior.elliptical();
+        let mean = elliptical.mu();
+        let x_mu = elliptical.x_mu(state)?.col_mat();
+        let mahalanobis_squared = (x_mu.t() * elliptical.sigma_inv_mul(x_mu.clone())?)[(0, 0)];
+        let nu = self.prior.nu();
+        let d = state.len() as f64;
+
+        let gamma = match RandGamma::new(0.5 * (nu + d), 2.0 / (nu + mahalanobis_squared)) {
+            Ok(v) => Ok(v),
+            Err(e) => Err(DistributionError::Others(e.into())),
+        }?;
+        let s = 1.0 / rng.sample(gamma);
+
+        let z = (0..elliptical.lsigma_cols())
+            .map(|_| rng.sample::<f64, _>(StandardNormal))
+            .collect::<Vec<_>>();
+        let direction = elliptical
+            .sample(z)?
+            .iter()
+            .zip(mean.iter())
+            .map(|(vi, mi)| s.sqrt() * (vi - mi))
+            .collect::<Vec<_>>();
+        if direction.len() != state.len() {
+            return Err(DistributionError::InvalidParameters(
+                EllipticalSliceSamplingError::DimensionMismatch.into(),
+            ));
+        }
+
+        let ln_likelihood = |b: &[f64]| -> Result<f64, DistributionError> {
+            Ok(self.likelihood.p_kernel(self.value, &b.to_vec())?.ln())
+        };
+        let b = elliptical_slice(&ln_likelihood, state, mean, &direction, rng)?;
+
+        Ok((b, MetropolisStatistics::always_accepted()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use opensrdk_linear_algebra::{pp::trf::PPTRF, *};
+    use rand::prelude::*;
+
+    #[test]
+    fn it_works() {
+        // a ~ N(b, I) with a = 0, b ~ t_4(0, I)
+        let likelihood = InstantDistribution::new(
+            |a: &Vec<f64>, b: &Vec<f64>| {
+                Ok((-0.5
+                    * a.iter()
+                        .zip(b.iter())
+                        .map(|(ai, bi)| (ai - bi).powi(2))
+                        .sum::<f64>())
+                .exp())
+            },
+            |b: &Vec<f64>, _rng| Ok(b.clone()),
+        );
+        let lsigma = PPTRF(
+            SymmetricPackedMatrix::from_mat(&mat!(
+                1.0, 0.0;
+                0.0, 1.0
+            ))
+            .unwrap(),
+        );
+        let prior = ExactMultivariateStudentTParams::new(4.0, vec![0.0, 0.0], lsigma).unwrap();
+        let value = vec![0.0, 0.0];
+
+        let sampler = GeneralizedEllipticalSliceSampler::new(&value, &likelihood, &prior);
+        let mut rng = StdRng::from_seed([1; 32]);
+        let chain = sampler.sample(10000, vec![1.0, -1.0], &mut rng).unwrap();
+
+        for i in 0..2 {
+            let mean = chain.samples().iter().map(|b| b[i]).sum::<f64>() / 10000.0;
+            let var = chain.samples().iter().map(|b| b[i].powi(2)).sum::<f64>() / 10000.0;
+            assert!(mean.abs() < 0.05);
+            // Posterior is narrower than both N(0, 1) and t_4(0, 1)
+            assert!(0.3 < var && var < 0.6);
+        }
+    }
+}
@@ -0,0 +1,224 @@
+use super::{elliptical_slice, EllipticalSliceSamplingError};
+use crate::mcmc::slice_sampling::slice_1d;
+use crate::nonparametric::{kernel_matrix, BaseEllipticalProcessParams};
+use crate::{
+    Distribution, DistributionError, MassMatrix, McmcChain, McmcKernel, MetropolisStatistics,
+    RandomVariable, SliceParams,
+};
+use opensrdk_kernel_method::*;
+use rand::prelude::*;
+
+/// Sample latent values `f` at the inputs of `base` and the kernel params `θ` from p(f, θ|a)
+/// with likelihood p(a|f), latent prior f ~ N(μ, K_θ(x, x) + σ^2 I) and hyperprior p(θ).
+/// Each transition moves `f` by elliptical slice sampling,
+/// and then each element of `θ` by univariate slice sampling given `f`.
+/// The state is the pair of `f` and `θ`.
+pub struct LatentProcessSampler<'a, L, A, K, T, P>
+where
+    L: Distribution<Value = A, Condition = Vec<f64>>,
+    A: RandomVariable,
+    K: PositiveDefiniteKernel<T>,
+    T: RandomVariable,
+    P: Distribution<Value = Vec<f64>, Condition = ()>,
+{
+    value: &'a A,
+    likelihood: &'a L,
+    base: &'a BaseEllipticalProcessParams<K, T>,
+    hyperprior: &'a P,
+    mean: Vec<f64>,
+    params: SliceParams,
+}
+
+impl<'a, L, A, K, T, P> LatentProcessSampler<'a, L, A, K, T, P>
+where
+    L: Distribution<Value = A, Condition = Vec<f64>>,
+    A: RandomVariable,
+    K: PositiveDefiniteKernel<T>,
+    T: RandomVariable,
+    P: Distribution<Value = Vec<f64>, Condition = ()>,
+{
+    /// - `base`: Kernel, inputs and white noise of the latent prior. Its `theta` is the initial `θ` of `sample`
+    /// - `mean`: Mean of the latent prior
+    /// - `params`: Params of the slice sampling of `θ`
+    pub fn new(
+        value: &'a A,
+        likelihood: &'a L,
+        base: &'a BaseEllipticalProcessParams<K, T>,
+        hyperprior: &'a P,
+        mean: Vec<f64>,
+        params: SliceParams,
+    ) -> Result<Self, DistributionError> {
+        if mean.len() != base.x().len() {
+            return Err(DistributionError::InvalidParameters(
+                EllipticalSliceSamplingError::DimensionMismatch.into(),
+            ));
+        }
+
+        Ok(Self {
+            value,
+            likelihood,
+            base,
+            hyperprior,
+            mean,
+            params,
+        })
+    }
+
+    pub fn sample(
+        &self,
+        iter: usize,
+        initial: Vec<f64>,
+        rng: &mut dyn RngCore,
+    ) -> Result<McmcChain<(Vec<f64>, Vec<f64>), MetropolisStatistics>, DistributionError> {
+        self.run(iter, (initial, self.base.theta().to_vec()), rng)
+    }
+
+    /// K_θ(x, x) + σ^2 I, or `None` where it is not positive definite
+    fn covariance(&self, theta: &[f64]) -> Result<Option<MassMatrix>, DistributionError> {
+        let x = self.base.x();
+        let mut k = kernel_matrix(self.base.kernel(), theta, x, x)?;
+        for i in 0..x.len() {
+            k[(i, i)] += self.base.sigma().powi(2);
+        }
+
+        Ok(MassMatrix::dense(k).ok())
+    }
+
+    /// ln p(f|θ) + ln p(θ) up to a constant
+    fn ln_p_theta(&self, f: &[f64], theta: &[f64]) -> Result<f64, DistributionError> {
+        let covariance = match self.covariance(theta)? {
+            Some(v) => v,
+            None => return Ok(f64::NEG_INFINITY),
+        };
+        let f_mu = f
+            .iter()
+            .zip(self.mean.iter())
+            .map(|(fi, mi)| fi - mi)
+            .collect::<Vec<_>>();
+        let ln_det = match &covariance {
+            MassMatrix::Dense(factor) => (0..f.len()).map(|i| factor.l()[(i, i)].ln()).sum::<f64>(),
+            _ => 0.0,
+        };
+
+        Ok(-covariance.kinetic_energy(&f_mu) - ln_det
+            + self.hyperprior.p_kernel(&theta.to_vec(), &())?.ln())
+    }
+}
+
+impl<'a, L, A, K, T, P> McmcKernel for LatentProcessSampler<'a, L, A, K, T, P>
+where
+    L: Distribution<Value = A, Condition = Vec<f64>>,
+    A: RandomVariable,
+    K: PositiveDefiniteKernel<T>,
+    T: RandomVariable,
+    P: Distribution<Value = Vec<f64>, Condition = ()>,
+{
+    type State = (Vec<f64>, Vec<f64>);
+    type Statistics = MetropolisStatistics;
+
+    fn transition(
+        &self,
+        state: &(Vec<f64>, Vec<f64>),
+        rng: &mut dyn RngCore,
+    ) -> Result<((Vec<f64>, Vec<f64>), MetropolisStatistics), DistributionError> {
+        let (f, theta) = state;
+        if f.len() != self.mean.len() {
+            return Err(DistributionError::InvalidParameters(
+                EllipticalSliceSamplingError::DimensionMismatch.into(),
+            ));
+        }
+
+        let covariance = match self.covariance(theta)? {
+            Some(v) => v,
+            None => {
+                return Err(DistributionError::InvalidParameters(
+                    EllipticalSliceSamplingError::InitialValueOutOfSupport.into(),
+                ))
+            }
+        };
+        let direction = covariance.sample(f.len(), rng);
+        let ln_likelihood = |f: &[f64]| -> Result<f64, DistributionError> {
+            Ok(self.likelihood.p_kernel(self.value, &f.to_vec())?.ln())
+        };
+        let f = elliptical_slice(&ln_likelihood, f, &self.mean, &direction, rng)?;
+
+        let mut theta = theta.clone();
+        let mut ln_p = self.ln_p_theta(&f, &theta)?;
+        if !ln_p.is_finite() {
+            return Err(DistributionError::InvalidParameters(
+                EllipticalSliceSamplingError::InitialValueOutOfSupport.into(),
+            ));
+        }
+        for i in 0..theta.len() {
+            let ln_p_i = |x: f64| {
+                let mut buf = theta.clone();
+                buf[i] = x;
+                self.ln_p_theta(&f, &buf)
+            };
+            let (x, ln_p_x) = slice_1d(&ln_p_i, theta[i], ln_p, &self.params, rng)?;
+            theta[i] = x;
+            ln_p = ln_p_x;
+        }
+
+        Ok(((f, theta), MetropolisStatistics::always_accepted()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::nonparametric::BaseEllipticalProcessParams;
+    use crate::*;
+    use opensrdk_kernel_method::*;
+    use rand::prelude::*;
+
+    #[test]
+    fn it_works() {
+        // a_i ~ N(f_i, 0.1^2), f ~ N(0, K_θ(x, x) + 0.01^2 I), θ_j ~ Exp(1) restricted to positive values
+        let x = (0..8).map(|i| vec![i as f64 * 0.5]).collect::<Vec<_>>();
+        let value = x.iter().map(|xi| xi[0].sin()).collect::<Vec<_>>();
+        let likelihood = InstantDistribution::new(
+            |a: &Vec<f64>, f: &Vec<f64>| {
+                Ok((-0.5
+                    * a.iter()
+                        .zip(f.iter())
+                        .map(|(ai, fi)| ((ai - fi) / 0.1).powi(2))
+                        .sum::<f64>())
+                .exp())
+            },
+            |f: &Vec<f64>, _rng| Ok(f.clone()),
+        );
+        let hyperprior = InstantDistribution::new(
+            |theta: &Vec<f64>, _: &()| {
+                if theta.iter().any(|&t| t <= 0.0) {
+                    return Ok(0.0);
+                }
+                Ok((-theta.iter().sum::<f64>()).exp())
+            },
+            |_: &(), _rng| Ok(vec![1.0; 2]),
+        );
+        let kernel = RBF;
+        let theta = vec![1.0; kernel.params_len()];
+        let base = BaseEllipticalProcessParams::new(kernel, x, theta, 0.01).unwrap();
+
+        let sampler = LatentProcessSampler::new(
+            &value,
+            &likelihood,
+            &base,
+            &hyperprior,
+            vec![0.0; 8],
+            SliceParams::default(),
+        )
+        .unwrap();
+        let mut rng = StdRng::from_seed([1; 32]);
+        let chain = sampler.sample(2000, vec![0.0; 8], &mut rng).unwrap();
+
+        let samples = &chain.samples()[1000..];
+        for (i, ai) in value.iter().enumerate() {
+            let mean = samples.iter().map(|(f, _)| f[i]).sum::<f64>() / 1000.0;
+            assert!((mean - ai).abs() < 0.2);
+        }
+        assert!(samples
+            .iter()
+            .all(|(_, theta)| theta.iter().all(|&t| 0.0 < t)));
+    }
+}
@@ -0,0 +1,190 @@
+pub mod generalized;
+pub mod latent_process;
+
+pub use generalized::*;
+pub use latent_process::*;
+
+use crate::{
+    Distribution, DistributionError, McmcChain, McmcKernel, MetropolisStatistics, RandomVariable,
+    SamplableDistribution,
+};
+use rand::prelude::*;
+use rand_distr::Exp1;
+use std::f64::consts::PI;
+
+#[derive(thiserror::Error, Debug)]
+pub enum EllipticalSliceSamplingError {
+    #[error("Dimension mismatch")]
+    DimensionMismatch,
+    #[error("Initial value is out of support")]
+    InitialValueOutOfSupport,
+    #[error("Unknown error")]
+    Unknown,
+}
+
+/// Sample `b` from posterior p(b|a) with likelihood p(a|b) and prior p(b)
+/// `b` must be generated by elliptical distribution whose mean is `mean`
+pub struct EllipticalSliceSampler<'a, L, P, A, B>
+where
+    L: Distribution<Value = A, Condition = B>,
+    P: SamplableDistribution<Value = B, Condition = ()>,
+    A: RandomVariable,
+    B: RandomVariable,
+{
+    value: &'a A,
+    likelihood: &'a L,
+    prior: &'a P,
+    mean: Vec<f64>,
+}
+
+impl<'a, L, P, A, B> EllipticalSliceSampler<'a, L, P, A, B>
+where
+    L: Distribution<Value = A, Condition = B>,
+    P: SamplableDistribution<Value = B, Condition = ()>,
+    A: RandomVariable,
+    B: RandomVariable,
+{
+    /// - `mean`: Mean of the prior as the vector of `b`
+    pub fn new(value: &'a A, likelihood: &'a L, prior: &'a P, mean: Vec<f64>) -> Self {
+        Self {
+            value,
+            likelihood,
+            prior,
+            mean,
+        }
+    }
+
+    pub fn sample(
+        &self,
+        iter: usize,
+        initial: B,
+        rng: &mut dyn RngCore,
+    ) -> Result<McmcChain<B, MetropolisStatistics>, DistributionError> {
+        self.run(iter, initial, rng)
+    }
+}
+
+impl<'a, L, P, A, B> McmcKernel for EllipticalSliceSampler<'a, L, P, A, B>
+where
+    L: Distribution<Value = A, Condition = B>,
+    P: SamplableDistribution<Value = B, Condition = ()>,
+    A: RandomVariable,
+    B: RandomVariable,
+{
+    type State = B;
+    type Statistics = MetropolisStatistics;
+
+    fn transition(
+        &self,
+        state: &B,
+        rng: &mut dyn RngCore,
+    ) -> Result<(B, MetropolisStatistics), DistributionError> {
+        let (b, info) = state.transform_vec();
+        let nu = self
+            .prior
+            .sample(&(), rng)?
+            .transform_vec()
+            .0
+            .iter()
+            .zip(self.mean.iter())
+            .map(|(vi, mi)| vi - mi)
+            .collect::<Vec<_>>();
+        if b.len() != self.mean.len() || nu.len() != self.mean.len() {
+            return Err(DistributionError::InvalidParameters(
+                EllipticalSliceSamplingError::DimensionMismatch.into(),
+            ));
+        }
+
+        let ln_likelihood = |b: &[f64]| -> Result<f64, DistributionError> {
+            Ok(self
+                .likelihood
+                .p_kernel(self.value, &B::restore(b, &info)?)?
+                .ln())
+        };
+        let b = elliptical_slice(&ln_likelihood, &b, &self.mean, &nu, rng)?;
+
+        Ok((
+            B::restore(&b, &info)?,
+            MetropolisStatistics::always_accepted(),
+        ))
+    }
+}
+
+/// Moves `b` on the ellipse μ + (b - μ) cos θ + ν sin θ to a point where ln L is above ln L(b) - e, e ~ Exp(1)
+/// `nu` must be drawn from the prior centered at μ.
+fn elliptical_slice(
+    ln_likelihood: &dyn Fn(&[f64]) -> Result<f64, DistributionError>,
+    b: &[f64],
+    mean: &[f64],
+    nu: &[f64],
+    rng: &mut dyn RngCore,
+) -> Result<Vec<f64>, DistributionError> {
+    let ln_l = ln_likelihood(b)?;
+    if !ln_l.is_finite() {
+        return Err(DistributionError::InvalidParameters(
+            EllipticalSliceSamplingError::InitialValueOutOfSupport.into(),
+        ));
+    }
+    let ln_y = ln_l - rng.sample::<f64, _>(Exp1);
+
+    let mut theta = rng.gen_range(0.0..2.0 * PI);
+    let mut start = theta - 2.0 * PI;
+    let mut end = theta;
+
+    loop {
+        let (sin, cos) = theta.sin_cos();
+        let candidate = b
+            .iter()
+            .zip(mean.iter())
+            .zip(nu.iter())
+            .map(|((bi, mi), nui)| mi + (bi - mi) * cos + nui * sin)
+            .collect::<Vec<_>>();
+        if ln_y < ln_likelihood(&candidate)? {
+            return Ok(candidate);
+        }
+
+        if 0.0 < theta {
+            end = theta;
+        } else {
+            start = theta;
+        }
+        if end - start <= f64::EPSILON {
+            return Ok(b.to_vec());
+        }
+        theta = rng.gen_range(start..end);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use rand::prelude::*;
+
+    #[test]
+    fn it_works() {
+        // a ~ N(b, 1), b ~ N(10, 2^2), so p(b|a) = N((4a + 10) / 5, 4 / 5)
+        let likelihood = InstantDistribution::new(
+            |a: &f64, b: &f64| Ok((-0.5 * (a - b).powi(2)).exp()),
+            |b: &f64, rng| Normal.sample(&NormalParams::new(*b, 1.0)?, rng),
+        );
+        let prior = InstantDistribution::new(
+            |b: &f64, _: &()| Ok((-0.5 * ((b - 10.0) / 2.0).powi(2)).exp()),
+            |_: &(), rng| Normal.sample(&NormalParams::new(10.0, 2.0)?, rng),
+        );
+        let value = 1.0;
+
+        let sampler = EllipticalSliceSampler::new(&value, &likelihood, &prior, vec![10.0]);
+        let mut rng = StdRng::from_seed([1; 32]);
+        let chain = sampler.sample(10000, 10.0, &mut rng).unwrap();
+
+        let mean = chain.samples().iter().sum::<f64>() / 10000.0;
+        let var = chain
+            .samples()
+            .iter()
+            .map(|b| (b - mean).powi(2))
+            .sum::<f64>()
+            / 10000.0;
+        assert!((mean - 2.8).abs() < 0.1);
+        assert!((var - 0.8).abs() < 0.1);
+    }
+}
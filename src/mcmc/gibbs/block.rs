@@ -0,0 +1,365 @@
+use super::{GibbsBlock, GibbsSamplingError};
+use crate::mcmc::slice_sampling::slice_1d;
+use crate::{
+    Distribution, DistributionError, MetropolisStatistics, RandomVariable, SamplableDistribution,
+    SliceParams, SliceSamplingError,
+};
+use rand::distributions::WeightedIndex;
+use rand::prelude::*;
+use std::marker::PhantomData;
+
+/// Draws a block exactly from its full conditional distribution
+/// `DiscretePosterior` can be used as the distribution of a block with a finite support.
+/// - `condition`: Condition of the distribution given the state
+/// - `set`: State whose block is replaced with the drawn value
+pub struct ConditionalBlock<S, D, C, U>
+where
+    S: RandomVariable,
+    D: SamplableDistribution,
+    C: Fn(&S) -> Result<D::Condition, DistributionError> + Send + Sync,
+    U: Fn(&S, D::Value) -> Result<S, DistributionError> + Send + Sync,
+{
+    distribution: D,
+    condition: C,
+    set: U,
+    phantom: PhantomData<S>,
+}
+
+impl<S, D, C, U> ConditionalBlock<S, D, C, U>
+where
+    S: RandomVariable,
+    D: SamplableDistribution,
+    C: Fn(&S) -> Result<D::Condition, DistributionError> + Send + Sync,
+    U: Fn(&S, D::Value) -> Result<S, DistributionError> + Send + Sync,
+{
+    pub fn new(distribution: D, condition: C, set: U) -> Self {
+        Self {
+            distribution,
+            condition,
+            set,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<S, D, C, U> GibbsBlock<S> for ConditionalBlock<S, D, C, U>
+where
+    S: RandomVariable,
+    D: SamplableDistribution,
+    C: Fn(&S) -> Result<D::Condition, DistributionError> + Send + Sync,
+    U: Fn(&S, D::Value) -> Result<S, DistributionError> + Send + Sync,
+{
+    fn update(
+        &self,
+        state: &S,
+        rng: &mut dyn RngCore,
+    ) -> Result<(S, MetropolisStatistics), DistributionError> {
+        let value = self.distribution.sample(&(self.condition)(state)?, rng)?;
+
+        Ok((
+            (self.set)(state, value)?,
+            MetropolisStatistics::always_accepted(),
+        ))
+    }
+}
+
+/// Updates a block by Metropolis-Hastings within Gibbs
+/// - `target`: Unnormalized density of the whole state, such as p(a|s) p(s) as a function of s
+/// - `proposal`: q(t'|t) of the block
+/// - `get`: Block of the state
+/// - `set`: State whose block is replaced with the given value
+pub struct MetropolisHastingsBlock<'a, S, J, PD, G, U>
+where
+    S: RandomVariable,
+    J: Distribution<Value = S, Condition = ()>,
+    PD: SamplableDistribution<Condition = <PD as Distribution>::Value>,
+    G: Fn(&S) -> Result<PD::Value, DistributionError> + Send + Sync,
+    U: Fn(&S, PD::Value) -> Result<S, DistributionError> + Send + Sync,
+{
+    target: &'a J,
+    proposal: &'a PD,
+    get: G,
+    set: U,
+}
+
+impl<'a, S, J, PD, G, U> MetropolisHastingsBlock<'a, S, J, PD, G, U>
+where
+    S: RandomVariable,
+    J: Distribution<Value = S, Condition = ()>,
+    PD: SamplableDistribution<Condition = <PD as Distribution>::Value>,
+    G: Fn(&S) -> Result<PD::Value, DistributionError> + Send + Sync,
+    U: Fn(&S, PD::Value) -> Result<S, DistributionError> + Send + Sync,
+{
+    pub fn new(target: &'a J, proposal: &'a PD, get: G, set: U) -> Self {
+        Self {
+            target,
+            proposal,
+            get,
+            set,
+        }
+    }
+}
+
+impl<'a, S, J, PD, G, U> GibbsBlock<S> for MetropolisHastingsBlock<'a, S, J, PD, G, U>
+where
+    S: RandomVariable,
+    J: Distribution<Value = S, Condition = ()>,
+    PD: SamplableDistribution<Condition = <PD as Distribution>::Value>,
+    G: Fn(&S) -> Result<PD::Value, DistributionError> + Send + Sync,
+    U: Fn(&S, PD::Value) -> Result<S, DistributionError> + Send + Sync,
+{
+    fn update(
+        &self,
+        state: &S,
+        rng: &mut dyn RngCore,
+    ) -> Result<(S, MetropolisStatistics), DistributionError> {
+        let current = (self.get)(state)?;
+        let proposed = self.proposal.sample(&current, rng)?;
+        let candidate = (self.set)(state, proposed.clone())?;
+        let ln_r = self.target.p_kernel(&candidate, &())?.ln()
+            + self.proposal.p_kernel(&current, &proposed)?.ln()
+            - self.target.p_kernel(state, &())?.ln()
+            - self.proposal.p_kernel(&proposed, &current)?.ln();
+        let statistics = MetropolisStatistics::from_ln_ratio(ln_r, rng);
+
+        if statistics.accepted {
+            Ok((candidate, statistics))
+        } else {
+            Ok((state.clone(), statistics))
+        }
+    }
+}
+
+/// Updates each element of the vector of a block in turn by univariate slice sampling
+/// - `target`: Unnormalized density of the whole state
+/// - `get`: Block of the state
+/// - `set`: State whose block is replaced with the given value
+pub struct SliceBlock<'a, S, J, T, G, U>
+where
+    S: RandomVariable,
+    J: Distribution<Value = S, Condition = ()>,
+    T: RandomVariable,
+    G: Fn(&S) -> Result<T, DistributionError> + Send + Sync,
+    U: Fn(&S, T) -> Result<S, DistributionError> + Send + Sync,
+{
+    target: &'a J,
+    params: SliceParams,
+    get: G,
+    set: U,
+    phantom: PhantomData<T>,
+}
+
+impl<'a, S, J, T, G, U> SliceBlock<'a, S, J, T, G, U>
+where
+    S: RandomVariable,
+    J: Distribution<Value = S, Condition = ()>,
+    T: RandomVariable,
+    G: Fn(&S) -> Result<T, DistributionError> + Send + Sync,
+    U: Fn(&S, T) -> Result<S, DistributionError> + Send + Sync,
+{
+    pub fn new(target: &'a J, params: SliceParams, get: G, set: U) -> Self {
+        Self {
+            target,
+            params,
+            get,
+            set,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<'a, S, J, T, G, U> GibbsBlock<S> for SliceBlock<'a, S, J, T, G, U>
+where
+    S: RandomVariable,
+    J: Distribution<Value = S, Condition = ()>,
+    T: RandomVariable,
+    G: Fn(&S) -> Result<T, DistributionError> + Send + Sync,
+    U: Fn(&S, T) -> Result<S, DistributionError> + Send + Sync,
+{
+    fn update(
+        &self,
+        state: &S,
+        rng: &mut dyn RngCore,
+    ) -> Result<(S, MetropolisStatistics), DistributionError> {
+        let (mut v, info) = (self.get)(state)?.transform_vec();
+        let ln_p = |v: &[f64]| -> Result<f64, DistributionError> {
+            let s = (self.set)(state, T::restore(v, &info)?)?;
+            Ok(self.target.p_kernel(&s, &())?.ln())
+        };
+        let mut ln_p_v = ln_p(&v)?;
+        if !ln_p_v.is_finite() {
+            return Err(DistributionError::InvalidParameters(
+                SliceSamplingError::InitialValueOutOfSupport.into(),
+            ));
+        }
+
+        for i in 0..v.len() {
+            let ln_p_i = |x: f64| {
+                let mut buf = v.clone();
+                buf[i] = x;
+                ln_p(&buf)
+            };
+            let (x, ln_p_x) = slice_1d(&ln_p_i, v[i], ln_p_v, &self.params, rng)?;
+            v[i] = x;
+            ln_p_v = ln_p_x;
+        }
+
+        Ok((
+            (self.set)(state, T::restore(&v, &info)?)?,
+            MetropolisStatistics::always_accepted(),
+        ))
+    }
+}
+
+/// Draws a block exactly from its full conditional over a finite range, by enumerating the density of the whole state
+/// It fails if the density is zero or not a number over the whole range.
+/// - `target`: Unnormalized density of the whole state
+/// - `set`: State whose block is replaced with the given value
+pub struct DiscreteBlock<'a, S, J, T, U>
+where
+    S: RandomVariable,
+    J: Distribution<Value = S, Condition = ()>,
+    T: RandomVariable,
+    U: Fn(&S, T) -> Result<S, DistributionError> + Send + Sync,
+{
+    target: &'a J,
+    range: Vec<T>,
+    set: U,
+}
+
+impl<'a, S, J, T, U> DiscreteBlock<'a, S, J, T, U>
+where
+    S: RandomVariable,
+    J: Distribution<Value = S, Condition = ()>,
+    T: RandomVariable,
+    U: Fn(&S, T) -> Result<S, DistributionError> + Send + Sync,
+{
+    pub fn new(target: &'a J, range: Vec<T>, set: U) -> Result<Self, DistributionError> {
+        if range.is_empty() {
+            return Err(DistributionError::InvalidParameters(
+                GibbsSamplingError::RangeIsEmpty.into(),
+            ));
+        }
+
+        Ok(Self { target, range, set })
+    }
+}
+
+impl<'a, S, J, T, U> GibbsBlock<S> for DiscreteBlock<'a, S, J, T, U>
+where
+    S: RandomVariable,
+    J: Distribution<Value = S, Condition = ()>,
+    T: RandomVariable,
+    U: Fn(&S, T) -> Result<S, DistributionError> + Send + Sync,
+{
+    fn update(
+        &self,
+        state: &S,
+        rng: &mut dyn RngCore,
+    ) -> Result<(S, MetropolisStatistics), DistributionError> {
+        let mut candidates = self
+            .range
+            .iter()
+            .map(|t| (self.set)(state, t.clone()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let ln_w = candidates
+            .iter()
+            .map(|s| Ok(self.target.p_kernel(s, &())?.ln()))
+            .collect::<Result<Vec<_>, DistributionError>>()?;
+        let max = ln_w.iter().fold(f64::NEG_INFINITY, |m, &w| m.max(w));
+        if !max.is_finite() {
+            return Err(DistributionError::Others(
+                GibbsSamplingError::AllWeightsAreZero.into(),
+            ));
+        }
+        let index = WeightedIndex::new(ln_w.iter().map(|w| (w - max).exp()))
+            .map_err(|_| DistributionError::Others(GibbsSamplingError::AllWeightsAreZero.into()))?;
+
+        Ok((
+            candidates.swap_remove(index.sample(rng)),
+            MetropolisStatistics::always_accepted(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use rand::prelude::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn it_works() {
+        // z ~ Bernoulli(0.5), x | z ~ N(±1, 1)
+        let target = InstantDistribution::new(
+            |s: &(f64, bool), _: &()| {
+                let mu = if s.1 { 1.0 } else { -1.0 };
+                Ok((-0.5 * (s.0 - mu).powi(2)).exp())
+            },
+            |_: &(), _rng| Ok((0.0, false)),
+        );
+        let proposal = InstantDistribution::new(
+            |x: &f64, y: &f64| Ok((-0.5 * (x - y).powi(2)).exp()),
+            |y: &f64, rng| Normal.sample(&NormalParams::new(*y, 1.0)?, rng),
+        );
+        let posterior = DiscretePosterior::new(
+            Normal.map_condition(|z: &bool| NormalParams::new(if *z { 1.0 } else { -1.0 }, 1.0)),
+            Bernoulli.map_condition(|_: &()| BernoulliParams::new(0.5)),
+            vec![true, false].into_iter().collect::<HashSet<_>>(),
+        );
+
+        let mh_block = MetropolisHastingsBlock::new(
+            &target,
+            &proposal,
+            |s: &(f64, bool)| Ok(s.0),
+            |s: &(f64, bool), x| Ok((x, s.1)),
+        );
+        let slice_block = SliceBlock::new(
+            &target,
+            SliceParams::default(),
+            |s: &(f64, bool)| Ok(s.0),
+            |s: &(f64, bool), x| Ok((x, s.1)),
+        );
+        let discrete_block =
+            DiscreteBlock::new(&target, vec![false, true], |s: &(f64, bool), z| {
+                Ok((s.0, z))
+            })
+            .unwrap();
+        let conditional_block = ConditionalBlock::new(
+            posterior,
+            |s: &(f64, bool)| Ok(s.0),
+            |s: &(f64, bool), z| Ok((s.0, z)),
+        );
+        let blocks: Vec<Box<dyn GibbsBlock<(f64, bool)>>> = vec![
+            Box::new(mh_block),
+            Box::new(discrete_block),
+            Box::new(slice_block),
+            Box::new(conditional_block),
+        ];
+        let sampler = GibbsSampler::new(blocks, GibbsScan::Random).unwrap();
+
+        let mut rng = StdRng::from_seed([1; 32]);
+        let chain = sampler.sample(20000, (0.0, false), &mut rng).unwrap();
+        let samples = &chain.samples()[2000..];
+        let n = samples.len() as f64;
+        let mean = samples.iter().map(|s| s.0).sum::<f64>() / n;
+        let rate = samples.iter().filter(|s| s.1).count() as f64 / n;
+        assert!(mean.abs() < 0.1);
+        assert!((rate - 0.5).abs() < 0.05);
+
+        let rates = sampler.block_acceptance_rates(&chain);
+        assert!(0.3 < rates[0] && rates[0] < 0.9);
+        assert_eq!(&rates[1..], &[1.0, 1.0, 1.0]);
+
+        assert!(DiscreteBlock::new(&target, vec![], |s: &(f64, bool), z| Ok((s.0, z))).is_err());
+
+        let zero = InstantDistribution::new(
+            |_: &(f64, bool), _: &()| Ok(0.0),
+            |_: &(), _rng| Ok((0.0, false)),
+        );
+        let zero_block =
+            DiscreteBlock::new(&zero, vec![false, true], |s: &(f64, bool), z| Ok((s.0, z)))
+                .unwrap();
+        assert!(zero_block.update(&(0.0, false), &mut rng).is_err());
+    }
+}
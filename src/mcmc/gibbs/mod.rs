@@ -0,0 +1,189 @@
+pub mod block;
+
+pub use block::*;
+
+use crate::{
+    DistributionError, McmcChain, McmcKernel, McmcStatistics, MetropolisStatistics, RandomVariable,
+};
+use rand::prelude::*;
+
+#[derive(thiserror::Error, Debug)]
+pub enum GibbsSamplingError {
+    #[error("All weights are zero")]
+    AllWeightsAreZero,
+    #[error("Blocks are empty")]
+    BlocksAreEmpty,
+    #[error("Range is empty")]
+    RangeIsEmpty,
+    #[error("Unknown error")]
+    Unknown,
+}
+
+/// Update of one block of the state given the other blocks
+pub trait GibbsBlock<S>: Send + Sync
+where
+    S: RandomVariable,
+{
+    fn update(
+        &self,
+        state: &S,
+        rng: &mut dyn RngCore,
+    ) -> Result<(S, MetropolisStatistics), DistributionError>;
+}
+
+/// Order of the block updates in one transition
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GibbsScan {
+    /// Updates every block once in the registered order
+    Systematic,
+    /// Updates as many blocks as registered, each chosen uniformly at random
+    Random,
+}
+
+/// Statistics of the block updates of one transition, as pairs of the index of the block and its statistics in the order applied
+#[derive(Clone, Debug, PartialEq)]
+pub struct GibbsStatistics {
+    pub updates: Vec<(usize, MetropolisStatistics)>,
+}
+
+impl McmcStatistics for GibbsStatistics {
+    fn accepted(&self) -> bool {
+        self.updates.iter().any(|(_, s)| s.accepted)
+    }
+
+    fn acceptance_probability(&self) -> f64 {
+        if self.updates.is_empty() {
+            return 1.0;
+        }
+        self.updates
+            .iter()
+            .map(|(_, s)| s.acceptance_probability)
+            .sum::<f64>()
+            / self.updates.len() as f64
+    }
+}
+
+/// Sample a state which consists of blocks, such as a tuple or a struct, by updating each block given the others
+pub struct GibbsSampler<'a, S>
+where
+    S: RandomVariable,
+{
+    blocks: Vec<Box<dyn GibbsBlock<S> + 'a>>,
+    scan: GibbsScan,
+}
+
+impl<'a, S> GibbsSampler<'a, S>
+where
+    S: RandomVariable,
+{
+    pub fn new(
+        blocks: Vec<Box<dyn GibbsBlock<S> + 'a>>,
+        scan: GibbsScan,
+    ) -> Result<Self, DistributionError> {
+        if blocks.is_empty() {
+            return Err(DistributionError::InvalidParameters(
+                GibbsSamplingError::BlocksAreEmpty.into(),
+            ));
+        }
+
+        Ok(Self { blocks, scan })
+    }
+
+    pub fn scan(&self) -> GibbsScan {
+        self.scan
+    }
+
+    pub fn sample(
+        &self,
+        iter: usize,
+        initial: S,
+        rng: &mut dyn RngCore,
+    ) -> Result<McmcChain<S, GibbsStatistics>, DistributionError> {
+        self.run(iter, initial, rng)
+    }
+
+    /// Fraction of accepted updates of each block in the kept transitions of `chain`
+    pub fn block_acceptance_rates(&self, chain: &McmcChain<S, GibbsStatistics>) -> Vec<f64> {
+        let mut accepted = vec![0usize; self.blocks.len()];
+        let mut updates = vec![0usize; self.blocks.len()];
+        for (i, s) in chain.statistics().iter().flat_map(|s| s.updates.iter()) {
+            updates[*i] += 1;
+            if s.accepted {
+                accepted[*i] += 1;
+            }
+        }
+
+        accepted
+            .iter()
+            .zip(updates.iter())
+            .map(|(&a, &u)| if u == 0 { 0.0 } else { a as f64 / u as f64 })
+            .collect()
+    }
+}
+
+impl<'a, S> McmcKernel for GibbsSampler<'a, S>
+where
+    S: RandomVariable,
+{
+    type State = S;
+    type Statistics = GibbsStatistics;
+
+    fn transition(
+        &self,
+        state: &S,
+        rng: &mut dyn RngCore,
+    ) -> Result<(S, GibbsStatistics), DistributionError> {
+        let n = self.blocks.len();
+        let order = match self.scan {
+            GibbsScan::Systematic => (0..n).collect::<Vec<_>>(),
+            GibbsScan::Random => (0..n).map(|_| rng.gen_range(0..n)).collect(),
+        };
+
+        let mut state = state.clone();
+        let mut updates = Vec::with_capacity(n);
+        for i in order {
+            let (next, statistics) = self.blocks[i].update(&state, rng)?;
+            state = next;
+            updates.push((i, statistics));
+        }
+
+        Ok((state, GibbsStatistics { updates }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use rand::prelude::*;
+
+    #[test]
+    fn it_works() {
+        // (x, y) ~ N(0, [[1, ρ], [ρ, 1]]), x | y ~ N(ρ y, 1 - ρ^2)
+        let rho = 0.5f64;
+        let x_block = ConditionalBlock::new(
+            Normal,
+            move |s: &(f64, f64)| NormalParams::new(rho * s.1, (1.0 - rho.powi(2)).sqrt()),
+            |s: &(f64, f64), x| Ok((x, s.1)),
+        );
+        let y_block = ConditionalBlock::new(
+            Normal,
+            move |s: &(f64, f64)| NormalParams::new(rho * s.0, (1.0 - rho.powi(2)).sqrt()),
+            |s: &(f64, f64), y| Ok((s.0, y)),
+        );
+        let blocks: Vec<Box<dyn GibbsBlock<(f64, f64)>>> =
+            vec![Box::new(x_block), Box::new(y_block)];
+        let sampler = GibbsSampler::new(blocks, GibbsScan::Systematic).unwrap();
+
+        let mut rng = StdRng::from_seed([1; 32]);
+        let chain = sampler.sample(10000, (3.0, -3.0), &mut rng).unwrap();
+        let samples = &chain.samples()[1000..];
+        let n = samples.len() as f64;
+        let mean_x = samples.iter().map(|s| s.0).sum::<f64>() / n;
+        let cov = samples.iter().map(|s| s.0 * s.1).sum::<f64>() / n;
+        assert!(mean_x.abs() < 0.1);
+        assert!((cov - rho).abs() < 0.1);
+        assert_eq!(sampler.block_acceptance_rates(&chain), vec![1.0, 1.0]);
+
+        assert!(GibbsSampler::<(f64, f64)>::new(vec![], GibbsScan::Random).is_err());
+    }
+}
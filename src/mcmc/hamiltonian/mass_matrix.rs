@@ -0,0 +1,176 @@
+use crate::DistributionError;
+use opensrdk_linear_algebra::*;
+use rand::prelude::*;
+use rand_distr::StandardNormal;
+
+/// Symmetric positive definite matrix M, stored as its diagonal or cholesky factor
+/// It is the mass matrix of the kinetic energy K(p) = p^T M^{-1} p / 2 in Hamiltonian samplers,
+/// the preconditioner of Langevin samplers, and the covariance of Gaussian proposals.
+#[derive(Clone, Debug)]
+pub enum MassMatrix {
+    Identity,
+    /// Diagonal elements of M
+    Diagonal(Vec<f64>),
+    /// Cholesky factor of M
+    /// Use `MassMatrix::dense` to construct it from M
+    Dense(CholeskyFactor),
+}
+
+/// Lower triangular cholesky factor L of M = L L^T, which only `MassMatrix::dense` constructs
+#[derive(Clone, Debug)]
+pub struct CholeskyFactor(Matrix);
+
+impl CholeskyFactor {
+    pub fn l(&self) -> &Matrix {
+        &self.0
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum MassMatrixError {
+    #[error("Matrix must be positive definite")]
+    NotPositiveDefinite,
+    #[error("Dimension mismatch")]
+    DimensionMismatch,
+}
+
+impl MassMatrix {
+    pub fn diagonal(m: Vec<f64>) -> Result<Self, DistributionError> {
+        if m.iter().any(|&mi| mi <= 0.0 || !mi.is_finite()) {
+            return Err(DistributionError::InvalidParameters(
+                MassMatrixError::NotPositiveDefinite.into(),
+            ));
+        }
+
+        Ok(MassMatrix::Diagonal(m))
+    }
+
+    pub fn dense(m: Matrix) -> Result<Self, DistributionError> {
+        let n = m.rows();
+        if m.cols() != n {
+            return Err(DistributionError::InvalidParameters(
+                MassMatrixError::DimensionMismatch.into(),
+            ));
+        }
+        let mut l = m.potrf()?.0;
+        for j in 1..n {
+            for i in 0..j {
+                l[(i, j)] = 0.0;
+            }
+        }
+        if (0..n).any(|i| l[(i, i)] <= 0.0 || !l[(i, i)].is_finite()) {
+            return Err(DistributionError::InvalidParameters(
+                MassMatrixError::NotPositiveDefinite.into(),
+            ));
+        }
+
+        Ok(MassMatrix::Dense(CholeskyFactor(l)))
+    }
+
+    /// Dimension of M, or `None` for the identity which fits any dimension
+    pub fn dim(&self) -> Option<usize> {
+        match self {
+            MassMatrix::Identity => None,
+            MassMatrix::Diagonal(m) => Some(m.len()),
+            MassMatrix::Dense(factor) => Some(factor.l().rows()),
+        }
+    }
+
+    /// x ~ N(0, M), e.g. the momentum of Hamiltonian samplers or the increment of random walk proposals
+    pub fn sample(&self, n: usize, rng: &mut dyn RngCore) -> Vec<f64> {
+        let z = (0..n)
+            .map(|_| rng.sample::<f64, _>(StandardNormal))
+            .collect::<Vec<_>>();
+
+        match self {
+            MassMatrix::Identity => z,
+            MassMatrix::Diagonal(m) => z
+                .iter()
+                .zip(m.iter())
+                .map(|(zi, mi)| zi * mi.sqrt())
+                .collect(),
+            MassMatrix::Dense(factor) => {
+                let l = factor.l();
+                (0..n)
+                    .map(|i| (0..=i).map(|j| l[(i, j)] * z[j]).sum::<f64>())
+                    .collect()
+            }
+        }
+    }
+
+    /// M^{-1} p
+    pub fn velocity(&self, p: &[f64]) -> Vec<f64> {
+        match self {
+            MassMatrix::Identity => p.to_vec(),
+            MassMatrix::Diagonal(m) => p.iter().zip(m.iter()).map(|(pi, mi)| pi / mi).collect(),
+            MassMatrix::Dense(factor) => {
+                let l = factor.l();
+                let n = p.len();
+                let mut y = Vec::with_capacity(n);
+                for (i, pi) in p.iter().enumerate() {
+                    let s = (0..i).map(|j| l[(i, j)] * y[j]).sum::<f64>();
+                    y.push((pi - s) / l[(i, i)]);
+                }
+                let mut v = vec![0.0; n];
+                for i in (0..n).rev() {
+                    let s = (i + 1..n).map(|j| l[(j, i)] * v[j]).sum::<f64>();
+                    v[i] = (y[i] - s) / l[(i, i)];
+                }
+                v
+            }
+        }
+    }
+
+    /// x^T M x
+    pub fn quadratic_form(&self, x: &[f64]) -> f64 {
+        match self {
+            MassMatrix::Identity => x.iter().map(|xi| xi.powi(2)).sum(),
+            MassMatrix::Diagonal(m) => x.iter().zip(m.iter()).map(|(xi, mi)| mi * xi.powi(2)).sum(),
+            MassMatrix::Dense(factor) => {
+                let l = factor.l();
+                (0..x.len())
+                    .map(|j| (j..x.len()).map(|i| l[(i, j)] * x[i]).sum::<f64>().powi(2))
+                    .sum()
+            }
+        }
+    }
+
+    pub fn kinetic_energy(&self, p: &[f64]) -> f64 {
+        0.5 * p
+            .iter()
+            .zip(self.velocity(p).iter())
+            .map(|(pi, vi)| pi * vi)
+            .sum::<f64>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MassMatrix;
+    use opensrdk_linear_algebra::*;
+
+    #[test]
+    fn it_works() {
+        let m = mat!(
+            4.0, 2.0;
+            2.0, 3.0
+        );
+        let mass_matrix = MassMatrix::dense(m).unwrap();
+        let p = vec![1.0, -2.0];
+        let v = mass_matrix.velocity(&p);
+
+        // M v = p
+        assert!((4.0 * v[0] + 2.0 * v[1] - p[0]).abs() < 1e-10);
+        assert!((2.0 * v[0] + 3.0 * v[1] - p[1]).abs() < 1e-10);
+
+        let diagonal = MassMatrix::diagonal(vec![2.0, 4.0]).unwrap();
+        let k = diagonal.kinetic_energy(&p);
+        assert!((k - 0.5 * (1.0 / 2.0 + 4.0 / 4.0)).abs() < 1e-10);
+
+        // x^T M x
+        let q = mass_matrix.quadratic_form(&p);
+        assert!((q - (4.0 - 2.0 * 2.0 * 2.0 + 3.0 * 4.0)).abs() < 1e-10);
+
+        assert!(MassMatrix::diagonal(vec![1.0, -1.0]).is_err());
+    }
+}
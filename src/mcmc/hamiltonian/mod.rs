@@ -0,0 +1,392 @@
+pub mod mass_matrix;
+pub mod no_u_turn;
+
+pub use mass_matrix::*;
+pub use no_u_turn::*;
+
+use crate::mcmc::ln_posterior::LnPosterior;
+use crate::{
+    ConditionDifferentiableDistribution, Distribution, DistributionError, McmcChain, McmcKernel,
+    McmcStatistics, RandomVariable, ValueDifferentiableDistribution,
+};
+use rand::prelude::*;
+
+/// Sample b from posterior p(b|a) with likelihood p(a|b) and prior p(b)
+/// Hamiltonian Monte Carlo on the vector given by `transform_vec` of b
+pub struct HamiltonianSampler<'a, L, P, A, B>
+where
+    L: Distribution<Value = A, Condition = B> + ConditionDifferentiableDistribution,
+    P: Distribution<Value = B, Condition = ()> + ValueDifferentiableDistribution,
+    A: RandomVariable,
+    B: RandomVariable,
+{
+    value: &'a A,
+    likelihood: &'a L,
+    prior: &'a P,
+    params: HamiltonianParams,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum HamiltonianSamplingError {
+    #[error("out of range")]
+    OutOfRange,
+    #[error("Step size must be positive")]
+    StepSizeMustBePositive,
+    #[error("Leapfrog steps must be positive")]
+    LeapfrogStepsMustBePositive,
+    #[error("Dimension mismatch")]
+    DimensionMismatch,
+    #[error("Initial value must have positive density")]
+    InitialValueOutOfSupport,
+    #[error("Unknown error")]
+    Unknown,
+}
+
+#[derive(Clone, Debug)]
+pub struct HamiltonianParams {
+    step_size: f64,
+    leapfrog_steps: usize,
+    mass_matrix: MassMatrix,
+}
+
+impl HamiltonianParams {
+    pub fn new(
+        step_size: f64,
+        leapfrog_steps: usize,
+        mass_matrix: MassMatrix,
+    ) -> Result<Self, DistributionError> {
+        if step_size <= 0.0 || !step_size.is_finite() {
+            return Err(DistributionError::InvalidParameters(
+                HamiltonianSamplingError::StepSizeMustBePositive.into(),
+            ));
+        }
+        if leapfrog_steps == 0 {
+            return Err(DistributionError::InvalidParameters(
+                HamiltonianSamplingError::LeapfrogStepsMustBePositive.into(),
+            ));
+        }
+
+        Ok(Self {
+            step_size,
+            leapfrog_steps,
+            mass_matrix,
+        })
+    }
+
+    pub fn step_size(&self) -> f64 {
+        self.step_size
+    }
+
+    pub fn leapfrog_steps(&self) -> usize {
+        self.leapfrog_steps
+    }
+
+    pub fn mass_matrix(&self) -> &MassMatrix {
+        &self.mass_matrix
+    }
+}
+
+/// Statistics of one transition of a Hamiltonian sampler
+#[derive(Clone, Debug, PartialEq)]
+pub struct HamiltonianStatistics {
+    /// Whether the proposal was accepted
+    pub accepted: bool,
+    /// min(1, exp(H(current) - H(proposal)))
+    pub acceptance_probability: f64,
+    /// Hamiltonian of the state after the transition
+    pub energy: f64,
+    /// Whether the simulated trajectory diverged
+    pub divergent: bool,
+}
+
+impl McmcStatistics for HamiltonianStatistics {
+    fn accepted(&self) -> bool {
+        self.accepted
+    }
+
+    fn acceptance_probability(&self) -> f64 {
+        self.acceptance_probability
+    }
+
+    fn divergent(&self) -> bool {
+        self.divergent
+    }
+}
+
+impl<'a, L, P, A, B> HamiltonianSampler<'a, L, P, A, B>
+where
+    L: Distribution<Value = A, Condition = B> + ConditionDifferentiableDistribution,
+    P: Distribution<Value = B, Condition = ()> + ValueDifferentiableDistribution,
+    A: RandomVariable,
+    B: RandomVariable,
+{
+    pub fn new(value: &'a A, likelihood: &'a L, prior: &'a P, params: HamiltonianParams) -> Self {
+        Self {
+            value,
+            likelihood,
+            prior,
+            params,
+        }
+    }
+
+    pub fn params(&self) -> &HamiltonianParams {
+        &self.params
+    }
+
+    pub fn sample(
+        &self,
+        iter: usize,
+        initial: B,
+        rng: &mut dyn RngCore,
+    ) -> Result<McmcChain<B, HamiltonianStatistics>, DistributionError> {
+        let (theta, info) = initial.transform_vec();
+        let posterior = LnPosterior::new(self.value, self.likelihood, self.prior, &info);
+        let mut current = posterior.initial_point(theta, &self.params.mass_matrix)?;
+        let mut chain = McmcChain::new();
+
+        for _ in 0..iter {
+            let (next, statistics) = self.step(&posterior, current, rng)?;
+            current = next;
+            chain.push(posterior.restore(&current.theta)?, statistics);
+        }
+
+        Ok(chain)
+    }
+
+    fn step(
+        &self,
+        posterior: &LnPosterior<L, P, A, B>,
+        mut current: PhasePoint,
+        rng: &mut dyn RngCore,
+    ) -> Result<(PhasePoint, HamiltonianStatistics), DistributionError> {
+        let mass_matrix = &self.params.mass_matrix;
+        current.momentum = mass_matrix.sample(current.theta.len(), rng);
+        let h0 = current.hamiltonian(mass_matrix);
+
+        let mut proposal = current.clone();
+        let mut divergent = false;
+        for _ in 0..self.params.leapfrog_steps {
+            proposal = posterior.leapfrog(mass_matrix, self.params.step_size, &proposal)?;
+            if !proposal.ln_p.is_finite()
+                || proposal.hamiltonian(mass_matrix) - h0 > MAX_ENERGY_ERROR
+            {
+                divergent = true;
+                break;
+            }
+        }
+
+        let h1 = proposal.hamiltonian(mass_matrix);
+        let acceptance_probability = if divergent || !h1.is_finite() {
+            0.0
+        } else {
+            (h0 - h1).exp().min(1.0)
+        };
+        let accepted = rng.gen_range(0.0..1.0) < acceptance_probability;
+        let statistics = HamiltonianStatistics {
+            accepted,
+            acceptance_probability,
+            energy: if accepted { h1 } else { h0 },
+            divergent,
+        };
+
+        if accepted {
+            Ok((proposal, statistics))
+        } else {
+            Ok((current, statistics))
+        }
+    }
+}
+
+impl<'a, L, P, A, B> McmcKernel for HamiltonianSampler<'a, L, P, A, B>
+where
+    L: Distribution<Value = A, Condition = B> + ConditionDifferentiableDistribution,
+    P: Distribution<Value = B, Condition = ()> + ValueDifferentiableDistribution,
+    A: RandomVariable,
+    B: RandomVariable,
+{
+    type State = B;
+    type Statistics = HamiltonianStatistics;
+
+    fn transition(
+        &self,
+        state: &B,
+        rng: &mut dyn RngCore,
+    ) -> Result<(B, HamiltonianStatistics), DistributionError> {
+        let (theta, info) = state.transform_vec();
+        let posterior = LnPosterior::new(self.value, self.likelihood, self.prior, &info);
+        let current = posterior.initial_point(theta, &self.params.mass_matrix)?;
+        let (next, statistics) = self.step(&posterior, current, rng)?;
+
+        Ok((posterior.restore(&next.theta)?, statistics))
+    }
+}
+
+/// Energy error beyond which a trajectory is regarded as divergent
+const MAX_ENERGY_ERROR: f64 = 1000.0;
+
+impl<'a, L, P, A, B> LnPosterior<'a, L, P, A, B>
+where
+    L: Distribution<Value = A, Condition = B> + ConditionDifferentiableDistribution,
+    P: Distribution<Value = B, Condition = ()> + ValueDifferentiableDistribution,
+    A: RandomVariable,
+    B: RandomVariable,
+{
+    fn phase_point(
+        &self,
+        theta: Vec<f64>,
+        momentum: Vec<f64>,
+    ) -> Result<PhasePoint, DistributionError> {
+        let (ln_p, ln_diff) = self.ln_p_with_diff(&theta)?;
+
+        Ok(PhasePoint {
+            theta,
+            momentum,
+            ln_p,
+            ln_diff,
+        })
+    }
+
+    fn initial_point(
+        &self,
+        theta: Vec<f64>,
+        mass_matrix: &MassMatrix,
+    ) -> Result<PhasePoint, DistributionError> {
+        if let Some(n) = mass_matrix.dim() {
+            if n != theta.len() {
+                return Err(DistributionError::InvalidParameters(
+                    HamiltonianSamplingError::DimensionMismatch.into(),
+                ));
+            }
+        }
+
+        let point = self.phase_point(theta, vec![])?;
+        if !point.ln_p.is_finite() {
+            return Err(DistributionError::InvalidParameters(
+                HamiltonianSamplingError::InitialValueOutOfSupport.into(),
+            ));
+        }
+
+        Ok(point)
+    }
+
+    /// One leapfrog step of size `step_size`, which may be negative to integrate backward
+    fn leapfrog(
+        &self,
+        mass_matrix: &MassMatrix,
+        step_size: f64,
+        point: &PhasePoint,
+    ) -> Result<PhasePoint, DistributionError> {
+        let half_momentum = point
+            .momentum
+            .iter()
+            .zip(point.ln_diff.iter())
+            .map(|(p, g)| p + 0.5 * step_size * g)
+            .collect::<Vec<_>>();
+        let theta = point
+            .theta
+            .iter()
+            .zip(mass_matrix.velocity(&half_momentum).iter())
+            .map(|(t, v)| t + step_size * v)
+            .collect::<Vec<_>>();
+        let mut next = self.phase_point(theta, half_momentum)?;
+        if next.ln_p.is_finite() {
+            next.momentum = next
+                .momentum
+                .iter()
+                .zip(next.ln_diff.iter())
+                .map(|(p, g)| p + 0.5 * step_size * g)
+                .collect();
+        }
+
+        Ok(next)
+    }
+}
+
+/// Position, momentum, ln p(position) and its gradient
+#[derive(Clone, Debug)]
+struct PhasePoint {
+    theta: Vec<f64>,
+    momentum: Vec<f64>,
+    ln_p: f64,
+    ln_diff: Vec<f64>,
+}
+
+impl PhasePoint {
+    /// H(θ, p) = -ln p(θ) + K(p)
+    fn hamiltonian(&self, mass_matrix: &MassMatrix) -> f64 {
+        -self.ln_p + mass_matrix.kinetic_energy(&self.momentum)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use opensrdk_linear_algebra::*;
+    use rand::prelude::*;
+
+    #[test]
+    fn it_works() {
+        // y ~ N(θ, I), θ ~ N(0, τ^2 I)
+        let tau = 2.0f64;
+        let likelihood = ConditionDifferentiableInstantDistribution::new(
+            InstantDistribution::new(
+                |y: &Vec<f64>, theta: &Vec<f64>| {
+                    Ok((-0.5
+                        * y.iter()
+                            .zip(theta.iter())
+                            .map(|(yi, ti)| (yi - ti).powi(2))
+                            .sum::<f64>())
+                    .exp())
+                },
+                |theta: &Vec<f64>, _rng| Ok(theta.clone()),
+            ),
+            |y: &Vec<f64>, theta: &Vec<f64>| {
+                Ok(y.iter().zip(theta.iter()).map(|(yi, ti)| yi - ti).collect())
+            },
+        );
+        let prior = ValueDifferentiableInstantDistribution::new(
+            InstantDistribution::new(
+                move |theta: &Vec<f64>, _: &()| {
+                    Ok((-0.5 * theta.iter().map(|ti| ti.powi(2)).sum::<f64>() / tau.powi(2)).exp())
+                },
+                |_: &(), _rng| Ok(vec![0.0; 2]),
+            ),
+            move |theta: &Vec<f64>, _: &()| Ok(theta.iter().map(|ti| -ti / tau.powi(2)).collect()),
+        );
+        let value = vec![1.0, -2.0];
+
+        let mass_matrices = vec![
+            MassMatrix::Identity,
+            MassMatrix::diagonal(vec![1.0, 2.0]).unwrap(),
+            MassMatrix::dense(mat!(
+                2.0, 0.5;
+                0.5, 1.0
+            ))
+            .unwrap(),
+        ];
+
+        for mass_matrix in mass_matrices {
+            let params = HamiltonianParams::new(0.2, 10, mass_matrix).unwrap();
+            let sampler = HamiltonianSampler::new(&value, &likelihood, &prior, params);
+            let mut rng = StdRng::from_seed([1; 32]);
+            let chain = sampler.sample(2000, vec![0.0, 0.0], &mut rng).unwrap();
+
+            assert_eq!(chain.samples().len(), 2000);
+            assert_eq!(chain.statistics().len(), 2000);
+            assert!(chain.acceptance_rate() > 0.8);
+            assert_eq!(chain.divergences(), 0);
+
+            let shrinkage = tau.powi(2) / (1.0 + tau.powi(2));
+            for (i, yi) in value.iter().enumerate() {
+                let mean = chain.samples().iter().map(|t| t[i]).sum::<f64>() / 2000.0;
+                assert!((mean - shrinkage * yi).abs() < 0.15);
+            }
+        }
+    }
+
+    #[test]
+    fn it_works2() {
+        assert!(HamiltonianParams::new(0.0, 10, MassMatrix::Identity).is_err());
+        assert!(HamiltonianParams::new(0.1, 0, MassMatrix::Identity).is_err());
+    }
+}
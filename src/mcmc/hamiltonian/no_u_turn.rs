@@ -0,0 +1,869 @@
+use super::{HamiltonianSamplingError, LnPosterior, MassMatrix, PhasePoint, MAX_ENERGY_ERROR};
+use crate::mcmc::util::ln_sum_exp;
+use crate::{
+    ConditionDifferentiableDistribution, Distribution, DistributionError, McmcChain, McmcKernel,
+    McmcStatistics, RandomVariable, ValueDifferentiableDistribution,
+};
+use opensrdk_linear_algebra::*;
+use rand::prelude::*;
+
+/// Sample b from posterior p(b|a) with likelihood p(a|b) and prior p(b)
+/// No-U-Turn sampler with multinomial sampling within subtrees
+/// and biased progressive sampling between them.
+/// Step size is adapted by dual averaging and mass matrix in windows during warmup.
+pub struct NoUTurnSampler<'a, L, P, A, B>
+where
+    L: Distribution<Value = A, Condition = B> + ConditionDifferentiableDistribution,
+    P: Distribution<Value = B, Condition = ()> + ValueDifferentiableDistribution,
+    A: RandomVariable,
+    B: RandomVariable,
+{
+    value: &'a A,
+    likelihood: &'a L,
+    prior: &'a P,
+    params: NoUTurnParams,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum NoUTurnSamplingError {
+    #[error("Target acceptance must be in (0, 1)")]
+    TargetAcceptanceOutOfRange,
+    #[error("Max tree depth must be positive")]
+    MaxTreeDepthMustBePositive,
+    #[error("Could not find a reasonable step size")]
+    StepSizeNotFound,
+    #[error("Unknown error")]
+    Unknown,
+}
+
+/// How the mass matrix is chosen
+#[derive(Clone, Debug)]
+pub enum MassMatrixAdaptation {
+    /// Use the given mass matrix throughout
+    Fixed(MassMatrix),
+    /// Estimate the marginal variances of the warmup draws in windows
+    Diagonal,
+    /// Estimate the covariance of the warmup draws in windows
+    Dense,
+}
+
+#[derive(Clone, Debug)]
+pub struct NoUTurnParams {
+    warmup: usize,
+    target_acceptance: f64,
+    max_tree_depth: usize,
+    adaptation: MassMatrixAdaptation,
+}
+
+impl Default for NoUTurnParams {
+    fn default() -> Self {
+        Self {
+            warmup: 1000,
+            target_acceptance: 0.8,
+            max_tree_depth: 10,
+            adaptation: MassMatrixAdaptation::Diagonal,
+        }
+    }
+}
+
+impl NoUTurnParams {
+    pub fn new(
+        warmup: usize,
+        target_acceptance: f64,
+        max_tree_depth: usize,
+        adaptation: MassMatrixAdaptation,
+    ) -> Result<Self, DistributionError> {
+        if target_acceptance <= 0.0 || 1.0 <= target_acceptance || target_acceptance.is_nan() {
+            return Err(DistributionError::InvalidParameters(
+                NoUTurnSamplingError::TargetAcceptanceOutOfRange.into(),
+            ));
+        }
+        if max_tree_depth == 0 {
+            return Err(DistributionError::InvalidParameters(
+                NoUTurnSamplingError::MaxTreeDepthMustBePositive.into(),
+            ));
+        }
+
+        Ok(Self {
+            warmup,
+            target_acceptance,
+            max_tree_depth,
+            adaptation,
+        })
+    }
+
+    pub fn warmup(&self) -> usize {
+        self.warmup
+    }
+
+    pub fn target_acceptance(&self) -> f64 {
+        self.target_acceptance
+    }
+
+    pub fn max_tree_depth(&self) -> usize {
+        self.max_tree_depth
+    }
+
+    pub fn adaptation(&self) -> &MassMatrixAdaptation {
+        &self.adaptation
+    }
+}
+
+/// Statistics of one transition of the No-U-Turn sampler
+#[derive(Clone, Debug, PartialEq)]
+pub struct NoUTurnStatistics {
+    /// Whether the chain moved from the initial state
+    pub accepted: bool,
+    /// Mean of min(1, exp(H(initial) - H(x))) over all states x of the trajectory
+    pub acceptance_probability: f64,
+    pub step_size: f64,
+    pub tree_depth: usize,
+    pub leapfrog_steps: usize,
+    /// Hamiltonian of the state after the transition
+    pub energy: f64,
+    /// Whether the simulated trajectory diverged
+    pub divergent: bool,
+}
+
+impl McmcStatistics for NoUTurnStatistics {
+    fn accepted(&self) -> bool {
+        self.accepted
+    }
+
+    fn acceptance_probability(&self) -> f64 {
+        self.acceptance_probability
+    }
+
+    fn divergent(&self) -> bool {
+        self.divergent
+    }
+}
+
+impl<'a, L, P, A, B> NoUTurnSampler<'a, L, P, A, B>
+where
+    L: Distribution<Value = A, Condition = B> + ConditionDifferentiableDistribution,
+    P: Distribution<Value = B, Condition = ()> + ValueDifferentiableDistribution,
+    A: RandomVariable,
+    B: RandomVariable,
+{
+    pub fn new(value: &'a A, likelihood: &'a L, prior: &'a P, params: NoUTurnParams) -> Self {
+        Self {
+            value,
+            likelihood,
+            prior,
+            params,
+        }
+    }
+
+    pub fn params(&self) -> &NoUTurnParams {
+        &self.params
+    }
+
+    /// Runs `warmup` adaptation iterations.
+    /// Returns the last state and the kernel with the adapted step size and mass matrix.
+    pub fn warmup(
+        &self,
+        initial: B,
+        rng: &mut dyn RngCore,
+    ) -> Result<(B, NoUTurnKernel<'a, L, P, A, B>), DistributionError> {
+        let (theta, info) = initial.transform_vec();
+        let posterior = LnPosterior::new(self.value, self.likelihood, self.prior, &info);
+        let (current, kernel) = self.adapt(&posterior, theta, rng)?;
+
+        Ok((posterior.restore(&current.theta)?, kernel))
+    }
+
+    /// Runs `warmup` adaptation iterations and then returns `iter` samples
+    pub fn sample(
+        &self,
+        iter: usize,
+        initial: B,
+        rng: &mut dyn RngCore,
+    ) -> Result<McmcChain<B, NoUTurnStatistics>, DistributionError> {
+        let (theta, info) = initial.transform_vec();
+        let posterior = LnPosterior::new(self.value, self.likelihood, self.prior, &info);
+        let (mut current, kernel) = self.adapt(&posterior, theta, rng)?;
+        let mut chain = McmcChain::new();
+
+        for _ in 0..iter {
+            let (next, statistics) = kernel.step(&posterior, &current, rng)?;
+            current = next;
+            chain.push(posterior.restore(&current.theta)?, statistics);
+        }
+
+        Ok(chain)
+    }
+
+    fn adapt(
+        &self,
+        posterior: &LnPosterior<L, P, A, B>,
+        theta: Vec<f64>,
+        rng: &mut dyn RngCore,
+    ) -> Result<(PhasePoint, NoUTurnKernel<'a, L, P, A, B>), DistributionError> {
+        let n = theta.len();
+        let (mass_matrix, mut mass_matrix_adapter) = match &self.params.adaptation {
+            MassMatrixAdaptation::Fixed(m) => (m.clone(), None),
+            MassMatrixAdaptation::Diagonal => (
+                MassMatrix::Identity,
+                MassMatrixAdapter::new(n, false, self.params.warmup),
+            ),
+            MassMatrixAdaptation::Dense => (
+                MassMatrix::Identity,
+                MassMatrixAdapter::new(n, true, self.params.warmup),
+            ),
+        };
+
+        let mut current = posterior.initial_point(theta, &mass_matrix)?;
+        let step_size = reasonable_step_size(posterior, &mass_matrix, &current, rng)?;
+        let mut kernel = NoUTurnKernel {
+            value: self.value,
+            likelihood: self.likelihood,
+            prior: self.prior,
+            step_size,
+            mass_matrix,
+            max_tree_depth: self.params.max_tree_depth,
+        };
+        let mut step_size_adapter = StepSizeAdapter::new(step_size, self.params.target_acceptance);
+
+        for _ in 0..self.params.warmup {
+            let (next, statistics) = kernel.step(posterior, &current, rng)?;
+            current = next;
+            kernel.step_size = step_size_adapter.learn(statistics.acceptance_probability);
+
+            if let Some(adapter) = mass_matrix_adapter.as_mut() {
+                if let Some(m) = adapter.learn(&current.theta)? {
+                    kernel.mass_matrix = m;
+                    kernel.step_size =
+                        reasonable_step_size(posterior, &kernel.mass_matrix, &current, rng)?;
+                    step_size_adapter =
+                        StepSizeAdapter::new(kernel.step_size, self.params.target_acceptance);
+                }
+            }
+        }
+        if self.params.warmup > 0 {
+            kernel.step_size = step_size_adapter.finalize();
+        }
+
+        Ok((current, kernel))
+    }
+}
+
+/// Transition of the No-U-Turn sampler with a fixed step size and mass matrix
+#[derive(Clone, Debug)]
+pub struct NoUTurnKernel<'a, L, P, A, B>
+where
+    L: Distribution<Value = A, Condition = B> + ConditionDifferentiableDistribution,
+    P: Distribution<Value = B, Condition = ()> + ValueDifferentiableDistribution,
+    A: RandomVariable,
+    B: RandomVariable,
+{
+    value: &'a A,
+    likelihood: &'a L,
+    prior: &'a P,
+    step_size: f64,
+    mass_matrix: MassMatrix,
+    max_tree_depth: usize,
+}
+
+impl<'a, L, P, A, B> NoUTurnKernel<'a, L, P, A, B>
+where
+    L: Distribution<Value = A, Condition = B> + ConditionDifferentiableDistribution,
+    P: Distribution<Value = B, Condition = ()> + ValueDifferentiableDistribution,
+    A: RandomVariable,
+    B: RandomVariable,
+{
+    pub fn new(
+        value: &'a A,
+        likelihood: &'a L,
+        prior: &'a P,
+        step_size: f64,
+        mass_matrix: MassMatrix,
+        max_tree_depth: usize,
+    ) -> Result<Self, DistributionError> {
+        if step_size <= 0.0 || !step_size.is_finite() {
+            return Err(DistributionError::InvalidParameters(
+                HamiltonianSamplingError::StepSizeMustBePositive.into(),
+            ));
+        }
+        if max_tree_depth == 0 {
+            return Err(DistributionError::InvalidParameters(
+                NoUTurnSamplingError::MaxTreeDepthMustBePositive.into(),
+            ));
+        }
+
+        Ok(Self {
+            value,
+            likelihood,
+            prior,
+            step_size,
+            mass_matrix,
+            max_tree_depth,
+        })
+    }
+
+    pub fn step_size(&self) -> f64 {
+        self.step_size
+    }
+
+    pub fn mass_matrix(&self) -> &MassMatrix {
+        &self.mass_matrix
+    }
+
+    pub fn max_tree_depth(&self) -> usize {
+        self.max_tree_depth
+    }
+
+    fn step(
+        &self,
+        posterior: &LnPosterior<L, P, A, B>,
+        current: &PhasePoint,
+        rng: &mut dyn RngCore,
+    ) -> Result<(PhasePoint, NoUTurnStatistics), DistributionError> {
+        let mut initial = current.clone();
+        initial.momentum = self.mass_matrix.sample(initial.theta.len(), rng);
+        let h0 = initial.hamiltonian(&self.mass_matrix);
+
+        let mut backward_end = initial.clone();
+        let mut forward_end = initial.clone();
+        let mut rho = initial.momentum.clone();
+        let mut ln_weight = 0.0;
+        let mut proposal = initial;
+
+        let mut tree_depth = 0;
+        let mut leapfrog_steps = 0;
+        let mut sum_acceptance = 0.0;
+        let mut divergent = false;
+
+        while tree_depth < self.max_tree_depth {
+            let forward = rng.gen::<bool>();
+            let subtree = if forward {
+                build_tree(
+                    posterior,
+                    &self.mass_matrix,
+                    &forward_end,
+                    tree_depth,
+                    self.step_size,
+                    h0,
+                    rng,
+                )?
+            } else {
+                build_tree(
+                    posterior,
+                    &self.mass_matrix,
+                    &backward_end,
+                    tree_depth,
+                    -self.step_size,
+                    h0,
+                    rng,
+                )?
+            };
+            leapfrog_steps += subtree.leapfrog_steps;
+            sum_acceptance += subtree.sum_acceptance;
+
+            if subtree.divergent {
+                divergent = true;
+                break;
+            }
+            if subtree.turning {
+                break;
+            }
+            tree_depth += 1;
+
+            // Biased progressive sampling favours the new subtree
+            if rng.gen_range(0.0..1.0) < (subtree.ln_weight - ln_weight).exp() {
+                proposal = subtree.proposal;
+            }
+            ln_weight = ln_sum_exp(ln_weight, subtree.ln_weight);
+
+            let turning = if forward {
+                is_turning(
+                    &self.mass_matrix,
+                    (&backward_end, &forward_end, &rho),
+                    (&subtree.beg, &subtree.end, &subtree.rho),
+                )
+            } else {
+                is_turning(
+                    &self.mass_matrix,
+                    (&forward_end, &backward_end, &rho),
+                    (&subtree.beg, &subtree.end, &subtree.rho),
+                )
+            };
+            rho = add(&rho, &subtree.rho);
+            if forward {
+                forward_end = subtree.end;
+            } else {
+                backward_end = subtree.end;
+            }
+
+            if turning {
+                break;
+            }
+        }
+
+        let statistics = NoUTurnStatistics {
+            accepted: proposal.theta != current.theta,
+            acceptance_probability: if leapfrog_steps == 0 {
+                0.0
+            } else {
+                sum_acceptance / leapfrog_steps as f64
+            },
+            step_size: self.step_size,
+            tree_depth,
+            leapfrog_steps,
+            energy: proposal.hamiltonian(&self.mass_matrix),
+            divergent,
+        };
+
+        Ok((proposal, statistics))
+    }
+}
+
+impl<'a, L, P, A, B> McmcKernel for NoUTurnKernel<'a, L, P, A, B>
+where
+    L: Distribution<Value = A, Condition = B> + ConditionDifferentiableDistribution,
+    P: Distribution<Value = B, Condition = ()> + ValueDifferentiableDistribution,
+    A: RandomVariable,
+    B: RandomVariable,
+{
+    type State = B;
+    type Statistics = NoUTurnStatistics;
+
+    fn transition(
+        &self,
+        state: &B,
+        rng: &mut dyn RngCore,
+    ) -> Result<(B, NoUTurnStatistics), DistributionError> {
+        let (theta, info) = state.transform_vec();
+        let posterior = LnPosterior::new(self.value, self.likelihood, self.prior, &info);
+        let current = posterior.initial_point(theta, &self.mass_matrix)?;
+        let (next, statistics) = self.step(&posterior, &current, rng)?;
+
+        Ok((posterior.restore(&next.theta)?, statistics))
+    }
+}
+
+/// Doubles or halves the step size until the acceptance probability of one leapfrog step crosses 0.8
+fn reasonable_step_size<L, P, A, B>(
+    posterior: &LnPosterior<L, P, A, B>,
+    mass_matrix: &MassMatrix,
+    current: &PhasePoint,
+    rng: &mut dyn RngCore,
+) -> Result<f64, DistributionError>
+where
+    L: Distribution<Value = A, Condition = B> + ConditionDifferentiableDistribution,
+    P: Distribution<Value = B, Condition = ()> + ValueDifferentiableDistribution,
+    A: RandomVariable,
+    B: RandomVariable,
+{
+    let ln_target = 0.8f64.ln();
+    let mut step_size = 1.0;
+    let mut direction = None;
+
+    for _ in 0..100 {
+        let mut point = current.clone();
+        point.momentum = mass_matrix.sample(point.theta.len(), rng);
+        let h0 = point.hamiltonian(mass_matrix);
+        let next = posterior.leapfrog(mass_matrix, step_size, &point)?;
+        let h = next.hamiltonian(mass_matrix);
+        let delta = if h.is_finite() {
+            h0 - h
+        } else {
+            f64::NEG_INFINITY
+        };
+
+        let increase = match direction {
+            None => {
+                direction = Some(delta > ln_target);
+                delta > ln_target
+            }
+            Some(increase) => {
+                if increase != (delta > ln_target) {
+                    return Ok(step_size);
+                }
+                increase
+            }
+        };
+
+        step_size *= if increase { 2.0 } else { 0.5 };
+        if step_size > 1e7 || step_size < 1e-12 {
+            break;
+        }
+    }
+
+    Err(DistributionError::Others(
+        NoUTurnSamplingError::StepSizeNotFound.into(),
+    ))
+}
+
+struct Tree {
+    beg: PhasePoint,
+    end: PhasePoint,
+    proposal: PhasePoint,
+    rho: Vec<f64>,
+    ln_weight: f64,
+    sum_acceptance: f64,
+    leapfrog_steps: usize,
+    turning: bool,
+    divergent: bool,
+}
+
+/// Builds a tree of 2^depth leapfrog steps from `point`, sampling the proposal multinomially
+fn build_tree<L, P, A, B>(
+    posterior: &LnPosterior<L, P, A, B>,
+    mass_matrix: &MassMatrix,
+    point: &PhasePoint,
+    depth: usize,
+    step_size: f64,
+    h0: f64,
+    rng: &mut dyn RngCore,
+) -> Result<Tree, DistributionError>
+where
+    L: Distribution<Value = A, Condition = B> + ConditionDifferentiableDistribution,
+    P: Distribution<Value = B, Condition = ()> + ValueDifferentiableDistribution,
+    A: RandomVariable,
+    B: RandomVariable,
+{
+    if depth == 0 {
+        let next = posterior.leapfrog(mass_matrix, step_size, point)?;
+        let h = next.hamiltonian(mass_matrix);
+        let divergent = !h.is_finite() || h - h0 > MAX_ENERGY_ERROR;
+        let ln_weight = if h.is_finite() {
+            h0 - h
+        } else {
+            f64::NEG_INFINITY
+        };
+
+        return Ok(Tree {
+            beg: next.clone(),
+            end: next.clone(),
+            rho: next.momentum.clone(),
+            proposal: next,
+            ln_weight,
+            sum_acceptance: ln_weight.exp().min(1.0),
+            leapfrog_steps: 1,
+            turning: false,
+            divergent,
+        });
+    }
+
+    let first = build_tree(posterior, mass_matrix, point, depth - 1, step_size, h0, rng)?;
+    if first.turning || first.divergent {
+        return Ok(first);
+    }
+    let second = build_tree(
+        posterior,
+        mass_matrix,
+        &first.end,
+        depth - 1,
+        step_size,
+        h0,
+        rng,
+    )?;
+    let sum_acceptance = first.sum_acceptance + second.sum_acceptance;
+    let leapfrog_steps = first.leapfrog_steps + second.leapfrog_steps;
+    if second.turning || second.divergent {
+        return Ok(Tree {
+            sum_acceptance,
+            leapfrog_steps,
+            turning: second.turning,
+            divergent: second.divergent,
+            ..first
+        });
+    }
+
+    let ln_weight = ln_sum_exp(first.ln_weight, second.ln_weight);
+    let turning = is_turning(
+        mass_matrix,
+        (&first.beg, &first.end, &first.rho),
+        (&second.beg, &second.end, &second.rho),
+    );
+    let rho = add(&first.rho, &second.rho);
+    let proposal = if rng.gen_range(0.0..1.0) < (second.ln_weight - ln_weight).exp() {
+        second.proposal
+    } else {
+        first.proposal
+    };
+
+    Ok(Tree {
+        beg: first.beg,
+        end: second.end,
+        proposal,
+        rho,
+        ln_weight,
+        sum_acceptance,
+        leapfrog_steps,
+        turning,
+        divergent: false,
+    })
+}
+
+/// Generalized no-U-turn criterion on the concatenation of two adjacent trajectories,
+/// each given as (first state, last state, sum of momenta) in the order of integration.
+/// Also checks the criterion across the junction of the two trajectories.
+fn is_turning(
+    mass_matrix: &MassMatrix,
+    first: (&PhasePoint, &PhasePoint, &[f64]),
+    second: (&PhasePoint, &PhasePoint, &[f64]),
+) -> bool {
+    let (beg1, end1, rho1) = first;
+    let (beg2, end2, rho2) = second;
+    let criterion = |a: &PhasePoint, b: &PhasePoint, rho: &[f64]| {
+        dot(&mass_matrix.velocity(&a.momentum), rho) > 0.0
+            && dot(&mass_matrix.velocity(&b.momentum), rho) > 0.0
+    };
+
+    !(criterion(beg1, end2, &add(rho1, rho2))
+        && criterion(beg1, beg2, &add(rho1, &beg2.momentum))
+        && criterion(end1, end2, &add(rho2, &end1.momentum)))
+}
+
+fn add(a: &[f64], b: &[f64]) -> Vec<f64> {
+    a.iter().zip(b.iter()).map(|(ai, bi)| ai + bi).collect()
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b.iter()).map(|(ai, bi)| ai * bi).sum()
+}
+
+/// Dual averaging of ln step size toward the target acceptance probability
+struct StepSizeAdapter {
+    mu: f64,
+    target_acceptance: f64,
+    s_bar: f64,
+    x_bar: f64,
+    counter: f64,
+}
+
+impl StepSizeAdapter {
+    const GAMMA: f64 = 0.05;
+    const T0: f64 = 10.0;
+    const KAPPA: f64 = 0.75;
+
+    fn new(step_size: f64, target_acceptance: f64) -> Self {
+        Self {
+            mu: (10.0 * step_size).ln(),
+            target_acceptance,
+            s_bar: 0.0,
+            x_bar: 0.0,
+            counter: 0.0,
+        }
+    }
+
+    fn learn(&mut self, acceptance_probability: f64) -> f64 {
+        self.counter += 1.0;
+        let eta = 1.0 / (self.counter + Self::T0);
+        self.s_bar = (1.0 - eta) * self.s_bar
+            + eta * (self.target_acceptance - acceptance_probability.min(1.0));
+        let x = self.mu - self.s_bar * self.counter.sqrt() / Self::GAMMA;
+        let x_eta = self.counter.powf(-Self::KAPPA);
+        self.x_bar = (1.0 - x_eta) * self.x_bar + x_eta * x;
+
+        x.exp()
+    }
+
+    fn finalize(&self) -> f64 {
+        self.x_bar.exp()
+    }
+}
+
+/// Estimates the mass matrix as the inverse of the (co)variance of warmup draws.
+/// Warmup is split into an initial buffer, slow windows doubling in size, and a terminal buffer.
+struct MassMatrixAdapter {
+    dense: bool,
+    warmup: usize,
+    term_buffer: usize,
+    window_start: usize,
+    window_end: usize,
+    window_size: usize,
+    counter: usize,
+    samples: Vec<Vec<f64>>,
+}
+
+impl MassMatrixAdapter {
+    fn new(n: usize, dense: bool, warmup: usize) -> Option<Self> {
+        if n == 0 || warmup < 20 {
+            return None;
+        }
+        let (mut init_buffer, mut term_buffer, mut window_size) = (75, 50, 25);
+        if init_buffer + term_buffer + window_size > warmup {
+            init_buffer = warmup * 15 / 100;
+            term_buffer = warmup / 10;
+            window_size = warmup - (init_buffer + term_buffer);
+        }
+
+        Some(Self {
+            dense,
+            warmup,
+            term_buffer,
+            window_start: init_buffer,
+            window_end: init_buffer + window_size,
+            window_size,
+            counter: 0,
+            samples: vec![],
+        })
+    }
+
+    /// Returns the new mass matrix at the end of each window
+    fn learn(&mut self, theta: &[f64]) -> Result<Option<MassMatrix>, DistributionError> {
+        let counter = self.counter;
+        self.counter += 1;
+        if counter < self.window_start || self.window_end <= counter {
+            return Ok(None);
+        }
+        self.samples.push(theta.to_vec());
+        if counter + 1 < self.window_end {
+            return Ok(None);
+        }
+
+        let mass_matrix = self.estimate()?;
+        self.samples.clear();
+
+        let last = self.warmup - self.term_buffer;
+        self.window_start = self.window_end;
+        if self.window_end < last {
+            self.window_size *= 2;
+            self.window_end += self.window_size;
+            // Stretch the window to the terminal buffer if the next one would not fit
+            if last <= self.window_end + 2 * self.window_size {
+                self.window_end = last;
+            }
+        }
+
+        Ok(Some(mass_matrix))
+    }
+
+    fn estimate(&self) -> Result<MassMatrix, DistributionError> {
+        let m = self.samples.len() as f64;
+        let n = self.samples[0].len();
+        let mean = self
+            .samples
+            .iter()
+            .fold(vec![0.0; n], |sum, x| add(&sum, x))
+            .into_iter()
+            .map(|s| s / m)
+            .collect::<Vec<_>>();
+        // Shrink toward 1e-3 I as in Stan
+        let w = m / (m + 5.0);
+        let shrinkage = 1e-3 * 5.0 / (m + 5.0);
+
+        if !self.dense {
+            let var = (0..n)
+                .map(|i| {
+                    let s = self
+                        .samples
+                        .iter()
+                        .map(|x| (x[i] - mean[i]).powi(2))
+                        .sum::<f64>();
+                    w * s / (m - 1.0) + shrinkage
+                })
+                .map(|v| 1.0 / v)
+                .collect::<Vec<_>>();
+
+            return MassMatrix::diagonal(var);
+        }
+
+        let mut cov = Matrix::new(n, n);
+        for x in self.samples.iter() {
+            let d = x
+                .iter()
+                .zip(mean.iter())
+                .map(|(xi, mi)| xi - mi)
+                .collect::<Vec<_>>();
+            for (i, di) in d.iter().enumerate() {
+                for (j, dj) in d.iter().enumerate() {
+                    cov[(i, j)] += di * dj;
+                }
+            }
+        }
+        let mut identity = Matrix::new(n, n);
+        for i in 0..n {
+            for j in 0..n {
+                cov[(i, j)] *= w / (m - 1.0);
+            }
+            cov[(i, i)] += shrinkage;
+            identity[(i, i)] = 1.0;
+        }
+        let cov_inv = cov.potrf()?.potrs(identity)?;
+
+        MassMatrix::dense(cov_inv)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use rand::prelude::*;
+
+    #[test]
+    fn it_works() {
+        // y ~ N(θ, diag(s^2)), θ ~ N(0, τ^2 I)
+        let s = vec![0.5, 5.0];
+        let tau = 10.0f64;
+        let s2 = s.clone();
+        let likelihood = ConditionDifferentiableInstantDistribution::new(
+            InstantDistribution::new(
+                move |y: &Vec<f64>, theta: &Vec<f64>| {
+                    Ok((-0.5
+                        * (0..2)
+                            .map(|i| ((y[i] - theta[i]) / s[i]).powi(2))
+                            .sum::<f64>())
+                    .exp())
+                },
+                |theta: &Vec<f64>, _rng| Ok(theta.clone()),
+            ),
+            move |y: &Vec<f64>, theta: &Vec<f64>| {
+                Ok((0..2).map(|i| (y[i] - theta[i]) / s2[i].powi(2)).collect())
+            },
+        );
+        let prior = ValueDifferentiableInstantDistribution::new(
+            InstantDistribution::new(
+                move |theta: &Vec<f64>, _: &()| {
+                    Ok((-0.5 * theta.iter().map(|ti| ti.powi(2)).sum::<f64>() / tau.powi(2)).exp())
+                },
+                |_: &(), _rng| Ok(vec![0.0; 2]),
+            ),
+            move |theta: &Vec<f64>, _: &()| Ok(theta.iter().map(|ti| -ti / tau.powi(2)).collect()),
+        );
+        let value = vec![1.0, -2.0];
+        let s = vec![0.5f64, 5.0];
+
+        for adaptation in vec![MassMatrixAdaptation::Diagonal, MassMatrixAdaptation::Dense] {
+            let params = NoUTurnParams::new(500, 0.8, 10, adaptation).unwrap();
+            let sampler = NoUTurnSampler::new(&value, &likelihood, &prior, params);
+            let mut rng = StdRng::from_seed([1; 32]);
+            let chain = sampler.sample(1000, vec![0.0, 0.0], &mut rng).unwrap();
+
+            assert_eq!(chain.samples().len(), 1000);
+            assert_eq!(chain.divergences(), 0);
+            assert!(chain.statistics().iter().all(|st| st.tree_depth <= 10));
+            let accept = chain
+                .statistics()
+                .iter()
+                .map(|st| st.acceptance_probability)
+                .sum::<f64>()
+                / 1000.0;
+            assert!(accept > 0.6);
+
+            for (i, (yi, si)) in value.iter().zip(s.iter()).enumerate() {
+                let precision = 1.0 / si.powi(2) + 1.0 / tau.powi(2);
+                let expected_mean = yi / si.powi(2) / precision;
+                let expected_var = 1.0 / precision;
+                let mean = chain.samples().iter().map(|t| t[i]).sum::<f64>() / 1000.0;
+                let var = chain
+                    .samples()
+                    .iter()
+                    .map(|t| (t[i] - mean).powi(2))
+                    .sum::<f64>()
+                    / 999.0;
+                assert!((mean - expected_mean).abs() < 0.2 * expected_var.sqrt());
+                assert!((var / expected_var - 1.0).abs() < 0.25);
+            }
+        }
+    }
+
+    #[test]
+    fn it_works2() {
+        assert!(NoUTurnParams::new(100, 1.0, 10, MassMatrixAdaptation::Diagonal).is_err());
+        assert!(NoUTurnParams::new(100, 0.8, 0, MassMatrixAdaptation::Dense).is_err());
+    }
+}
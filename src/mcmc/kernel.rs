@@ -0,0 +1,86 @@
+use crate::{DistributionError, McmcChain, RandomVariable};
+use rand::prelude::*;
+use std::fmt::Debug;
+
+/// Transition kernel of a Markov chain, which moves one state to the next
+pub trait McmcKernel: Send + Sync {
+    type State: RandomVariable;
+    type Statistics: McmcStatistics;
+
+    fn transition(
+        &self,
+        state: &Self::State,
+        rng: &mut dyn RngCore,
+    ) -> Result<(Self::State, Self::Statistics), DistributionError>;
+
+    /// Runs `iter` transitions from `initial` and keeps every state
+    fn run(
+        &self,
+        iter: usize,
+        initial: Self::State,
+        rng: &mut dyn RngCore,
+    ) -> Result<McmcChain<Self::State, Self::Statistics>, DistributionError> {
+        let mut chain = McmcChain::new();
+        let mut state = initial;
+
+        for _ in 0..iter {
+            let (next, statistics) = self.transition(&state, rng)?;
+            state = next;
+            chain.push(state.clone(), statistics);
+        }
+
+        Ok(chain)
+    }
+}
+
+/// Diagnostics of one transition
+pub trait McmcStatistics: Clone + Debug + Send + Sync {
+    /// Whether the chain moved to a proposed state
+    fn accepted(&self) -> bool;
+    fn acceptance_probability(&self) -> f64;
+    fn divergent(&self) -> bool {
+        false
+    }
+}
+
+/// Statistics of one transition with an accept/reject step
+#[derive(Clone, Debug, PartialEq)]
+pub struct MetropolisStatistics {
+    pub accepted: bool,
+    pub acceptance_probability: f64,
+}
+
+impl MetropolisStatistics {
+    /// Accepts with probability min(1, r) given ln r.
+    /// A ratio which is NaN, e.g. 0 / 0, is rejected.
+    pub fn from_ln_ratio(ln_ratio: f64, rng: &mut dyn RngCore) -> Self {
+        let acceptance_probability = if ln_ratio.is_nan() {
+            0.0
+        } else {
+            ln_ratio.exp().min(1.0)
+        };
+
+        Self {
+            accepted: rng.gen_range(0.0..1.0) < acceptance_probability,
+            acceptance_probability,
+        }
+    }
+
+    /// Statistics of a transition which always moves, such as Gibbs or slice sampling
+    pub fn always_accepted() -> Self {
+        Self {
+            accepted: true,
+            acceptance_probability: 1.0,
+        }
+    }
+}
+
+impl McmcStatistics for MetropolisStatistics {
+    fn accepted(&self) -> bool {
+        self.accepted
+    }
+
+    fn acceptance_probability(&self) -> f64 {
+        self.acceptance_probability
+    }
+}
@@ -0,0 +1,647 @@
+use crate::mcmc::ln_posterior::LnPosterior;
+use crate::{
+    ConditionDifferentiableDistribution, Distribution, DistributionError,
+    IndependentValueArrayJoint, MassMatrix, McmcChain, McmcKernel, MetropolisStatistics,
+    RandomVariable, ValueDifferentiableDistribution,
+};
+use rand::prelude::*;
+use rand::seq::index;
+use rand_distr::StandardNormal;
+
+#[derive(thiserror::Error, Debug)]
+pub enum LangevinSamplingError {
+    #[error("Step size must be positive")]
+    StepSizeMustBePositive,
+    #[error("Batch size must be in 1..=length of value")]
+    BatchSizeOutOfRange,
+    #[error("Offset of step size schedule must be positive")]
+    OffsetMustBePositive,
+    #[error("Decay of step size schedule must be in [0, 1]")]
+    DecayOutOfRange,
+    #[error("Dimension mismatch")]
+    DimensionMismatch,
+    #[error("Initial value must have positive density")]
+    InitialValueOutOfSupport,
+    #[error("Chain left the support")]
+    OutOfSupport,
+    #[error("Unknown error")]
+    Unknown,
+}
+
+/// Step size h and preconditioner M.
+/// A Langevin step moves θ to θ + (h/2) M^{-1} ∇ln p(θ) + √h M^{-1/2} z with z ~ N(0, I).
+#[derive(Clone, Debug)]
+pub struct LangevinParams {
+    step_size: f64,
+    preconditioner: MassMatrix,
+}
+
+impl LangevinParams {
+    pub fn new(step_size: f64, preconditioner: MassMatrix) -> Result<Self, DistributionError> {
+        if step_size <= 0.0 || !step_size.is_finite() {
+            return Err(DistributionError::InvalidParameters(
+                LangevinSamplingError::StepSizeMustBePositive.into(),
+            ));
+        }
+
+        Ok(Self {
+            step_size,
+            preconditioner,
+        })
+    }
+
+    pub fn step_size(&self) -> f64 {
+        self.step_size
+    }
+
+    pub fn preconditioner(&self) -> &MassMatrix {
+        &self.preconditioner
+    }
+
+    /// θ + (h/2) M^{-1} ∇ln p(θ)
+    fn drift(&self, theta: &[f64], ln_diff: &[f64]) -> Vec<f64> {
+        theta
+            .iter()
+            .zip(self.preconditioner.velocity(ln_diff).iter())
+            .map(|(t, v)| t + 0.5 * self.step_size * v)
+            .collect()
+    }
+
+    /// drift + √h M^{-1/2} z
+    fn propose(&self, theta: &[f64], ln_diff: &[f64], rng: &mut dyn RngCore) -> Vec<f64> {
+        let p = self.preconditioner.sample(theta.len(), rng);
+        self.drift(theta, ln_diff)
+            .iter()
+            .zip(self.preconditioner.velocity(&p).iter())
+            .map(|(d, v)| d + self.step_size.sqrt() * v)
+            .collect()
+    }
+
+    /// ln q(to|from) up to a constant
+    fn ln_q(&self, to: &[f64], from: &[f64], from_ln_diff: &[f64]) -> f64 {
+        let d = to
+            .iter()
+            .zip(self.drift(from, from_ln_diff).iter())
+            .map(|(t, m)| t - m)
+            .collect::<Vec<_>>();
+
+        -self.preconditioner.quadratic_form(&d) / (2.0 * self.step_size)
+    }
+
+    fn initial<L, P, A, B>(
+        &self,
+        posterior: &LnPosterior<L, P, A, B>,
+        theta: Vec<f64>,
+    ) -> Result<LangevinPoint, DistributionError>
+    where
+        L: Distribution<Value = A, Condition = B> + ConditionDifferentiableDistribution,
+        P: Distribution<Value = B, Condition = ()> + ValueDifferentiableDistribution,
+        A: RandomVariable,
+        B: RandomVariable,
+    {
+        if let Some(n) = self.preconditioner.dim() {
+            if n != theta.len() {
+                return Err(DistributionError::InvalidParameters(
+                    LangevinSamplingError::DimensionMismatch.into(),
+                ));
+            }
+        }
+        let (ln_p, ln_diff) = posterior.ln_p_with_diff(&theta)?;
+        if !ln_p.is_finite() {
+            return Err(DistributionError::InvalidParameters(
+                LangevinSamplingError::InitialValueOutOfSupport.into(),
+            ));
+        }
+
+        Ok(LangevinPoint {
+            theta,
+            ln_p,
+            ln_diff,
+        })
+    }
+}
+
+/// Position, ln p(position) and its gradient
+struct LangevinPoint {
+    theta: Vec<f64>,
+    ln_p: f64,
+    ln_diff: Vec<f64>,
+}
+
+/// Sample b from posterior p(b|a) with likelihood p(a|b) and prior p(b)
+/// Metropolis-adjusted Langevin algorithm
+pub struct MalaSampler<'a, L, P, A, B>
+where
+    L: Distribution<Value = A, Condition = B> + ConditionDifferentiableDistribution,
+    P: Distribution<Value = B, Condition = ()> + ValueDifferentiableDistribution,
+    A: RandomVariable,
+    B: RandomVariable,
+{
+    value: &'a A,
+    likelihood: &'a L,
+    prior: &'a P,
+    params: LangevinParams,
+}
+
+impl<'a, L, P, A, B> MalaSampler<'a, L, P, A, B>
+where
+    L: Distribution<Value = A, Condition = B> + ConditionDifferentiableDistribution,
+    P: Distribution<Value = B, Condition = ()> + ValueDifferentiableDistribution,
+    A: RandomVariable,
+    B: RandomVariable,
+{
+    pub fn new(value: &'a A, likelihood: &'a L, prior: &'a P, params: LangevinParams) -> Self {
+        Self {
+            value,
+            likelihood,
+            prior,
+            params,
+        }
+    }
+
+    pub fn params(&self) -> &LangevinParams {
+        &self.params
+    }
+
+    pub fn sample(
+        &self,
+        iter: usize,
+        initial: B,
+        rng: &mut dyn RngCore,
+    ) -> Result<McmcChain<B, MetropolisStatistics>, DistributionError> {
+        let (theta, info) = initial.transform_vec();
+        let posterior = LnPosterior::new(self.value, self.likelihood, self.prior, &info);
+        let mut current = self.params.initial(&posterior, theta)?;
+        let mut chain = McmcChain::new();
+
+        for _ in 0..iter {
+            let (next, statistics) = self.step(&posterior, current, rng)?;
+            current = next;
+            chain.push(posterior.restore(&current.theta)?, statistics);
+        }
+
+        Ok(chain)
+    }
+
+    fn step(
+        &self,
+        posterior: &LnPosterior<L, P, A, B>,
+        current: LangevinPoint,
+        rng: &mut dyn RngCore,
+    ) -> Result<(LangevinPoint, MetropolisStatistics), DistributionError> {
+        let theta = self.params.propose(&current.theta, &current.ln_diff, rng);
+        let (ln_p, ln_diff) = posterior.ln_p_with_diff(&theta)?;
+        let ln_r = if ln_p.is_finite() {
+            ln_p - current.ln_p + self.params.ln_q(&current.theta, &theta, &ln_diff)
+                - self.params.ln_q(&theta, &current.theta, &current.ln_diff)
+        } else {
+            f64::NEG_INFINITY
+        };
+        let statistics = MetropolisStatistics::from_ln_ratio(ln_r, rng);
+
+        if statistics.accepted {
+            Ok((
+                LangevinPoint {
+                    theta,
+                    ln_p,
+                    ln_diff,
+                },
+                statistics,
+            ))
+        } else {
+            Ok((current, statistics))
+        }
+    }
+}
+
+impl<'a, L, P, A, B> McmcKernel for MalaSampler<'a, L, P, A, B>
+where
+    L: Distribution<Value = A, Condition = B> + ConditionDifferentiableDistribution,
+    P: Distribution<Value = B, Condition = ()> + ValueDifferentiableDistribution,
+    A: RandomVariable,
+    B: RandomVariable,
+{
+    type State = B;
+    type Statistics = MetropolisStatistics;
+
+    fn transition(
+        &self,
+        state: &B,
+        rng: &mut dyn RngCore,
+    ) -> Result<(B, MetropolisStatistics), DistributionError> {
+        let (theta, info) = state.transform_vec();
+        let posterior = LnPosterior::new(self.value, self.likelihood, self.prior, &info);
+        let current = self.params.initial(&posterior, theta)?;
+        let (next, statistics) = self.step(&posterior, current, rng)?;
+
+        Ok((posterior.restore(&next.theta)?, statistics))
+    }
+}
+
+/// Sample b from posterior p(b|a) with likelihood p(a|b) and prior p(b)
+/// Unadjusted Langevin algorithm, whose samples are biased by the discretization of order of the step size
+pub struct LangevinSampler<'a, L, P, A, B>
+where
+    L: Distribution<Value = A, Condition = B> + ConditionDifferentiableDistribution,
+    P: Distribution<Value = B, Condition = ()> + ValueDifferentiableDistribution,
+    A: RandomVariable,
+    B: RandomVariable,
+{
+    value: &'a A,
+    likelihood: &'a L,
+    prior: &'a P,
+    params: LangevinParams,
+}
+
+impl<'a, L, P, A, B> LangevinSampler<'a, L, P, A, B>
+where
+    L: Distribution<Value = A, Condition = B> + ConditionDifferentiableDistribution,
+    P: Distribution<Value = B, Condition = ()> + ValueDifferentiableDistribution,
+    A: RandomVariable,
+    B: RandomVariable,
+{
+    pub fn new(value: &'a A, likelihood: &'a L, prior: &'a P, params: LangevinParams) -> Self {
+        Self {
+            value,
+            likelihood,
+            prior,
+            params,
+        }
+    }
+
+    pub fn params(&self) -> &LangevinParams {
+        &self.params
+    }
+
+    pub fn sample(
+        &self,
+        iter: usize,
+        initial: B,
+        rng: &mut dyn RngCore,
+    ) -> Result<McmcChain<B, MetropolisStatistics>, DistributionError> {
+        let (theta, info) = initial.transform_vec();
+        let posterior = LnPosterior::new(self.value, self.likelihood, self.prior, &info);
+        let mut current = self.params.initial(&posterior, theta)?;
+        let mut chain = McmcChain::new();
+
+        for _ in 0..iter {
+            current = self.step(&posterior, &current, rng)?;
+            chain.push(
+                posterior.restore(&current.theta)?,
+                MetropolisStatistics::always_accepted(),
+            );
+        }
+
+        Ok(chain)
+    }
+
+    fn step(
+        &self,
+        posterior: &LnPosterior<L, P, A, B>,
+        current: &LangevinPoint,
+        rng: &mut dyn RngCore,
+    ) -> Result<LangevinPoint, DistributionError> {
+        let theta = self.params.propose(&current.theta, &current.ln_diff, rng);
+        let (ln_p, ln_diff) = posterior.ln_p_with_diff(&theta)?;
+        if !ln_p.is_finite() {
+            return Err(DistributionError::Others(
+                LangevinSamplingError::OutOfSupport.into(),
+            ));
+        }
+
+        Ok(LangevinPoint {
+            theta,
+            ln_p,
+            ln_diff,
+        })
+    }
+}
+
+impl<'a, L, P, A, B> McmcKernel for LangevinSampler<'a, L, P, A, B>
+where
+    L: Distribution<Value = A, Condition = B> + ConditionDifferentiableDistribution,
+    P: Distribution<Value = B, Condition = ()> + ValueDifferentiableDistribution,
+    A: RandomVariable,
+    B: RandomVariable,
+{
+    type State = B;
+    type Statistics = MetropolisStatistics;
+
+    fn transition(
+        &self,
+        state: &B,
+        rng: &mut dyn RngCore,
+    ) -> Result<(B, MetropolisStatistics), DistributionError> {
+        let (theta, info) = state.transform_vec();
+        let posterior = LnPosterior::new(self.value, self.likelihood, self.prior, &info);
+        let current = self.params.initial(&posterior, theta)?;
+        let next = self.step(&posterior, &current, rng)?;
+
+        Ok((
+            posterior.restore(&next.theta)?,
+            MetropolisStatistics::always_accepted(),
+        ))
+    }
+}
+
+/// Batch size and step size schedule ε_t = a (1 + t / b)^{-γ}.
+/// γ in (0.5, 1] makes the chain converge to the posterior, and γ = 0 keeps the step size constant.
+#[derive(Clone, Debug)]
+pub struct StochasticGradientLangevinParams {
+    batch_size: usize,
+    step_size: f64,
+    offset: f64,
+    decay: f64,
+}
+
+impl StochasticGradientLangevinParams {
+    pub fn new(
+        batch_size: usize,
+        step_size: f64,
+        offset: f64,
+        decay: f64,
+    ) -> Result<Self, DistributionError> {
+        if batch_size == 0 {
+            return Err(DistributionError::InvalidParameters(
+                LangevinSamplingError::BatchSizeOutOfRange.into(),
+            ));
+        }
+        if step_size <= 0.0 || !step_size.is_finite() {
+            return Err(DistributionError::InvalidParameters(
+                LangevinSamplingError::StepSizeMustBePositive.into(),
+            ));
+        }
+        if offset <= 0.0 || !offset.is_finite() {
+            return Err(DistributionError::InvalidParameters(
+                LangevinSamplingError::OffsetMustBePositive.into(),
+            ));
+        }
+        if !(0.0..=1.0).contains(&decay) {
+            return Err(DistributionError::InvalidParameters(
+                LangevinSamplingError::DecayOutOfRange.into(),
+            ));
+        }
+
+        Ok(Self {
+            batch_size,
+            step_size,
+            offset,
+            decay,
+        })
+    }
+
+    pub fn batch_size(&self) -> usize {
+        self.batch_size
+    }
+
+    /// ε_t
+    pub fn step_size(&self, t: usize) -> f64 {
+        self.step_size * (1.0 + t as f64 / self.offset).powf(-self.decay)
+    }
+}
+
+/// Sample b from posterior p(b|a) with likelihood p(a|b) = Π p(ai|b) and prior p(b)
+/// Stochastic gradient Langevin dynamics, which estimates the gradient of the likelihood from a random batch of ai
+pub struct StochasticGradientLangevin<'a, D, T, P, B>
+where
+    D: Distribution<Value = T, Condition = B> + ConditionDifferentiableDistribution,
+    P: Distribution<Value = B, Condition = ()> + ValueDifferentiableDistribution,
+    T: RandomVariable,
+    B: RandomVariable,
+{
+    value: &'a [T],
+    likelihood: &'a IndependentValueArrayJoint<D, T, B>,
+    prior: &'a P,
+    params: StochasticGradientLangevinParams,
+}
+
+impl<'a, D, T, P, B> StochasticGradientLangevin<'a, D, T, P, B>
+where
+    D: Distribution<Value = T, Condition = B> + ConditionDifferentiableDistribution,
+    P: Distribution<Value = B, Condition = ()> + ValueDifferentiableDistribution,
+    T: RandomVariable,
+    B: RandomVariable,
+{
+    pub fn new(
+        value: &'a [T],
+        likelihood: &'a IndependentValueArrayJoint<D, T, B>,
+        prior: &'a P,
+        params: StochasticGradientLangevinParams,
+    ) -> Result<Self, DistributionError> {
+        if value.len() != likelihood.distributions().len() {
+            return Err(DistributionError::InvalidParameters(
+                LangevinSamplingError::DimensionMismatch.into(),
+            ));
+        }
+        if value.len() < params.batch_size {
+            return Err(DistributionError::InvalidParameters(
+                LangevinSamplingError::BatchSizeOutOfRange.into(),
+            ));
+        }
+
+        Ok(Self {
+            value,
+            likelihood,
+            prior,
+            params,
+        })
+    }
+
+    pub fn params(&self) -> &StochasticGradientLangevinParams {
+        &self.params
+    }
+
+    /// ∇ln p(b) + (n / m) Σ_{i in batch} ∇ln p(ai|b)
+    pub fn ln_diff(&self, b: &B, rng: &mut dyn RngCore) -> Result<Vec<f64>, DistributionError> {
+        let n = self.value.len();
+        let m = self.params.batch_size;
+        let scale = n as f64 / m as f64;
+        let distributions = self.likelihood.distributions();
+
+        let mut diff = self.prior.ln_diff_value(b, &())?;
+        for i in index::sample(rng, n, m).iter() {
+            let diff_i = distributions[i].ln_diff_condition(&self.value[i], b)?;
+            if diff_i.len() != diff.len() {
+                return Err(DistributionError::InvalidParameters(
+                    LangevinSamplingError::DimensionMismatch.into(),
+                ));
+            }
+            diff = diff
+                .iter()
+                .zip(diff_i.iter())
+                .map(|(d, di)| d + scale * di)
+                .collect();
+        }
+
+        Ok(diff)
+    }
+
+    pub fn sample(
+        &self,
+        iter: usize,
+        initial: B,
+        rng: &mut dyn RngCore,
+    ) -> Result<McmcChain<B, MetropolisStatistics>, DistributionError> {
+        let mut b = initial;
+        let mut chain = McmcChain::new();
+
+        for t in 0..iter {
+            b = self.step(&b, t, rng)?;
+            chain.push(b.clone(), MetropolisStatistics::always_accepted());
+        }
+
+        Ok(chain)
+    }
+
+    /// Moves b with step size ε_t
+    fn step(&self, b: &B, t: usize, rng: &mut dyn RngCore) -> Result<B, DistributionError> {
+        let (theta, info) = b.transform_vec();
+        let diff = self.ln_diff(b, rng)?;
+        if diff.len() != theta.len() {
+            return Err(DistributionError::InvalidParameters(
+                LangevinSamplingError::DimensionMismatch.into(),
+            ));
+        }
+        let eps = self.params.step_size(t);
+        let theta = theta
+            .iter()
+            .zip(diff.iter())
+            .map(|(ti, di)| ti + 0.5 * eps * di + eps.sqrt() * rng.sample::<f64, _>(StandardNormal))
+            .collect::<Vec<_>>();
+
+        B::restore(&theta, &info)
+    }
+}
+
+/// The state is the pair of b and the number of transitions so far, which determines the step size
+impl<'a, D, T, P, B> McmcKernel for StochasticGradientLangevin<'a, D, T, P, B>
+where
+    D: Distribution<Value = T, Condition = B> + ConditionDifferentiableDistribution,
+    P: Distribution<Value = B, Condition = ()> + ValueDifferentiableDistribution,
+    T: RandomVariable,
+    B: RandomVariable,
+{
+    type State = (B, usize);
+    type Statistics = MetropolisStatistics;
+
+    fn transition(
+        &self,
+        state: &(B, usize),
+        rng: &mut dyn RngCore,
+    ) -> Result<((B, usize), MetropolisStatistics), DistributionError> {
+        let (b, t) = state;
+        let next = self.step(b, *t, rng)?;
+
+        Ok(((next, t + 1), MetropolisStatistics::always_accepted()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use opensrdk_linear_algebra::*;
+    use rand::prelude::*;
+    use rand_distr::StandardNormal;
+
+    #[test]
+    fn it_works() {
+        // y ~ N(θ, I), θ ~ N(0, τ^2 I)
+        let tau = 2.0f64;
+        let likelihood = ConditionDifferentiableInstantDistribution::new(
+            InstantDistribution::new(
+                |y: &Vec<f64>, theta: &Vec<f64>| {
+                    Ok((-0.5
+                        * y.iter()
+                            .zip(theta.iter())
+                            .map(|(yi, ti)| (yi - ti).powi(2))
+                            .sum::<f64>())
+                    .exp())
+                },
+                |theta: &Vec<f64>, _rng| Ok(theta.clone()),
+            ),
+            |y: &Vec<f64>, theta: &Vec<f64>| {
+                Ok(y.iter().zip(theta.iter()).map(|(yi, ti)| yi - ti).collect())
+            },
+        );
+        let prior = ValueDifferentiableInstantDistribution::new(
+            InstantDistribution::new(
+                move |theta: &Vec<f64>, _: &()| {
+                    Ok((-0.5 * theta.iter().map(|ti| ti.powi(2)).sum::<f64>() / tau.powi(2)).exp())
+                },
+                |_: &(), _rng| Ok(vec![0.0; 2]),
+            ),
+            move |theta: &Vec<f64>, _: &()| Ok(theta.iter().map(|ti| -ti / tau.powi(2)).collect()),
+        );
+        let value = vec![1.0, -2.0];
+        let shrinkage = tau.powi(2) / (1.0 + tau.powi(2));
+
+        let preconditioners = vec![
+            MassMatrix::Identity,
+            MassMatrix::dense(mat!(
+                2.0, 0.5;
+                0.5, 1.0
+            ))
+            .unwrap(),
+        ];
+
+        for preconditioner in preconditioners {
+            let params = LangevinParams::new(0.5, preconditioner).unwrap();
+            let mala = MalaSampler::new(&value, &likelihood, &prior, params.clone());
+            let mut rng = StdRng::from_seed([1; 32]);
+            let chain = mala.sample(5000, vec![0.0, 0.0], &mut rng).unwrap();
+
+            assert!(chain.acceptance_rate() > 0.5);
+            for (i, yi) in value.iter().enumerate() {
+                let mean = chain.samples().iter().map(|t| t[i]).sum::<f64>() / 5000.0;
+                assert!((mean - shrinkage * yi).abs() < 0.15);
+            }
+
+            let params = LangevinParams::new(0.05, params.preconditioner().clone()).unwrap();
+            let ula = LangevinSampler::new(&value, &likelihood, &prior, params);
+            let chain = ula.sample(20000, vec![0.0, 0.0], &mut rng).unwrap();
+            for (i, yi) in value.iter().enumerate() {
+                let mean = chain.samples().iter().map(|t| t[i]).sum::<f64>() / 20000.0;
+                assert!((mean - shrinkage * yi).abs() < 0.25);
+            }
+        }
+    }
+
+    #[test]
+    fn it_works2() {
+        // yi ~ N(θ, 1), θ ~ N(0, 10^2)
+        let n = 100;
+        let mut rng = StdRng::from_seed([1; 32]);
+        let value = (0..n)
+            .map(|_| 1.0 + rng.sample::<f64, _>(StandardNormal))
+            .collect::<Vec<_>>();
+        let likelihood = (0..n)
+            .map(|_| {
+                ConditionDifferentiableInstantDistribution::new(
+                    InstantDistribution::new(
+                        |y: &f64, theta: &f64| Ok((-0.5 * (y - theta).powi(2)).exp()),
+                        |theta: &f64, _rng| Ok(*theta),
+                    ),
+                    |y: &f64, theta: &f64| Ok(vec![y - theta]),
+                )
+            })
+            .only_value_joint();
+        let prior = ValueDifferentiableInstantDistribution::new(
+            InstantDistribution::new(
+                |theta: &f64, _: &()| Ok((-0.5 * theta.powi(2) / 100.0).exp()),
+                |_: &(), _rng| Ok(0.0),
+            ),
+            |theta: &f64, _: &()| Ok(vec![-theta / 100.0]),
+        );
+
+        let params = StochasticGradientLangevinParams::new(10, 0.01, 1.0, 0.55).unwrap();
+        let sgld = StochasticGradientLangevin::new(&value, &likelihood, &prior, params).unwrap();
+        let chain = sgld.sample(4000, 0.0, &mut rng).unwrap();
+
+        let expected = value.iter().sum::<f64>() / (n as f64 + 0.01);
+        let mean = chain.samples()[2000..].iter().sum::<f64>() / 2000.0;
+        assert!((mean - expected).abs() < 0.1);
+
+        assert!(StochasticGradientLangevinParams::new(0, 0.01, 1.0, 0.55).is_err());
+        assert!(StochasticGradientLangevinParams::new(10, 0.01, 1.0, 1.5).is_err());
+    }
+}
@@ -0,0 +1,70 @@
+use crate::{
+    ConditionDifferentiableDistribution, Distribution, DistributionError, RandomVariable,
+    ValueDifferentiableDistribution,
+};
+
+#[derive(thiserror::Error, Debug)]
+enum LnPosteriorError {
+    #[error("Length of gradient must equal length of vector")]
+    DimensionMismatch,
+}
+
+/// ln p(b|a) up to a constant and its gradient, as functions of the vector of b
+pub struct LnPosterior<'a, L, P, A, B>
+where
+    L: Distribution<Value = A, Condition = B> + ConditionDifferentiableDistribution,
+    P: Distribution<Value = B, Condition = ()> + ValueDifferentiableDistribution,
+    A: RandomVariable,
+    B: RandomVariable,
+{
+    value: &'a A,
+    likelihood: &'a L,
+    prior: &'a P,
+    info: &'a B::RestoreInfo,
+}
+
+impl<'a, L, P, A, B> LnPosterior<'a, L, P, A, B>
+where
+    L: Distribution<Value = A, Condition = B> + ConditionDifferentiableDistribution,
+    P: Distribution<Value = B, Condition = ()> + ValueDifferentiableDistribution,
+    A: RandomVariable,
+    B: RandomVariable,
+{
+    pub fn new(value: &'a A, likelihood: &'a L, prior: &'a P, info: &'a B::RestoreInfo) -> Self {
+        Self {
+            value,
+            likelihood,
+            prior,
+            info,
+        }
+    }
+
+    pub fn restore(&self, theta: &[f64]) -> Result<B, DistributionError> {
+        B::restore(theta, self.info)
+    }
+
+    /// Returns ln p and its gradient. The gradient is not evaluated where p is 0.
+    pub fn ln_p_with_diff(&self, theta: &[f64]) -> Result<(f64, Vec<f64>), DistributionError> {
+        let b = self.restore(theta)?;
+        let ln_p =
+            self.likelihood.p_kernel(self.value, &b)?.ln() + self.prior.p_kernel(&b, &())?.ln();
+        if !ln_p.is_finite() {
+            return Ok((f64::NEG_INFINITY, vec![0.0; theta.len()]));
+        }
+
+        let diff = self
+            .likelihood
+            .ln_diff_condition(self.value, &b)?
+            .iter()
+            .zip(self.prior.ln_diff_value(&b, &())?.iter())
+            .map(|(l, p)| l + p)
+            .collect::<Vec<_>>();
+        if diff.len() != theta.len() {
+            return Err(DistributionError::InvalidParameters(
+                LnPosteriorError::DimensionMismatch.into(),
+            ));
+        }
+
+        Ok((ln_p, diff))
+    }
+}
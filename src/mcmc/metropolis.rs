@@ -1,4 +1,7 @@
-use crate::{Distribution, DistributionError, RandomVariable, SamplableDistribution};
+use crate::{
+    Distribution, DistributionError, McmcChain, McmcKernel, MetropolisStatistics, RandomVariable,
+    SamplableDistribution,
+};
 use rand::prelude::*;
 
 /// Sample b from posterior p(b|a) with likelihood p(a|b) and prior p(b)
@@ -38,25 +41,38 @@ where
         iter: usize,
         initial: B,
         rng: &mut dyn RngCore,
-    ) -> Result<B, DistributionError> {
-        let mut state = initial;
-        let mut count = 0;
+    ) -> Result<McmcChain<B, MetropolisStatistics>, DistributionError> {
+        self.run(iter, initial, rng)
+    }
+}
 
-        while count < iter {
-            let candidate = self.proposal.sample(&state, rng)?;
-            let r = (self.likelihood.p_kernel(self.value, &candidate)?
-                * self.prior.p_kernel(&candidate, &())?)
-                / (self.likelihood.p_kernel(self.value, &state)?
-                    * self.prior.p_kernel(&state, &())?);
-            let r = r.min(1.0);
-            let p = rng.gen_range(0.0..=1.0);
+impl<'a, L, P, A, B, PD> McmcKernel for MetropolisSampler<'a, L, P, A, B, PD>
+where
+    L: Distribution<Value = A, Condition = B>,
+    P: Distribution<Value = B, Condition = ()>,
+    A: RandomVariable,
+    B: RandomVariable,
+    PD: SamplableDistribution<Value = B, Condition = B>,
+{
+    type State = B;
+    type Statistics = MetropolisStatistics;
 
-            if p < r {
-                state = candidate;
-                count += 1;
-            }
-        }
+    fn transition(
+        &self,
+        state: &B,
+        rng: &mut dyn RngCore,
+    ) -> Result<(B, MetropolisStatistics), DistributionError> {
+        let candidate = self.proposal.sample(state, rng)?;
+        let ln_r = self.likelihood.p_kernel(self.value, &candidate)?.ln()
+            + self.prior.p_kernel(&candidate, &())?.ln()
+            - self.likelihood.p_kernel(self.value, state)?.ln()
+            - self.prior.p_kernel(state, &())?.ln();
+        let statistics = MetropolisStatistics::from_ln_ratio(ln_r, rng);
 
-        Ok(state)
+        if statistics.accepted {
+            Ok((candidate, statistics))
+        } else {
+            Ok((state.clone(), statistics))
+        }
     }
 }
@@ -1,15 +1,34 @@
+pub mod adaptive_metropolis;
 pub mod elliptical_slice_sampling;
+pub mod gibbs;
 pub mod hamiltonian;
 pub mod importance_sampling;
+pub mod kernel;
+pub mod langevin;
 pub mod metropolis;
 pub mod metropolis_hastings;
+pub mod parallel_tempering;
+pub mod reversible_jump;
+pub mod sequential_monte_carlo;
 pub mod sir;
 pub mod slice_sampling;
+pub mod trace;
 
+mod ln_posterior;
+pub(crate) mod util;
+
+pub use adaptive_metropolis::*;
 pub use elliptical_slice_sampling::*;
+pub use gibbs::*;
 pub use hamiltonian::*;
 pub use importance_sampling::*;
+pub use kernel::*;
+pub use langevin::*;
 pub use metropolis::*;
 pub use metropolis_hastings::*;
+pub use parallel_tempering::*;
+pub use reversible_jump::*;
+pub use sequential_monte_carlo::*;
 pub use sir::*;
 pub use slice_sampling::*;
+pub use trace::*;
@@ -0,0 +1,425 @@
+pub mod tempered;
+
+pub use tempered::*;
+
+use crate::mcmc::util::seeds;
+use crate::{
+    Distribution, DistributionError, McmcChain, McmcStatistics, MetropolisStatistics,
+    RandomVariable,
+};
+use rand::prelude::*;
+use rayon::prelude::*;
+
+#[derive(thiserror::Error, Debug)]
+pub enum ParallelTemperingError {
+    #[error("Inverse temperatures are empty")]
+    InverseTemperaturesAreEmpty,
+    #[error("Inverse temperatures must decrease from 1 and be positive")]
+    InverseTemperaturesOutOfRange,
+    #[error("Swap interval must be positive")]
+    SwapIntervalMustBePositive,
+    #[error("Number of initial states must equal number of replicas")]
+    DimensionMismatch,
+    #[error("Unknown error")]
+    Unknown,
+}
+
+/// Lag t_0 and rate ν of the ladder adaptation κ(t) = t_0 / (ν (t + t_0)) (Vousden et al., 2016)
+const ADAPTATION_LAG: f64 = 1000.0;
+const ADAPTATION_RATE: f64 = 100.0;
+
+/// Inverse temperatures 1 = β_0 > β_1 > ... > β_{K-1} > 0 of the replicas, and number of transitions between swaps
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParallelTemperingParams {
+    betas: Vec<f64>,
+    swap_interval: usize,
+}
+
+impl Default for ParallelTemperingParams {
+    fn default() -> Self {
+        Self {
+            betas: vec![1.0, 0.1f64.powf(1.0 / 3.0), 0.1f64.powf(2.0 / 3.0), 0.1],
+            swap_interval: 1,
+        }
+    }
+}
+
+impl ParallelTemperingParams {
+    pub fn new(betas: Vec<f64>, swap_interval: usize) -> Result<Self, DistributionError> {
+        if betas.is_empty() {
+            return Err(DistributionError::InvalidParameters(
+                ParallelTemperingError::InverseTemperaturesAreEmpty.into(),
+            ));
+        }
+        if betas[0] != 1.0
+            || betas.windows(2).any(|w| w[1] >= w[0])
+            || betas.iter().any(|&beta| beta <= 0.0)
+        {
+            return Err(DistributionError::InvalidParameters(
+                ParallelTemperingError::InverseTemperaturesOutOfRange.into(),
+            ));
+        }
+        if swap_interval == 0 {
+            return Err(DistributionError::InvalidParameters(
+                ParallelTemperingError::SwapIntervalMustBePositive.into(),
+            ));
+        }
+
+        Ok(Self {
+            betas,
+            swap_interval,
+        })
+    }
+
+    /// `replicas` inverse temperatures β_k = β_min^{k / (K - 1)}
+    pub fn geometric(
+        replicas: usize,
+        beta_min: f64,
+        swap_interval: usize,
+    ) -> Result<Self, DistributionError> {
+        let betas = (0..replicas)
+            .map(|k| {
+                if k == 0 {
+                    1.0
+                } else {
+                    beta_min.powf(k as f64 / (replicas - 1) as f64)
+                }
+            })
+            .collect();
+
+        Self::new(betas, swap_interval)
+    }
+
+    pub fn betas(&self) -> &[f64] {
+        &self.betas
+    }
+
+    pub fn swap_interval(&self) -> usize {
+        self.swap_interval
+    }
+}
+
+/// Samples of the cold replica with β = 1, and how often neighbouring replicas swapped
+#[derive(Clone, Debug)]
+pub struct ParallelTemperingChain<B, T>
+where
+    B: RandomVariable,
+    T: McmcStatistics,
+{
+    cold: McmcChain<B, T>,
+    swap_acceptance_rates: Vec<f64>,
+    states: Vec<B>,
+}
+
+impl<B, T> ParallelTemperingChain<B, T>
+where
+    B: RandomVariable,
+    T: McmcStatistics,
+{
+    pub fn cold(&self) -> &McmcChain<B, T> {
+        &self.cold
+    }
+
+    /// Fraction of accepted swaps between the replicas k and k + 1
+    pub fn swap_acceptance_rates(&self) -> &[f64] {
+        &self.swap_acceptance_rates
+    }
+
+    /// Last states of all replicas, from the coldest
+    pub fn states(&self) -> &[B] {
+        &self.states
+    }
+}
+
+/// Sample b from posterior p(b|a) with likelihood p(a|b) and prior p(b)
+/// Parallel tempering, or replica exchange: K replicas target p(a|b)^{β_k} p(b) and move by `transition` in parallel.
+/// Every `swap_interval` transitions, neighbouring replicas swap their states with probability
+/// min(1, (p(a|b_{k+1}) / p(a|b_k))^{β_k - β_{k+1}}), so that the cold replica escapes from local modes.
+/// `transition` moves a state of p(a|b)^β p(b) given the tempered likelihood, e.g. by building any kernel from it.
+pub struct ParallelTemperingSampler<'a, L, A, B, T, F>
+where
+    L: Distribution<Value = A, Condition = B>,
+    A: RandomVariable,
+    B: RandomVariable,
+    T: McmcStatistics,
+    F: Fn(&TemperedDistribution<'a, L>, &B, &mut dyn RngCore) -> Result<(B, T), DistributionError>
+        + Send
+        + Sync,
+{
+    value: &'a A,
+    likelihood: &'a L,
+    transition: &'a F,
+    params: ParallelTemperingParams,
+}
+
+impl<'a, L, A, B, T, F> ParallelTemperingSampler<'a, L, A, B, T, F>
+where
+    L: Distribution<Value = A, Condition = B>,
+    A: RandomVariable,
+    B: RandomVariable,
+    T: McmcStatistics,
+    F: Fn(&TemperedDistribution<'a, L>, &B, &mut dyn RngCore) -> Result<(B, T), DistributionError>
+        + Send
+        + Sync,
+{
+    pub fn new(
+        value: &'a A,
+        likelihood: &'a L,
+        transition: &'a F,
+        params: ParallelTemperingParams,
+    ) -> Self {
+        Self {
+            value,
+            likelihood,
+            transition,
+            params,
+        }
+    }
+
+    pub fn params(&self) -> &ParallelTemperingParams {
+        &self.params
+    }
+
+    /// Runs `iter` transitions of all replicas from `initials`, one per replica from the coldest, and keeps the cold states
+    pub fn sample(
+        &self,
+        iter: usize,
+        initials: Vec<B>,
+        rng: &mut dyn RngCore,
+    ) -> Result<ParallelTemperingChain<B, T>, DistributionError> {
+        Ok(self.run(iter, initials, false, rng)?.0)
+    }
+
+    /// Runs `iter` transitions while spacing the temperatures so that the swap acceptance rates become equal.
+    /// The coldest and hottest temperatures are kept.
+    /// Returns the last states and the sampler with the adapted temperatures.
+    pub fn warmup(
+        &self,
+        iter: usize,
+        initials: Vec<B>,
+        rng: &mut dyn RngCore,
+    ) -> Result<(Vec<B>, Self), DistributionError> {
+        let (chain, betas) = self.run(iter, initials, true, rng)?;
+        let params = ParallelTemperingParams::new(betas, self.params.swap_interval)?;
+
+        Ok((
+            chain.states,
+            Self::new(self.value, self.likelihood, self.transition, params),
+        ))
+    }
+
+    fn run(
+        &self,
+        iter: usize,
+        initials: Vec<B>,
+        adapt: bool,
+        rng: &mut dyn RngCore,
+    ) -> Result<(ParallelTemperingChain<B, T>, Vec<f64>), DistributionError> {
+        let mut betas = self.params.betas.clone();
+        let k = betas.len();
+        if initials.len() != k {
+            return Err(DistributionError::InvalidParameters(
+                ParallelTemperingError::DimensionMismatch.into(),
+            ));
+        }
+
+        let mut states = initials;
+        let mut ln_ls = Vec::with_capacity(k);
+        let mut statistics = Vec::with_capacity(k);
+        let mut cold = McmcChain::new();
+        let mut swaps = 0;
+        let mut accepted = vec![0usize; k - 1];
+
+        for t in 0..iter {
+            let seeds = seeds(k, rng);
+
+            let moved = states
+                .par_iter()
+                .zip(betas.par_iter())
+                .zip(seeds.into_par_iter())
+                .map(|((state, &beta), seed)| {
+                    let mut rng = StdRng::from_seed(seed);
+                    let tempered = TemperedDistribution::new(self.likelihood, beta);
+                    let (next, statistics) = (self.transition)(&tempered, state, &mut rng)?;
+                    let ln_l = self.ln_l(&next)?;
+
+                    Ok((next, ln_l, statistics))
+                })
+                .collect::<Result<Vec<_>, DistributionError>>()?;
+
+            states.clear();
+            ln_ls.clear();
+            statistics.clear();
+            for (next, ln_l, s) in moved {
+                states.push(next);
+                ln_ls.push(ln_l);
+                statistics.push(s);
+            }
+
+            if (t + 1) % self.params.swap_interval == 0 && 1 < k {
+                let mut probabilities = vec![0.0; k - 1];
+                for i in 0..k - 1 {
+                    let ln_r = (betas[i] - betas[i + 1]) * (ln_ls[i + 1] - ln_ls[i]);
+                    let swap = MetropolisStatistics::from_ln_ratio(ln_r, rng);
+                    probabilities[i] = swap.acceptance_probability;
+                    if swap.accepted {
+                        // The statistics of a transition follow its state
+                        states.swap(i, i + 1);
+                        ln_ls.swap(i, i + 1);
+                        statistics.swap(i, i + 1);
+                        accepted[i] += 1;
+                    }
+                }
+                if adapt {
+                    betas = adapt_betas(&betas, &probabilities, swaps);
+                }
+                swaps += 1;
+            }
+
+            cold.push(states[0].clone(), statistics.swap_remove(0));
+        }
+
+        let swap_acceptance_rates = accepted
+            .iter()
+            .map(|&a| {
+                if swaps == 0 {
+                    0.0
+                } else {
+                    a as f64 / swaps as f64
+                }
+            })
+            .collect();
+
+        Ok((
+            ParallelTemperingChain {
+                cold,
+                swap_acceptance_rates,
+                states,
+            },
+            betas,
+        ))
+    }
+
+    fn ln_l(&self, b: &B) -> Result<f64, DistributionError> {
+        Ok(self.likelihood.p_kernel(self.value, b)?.ln())
+    }
+}
+
+/// Moves the log spacings ln(T_{k+1} - T_k) of the temperatures T = 1 / β by κ(t) (A_k - Ā),
+/// where A_k is the swap acceptance probability, and rescales them to keep the coldest and hottest temperatures
+fn adapt_betas(betas: &[f64], probabilities: &[f64], t: usize) -> Vec<f64> {
+    let kappa = ADAPTATION_LAG / (ADAPTATION_RATE * (t as f64 + ADAPTATION_LAG));
+    let mean = probabilities.iter().sum::<f64>() / probabilities.len() as f64;
+
+    let spacings = betas
+        .windows(2)
+        .zip(probabilities.iter())
+        .map(|(w, a)| ((1.0 / w[1] - 1.0 / w[0]).ln() + kappa * (a - mean)).exp())
+        .collect::<Vec<_>>();
+    let scale = (1.0 / betas[betas.len() - 1] - 1.0) / spacings.iter().sum::<f64>();
+
+    let mut temperature = 1.0;
+    let mut adapted = vec![1.0];
+    for spacing in spacings {
+        temperature += scale * spacing;
+        adapted.push(1.0 / temperature);
+    }
+
+    adapted
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use rand::prelude::*;
+
+    #[test]
+    fn it_works() {
+        // p(a|b) = (N(b; -4, 0.5^2) + N(b; 4, 0.5^2)) / 2, p(b) = N(0, 10^2)
+        let likelihood = InstantDistribution::new(
+            |_: &(), b: &f64| {
+                Ok((-2.0 * (b + 4.0).powi(2)).exp() + (-2.0 * (b - 4.0).powi(2)).exp())
+            },
+            |_: &f64, _rng: &mut dyn RngCore| Ok(()),
+        );
+        let prior = InstantDistribution::new(
+            |b: &f64, _: &()| Ok((-0.5 * (b / 10.0).powi(2)).exp()),
+            |_: &(), rng: &mut dyn RngCore| Normal.sample(&NormalParams::new(0.0, 10.0)?, rng),
+        );
+        let proposal = InstantDistribution::new(
+            |x: &f64, b: &f64| Ok((-0.5 * (x - b).powi(2)).exp()),
+            |b: &f64, rng: &mut dyn RngCore| Normal.sample(&NormalParams::new(*b, 1.0)?, rng),
+        );
+        let transition = |likelihood: &TemperedDistribution<_>, b: &f64, rng: &mut dyn RngCore| {
+            MetropolisSampler::new(&(), likelihood, &prior, &proposal).transition(b, rng)
+        };
+
+        let params = ParallelTemperingParams::geometric(6, 0.005, 1).unwrap();
+        let sampler = ParallelTemperingSampler::new(&(), &likelihood, &transition, params);
+        let mut rng = StdRng::from_seed([1; 32]);
+
+        let (states, sampler) = sampler.warmup(1000, vec![4.0; 6], &mut rng).unwrap();
+        let betas = sampler.params().betas();
+        assert_eq!(betas[0], 1.0);
+        assert!((betas[5] - 0.005).abs() < 1e-12);
+        assert!(betas.windows(2).all(|w| w[1] < w[0]));
+
+        let chain = sampler.sample(4000, states, &mut rng).unwrap();
+        assert_eq!(chain.cold().samples().len(), 4000);
+        assert_eq!(chain.swap_acceptance_rates().len(), 5);
+        assert!(chain.swap_acceptance_rates().iter().all(|&r| 0.05 < r));
+
+        let positive = chain.cold().samples().iter().filter(|&&b| 0.0 < b).count() as f64 / 4000.0;
+        assert!(0.3 < positive && positive < 0.7);
+
+        assert!(ParallelTemperingParams::new(vec![1.0, 1.0], 1).is_err());
+        assert!(ParallelTemperingParams::new(vec![0.5, 0.1], 1).is_err());
+    }
+
+    #[test]
+    fn it_works2() {
+        // The same mixture with the Metropolis-adjusted Langevin kernel, which uses β ∇ln p(a|b)
+        let ln_components = |b: f64| (-2.0 * (b + 4.0).powi(2), -2.0 * (b - 4.0).powi(2));
+        let likelihood = ConditionDifferentiableInstantDistribution::new(
+            InstantDistribution::new(
+                move |_: &(), b: &f64| {
+                    let (l1, l2) = ln_components(*b);
+                    Ok(l1.exp() + l2.exp())
+                },
+                |_: &f64, _rng: &mut dyn RngCore| Ok(()),
+            ),
+            move |_: &(), b: &f64| {
+                let (l1, l2) = ln_components(*b);
+                let max = l1.max(l2);
+                let (w1, w2) = ((l1 - max).exp(), (l2 - max).exp());
+                Ok(vec![
+                    (-4.0 * (b + 4.0) * w1 - 4.0 * (b - 4.0) * w2) / (w1 + w2),
+                ])
+            },
+        );
+        let prior = ValueDifferentiableInstantDistribution::new(
+            InstantDistribution::new(
+                |b: &f64, _: &()| Ok((-0.5 * (b / 10.0).powi(2)).exp()),
+                |_: &(), rng: &mut dyn RngCore| Normal.sample(&NormalParams::new(0.0, 10.0)?, rng),
+            ),
+            |b: &f64, _: &()| Ok(vec![-b / 100.0]),
+        );
+        // The step size grows with the width 0.5 / √β of the tempered modes up to that of the prior
+        let transition = |likelihood: &TemperedDistribution<_>, b: &f64, rng: &mut dyn RngCore| {
+            let step_size = (0.25 / likelihood.beta()).min(25.0);
+            let params = LangevinParams::new(step_size, MassMatrix::Identity)?;
+            MalaSampler::new(&(), likelihood, &prior, params).transition(b, rng)
+        };
+
+        let params = ParallelTemperingParams::geometric(6, 0.005, 1).unwrap();
+        let sampler = ParallelTemperingSampler::new(&(), &likelihood, &transition, params);
+        let mut rng = StdRng::from_seed([1; 32]);
+
+        let chain = sampler.sample(4000, vec![4.0; 6], &mut rng).unwrap();
+        assert!(0.2 < chain.cold().acceptance_rate());
+        assert!(chain.swap_acceptance_rates().iter().all(|&r| 0.0 < r));
+
+        let positive = chain.cold().samples().iter().filter(|&&b| 0.0 < b).count() as f64 / 4000.0;
+        assert!(0.3 < positive && positive < 0.7);
+    }
+}
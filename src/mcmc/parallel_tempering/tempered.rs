@@ -0,0 +1,55 @@
+use crate::{ConditionDifferentiableDistribution, Distribution, DistributionError};
+
+/// p(a|b)^β, which flattens the likelihood p(a|b) as the inverse temperature β decreases to 0
+#[derive(Clone, Debug)]
+pub struct TemperedDistribution<'a, L>
+where
+    L: Distribution,
+{
+    distribution: &'a L,
+    beta: f64,
+}
+
+impl<'a, L> TemperedDistribution<'a, L>
+where
+    L: Distribution,
+{
+    pub fn new(distribution: &'a L, beta: f64) -> Self {
+        Self { distribution, beta }
+    }
+
+    pub fn beta(&self) -> f64 {
+        self.beta
+    }
+}
+
+impl<'a, L> Distribution for TemperedDistribution<'a, L>
+where
+    L: Distribution,
+{
+    type Value = L::Value;
+    type Condition = L::Condition;
+
+    fn p_kernel(&self, x: &Self::Value, theta: &Self::Condition) -> Result<f64, DistributionError> {
+        Ok(self.distribution.p_kernel(x, theta)?.powf(self.beta))
+    }
+}
+
+impl<'a, L> ConditionDifferentiableDistribution for TemperedDistribution<'a, L>
+where
+    L: ConditionDifferentiableDistribution,
+{
+    /// β ∇_b ln p(a|b)
+    fn ln_diff_condition(
+        &self,
+        x: &Self::Value,
+        theta: &Self::Condition,
+    ) -> Result<Vec<f64>, DistributionError> {
+        Ok(self
+            .distribution
+            .ln_diff_condition(x, theta)?
+            .into_iter()
+            .map(|d| self.beta * d)
+            .collect())
+    }
+}
@@ -0,0 +1,473 @@
+use crate::{
+    Distribution, DistributionError, McmcChain, McmcKernel, McmcStatistics, MetropolisStatistics,
+    RandomVariable,
+};
+use rand::prelude::*;
+
+#[derive(thiserror::Error, Debug)]
+pub enum ReversibleJumpError {
+    #[error("Moves are empty")]
+    MovesAreEmpty,
+    #[error("Weight must be positive")]
+    WeightMustBePositive,
+    #[error("Unknown error")]
+    Unknown,
+}
+
+/// State proposed by a move, with the terms of the acceptance ratio other than the target
+#[derive(Clone, Debug, PartialEq)]
+pub struct JumpProposal<B>
+where
+    B: RandomVariable,
+{
+    state: B,
+    ln_q_forward: f64,
+    ln_q_reverse: f64,
+    ln_jacobian: f64,
+}
+
+impl<B> JumpProposal<B>
+where
+    B: RandomVariable,
+{
+    /// - `ln_q_forward`: ln of the probability or density of the random choices of this move, e.g. which component to remove and the auxiliary variables u
+    /// - `ln_q_reverse`: ln of the probability or density of the choices with which the reverse move returns to the current state
+    /// - `ln_jacobian`: ln |det ∂(b', u') / ∂(b, u)| of the dimension matching map from the current state and u to the proposed state and the u' of the reverse move
+    pub fn new(state: B, ln_q_forward: f64, ln_q_reverse: f64, ln_jacobian: f64) -> Self {
+        Self {
+            state,
+            ln_q_forward,
+            ln_q_reverse,
+            ln_jacobian,
+        }
+    }
+
+    pub fn state(&self) -> &B {
+        &self.state
+    }
+
+    pub fn ln_q_forward(&self) -> f64 {
+        self.ln_q_forward
+    }
+
+    pub fn ln_q_reverse(&self) -> f64 {
+        self.ln_q_reverse
+    }
+
+    pub fn ln_jacobian(&self) -> f64 {
+        self.ln_jacobian
+    }
+}
+
+/// Move of a reversible jump sampler, such as birth, death, split or merge
+pub trait JumpMove<B>: Send + Sync
+where
+    B: RandomVariable,
+{
+    /// Returns `None` if the move is impossible from `state`, e.g. death with no component, and then the chain stays.
+    fn propose(
+        &self,
+        state: &B,
+        rng: &mut dyn RngCore,
+    ) -> Result<Option<JumpProposal<B>>, DistributionError>;
+}
+
+/// Moves registered to a reversible jump sampler
+pub enum ReversibleJump<'a, B>
+where
+    B: RandomVariable,
+{
+    /// A move and its reverse, such as birth and death or split and merge, each chosen with the same probability
+    Pair(Box<dyn JumpMove<B> + 'a>, Box<dyn JumpMove<B> + 'a>),
+    /// A move which is its own reverse, such as a random walk within the current dimension
+    Single(Box<dyn JumpMove<B> + 'a>),
+}
+
+/// Statistics of a transition by the `jump`-th registered moves, where `reverse` is whether the second of a pair was chosen
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReversibleJumpStatistics {
+    pub jump: usize,
+    pub reverse: bool,
+    pub statistics: MetropolisStatistics,
+}
+
+impl McmcStatistics for ReversibleJumpStatistics {
+    fn accepted(&self) -> bool {
+        self.statistics.accepted
+    }
+
+    fn acceptance_probability(&self) -> f64 {
+        self.statistics.acceptance_probability
+    }
+}
+
+/// Sample b from posterior p(b|a) with likelihood p(a|b) and prior p(b), where the dimension of b varies
+/// Reversible jump MCMC (Green, 1995): a registered move is chosen with probability proportional to its weight,
+/// and the state it proposes is accepted with probability
+/// min(1, p(a|b') p(b') q_reverse / (p(a|b) p(b) q_forward) |det ∂(b', u') / ∂(b, u)|).
+/// The prior must be a density with respect to the same measure in each dimension as the moves.
+pub struct ReversibleJumpSampler<'a, L, P, A, B>
+where
+    L: Distribution<Value = A, Condition = B>,
+    P: Distribution<Value = B, Condition = ()>,
+    A: RandomVariable,
+    B: RandomVariable,
+{
+    value: &'a A,
+    likelihood: &'a L,
+    prior: &'a P,
+    jumps: Vec<(f64, ReversibleJump<'a, B>)>,
+}
+
+impl<'a, L, P, A, B> ReversibleJumpSampler<'a, L, P, A, B>
+where
+    L: Distribution<Value = A, Condition = B>,
+    P: Distribution<Value = B, Condition = ()>,
+    A: RandomVariable,
+    B: RandomVariable,
+{
+    /// `jumps` are pairs of the weight and the moves
+    pub fn new(
+        value: &'a A,
+        likelihood: &'a L,
+        prior: &'a P,
+        jumps: Vec<(f64, ReversibleJump<'a, B>)>,
+    ) -> Result<Self, DistributionError> {
+        if jumps.is_empty() {
+            return Err(DistributionError::InvalidParameters(
+                ReversibleJumpError::MovesAreEmpty.into(),
+            ));
+        }
+        if jumps.iter().any(|(w, _)| *w <= 0.0 || !w.is_finite()) {
+            return Err(DistributionError::InvalidParameters(
+                ReversibleJumpError::WeightMustBePositive.into(),
+            ));
+        }
+
+        Ok(Self {
+            value,
+            likelihood,
+            prior,
+            jumps,
+        })
+    }
+
+    pub fn sample(
+        &self,
+        iter: usize,
+        initial: B,
+        rng: &mut dyn RngCore,
+    ) -> Result<McmcChain<B, ReversibleJumpStatistics>, DistributionError> {
+        self.run(iter, initial, rng)
+    }
+
+    /// Fraction of accepted transitions of each registered moves in `chain`
+    pub fn jump_acceptance_rates(
+        &self,
+        chain: &McmcChain<B, ReversibleJumpStatistics>,
+    ) -> Vec<f64> {
+        let mut accepted = vec![0usize; self.jumps.len()];
+        let mut proposed = vec![0usize; self.jumps.len()];
+        for s in chain.statistics().iter() {
+            proposed[s.jump] += 1;
+            if s.statistics.accepted {
+                accepted[s.jump] += 1;
+            }
+        }
+
+        accepted
+            .iter()
+            .zip(proposed.iter())
+            .map(|(&a, &p)| if p == 0 { 0.0 } else { a as f64 / p as f64 })
+            .collect()
+    }
+
+    fn ln_p(&self, b: &B) -> Result<f64, DistributionError> {
+        Ok(self.likelihood.p_kernel(self.value, b)?.ln() + self.prior.p_kernel(b, &())?.ln())
+    }
+}
+
+impl<'a, L, P, A, B> McmcKernel for ReversibleJumpSampler<'a, L, P, A, B>
+where
+    L: Distribution<Value = A, Condition = B>,
+    P: Distribution<Value = B, Condition = ()>,
+    A: RandomVariable,
+    B: RandomVariable,
+{
+    type State = B;
+    type Statistics = ReversibleJumpStatistics;
+
+    fn transition(
+        &self,
+        state: &B,
+        rng: &mut dyn RngCore,
+    ) -> Result<(B, ReversibleJumpStatistics), DistributionError> {
+        let total = self.jumps.iter().map(|(w, _)| w).sum::<f64>();
+        let mut u = rng.gen_range(0.0..total);
+        let mut jump = self.jumps.len() - 1;
+        for (i, (w, _)) in self.jumps.iter().enumerate() {
+            if u < *w {
+                jump = i;
+                break;
+            }
+            u -= w;
+        }
+
+        let (reverse, moves) = match &self.jumps[jump].1 {
+            ReversibleJump::Pair(forward, backward) => {
+                if rng.gen_bool(0.5) {
+                    (true, backward)
+                } else {
+                    (false, forward)
+                }
+            }
+            ReversibleJump::Single(single) => (false, single),
+        };
+
+        let proposal = match moves.propose(state, rng)? {
+            Some(proposal) => proposal,
+            None => {
+                let statistics = MetropolisStatistics {
+                    accepted: false,
+                    acceptance_probability: 0.0,
+                };
+                return Ok((
+                    state.clone(),
+                    ReversibleJumpStatistics {
+                        jump,
+                        reverse,
+                        statistics,
+                    },
+                ));
+            }
+        };
+
+        let ln_r = self.ln_p(&proposal.state)? - self.ln_p(state)? + proposal.ln_q_reverse
+            - proposal.ln_q_forward
+            + proposal.ln_jacobian;
+        let statistics = MetropolisStatistics::from_ln_ratio(ln_r, rng);
+        let next = if statistics.accepted {
+            proposal.state
+        } else {
+            state.clone()
+        };
+
+        Ok((
+            next,
+            ReversibleJumpStatistics {
+                jump,
+                reverse,
+                statistics,
+            },
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use rand::prelude::*;
+    use rand_distr::StandardNormal;
+
+    /// Change points s_1 < ... < s_k in 1..T and the rates h_0, ..., h_k of the segments
+    type ChangePoints = (Vec<usize>, Vec<f64>);
+
+    /// Segment [s_j, s_{j+1}) around the change point position `s`, with s_0 = 0 and s_{k+1} = T
+    fn segment(points: &[usize], t: usize, s: usize) -> (usize, usize, usize) {
+        let j = points.iter().filter(|&&p| p <= s).count();
+        let start = if j == 0 { 0 } else { points[j - 1] };
+        let end = if j == points.len() { t } else { points[j] };
+
+        (j, start, end)
+    }
+
+    /// Adds a change point at a free position chosen uniformly and splits the rate h of the segment into h' and h''
+    /// so that the weighted geometric mean is kept and h'' / h' = (1 - u) / u, u ~ U(0, 1).
+    struct Birth {
+        t: usize,
+    }
+
+    impl JumpMove<ChangePoints> for Birth {
+        fn propose(
+            &self,
+            (points, rates): &ChangePoints,
+            rng: &mut dyn RngCore,
+        ) -> Result<Option<JumpProposal<ChangePoints>>, DistributionError> {
+            let free = (1..self.t)
+                .filter(|s| !points.contains(s))
+                .collect::<Vec<_>>();
+            if free.is_empty() {
+                return Ok(None);
+            }
+            let s = free[rng.gen_range(0..free.len())];
+            let u = rng.gen_range(0.0..1.0);
+
+            let (j, start, end) = segment(points, self.t, s);
+            let (a, c) = ((s - start) as f64, (end - s) as f64);
+            let r = ((1.0 - u) / u).ln();
+            let h = rates[j];
+            let h1 = h * (-c * r / (a + c)).exp();
+            let h2 = h * (a * r / (a + c)).exp();
+
+            let mut points = points.clone();
+            let mut rates = rates.clone();
+            points.insert(j, s);
+            rates[j] = h1;
+            rates.insert(j + 1, h2);
+
+            // The death of the reverse chooses one of the k + 1 change points
+            let k = points.len();
+
+            Ok(Some(JumpProposal::new(
+                (points, rates),
+                -(free.len() as f64).ln(),
+                -(k as f64).ln(),
+                ((h1 + h2).powi(2) / h).ln(),
+            )))
+        }
+    }
+
+    /// Removes a change point chosen uniformly and merges the rates of its segments, the reverse of `Birth`
+    struct Death {
+        t: usize,
+    }
+
+    impl JumpMove<ChangePoints> for Death {
+        fn propose(
+            &self,
+            (points, rates): &ChangePoints,
+            rng: &mut dyn RngCore,
+        ) -> Result<Option<JumpProposal<ChangePoints>>, DistributionError> {
+            let k = points.len();
+            if k == 0 {
+                return Ok(None);
+            }
+            let i = rng.gen_range(0..k);
+            let s = points[i];
+
+            let mut points = points.clone();
+            let mut rates = rates.clone();
+            points.remove(i);
+            let (_, start, end) = segment(&points, self.t, s);
+            let (a, c) = ((s - start) as f64, (end - s) as f64);
+            let (h1, h2) = (rates[i], rates[i + 1]);
+            let h = ((a * h1.ln() + c * h2.ln()) / (a + c)).exp();
+            rates[i] = h;
+            rates.remove(i + 1);
+
+            // The birth of the reverse chooses one of the free positions and u = h' / (h' + h'')
+            let free = self.t - 1 - points.len();
+
+            Ok(Some(JumpProposal::new(
+                (points, rates),
+                -(k as f64).ln(),
+                -(free as f64).ln(),
+                -((h1 + h2).powi(2) / h).ln(),
+            )))
+        }
+    }
+
+    /// Multiplies the rate of a segment chosen uniformly by e^ε, ε ~ N(0, 0.3^2)
+    struct Rate;
+
+    impl JumpMove<ChangePoints> for Rate {
+        fn propose(
+            &self,
+            (points, rates): &ChangePoints,
+            rng: &mut dyn RngCore,
+        ) -> Result<Option<JumpProposal<ChangePoints>>, DistributionError> {
+            let j = rng.gen_range(0..rates.len());
+            let epsilon = 0.3 * rng.sample::<f64, _>(StandardNormal);
+            let mut rates = rates.clone();
+            rates[j] *= epsilon.exp();
+
+            Ok(Some(JumpProposal::new(
+                (points.clone(), rates),
+                0.0,
+                0.0,
+                epsilon,
+            )))
+        }
+    }
+
+    #[test]
+    fn it_works() {
+        // Counts with the rate 2 before t = 20 and 8 after it
+        let t = 40;
+        let mut rng = StdRng::from_seed([1; 32]);
+        let value = (0..t)
+            .map(|i| {
+                let lambda = if i < 20 { 2.0 } else { 8.0 };
+                Poisson.sample(&PoissonParams::new(lambda).unwrap(), &mut rng)
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        // p(y|s, h) = Π_t h_{j(t)}^{y_t} e^{-h_{j(t)}}
+        let likelihood = InstantDistribution::new(
+            |y: &Vec<u64>, (points, rates): &ChangePoints| {
+                let mut ln_p = 0.0;
+                for (i, &y_i) in y.iter().enumerate() {
+                    let h = rates[points.iter().filter(|&&p| p <= i).count()];
+                    ln_p += y_i as f64 * h.ln() - h;
+                }
+                Ok(ln_p.exp())
+            },
+            |_: &ChangePoints, _rng: &mut dyn RngCore| Ok(vec![]),
+        );
+        // k ~ Poisson(1), s uniform over the subsets of size k of 1..T, h_j ~ Gamma(2, 2)
+        let prior = InstantDistribution::new(
+            move |(points, rates): &ChangePoints, _: &()| {
+                let k = points.len();
+                let ln_choose = (0..k)
+                    .map(|i| (((t - 1 - i) as f64) / (i + 1) as f64).ln())
+                    .sum::<f64>();
+                let ln_k = -1.0 - (1..=k).map(|i| (i as f64).ln()).sum::<f64>();
+                let ln_h = rates
+                    .iter()
+                    .map(|&h| {
+                        if h <= 0.0 {
+                            f64::NEG_INFINITY
+                        } else {
+                            h.ln() - h / 2.0 - 4f64.ln()
+                        }
+                    })
+                    .sum::<f64>();
+                Ok((ln_k - ln_choose + ln_h).exp())
+            },
+            |_: &(), _rng: &mut dyn RngCore| Ok((vec![], vec![1.0])),
+        );
+
+        let jumps = vec![
+            (
+                1.0,
+                ReversibleJump::Pair(Box::new(Birth { t }), Box::new(Death { t })),
+            ),
+            (1.0, ReversibleJump::Single(Box::new(Rate))),
+        ];
+        let sampler = ReversibleJumpSampler::new(&value, &likelihood, &prior, jumps).unwrap();
+        let chain = sampler
+            .sample(20000, (vec![], vec![5.0]), &mut rng)
+            .unwrap();
+
+        let samples = &chain.samples()[2000..];
+        let n = samples.len() as f64;
+        let one = samples.iter().filter(|(p, _)| p.len() == 1).count() as f64 / n;
+        assert!(0.5 < one);
+
+        let rate_at = |i: usize| {
+            samples
+                .iter()
+                .map(|(points, rates)| rates[points.iter().filter(|&&p| p <= i).count()])
+                .sum::<f64>()
+                / n
+        };
+        assert!((rate_at(5) - 2.0).abs() < 1.0);
+        assert!((rate_at(35) - 8.0).abs() < 2.0);
+
+        let rates = sampler.jump_acceptance_rates(&chain);
+        assert!(rates.iter().all(|&r| 0.0 < r && r < 1.0));
+
+        assert!(ReversibleJumpSampler::new(&value, &likelihood, &prior, vec![]).is_err());
+    }
+}
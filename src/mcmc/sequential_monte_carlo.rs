@@ -0,0 +1,421 @@
+use crate::mcmc::util::seeds;
+use crate::{
+    ContinuousSamplesDistribution, Distribution, DistributionError, McmcStatistics, RandomVariable,
+    SamplableDistribution, TemperedDistribution,
+};
+use rand::prelude::*;
+use rayon::prelude::*;
+
+#[derive(thiserror::Error, Debug)]
+pub enum SequentialMonteCarloError {
+    #[error("Number of particles must be positive")]
+    ParticlesMustBePositive,
+    #[error("Target ESS ratio must be in (0, 1)")]
+    TargetEssRatioOutOfRange,
+    #[error("All weights are zero")]
+    AllWeightsAreZero,
+    #[error("Unknown error")]
+    Unknown,
+}
+
+/// Iterations of the bisection which chooses the next inverse temperature
+const BISECTION_ITER: usize = 50;
+
+/// Number of particles, ESS kept at each step as a ratio of it, and transitions of each particle after resampling
+#[derive(Clone, Debug, PartialEq)]
+pub struct SequentialMonteCarloParams {
+    particles: usize,
+    target_ess_ratio: f64,
+    rejuvenation_steps: usize,
+}
+
+impl Default for SequentialMonteCarloParams {
+    fn default() -> Self {
+        Self {
+            particles: 1000,
+            target_ess_ratio: 0.5,
+            rejuvenation_steps: 5,
+        }
+    }
+}
+
+impl SequentialMonteCarloParams {
+    pub fn new(
+        particles: usize,
+        target_ess_ratio: f64,
+        rejuvenation_steps: usize,
+    ) -> Result<Self, DistributionError> {
+        if particles == 0 {
+            return Err(DistributionError::InvalidParameters(
+                SequentialMonteCarloError::ParticlesMustBePositive.into(),
+            ));
+        }
+        if target_ess_ratio <= 0.0 || 1.0 <= target_ess_ratio || target_ess_ratio.is_nan() {
+            return Err(DistributionError::InvalidParameters(
+                SequentialMonteCarloError::TargetEssRatioOutOfRange.into(),
+            ));
+        }
+
+        Ok(Self {
+            particles,
+            target_ess_ratio,
+            rejuvenation_steps,
+        })
+    }
+
+    pub fn particles(&self) -> usize {
+        self.particles
+    }
+
+    pub fn target_ess_ratio(&self) -> f64 {
+        self.target_ess_ratio
+    }
+
+    pub fn rejuvenation_steps(&self) -> usize {
+        self.rejuvenation_steps
+    }
+}
+
+/// Weighted particles of the posterior and the estimate of ln p(a)
+#[derive(Clone, Debug)]
+pub struct TemperedParticles<B>
+where
+    B: RandomVariable,
+{
+    particles: ContinuousSamplesDistribution<B>,
+    weights: Vec<f64>,
+    ln_marginal_likelihood: f64,
+    betas: Vec<f64>,
+    acceptance_rates: Vec<f64>,
+}
+
+impl<B> TemperedParticles<B>
+where
+    B: RandomVariable,
+{
+    pub fn particles(&self) -> &ContinuousSamplesDistribution<B> {
+        &self.particles
+    }
+
+    /// Normalized weights of the particles
+    pub fn weights(&self) -> &[f64] {
+        &self.weights
+    }
+
+    /// ln ∫ p(a|b) p(b) db, where p(a|b) is the kernel of the likelihood
+    pub fn ln_marginal_likelihood(&self) -> f64 {
+        self.ln_marginal_likelihood
+    }
+
+    /// Inverse temperatures from 0 to 1 chosen adaptively
+    pub fn betas(&self) -> &[f64] {
+        &self.betas
+    }
+
+    /// Acceptance rates of the rejuvenation at each inverse temperature after the first
+    pub fn acceptance_rates(&self) -> &[f64] {
+        &self.acceptance_rates
+    }
+
+    /// Equally weighted particles by systematic resampling
+    pub fn resample(
+        &self,
+        rng: &mut dyn RngCore,
+    ) -> Result<ContinuousSamplesDistribution<B>, DistributionError> {
+        let samples = self.particles.samples();
+        let indices = systematic_resampling(&self.weights, samples.len(), rng);
+
+        Ok(ContinuousSamplesDistribution::new(
+            indices.into_iter().map(|i| samples[i].clone()).collect(),
+        ))
+    }
+}
+
+/// Sample b from posterior p(b|a) with likelihood p(a|b) and prior p(b)
+/// Sequential Monte Carlo moves particles from p(b) to p(b|a) through p(a|b)^β p(b), 0 = β_0 < β_1 < ... < β_T = 1.
+/// Each β_{t+1} is chosen by bisection so that the ESS of the reweighted particles is `target_ess_ratio` of their number.
+/// The particles are then resampled systematically and moved by `transition`, which leaves p(a|b)^β p(b) invariant,
+/// e.g. by building any kernel from the tempered likelihood.
+/// The product of the mean incremental weights estimates the marginal likelihood.
+pub struct SequentialMonteCarloSampler<'a, L, P, A, B, T, F>
+where
+    L: Distribution<Value = A, Condition = B>,
+    P: SamplableDistribution<Value = B, Condition = ()>,
+    A: RandomVariable,
+    B: RandomVariable,
+    T: McmcStatistics,
+    F: Fn(&TemperedDistribution<'a, L>, &B, &mut dyn RngCore) -> Result<(B, T), DistributionError>
+        + Send
+        + Sync,
+{
+    value: &'a A,
+    likelihood: &'a L,
+    prior: &'a P,
+    transition: &'a F,
+    params: SequentialMonteCarloParams,
+}
+
+impl<'a, L, P, A, B, T, F> SequentialMonteCarloSampler<'a, L, P, A, B, T, F>
+where
+    L: Distribution<Value = A, Condition = B>,
+    P: SamplableDistribution<Value = B, Condition = ()>,
+    A: RandomVariable,
+    B: RandomVariable,
+    T: McmcStatistics,
+    F: Fn(&TemperedDistribution<'a, L>, &B, &mut dyn RngCore) -> Result<(B, T), DistributionError>
+        + Send
+        + Sync,
+{
+    pub fn new(
+        value: &'a A,
+        likelihood: &'a L,
+        prior: &'a P,
+        transition: &'a F,
+        params: SequentialMonteCarloParams,
+    ) -> Self {
+        Self {
+            value,
+            likelihood,
+            prior,
+            transition,
+            params,
+        }
+    }
+
+    pub fn sample(&self, rng: &mut dyn RngCore) -> Result<TemperedParticles<B>, DistributionError> {
+        let n = self.params.particles;
+        let target_ess = self.params.target_ess_ratio * n as f64;
+
+        let mut particles = (0..n)
+            .map(|_| self.prior.sample(&(), rng))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut ln_ls = particles
+            .iter()
+            .map(|b| self.ln_l(b))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut weights = vec![1.0 / n as f64; n];
+        let mut ln_marginal_likelihood = 0.0;
+        let mut betas = vec![0.0];
+        let mut acceptance_rates = vec![];
+
+        loop {
+            let beta = betas[betas.len() - 1];
+            let max = 1.0 - beta;
+            let delta = next_delta(&weights, &ln_ls, max, target_ess);
+            let (next_weights, ln_mean) = reweight(&weights, &ln_ls, delta)?;
+            weights = next_weights;
+            ln_marginal_likelihood += ln_mean;
+
+            if delta == max {
+                betas.push(1.0);
+                break;
+            }
+            let beta = beta + delta;
+            betas.push(beta);
+
+            let indices = systematic_resampling(&weights, n, rng);
+            let resampled = indices
+                .into_iter()
+                .map(|i| particles[i].clone())
+                .collect::<Vec<_>>();
+            weights = vec![1.0 / n as f64; n];
+
+            let (moved, accepted) = self.rejuvenate(resampled, beta, rng)?;
+            particles = moved;
+            ln_ls = particles
+                .par_iter()
+                .map(|b| self.ln_l(b))
+                .collect::<Result<Vec<_>, _>>()?;
+            let transitions = n * self.params.rejuvenation_steps;
+            acceptance_rates.push(if transitions == 0 {
+                0.0
+            } else {
+                accepted as f64 / transitions as f64
+            });
+        }
+
+        Ok(TemperedParticles {
+            particles: ContinuousSamplesDistribution::new(particles),
+            weights,
+            ln_marginal_likelihood,
+            betas,
+            acceptance_rates,
+        })
+    }
+
+    fn rejuvenate(
+        &self,
+        particles: Vec<B>,
+        beta: f64,
+        rng: &mut dyn RngCore,
+    ) -> Result<(Vec<B>, usize), DistributionError> {
+        let seeds = seeds(particles.len(), rng);
+        let tempered = TemperedDistribution::new(self.likelihood, beta);
+
+        let moved = particles
+            .into_par_iter()
+            .zip(seeds.into_par_iter())
+            .map(|(particle, seed)| {
+                let mut rng = StdRng::from_seed(seed);
+                let mut state = particle;
+                let mut accepted = 0;
+                for _ in 0..self.params.rejuvenation_steps {
+                    let (next, statistics) = (self.transition)(&tempered, &state, &mut rng)?;
+                    state = next;
+                    if statistics.accepted() {
+                        accepted += 1;
+                    }
+                }
+
+                Ok((state, accepted))
+            })
+            .collect::<Result<Vec<_>, DistributionError>>()?;
+
+        let accepted = moved.iter().map(|&(_, a)| a).sum();
+
+        Ok((moved.into_iter().map(|(b, _)| b).collect(), accepted))
+    }
+
+    fn ln_l(&self, b: &B) -> Result<f64, DistributionError> {
+        Ok(self.likelihood.p_kernel(self.value, b)?.ln())
+    }
+}
+
+/// Increment of β in (0, `max`] whose reweighted particles have the ESS `target_ess`, or `max` if the ESS is kept at `max`
+fn next_delta(weights: &[f64], ln_ls: &[f64], max: f64, target_ess: f64) -> f64 {
+    if target_ess <= ess(weights, ln_ls, max) {
+        return max;
+    }
+
+    let mut lower = 0.0;
+    let mut upper = max;
+    for _ in 0..BISECTION_ITER {
+        let middle = 0.5 * (lower + upper);
+        if target_ess <= ess(weights, ln_ls, middle) {
+            lower = middle;
+        } else {
+            upper = middle;
+        }
+    }
+
+    // A positive increment ensures progress even if one particle dominates
+    if lower == 0.0 {
+        upper
+    } else {
+        lower
+    }
+}
+
+/// ESS (Σ w_i)^2 / Σ w_i^2 of the weights w_i = W_i p(a|b_i)^δ
+fn ess(weights: &[f64], ln_ls: &[f64], delta: f64) -> f64 {
+    let ln_ws = ln_incremental(weights, ln_ls, delta);
+    let max = ln_ws.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    if !max.is_finite() {
+        return 0.0;
+    }
+    let sum = ln_ws.iter().map(|w| (w - max).exp()).sum::<f64>();
+    let sum2 = ln_ws.iter().map(|w| (2.0 * (w - max)).exp()).sum::<f64>();
+
+    sum.powi(2) / sum2
+}
+
+/// Normalized weights W_i p(a|b_i)^δ / Σ_j W_j p(a|b_j)^δ, and ln Σ_j W_j p(a|b_j)^δ
+fn reweight(
+    weights: &[f64],
+    ln_ls: &[f64],
+    delta: f64,
+) -> Result<(Vec<f64>, f64), DistributionError> {
+    let ln_ws = ln_incremental(weights, ln_ls, delta);
+    let max = ln_ws.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    if !max.is_finite() {
+        return Err(DistributionError::Others(
+            SequentialMonteCarloError::AllWeightsAreZero.into(),
+        ));
+    }
+    let ws = ln_ws.iter().map(|w| (w - max).exp()).collect::<Vec<_>>();
+    let sum = ws.iter().sum::<f64>();
+
+    Ok((ws.into_iter().map(|w| w / sum).collect(), max + sum.ln()))
+}
+
+fn ln_incremental(weights: &[f64], ln_ls: &[f64], delta: f64) -> Vec<f64> {
+    weights
+        .iter()
+        .zip(ln_ls.iter())
+        .map(|(w, ln_l)| {
+            let ln_w = w.ln() + delta * ln_l;
+            if ln_w.is_nan() {
+                f64::NEG_INFINITY
+            } else {
+                ln_w
+            }
+        })
+        .collect()
+}
+
+/// Indices of `n` particles drawn at the points (u + i) / n, u ~ U(0, 1), of the cumulative weights
+fn systematic_resampling(weights: &[f64], n: usize, rng: &mut dyn RngCore) -> Vec<usize> {
+    let u = rng.gen_range(0.0..1.0);
+    let mut indices = Vec::with_capacity(n);
+    let mut cumulative = 0.0;
+    let mut j = 0;
+
+    for i in 0..n {
+        let point = (u + i as f64) / n as f64;
+        while j + 1 < weights.len() && cumulative + weights[j] < point {
+            cumulative += weights[j];
+            j += 1;
+        }
+        indices.push(j);
+    }
+
+    indices
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::evidence::fixtures::normal_mean;
+    use crate::*;
+    use rand::prelude::*;
+
+    #[test]
+    fn it_works() {
+        let (value, likelihood, prior, ln_z) = normal_mean();
+        let proposal = InstantDistribution::new(
+            |x: &f64, b: &f64| Ok((-2.0 * (x - b).powi(2)).exp()),
+            |b: &f64, rng: &mut dyn RngCore| Normal.sample(&NormalParams::new(*b, 0.5)?, rng),
+        );
+        let transition = |likelihood: &TemperedDistribution<_>, b: &f64, rng: &mut dyn RngCore| {
+            MetropolisSampler::new(&value, likelihood, &prior, &proposal).transition(b, rng)
+        };
+
+        let params = SequentialMonteCarloParams::new(2000, 0.5, 5).unwrap();
+        let sampler =
+            SequentialMonteCarloSampler::new(&value, &likelihood, &prior, &transition, params);
+        let mut rng = StdRng::from_seed([1; 32]);
+        let particles = sampler.sample(&mut rng).unwrap();
+
+        let betas = particles.betas();
+        assert_eq!(betas[0], 0.0);
+        assert_eq!(betas[betas.len() - 1], 1.0);
+        assert!(betas.windows(2).all(|w| w[0] < w[1]));
+
+        // p(b|a) = N(6 / 3.25, 1 / 3.25)
+        let mean = particles
+            .particles()
+            .samples()
+            .iter()
+            .zip(particles.weights().iter())
+            .map(|(b, w)| w * b)
+            .sum::<f64>();
+        assert!((mean - 6.0 / 3.25).abs() < 0.05);
+
+        assert!((particles.ln_marginal_likelihood() - ln_z).abs() < 0.1);
+
+        let resampled = particles.resample(&mut rng).unwrap();
+        assert_eq!(resampled.samples().len(), 2000);
+
+        assert!(SequentialMonteCarloParams::new(0, 0.5, 5).is_err());
+        assert!(SequentialMonteCarloParams::new(100, 1.0, 5).is_err());
+    }
+}
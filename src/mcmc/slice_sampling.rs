@@ -1,9 +1,91 @@
-use crate::{Distribution, DistributionError, RandomVariable};
+use crate::{
+    Distribution, DistributionError, McmcChain, McmcKernel, MetropolisStatistics, RandomVariable,
+};
 use rand::prelude::*;
+use rand_distr::Exp1;
+
+#[derive(thiserror::Error, Debug)]
+pub enum SliceSamplingError {
+    #[error("out of range")]
+    OutOfRange,
+    #[error("Width must be positive")]
+    WidthMustBePositive,
+    #[error("Max steps must be positive")]
+    MaxStepsMustBePositive,
+    #[error("Dimension mismatch")]
+    DimensionMismatch,
+    #[error("Initial value is out of support")]
+    InitialValueOutOfSupport,
+    #[error("Unknown error")]
+    Unknown,
+}
+
+/// Procedure to find an interval around the current point which contains the slice (Neal, 2003)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SliceProcedure {
+    /// Extends the interval by the width at most `max_steps` times in total
+    SteppingOut,
+    /// Doubles the interval at most `max_steps` times
+    Doubling,
+}
+
+/// Initial width of the interval, limit of the expansion, and procedure of the expansion
+#[derive(Clone, Debug, PartialEq)]
+pub struct SliceParams {
+    width: f64,
+    max_steps: usize,
+    procedure: SliceProcedure,
+}
+
+impl Default for SliceParams {
+    fn default() -> Self {
+        Self {
+            width: 1.0,
+            max_steps: 32,
+            procedure: SliceProcedure::SteppingOut,
+        }
+    }
+}
+
+impl SliceParams {
+    pub fn new(
+        width: f64,
+        max_steps: usize,
+        procedure: SliceProcedure,
+    ) -> Result<Self, DistributionError> {
+        if width <= 0.0 || !width.is_finite() {
+            return Err(DistributionError::InvalidParameters(
+                SliceSamplingError::WidthMustBePositive.into(),
+            ));
+        }
+        if max_steps == 0 {
+            return Err(DistributionError::InvalidParameters(
+                SliceSamplingError::MaxStepsMustBePositive.into(),
+            ));
+        }
+
+        Ok(Self {
+            width,
+            max_steps,
+            procedure,
+        })
+    }
+
+    pub fn width(&self) -> f64 {
+        self.width
+    }
+
+    pub fn max_steps(&self) -> usize {
+        self.max_steps
+    }
+
+    pub fn procedure(&self) -> SliceProcedure {
+        self.procedure
+    }
+}
 
 /// Sample b from posterior p(b|a) with likelihood p(a|b) and prior p(b)
-/// Unbounded Slice Sampling
-/// http://chasen.org/~daiti-m/diary/?201510
+/// Univariate slice sampling in log space
 pub struct SliceSampler<L, P, A>
 where
     L: Distribution<Value = A, Condition = f64>,
@@ -13,69 +95,459 @@ where
     value: A,
     likelihood: L,
     prior: P,
+    params: SliceParams,
 }
 
-#[derive(thiserror::Error, Debug)]
-pub enum SliceSamplingError {
-    #[error("out of range")]
-    OutOfRange,
-    #[error("Unknown error")]
-    Unknown,
+impl<L, P, A> SliceSampler<L, P, A>
+where
+    L: Distribution<Value = A, Condition = f64>,
+    P: Distribution<Value = f64, Condition = ()>,
+    A: RandomVariable,
+{
+    pub fn new(value: A, likelihood: L, prior: P, params: SliceParams) -> Self {
+        Self {
+            value,
+            likelihood,
+            prior,
+            params,
+        }
+    }
+
+    fn ln_p(&self, x: f64) -> Result<f64, DistributionError> {
+        Ok(self.likelihood.p_kernel(&self.value, &x)?.ln() + self.prior.p_kernel(&x, &())?.ln())
+    }
+
+    /// Moves x to a point uniformly drawn from the slice {x' | ln p(x') > ln p(x) - e}, e ~ Exp(1)
+    pub fn sample(&self, x: f64, rng: &mut dyn RngCore) -> Result<f64, DistributionError> {
+        let ln_p = self.ln_p(x)?;
+        if !ln_p.is_finite() {
+            return Err(DistributionError::InvalidParameters(
+                SliceSamplingError::InitialValueOutOfSupport.into(),
+            ));
+        }
+
+        Ok(slice_1d(&|x| self.ln_p(x), x, ln_p, &self.params, rng)?.0)
+    }
+
+    pub fn sample_chain(
+        &self,
+        iter: usize,
+        initial: f64,
+        rng: &mut dyn RngCore,
+    ) -> Result<McmcChain<f64, MetropolisStatistics>, DistributionError> {
+        self.run(iter, initial, rng)
+    }
 }
 
-impl<L, P, A> SliceSampler<L, P, A>
+impl<L, P, A> McmcKernel for SliceSampler<L, P, A>
 where
     L: Distribution<Value = A, Condition = f64>,
     P: Distribution<Value = f64, Condition = ()>,
     A: RandomVariable,
 {
-    pub fn new(value: A, likelihood: L, prior: P) -> Result<Self, DistributionError> {
+    type State = f64;
+    type Statistics = MetropolisStatistics;
+
+    fn transition(
+        &self,
+        state: &f64,
+        rng: &mut dyn RngCore,
+    ) -> Result<(f64, MetropolisStatistics), DistributionError> {
+        let x = self.sample(*state, rng)?;
+
+        Ok((x, MetropolisStatistics::always_accepted()))
+    }
+}
+
+/// Sample b from posterior p(b|a) with likelihood p(a|b) and prior p(b)
+/// Updates each component of the vector of b in turn by univariate slice sampling
+pub struct CoordinateSliceSampler<'a, L, P, A, B>
+where
+    L: Distribution<Value = A, Condition = B>,
+    P: Distribution<Value = B, Condition = ()>,
+    A: RandomVariable,
+    B: RandomVariable,
+{
+    value: &'a A,
+    likelihood: &'a L,
+    prior: &'a P,
+    params: SliceParams,
+}
+
+impl<'a, L, P, A, B> CoordinateSliceSampler<'a, L, P, A, B>
+where
+    L: Distribution<Value = A, Condition = B>,
+    P: Distribution<Value = B, Condition = ()>,
+    A: RandomVariable,
+    B: RandomVariable,
+{
+    pub fn new(value: &'a A, likelihood: &'a L, prior: &'a P, params: SliceParams) -> Self {
+        Self {
+            value,
+            likelihood,
+            prior,
+            params,
+        }
+    }
+
+    pub fn sample(
+        &self,
+        iter: usize,
+        initial: B,
+        rng: &mut dyn RngCore,
+    ) -> Result<McmcChain<B, MetropolisStatistics>, DistributionError> {
+        self.run(iter, initial, rng)
+    }
+}
+
+impl<'a, L, P, A, B> McmcKernel for CoordinateSliceSampler<'a, L, P, A, B>
+where
+    L: Distribution<Value = A, Condition = B>,
+    P: Distribution<Value = B, Condition = ()>,
+    A: RandomVariable,
+    B: RandomVariable,
+{
+    type State = B;
+    type Statistics = MetropolisStatistics;
+
+    fn transition(
+        &self,
+        state: &B,
+        rng: &mut dyn RngCore,
+    ) -> Result<(B, MetropolisStatistics), DistributionError> {
+        let (mut theta, info) = state.transform_vec();
+        let ln_p = |theta: &[f64]| -> Result<f64, DistributionError> {
+            let b = B::restore(theta, &info)?;
+            Ok(self.likelihood.p_kernel(self.value, &b)?.ln() + self.prior.p_kernel(&b, &())?.ln())
+        };
+        let mut ln_p_theta = ln_p(&theta)?;
+        if !ln_p_theta.is_finite() {
+            return Err(DistributionError::InvalidParameters(
+                SliceSamplingError::InitialValueOutOfSupport.into(),
+            ));
+        }
+
+        for i in 0..theta.len() {
+            let ln_p_i = |x: f64| {
+                let mut buf = theta.clone();
+                buf[i] = x;
+                ln_p(&buf)
+            };
+            let (x, ln_p_x) = slice_1d(&ln_p_i, theta[i], ln_p_theta, &self.params, rng)?;
+            theta[i] = x;
+            ln_p_theta = ln_p_x;
+        }
+
+        Ok((
+            B::restore(&theta, &info)?,
+            MetropolisStatistics::always_accepted(),
+        ))
+    }
+}
+
+/// Sample b from posterior p(b|a) with likelihood p(a|b) and prior p(b)
+/// Multivariate slice sampling which places a hyperrectangle of the given widths randomly around the vector of b,
+/// and shrinks it toward the current point until a point in the slice is drawn
+pub struct HyperrectangleSliceSampler<'a, L, P, A, B>
+where
+    L: Distribution<Value = A, Condition = B>,
+    P: Distribution<Value = B, Condition = ()>,
+    A: RandomVariable,
+    B: RandomVariable,
+{
+    value: &'a A,
+    likelihood: &'a L,
+    prior: &'a P,
+    widths: Vec<f64>,
+}
+
+impl<'a, L, P, A, B> HyperrectangleSliceSampler<'a, L, P, A, B>
+where
+    L: Distribution<Value = A, Condition = B>,
+    P: Distribution<Value = B, Condition = ()>,
+    A: RandomVariable,
+    B: RandomVariable,
+{
+    pub fn new(
+        value: &'a A,
+        likelihood: &'a L,
+        prior: &'a P,
+        widths: Vec<f64>,
+    ) -> Result<Self, DistributionError> {
+        if widths.iter().any(|&w| w <= 0.0 || !w.is_finite()) {
+            return Err(DistributionError::InvalidParameters(
+                SliceSamplingError::WidthMustBePositive.into(),
+            ));
+        }
+
         Ok(Self {
             value,
             likelihood,
             prior,
+            widths,
         })
     }
 
+    pub fn widths(&self) -> &[f64] {
+        &self.widths
+    }
+
     pub fn sample(
         &self,
-        x: f64,
-        max_iter: usize,
+        iter: usize,
+        initial: B,
         rng: &mut dyn RngCore,
-    ) -> Result<f64, DistributionError> {
-        let mut st = 0.0;
-        let mut ed = 1.0;
-
-        let r = shrink(x)?;
-        let slice = self.likelihood.p_kernel(&self.value, &x)? * self.prior.p_kernel(&x, &())?
-            - 2.0 * rng.gen_range(0.0f64..1.0f64).ln();
-
-        for _iter in 0..max_iter {
-            let rnew = rng.gen_range(st..ed);
-            let expanded = expand(rnew)?;
-
-            let newlik = self.likelihood.p_kernel(&self.value, &expanded)?
-                * self.prior.p_kernel(&expanded, &())?
-                - (2.0 * rnew * (1.0 - rnew));
-
-            if newlik > slice {
-                return expand(rnew);
-            } else if rnew > r {
-                ed = rnew;
-            } else if rnew < r {
-                st = rnew;
-            } else {
-                return Ok(x);
+    ) -> Result<McmcChain<B, MetropolisStatistics>, DistributionError> {
+        self.run(iter, initial, rng)
+    }
+}
+
+impl<'a, L, P, A, B> McmcKernel for HyperrectangleSliceSampler<'a, L, P, A, B>
+where
+    L: Distribution<Value = A, Condition = B>,
+    P: Distribution<Value = B, Condition = ()>,
+    A: RandomVariable,
+    B: RandomVariable,
+{
+    type State = B;
+    type Statistics = MetropolisStatistics;
+
+    fn transition(
+        &self,
+        state: &B,
+        rng: &mut dyn RngCore,
+    ) -> Result<(B, MetropolisStatistics), DistributionError> {
+        let (theta, info) = state.transform_vec();
+        if theta.len() != self.widths.len() {
+            return Err(DistributionError::InvalidParameters(
+                SliceSamplingError::DimensionMismatch.into(),
+            ));
+        }
+        let ln_p = |theta: &[f64]| -> Result<f64, DistributionError> {
+            let b = B::restore(theta, &info)?;
+            Ok(self.likelihood.p_kernel(self.value, &b)?.ln() + self.prior.p_kernel(&b, &())?.ln())
+        };
+        let ln_p_theta = ln_p(&theta)?;
+        if !ln_p_theta.is_finite() {
+            return Err(DistributionError::InvalidParameters(
+                SliceSamplingError::InitialValueOutOfSupport.into(),
+            ));
+        }
+        let ln_y = ln_p_theta - rng.sample::<f64, _>(Exp1);
+
+        let mut lower = theta
+            .iter()
+            .zip(self.widths.iter())
+            .map(|(ti, wi)| ti - wi * rng.gen_range(0.0..1.0))
+            .collect::<Vec<_>>();
+        let mut upper = lower
+            .iter()
+            .zip(self.widths.iter())
+            .map(|(li, wi)| li + wi)
+            .collect::<Vec<_>>();
+
+        for _ in 0..MAX_SHRINKAGE {
+            let candidate = lower
+                .iter()
+                .zip(upper.iter())
+                .map(|(li, ui)| li + (ui - li) * rng.gen_range(0.0..1.0))
+                .collect::<Vec<_>>();
+            if ln_y < ln_p(&candidate)? {
+                return Ok((
+                    B::restore(&candidate, &info)?,
+                    MetropolisStatistics::always_accepted(),
+                ));
+            }
+            for (((li, ui), ci), ti) in lower
+                .iter_mut()
+                .zip(upper.iter_mut())
+                .zip(candidate.iter())
+                .zip(theta.iter())
+            {
+                if ci < ti {
+                    *li = *ci;
+                } else {
+                    *ui = *ci;
+                }
             }
         }
-        Ok(x)
+
+        Ok((state.clone(), MetropolisStatistics::always_accepted()))
     }
 }
 
-fn expand(p: f64) -> Result<f64, DistributionError> {
-    Ok(-100.0 * (1.0 / (p - 1.0)).ln())
+/// Limit of shrinkages, which is only reached when the interval has shrunk below the precision of f64
+const MAX_SHRINKAGE: usize = 1000;
+
+/// Draws a point from the slice {x | ln f(x) > ln f(x0) - e}, e ~ Exp(1), and returns it with its ln f
+pub(crate) fn slice_1d(
+    ln_f: &dyn Fn(f64) -> Result<f64, DistributionError>,
+    x0: f64,
+    ln_f_x0: f64,
+    params: &SliceParams,
+    rng: &mut dyn RngCore,
+) -> Result<(f64, f64), DistributionError> {
+    let ln_y = ln_f_x0 - rng.sample::<f64, _>(Exp1);
+    let width = params.width;
+
+    let mut lower = x0 - width * rng.gen_range(0.0..1.0);
+    let mut upper = lower + width;
+    match params.procedure {
+        SliceProcedure::SteppingOut => {
+            let mut j = rng.gen_range(0..params.max_steps);
+            let mut k = params.max_steps - 1 - j;
+            while 0 < j && ln_y < ln_f(lower)? {
+                lower -= width;
+                j -= 1;
+            }
+            while 0 < k && ln_y < ln_f(upper)? {
+                upper += width;
+                k -= 1;
+            }
+        }
+        SliceProcedure::Doubling => {
+            let mut ln_f_lower = ln_f(lower)?;
+            let mut ln_f_upper = ln_f(upper)?;
+            for _ in 0..params.max_steps {
+                if ln_f_lower <= ln_y && ln_f_upper <= ln_y {
+                    break;
+                }
+                if rng.gen_bool(0.5) {
+                    lower -= upper - lower;
+                    ln_f_lower = ln_f(lower)?;
+                } else {
+                    upper += upper - lower;
+                    ln_f_upper = ln_f(upper)?;
+                }
+            }
+        }
+    }
+
+    let (start, end) = (lower, upper);
+    for _ in 0..MAX_SHRINKAGE {
+        let x1 = rng.gen_range(lower..upper);
+        let ln_f_x1 = ln_f(x1)?;
+        if ln_y < ln_f_x1
+            && (params.procedure == SliceProcedure::SteppingOut
+                || doubling_accepts(ln_f, x0, x1, ln_y, start, end, width)?)
+        {
+            return Ok((x1, ln_f_x1));
+        }
+        if x1 < x0 {
+            lower = x1;
+        } else {
+            upper = x1;
+        }
+    }
+
+    Ok((x0, ln_f_x0))
 }
 
-fn shrink(x: f64) -> Result<f64, DistributionError> {
-    Ok(1.0 / (1.0 + (-x / 100.0).exp()))
+/// Whether the doubling procedure from x1 could have produced the same interval, which keeps detailed balance
+fn doubling_accepts(
+    ln_f: &dyn Fn(f64) -> Result<f64, DistributionError>,
+    x0: f64,
+    x1: f64,
+    ln_y: f64,
+    mut lower: f64,
+    mut upper: f64,
+    width: f64,
+) -> Result<bool, DistributionError> {
+    let mut differ = false;
+    while upper - lower > 1.1 * width {
+        let middle = 0.5 * (lower + upper);
+        if (x0 < middle) != (x1 < middle) {
+            differ = true;
+        }
+        if x1 < middle {
+            upper = middle;
+        } else {
+            lower = middle;
+        }
+        if differ && ln_f(lower)? <= ln_y && ln_f(upper)? <= ln_y {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use rand::prelude::*;
+
+    #[test]
+    fn it_works() {
+        // y ~ N(b, 1), b ~ N(0, 1), so p(b|y) = N(y / 2, 1 / 2)
+        let likelihood = InstantDistribution::new(
+            |y: &f64, b: &f64| Ok((-0.5 * (y - b).powi(2)).exp()),
+            |b: &f64, rng| Normal.sample(&NormalParams::new(*b, 1.0)?, rng),
+        );
+        let prior = InstantDistribution::new(
+            |b: &f64, _: &()| Ok((-0.5 * b.powi(2)).exp()),
+            |_: &(), rng| Normal.sample(&NormalParams::new(0.0, 1.0)?, rng),
+        );
+
+        for procedure in [SliceProcedure::SteppingOut, SliceProcedure::Doubling] {
+            let params = SliceParams::new(0.5, 16, procedure).unwrap();
+            let sampler = SliceSampler::new(2.0, likelihood.clone(), prior.clone(), params);
+            let mut rng = StdRng::from_seed([1; 32]);
+            let chain = sampler.sample_chain(10000, 0.0, &mut rng).unwrap();
+
+            let mean = chain.samples().iter().sum::<f64>() / 10000.0;
+            let var = chain
+                .samples()
+                .iter()
+                .map(|x| (x - mean).powi(2))
+                .sum::<f64>()
+                / 10000.0;
+            assert!((mean - 1.0).abs() < 0.05);
+            assert!((var - 0.5).abs() < 0.05);
+        }
+
+        assert!(SliceParams::new(0.0, 16, SliceProcedure::SteppingOut).is_err());
+        assert!(SliceParams::new(1.0, 0, SliceProcedure::Doubling).is_err());
+    }
+
+    #[test]
+    fn it_works2() {
+        // p(b) = N(0, Σ), Σ = [[1, 0.8], [0.8, 1]]
+        let likelihood =
+            InstantDistribution::new(|_: &(), _: &Vec<f64>| Ok(1.0), |_: &Vec<f64>, _rng| Ok(()));
+        let prior = InstantDistribution::new(
+            |b: &Vec<f64>, _: &()| {
+                Ok((-0.5 * (b[0].powi(2) - 1.6 * b[0] * b[1] + b[1].powi(2)) / 0.36).exp())
+            },
+            |_: &(), _rng| Ok(vec![0.0; 2]),
+        );
+        let mut rng = StdRng::from_seed([1; 32]);
+
+        let coordinate =
+            CoordinateSliceSampler::new(&(), &likelihood, &prior, SliceParams::default());
+        let hyperrectangle =
+            HyperrectangleSliceSampler::new(&(), &likelihood, &prior, vec![3.0, 3.0]).unwrap();
+        let chains = vec![
+            coordinate.sample(10000, vec![0.0, 0.0], &mut rng).unwrap(),
+            hyperrectangle
+                .sample(10000, vec![0.0, 0.0], &mut rng)
+                .unwrap(),
+        ];
+
+        for chain in chains {
+            let samples = chain.samples();
+            let mean = (0..2)
+                .map(|i| samples.iter().map(|s| s[i]).sum::<f64>() / 10000.0)
+                .collect::<Vec<_>>();
+            let cov = samples
+                .iter()
+                .map(|s| (s[0] - mean[0]) * (s[1] - mean[1]))
+                .sum::<f64>()
+                / 10000.0;
+            assert!(mean[0].abs() < 0.1 && mean[1].abs() < 0.1);
+            assert!((cov - 0.8).abs() < 0.1);
+        }
+
+        assert!(HyperrectangleSliceSampler::new(&(), &likelihood, &prior, vec![0.0]).is_err());
+    }
 }
@@ -0,0 +1,276 @@
+use crate::mcmc::util::seeds;
+use crate::{DistributionError, McmcKernel, McmcStatistics, RandomVariable};
+use rand::prelude::*;
+use rayon::prelude::*;
+
+#[derive(thiserror::Error, Debug)]
+pub enum McmcError {
+    #[error("Thinning must be positive")]
+    ThinningMustBePositive,
+    #[error("Initial states are empty")]
+    InitialStatesAreEmpty,
+    #[error("Unknown error")]
+    Unknown,
+}
+
+/// Number of kept samples per chain, burn-in transitions discarded first, and thinning interval
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct McmcParams {
+    iter: usize,
+    burn_in: usize,
+    thinning: usize,
+}
+
+impl McmcParams {
+    pub fn new(iter: usize, burn_in: usize, thinning: usize) -> Result<Self, DistributionError> {
+        if thinning == 0 {
+            return Err(DistributionError::InvalidParameters(
+                McmcError::ThinningMustBePositive.into(),
+            ));
+        }
+
+        Ok(Self {
+            iter,
+            burn_in,
+            thinning,
+        })
+    }
+
+    pub fn iter(&self) -> usize {
+        self.iter
+    }
+
+    pub fn burn_in(&self) -> usize {
+        self.burn_in
+    }
+
+    pub fn thinning(&self) -> usize {
+        self.thinning
+    }
+}
+
+/// Samples of one chain and the statistics of the transitions which produced them
+#[derive(Clone, Debug)]
+pub struct McmcChain<S, T>
+where
+    S: RandomVariable,
+    T: McmcStatistics,
+{
+    samples: Vec<S>,
+    statistics: Vec<T>,
+    transitions: usize,
+    accepted: usize,
+}
+
+impl<S, T> Default for McmcChain<S, T>
+where
+    S: RandomVariable,
+    T: McmcStatistics,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S, T> McmcChain<S, T>
+where
+    S: RandomVariable,
+    T: McmcStatistics,
+{
+    pub fn new() -> Self {
+        Self {
+            samples: vec![],
+            statistics: vec![],
+            transitions: 0,
+            accepted: 0,
+        }
+    }
+
+    /// Keeps a sample
+    pub fn push(&mut self, sample: S, statistics: T) {
+        self.count(&statistics);
+        self.samples.push(sample);
+        self.statistics.push(statistics);
+    }
+
+    /// Counts a transition whose state is thinned out
+    pub fn count(&mut self, statistics: &T) {
+        self.transitions += 1;
+        if statistics.accepted() {
+            self.accepted += 1;
+        }
+    }
+
+    pub fn samples(&self) -> &[S] {
+        &self.samples
+    }
+
+    pub fn statistics(&self) -> &[T] {
+        &self.statistics
+    }
+
+    pub fn last(&self) -> Option<&S> {
+        self.samples.last()
+    }
+
+    /// Fraction of accepted transitions, including thinned ones
+    pub fn acceptance_rate(&self) -> f64 {
+        if self.transitions == 0 {
+            return 0.0;
+        }
+        self.accepted as f64 / self.transitions as f64
+    }
+
+    pub fn divergences(&self) -> usize {
+        self.statistics.iter().filter(|s| s.divergent()).count()
+    }
+}
+
+/// Chains run by `run_chains`
+#[derive(Clone, Debug)]
+pub struct McmcTrace<S, T>
+where
+    S: RandomVariable,
+    T: McmcStatistics,
+{
+    chains: Vec<McmcChain<S, T>>,
+}
+
+impl<S, T> McmcTrace<S, T>
+where
+    S: RandomVariable,
+    T: McmcStatistics,
+{
+    pub fn chains(&self) -> &[McmcChain<S, T>] {
+        &self.chains
+    }
+
+    /// Samples of all chains
+    pub fn samples(&self) -> Vec<S> {
+        self.chains
+            .iter()
+            .flat_map(|c| c.samples().iter().cloned())
+            .collect()
+    }
+
+    pub fn acceptance_rates(&self) -> Vec<f64> {
+        self.chains.iter().map(|c| c.acceptance_rate()).collect()
+    }
+
+    /// Fraction of accepted transitions over all chains
+    pub fn acceptance_rate(&self) -> f64 {
+        let transitions = self.chains.iter().map(|c| c.transitions).sum::<usize>();
+        if transitions == 0 {
+            return 0.0;
+        }
+        self.chains.iter().map(|c| c.accepted).sum::<usize>() as f64 / transitions as f64
+    }
+
+    pub fn divergences(&self) -> usize {
+        self.chains.iter().map(|c| c.divergences()).sum()
+    }
+}
+
+/// Runs a chain from each initial state in parallel.
+/// Each chain discards `burn_in` transitions and then keeps every `thinning`-th state until `iter` samples are kept.
+/// Chains use their own generators seeded from `rng`.
+pub fn run_chains<K>(
+    kernel: &K,
+    initials: Vec<K::State>,
+    params: &McmcParams,
+    rng: &mut dyn RngCore,
+) -> Result<McmcTrace<K::State, K::Statistics>, DistributionError>
+where
+    K: McmcKernel,
+{
+    if initials.is_empty() {
+        return Err(DistributionError::InvalidParameters(
+            McmcError::InitialStatesAreEmpty.into(),
+        ));
+    }
+
+    let seeds = seeds(initials.len(), rng);
+
+    let chains = initials
+        .into_par_iter()
+        .zip(seeds.into_par_iter())
+        .map(|(initial, seed)| {
+            let mut rng = StdRng::from_seed(seed);
+            run_chain(kernel, initial, params, &mut rng)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(McmcTrace { chains })
+}
+
+fn run_chain<K>(
+    kernel: &K,
+    initial: K::State,
+    params: &McmcParams,
+    rng: &mut dyn RngCore,
+) -> Result<McmcChain<K::State, K::Statistics>, DistributionError>
+where
+    K: McmcKernel,
+{
+    let mut state = initial;
+    for _ in 0..params.burn_in {
+        state = kernel.transition(&state, rng)?.0;
+    }
+
+    let mut chain = McmcChain::new();
+    for _ in 0..params.iter {
+        for _ in 1..params.thinning {
+            let (next, statistics) = kernel.transition(&state, rng)?;
+            state = next;
+            chain.count(&statistics);
+        }
+        let (next, statistics) = kernel.transition(&state, rng)?;
+        state = next;
+        chain.push(state.clone(), statistics);
+    }
+
+    Ok(chain)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use rand::prelude::*;
+
+    #[test]
+    fn it_works() {
+        // p(b) = N(0, 1), proposal N(b, 1)
+        let likelihood = InstantDistribution::new(
+            |_: &(), _: &f64| Ok(1.0),
+            |_: &f64, _rng: &mut dyn RngCore| Ok(()),
+        );
+        let prior = InstantDistribution::new(
+            |b: &f64, _: &()| Ok((-0.5 * b.powi(2)).exp()),
+            |_: &(), rng: &mut dyn RngCore| Normal.sample(&NormalParams::new(0.0, 1.0)?, rng),
+        );
+        let proposal = InstantDistribution::new(
+            |x: &f64, b: &f64| Ok((-0.5 * (x - b).powi(2)).exp()),
+            |b: &f64, rng: &mut dyn RngCore| Normal.sample(&NormalParams::new(*b, 1.0)?, rng),
+        );
+        let sampler = MetropolisSampler::new(&(), &likelihood, &prior, &proposal);
+
+        let params = McmcParams::new(2000, 500, 2).unwrap();
+        let mut rng = StdRng::from_seed([1; 32]);
+        let trace = run_chains(&sampler, vec![-3.0, 0.0, 3.0, 10.0], &params, &mut rng).unwrap();
+
+        assert_eq!(trace.chains().len(), 4);
+        for chain in trace.chains() {
+            assert_eq!(chain.samples().len(), 2000);
+            let rate = chain.acceptance_rate();
+            assert!(0.5 < rate && rate < 0.9);
+        }
+
+        let samples = trace.samples();
+        let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+        let var = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / samples.len() as f64;
+        assert!(mean.abs() < 0.1);
+        assert!((var - 1.0).abs() < 0.15);
+
+        assert!(McmcParams::new(10, 0, 0).is_err());
+    }
+}
@@ -0,0 +1,23 @@
+use rand::prelude::*;
+
+/// ln(e^a + e^b)
+pub(crate) fn ln_sum_exp(a: f64, b: f64) -> f64 {
+    let max = a.max(b);
+    if !max.is_finite() {
+        return max;
+    }
+
+    max + ((a - max).exp() + (b - max).exp()).ln()
+}
+
+/// Seeds of the generators of `n` tasks run in parallel, drawn from `rng` in order.
+/// Each task uses `StdRng::from_seed`, so results do not depend on the scheduling of the tasks.
+pub(crate) fn seeds(n: usize, rng: &mut dyn RngCore) -> Vec<[u8; 32]> {
+    (0..n)
+        .map(|_| {
+            let mut seed = [0u8; 32];
+            rng.fill_bytes(&mut seed);
+            seed
+        })
+        .collect()
+}
@@ -109,6 +109,14 @@ where
         Ok(self.lsigma.potrs(v)?)
     }
 
+    /// `ln|Sigma| = 2 ln|L|`
+    fn ln_det_sigma(&self) -> f64 {
+        (0..self.lsigma.0.cols())
+            .into_iter()
+            .map(|i| 2.0 * self.lsigma.0[(i, i)].ln())
+            .sum::<f64>()
+    }
+
     fn lsigma_cols(&self) -> usize {
         self.lsigma.0.cols()
     }
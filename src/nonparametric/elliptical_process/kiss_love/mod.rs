@@ -32,6 +32,7 @@ where
     wx: Vec<SparseMatrix>,
     kuu: KroneckerMatrices,
     lkuu: KroneckerMatrices,
+    ln_det_sigma: f64,
     mahalanobis_squared: f64,
 }
 
@@ -99,6 +100,11 @@ where
         // t = l * d * lt
         let pttrf_result = t.pttrf()?;
 
+        // ln|Sigma| = ln|T| when k = n, and it is extrapolated from the k steps otherwise
+        let d = pttrf_result.1.clone().mat();
+        let ln_det_sigma =
+            n as f64 / k as f64 * (0..k).into_iter().map(|i| d[(i, i)].ln()).sum::<f64>();
+
         let s = (0..p)
             .into_iter()
             .map(|pi| {
@@ -154,6 +160,7 @@ where
             wx,
             kuu,
             lkuu,
+            ln_det_sigma,
             mahalanobis_squared,
         })
     }
@@ -284,6 +291,10 @@ where
         Self::sigma_inv_mul_with_params(n, sigma2, &self.wx, &self.kuu, &v)
     }
 
+    fn ln_det_sigma(&self) -> f64 {
+        self.ln_det_sigma
+    }
+
     fn lsigma_cols(&self) -> usize {
         self.mu.len()
     }
@@ -71,6 +71,22 @@ where
             sigma,
         })
     }
+
+    pub fn kernel(&self) -> &K {
+        &self.kernel
+    }
+
+    pub fn x(&self) -> &[T] {
+        &self.x
+    }
+
+    pub fn theta(&self) -> &[f64] {
+        &self.theta
+    }
+
+    pub fn sigma(&self) -> f64 {
+        self.sigma
+    }
 }
 
 pub trait EllipticalProcessParams<K, T>: EllipticalParams
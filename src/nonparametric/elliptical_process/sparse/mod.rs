@@ -28,6 +28,7 @@ where
     ls: POTRF,
     s_inv_kux_omega_y: Matrix,
     kxx_det_sqrt: f64,
+    ln_det_sigma: f64,
     mahalanobis_squared: f64,
 }
 
@@ -73,8 +74,9 @@ where
             .map(|(kxixi, kuxi)| {
                 Ok(kxixi - (kuxi.t() * lkuu.potrs(kuxi)?)[(0, 0)] + base.sigma.powi(2))
             })
-            .collect::<Result<Vec<_>, MatrixError>>()?
-            .diag();
+            .collect::<Result<Vec<_>, MatrixError>>()?;
+        let ln_det_omega = omega.iter().map(|oi| oi.ln()).sum::<f64>();
+        let omega = omega.diag();
         let y_ey = y_ey(y, ey).col_mat();
         let omega_y = &omega * y_ey.elems().to_vec();
 
@@ -83,6 +85,15 @@ where
         let s = &kuu + &kux * &omega_inv_mat * kux.t();
         let ls = s.potrf()?;
 
+        // ln|Sigma| = ln|Omega| + ln|S| - ln|Kuu| by the matrix determinant lemma
+        let ln_det_l = |l: &Matrix| {
+            (0..l.rows())
+                .into_iter()
+                .map(|i| 2.0 * l[(i, i)].ln())
+                .sum::<f64>()
+        };
+        let ln_det_sigma = ln_det_omega + ln_det_l(&ls.0) - ln_det_l(&lkuu.0);
+
         let omega_inv_ref = &omega_inv_mat;
         // let sigma_inv_mul = move |v: Vec<f64>| match Self::sigma_inv_mul(
         //     kux_ref,
@@ -115,6 +126,7 @@ where
             ls,
             kxx_det_sqrt,
             s_inv_kux_omega_y,
+            ln_det_sigma,
             mahalanobis_squared,
         })
     }
@@ -181,6 +193,10 @@ where
         Self::sigma_inv_mul(&self.kux, &self.omega_inv.mat(), &self.ls, v)
     }
 
+    fn ln_det_sigma(&self) -> f64 {
+        self.ln_det_sigma
+    }
+
     fn lsigma_cols(&self) -> usize {
         self.lsigma.cols()
     }
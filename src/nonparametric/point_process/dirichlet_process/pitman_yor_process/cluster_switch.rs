@@ -110,6 +110,46 @@ where
     }
 }
 
+/// The parameters of the clusters in the order of the keys are the vector, and the assignments are kept in the restore info.
+impl<T> RandomVariable for ClusterSwitch<T>
+where
+    T: RandomVariable,
+{
+    type RestoreInfo = (Vec<u32>, Vec<u32>, <Vec<T> as RandomVariable>::RestoreInfo);
+
+    fn transform_vec(&self) -> (Vec<f64>, Self::RestoreInfo) {
+        let mut keys = self.theta.keys().cloned().collect::<Vec<_>>();
+        keys.sort_unstable();
+        let (v, info) = keys
+            .iter()
+            .map(|k| self.theta[k].clone())
+            .collect::<Vec<_>>()
+            .transform_vec();
+
+        (v, (self.s.clone(), keys, info))
+    }
+
+    fn len(&self) -> usize {
+        self.theta
+            .values()
+            .map(|theta_k| theta_k.len())
+            .sum::<usize>()
+    }
+
+    fn restore(v: &[f64], info: &Self::RestoreInfo) -> Result<Self, DistributionError> {
+        let (s, keys, info) = info;
+        let theta = Vec::<T>::restore(v, info)?;
+        if theta.len() != keys.len() {
+            return Err(DistributionError::InvalidRestoreVector);
+        }
+
+        Self::new(
+            s.clone(),
+            keys.iter().cloned().zip(theta.into_iter()).collect(),
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::nonparametric::*;
@@ -1,45 +1,78 @@
 use std::collections::HashSet;
 
+use crate::mcmc::util::seeds;
 use crate::*;
 use crate::{nonparametric::*, Distribution};
 use rand::prelude::*;
 use rayon::prelude::*;
 
 /// # Pitman-Yor process
-pub struct PitmanYorGibbsSampler<'a, L, T, U, G0>
+/// One transition is a sweep which reassigns every point to a cluster, followed by the update of the parameters of each cluster.
+pub struct PitmanYorGibbsSampler<'a, L, T, U, G0, P>
 where
     L: SamplableDistribution<Value = T, Condition = U>,
     T: RandomVariable,
     U: RandomVariable,
     G0: SamplableDistribution<Value = U, Condition = ()>,
+    P: SamplableDistribution<Value = U, Condition = U>,
 {
     base: &'a PitmanYorProcessParams<G0, U>,
-    switch: &'a ClusterSwitch<U>,
     x: &'a [T],
     likelihood: &'a L,
+    proposal: &'a P,
 }
 
-impl<'a, L, T, U, G0> PitmanYorGibbsSampler<'a, L, T, U, G0>
+/// Statistics of one sweep
+#[derive(Clone, Debug, PartialEq)]
+pub struct PitmanYorGibbsStatistics {
+    /// Number of clusters after the sweep
+    pub clusters: usize,
+    /// Number of points assigned to another cluster
+    pub reassigned: usize,
+}
+
+impl McmcStatistics for PitmanYorGibbsStatistics {
+    fn accepted(&self) -> bool {
+        true
+    }
+
+    fn acceptance_probability(&self) -> f64 {
+        1.0
+    }
+}
+
+impl<'a, L, T, U, G0, P> PitmanYorGibbsSampler<'a, L, T, U, G0, P>
 where
     L: SamplableDistribution<Value = T, Condition = U>,
     T: RandomVariable,
     U: RandomVariable,
     G0: SamplableDistribution<Value = U, Condition = ()>,
+    P: SamplableDistribution<Value = U, Condition = U>,
 {
+    /// - `proposal`: random walk of the parameters of a cluster in the Metropolis-Hastings update
     pub fn new(
         base: &'a PitmanYorProcessParams<G0, U>,
-        s: &'a ClusterSwitch<U>,
         value: &'a [T],
         likelihood: &'a L,
+        proposal: &'a P,
     ) -> Self {
         Self {
             base,
-            switch: s,
             x: value,
             likelihood,
+            proposal,
         }
     }
 
+    pub fn sample(
+        &self,
+        iter: usize,
+        initial: ClusterSwitch<U>,
+        rng: &mut dyn RngCore,
+    ) -> Result<McmcChain<ClusterSwitch<U>, PitmanYorGibbsStatistics>, DistributionError> {
+        self.run(iter, initial, rng)
+    }
+
     fn sample_s(
         &self,
         x: &T,
@@ -85,30 +118,49 @@ where
     fn sample_theta(
         &self,
         x_in_k: &Vec<T>,
-        proposal: &impl SamplableDistribution<Value = U, Condition = U>,
+        theta_k: &U,
+        rng: &mut dyn RngCore,
     ) -> Result<U, DistributionError> {
         let x_likelihood = vec![self.likelihood.clone(); x_in_k.len()]
             .into_iter()
             .only_value_joint();
 
-        let mh_sampler =
-            MetropolisHastingsSampler::new(x_in_k, &x_likelihood, &self.base.g0.distr, proposal);
-        let mut rng = thread_rng();
+        let mh_sampler = MetropolisHastingsSampler::new(
+            x_in_k,
+            &x_likelihood,
+            &self.base.g0.distr,
+            self.proposal,
+        );
+        let chain = mh_sampler.sample(4, theta_k.clone(), rng)?;
 
-        mh_sampler.sample(4, self.base.g0.distr.sample(&(), &mut rng)?, &mut rng)
+        Ok(chain.last().cloned().unwrap_or_else(|| theta_k.clone()))
     }
+}
+
+impl<'a, L, T, U, G0, P> McmcKernel for PitmanYorGibbsSampler<'a, L, T, U, G0, P>
+where
+    L: SamplableDistribution<Value = T, Condition = U>,
+    T: RandomVariable,
+    U: RandomVariable,
+    G0: SamplableDistribution<Value = U, Condition = ()>,
+    P: SamplableDistribution<Value = U, Condition = U>,
+{
+    type State = ClusterSwitch<U>;
+    type Statistics = PitmanYorGibbsStatistics;
 
-    pub fn step_sample(
+    fn transition(
         &self,
-        proposal: &impl SamplableDistribution<Value = U, Condition = U>,
+        state: &ClusterSwitch<U>,
         rng: &mut dyn RngCore,
-    ) -> Result<ClusterSwitch<U>, DistributionError> {
-        let n = self.switch.s().len();
+    ) -> Result<(ClusterSwitch<U>, PitmanYorGibbsStatistics), DistributionError> {
+        let n = state.s().len();
 
-        let mut ret = self.switch.clone();
+        let mut ret = state.clone();
+        let mut reassigned = 0;
 
         for remove_index in 0..n {
             let new_theta = self.base.g0.distr.sample(&(), rng)?;
+            let previous = ret.s()[remove_index];
 
             ret.remove(remove_index);
 
@@ -118,23 +170,103 @@ where
             if !ret.theta().contains_key(&si) {
                 ret.theta_mut().insert(si, new_theta);
             }
+            if si != previous {
+                reassigned += 1;
+            }
         }
 
-        *ret.theta_mut() = ret
-            .s_inv()
-            .par_iter()
-            .map(|(&k, indice)| -> Result<_, DistributionError> {
+        // Each cluster is updated in parallel with its own generator seeded from `rng`.
+        // The clusters and their points are sorted so that the seeds do not depend on the order of the hash maps.
+        let mut keys = ret.s_inv().keys().cloned().collect::<Vec<_>>();
+        keys.sort_unstable();
+        let clusters = keys
+            .into_iter()
+            .map(|k| {
+                let mut indice = ret.s_inv()[&k].iter().cloned().collect::<Vec<_>>();
+                indice.sort_unstable();
                 let x_in_k = indice
-                    .par_iter()
-                    .map(|&i| self.x[i].clone())
+                    .into_iter()
+                    .map(|i| self.x[i].clone())
                     .collect::<Vec<_>>();
+                let theta_k = match ret.theta().get(&k) {
+                    Some(theta_k) => theta_k.clone(),
+                    None => self.base.g0.distr.sample(&(), rng)?,
+                };
 
-                let theta_k = self.sample_theta(&x_in_k, proposal)?;
-
-                Ok((k, theta_k))
+                Ok((k, x_in_k, theta_k))
             })
+            .collect::<Result<Vec<_>, DistributionError>>()?;
+        let seeds = seeds(clusters.len(), rng);
+
+        *ret.theta_mut() = clusters
+            .into_par_iter()
+            .zip(seeds.into_par_iter())
+            .map(
+                |((k, x_in_k, theta_k), seed)| -> Result<_, DistributionError> {
+                    let mut rng = StdRng::from_seed(seed);
+                    let theta_k = self.sample_theta(&x_in_k, &theta_k, &mut rng)?;
+
+                    Ok((k, theta_k))
+                },
+            )
             .collect::<Result<_, _>>()?;
 
-        Ok(ret)
+        let statistics = PitmanYorGibbsStatistics {
+            clusters: ret.clusters_len(),
+            reassigned,
+        };
+
+        Ok((ret, statistics))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::nonparametric::*;
+    use crate::*;
+    use rand::prelude::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn it_works() {
+        let x = vec![-5.1, -4.9, -5.0, 5.0, 4.8, 5.2];
+        let g0 = BaselineMeasure::new(InstantDistribution::new(
+            |theta: &NormalParams, _: &()| Ok((-0.5 * (theta.mu() / 10.0).powi(2)).exp()),
+            |_: &(), rng: &mut dyn RngCore| {
+                NormalParams::new(Normal.sample(&NormalParams::new(0.0, 10.0)?, rng)?, 1.0)
+            },
+        ));
+        let base = PitmanYorProcessParams::new(1.0, 0.1, g0).unwrap();
+        let proposal = InstantDistribution::new(
+            |x: &NormalParams, theta: &NormalParams| {
+                Ok((-0.5 * (x.mu() - theta.mu()).powi(2)).exp())
+            },
+            |theta: &NormalParams, rng: &mut dyn RngCore| {
+                NormalParams::new(
+                    Normal.sample(&NormalParams::new(theta.mu(), 1.0)?, rng)?,
+                    1.0,
+                )
+            },
+        );
+        let sampler = PitmanYorGibbsSampler::new(&base, &x, &Normal, &proposal);
+
+        let mut theta = HashMap::new();
+        theta.insert(0u32, NormalParams::new(0.0, 1.0).unwrap());
+        let initial = ClusterSwitch::new(vec![0; x.len()], theta).unwrap();
+
+        let params = McmcParams::new(20, 10, 2).unwrap();
+        let mut rng = StdRng::from_seed([1; 32]);
+        let trace =
+            run_chains(&sampler, vec![initial.clone(), initial], &params, &mut rng).unwrap();
+
+        for chain in trace.chains() {
+            assert_eq!(chain.samples().len(), 20);
+            assert_eq!(chain.acceptance_rate(), 1.0);
+
+            // The two groups are separated
+            let last = chain.last().unwrap();
+            assert_ne!(last.s()[0], last.s()[5]);
+            assert_eq!(last.clusters_len(), last.theta().len());
+        }
     }
 }